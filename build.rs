@@ -1,8 +1,4 @@
-use std::{
-    env,
-    io::{Error, ErrorKind},
-    process::Command,
-};
+use std::{env, io::Error, process::Command};
 
 fn get_version_from_closest_tag() -> Result<String, Error> {
     let output = Command::new("git")
@@ -13,14 +9,11 @@ fn get_version_from_closest_tag() -> Result<String, Error> {
 
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
-        return Err(Error::new(
-            ErrorKind::Other,
-            format!(
-                "can not get closest tag: {} - code: {}",
-                stderr,
-                output.status.code().unwrap()
-            ),
-        ));
+        return Err(Error::other(format!(
+            "can not get closest tag: {} - code: {}",
+            stderr,
+            output.status.code().unwrap()
+        )));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout).to_string();
@@ -28,10 +21,7 @@ fn get_version_from_closest_tag() -> Result<String, Error> {
 }
 
 fn get_version_from_env(env_var_name: &str) -> Option<String> {
-    match env::var(env_var_name) {
-        Ok(v) => Some(v),
-        Err(_) => None,
-    }
+    env::var(env_var_name).ok()
 }
 
 fn print_cargo_version(version: String) {
@@ -0,0 +1,22 @@
+//! Fuzz target for `parsing::parse_commit_details` using the default commit pattern.
+//!
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tag_track::{config, parsing};
+
+fuzz_target!(|message: &str| {
+    let config = config::Config::new();
+    let details =
+        parsing::parse_commit_details(message, &config.commit_pattern, config.scope_rules.case)
+            .expect("default commit pattern must be valid");
+
+    if let Some(details) = details {
+        assert!(!details.commit_type.is_empty());
+        assert!(!details.description.is_empty());
+        if let Some(scope) = &details.scope {
+            assert!(!scope.contains(['(', ')', '\n']));
+        }
+    }
+});
@@ -0,0 +1,45 @@
+//! Fuzz target for the parsers of the `git` command outputs used by the git source.
+//!
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tag_track::{config, git};
+
+fuzz_target!(|stdout: &str| {
+    let config = config::Config::new();
+
+    if let Ok(tags) =
+        git::parse_show_ref_output(stdout, &config.tag_pattern, config.scope_rules.case)
+    {
+        for tag in tags {
+            assert!(!tag.name.is_empty());
+            assert!(!tag.commit_sha.is_empty());
+            assert!(tag.commit_sha.chars().all(|c| c.is_ascii_hexdigit()));
+        }
+    }
+
+    let commit = git::parse_rev_list_output(
+        stdout,
+        &config.commit_pattern,
+        config.scope_rules.case,
+        config.max_commit_message_length,
+    )
+    .expect("default commit pattern must be valid");
+    if let Some(commit) = commit {
+        assert!(!commit.sha.is_empty());
+        assert!(commit.sha.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+
+    let commits = git::parse_rev_list_commits(
+        stdout,
+        &config.commit_pattern,
+        config.scope_rules.case,
+        config.max_commit_message_length,
+    )
+    .expect("default commit pattern must be valid");
+    for commit in commits {
+        assert!(!commit.sha.is_empty());
+        assert!(commit.sha.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+});
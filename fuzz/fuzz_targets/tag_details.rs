@@ -0,0 +1,22 @@
+//! Fuzz target for `parsing::parse_tag_details` using the default tag pattern and a scoped tag pattern.
+//!
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tag_track::{config, parsing};
+
+/// Tag pattern commonly used by monorepos, tags look like `app/0.1.0`.
+const SCOPED_TAG_PATTERN: &str = r"(?<scope>.*)/(?<version>.*)";
+
+fuzz_target!(|tag_name: &str| {
+    let config = config::Config::new();
+
+    for tag_pattern in [config.tag_pattern.as_str(), SCOPED_TAG_PATTERN] {
+        if let Ok(Some(details)) =
+            parsing::parse_tag_details(tag_name, tag_pattern, config.scope_rules.case)
+        {
+            assert!(tag_name.contains(&details.version.major.to_string()));
+        }
+    }
+});
@@ -0,0 +1,201 @@
+//! Node.js bindings of Tag Track, built with napi-rs.
+//!
+//! The bindings expose the version bump calculation of the `tag_track` library to JavaScript, so the GitHub Action
+//! can read the version bumps and set its outputs natively instead of running the CLI and parsing its JSON output.
+//! The history is read from the local git repository, tags and releases are never created. Configurations whose
+//! `sources` include other sources than `git` are rejected.
+//!
+
+use napi_derive::napi;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use tag_track::bump::OutputVersionBump;
+use tag_track::config::{is_config_available, parse_config_file, Config};
+use tag_track::git;
+use tag_track::source::{git::GitSource, SourceActions, SourceKind, SourceName};
+use tag_track::{BumpResult, Error, ErrorKind};
+
+/// Options of `calculateBump`.
+#[napi(object)]
+pub struct CalculateBumpOptions {
+    /// Directory of the repository, defaults to the current directory. The directory can only be set once per
+    /// process, following calls with a different directory throw an error and calls without it use the directory
+    /// of the first one.
+    pub repo_path: Option<String>,
+    /// Path of the configuration file, defaults to the configuration file of the repository if there is one.
+    pub config_file: Option<String>,
+    /// SHA of the commit whose version bumps are calculated, defaults to the latest commit.
+    pub commit_sha: Option<String>,
+}
+
+/// Calculates the version bump of each scope of the repository. Returns the `version_bumps`, `skipped_commits`,
+/// `ignored_commits` and `dependency_updates` fields with the same structure as the JSON output of the CLI.
+///
+/// Throws an error whose `code` is the error kind reported by the JSON output of the CLI, such as
+/// `not_git_working_tree`, if the version bumps can not be calculated.
+///
+/// # Arguments
+///
+/// * `options` - Repository, configuration file and commit used to calculate the version bumps.
+///
+#[napi]
+pub fn calculate_bump(options: CalculateBumpOptions) -> napi::Result<Value, String> {
+    calculate(options)
+        .map_err(|error| napi::Error::new(error.kind.code().to_owned(), error.to_string()))
+}
+
+/// Calculates the version bump of each scope with the given options and returns the JSON value of `calculateBump`.
+///
+/// # Arguments
+///
+/// * `options` - Repository, configuration file and commit used to calculate the version bumps.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the repository path is not a directory, if a
+/// different repository path was set by a previous call or if the configuration includes other sources than `git`.
+///
+/// Returns the errors of `config::parse_config_file`, of the git source and of `tag_track::calculate`.
+///
+fn calculate(options: CalculateBumpOptions) -> Result<Value, Error> {
+    if let Some(repo_path) = options.repo_path {
+        let repo_path = PathBuf::from(repo_path);
+        if !repo_path.is_dir() {
+            return Err(Error::new(
+                ErrorKind::Other,
+                Some(&format!(
+                    "the repository path `{}` is not a directory",
+                    repo_path.display()
+                )),
+            ));
+        }
+        git::set_repo_path(repo_path)?;
+    }
+
+    let config_file = options
+        .config_file
+        .map(|path| git::repo_path().join(path))
+        .or_else(is_config_available);
+    let config = match config_file {
+        Some(path) => parse_config_file(path)?,
+        None => Config::new(),
+    };
+    if let Some(source_name) = config.sources.iter().find(|name| **name != SourceName::Git) {
+        return Err(Error::new(
+            ErrorKind::Other,
+            Some(&format!(
+                "the `{}` source is not supported by the Node.js bindings, only the `git` source can be configured",
+                source_name.as_str()
+            )),
+        ));
+    }
+
+    let source = SourceKind::Git(GitSource::new(&config, false, false));
+    let commit_sha = match options.commit_sha {
+        Some(commit_sha) => commit_sha,
+        None => source.get_latest_commit_sha()?,
+    };
+    let result = tag_track::calculate(&config, &source, &commit_sha)?;
+    Ok(to_output(&result))
+}
+
+/// Returns the JSON value of the given version bumps, with the same structure as the JSON output of the CLI.
+///
+/// # Arguments
+///
+/// * `result` - Calculated version bumps.
+///
+fn to_output(result: &BumpResult) -> Value {
+    let version_bumps: Vec<OutputVersionBump> = result
+        .version_bumps
+        .iter()
+        .map(OutputVersionBump::new)
+        .collect();
+
+    json!({
+        "version_bumps": version_bumps,
+        "skipped_commits": result.skipped_commits,
+        "ignored_commits": result.ignored_commits,
+        "dependency_updates": result.dependency_updates,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::process::Command;
+    use std::sync::OnceLock;
+    use std::{env, fs, process};
+
+    /// Returns the repository used by the tests, built once per process as the repository path can only be set once.
+    /// It has a `feat` commit tagged `1.0.0` followed by a `fix` commit, and a `github.yml` configuration file that
+    /// selects the `github` source.
+    fn fixture_repo() -> &'static Path {
+        static REPO: OnceLock<PathBuf> = OnceLock::new();
+        REPO.get_or_init(|| {
+            let path = env::temp_dir().join(format!("tag-track-node-{}", process::id()));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            let git = |args: &[&str]| {
+                let status = Command::new("git")
+                    .args(["-c", "user.name=A", "-c", "user.email=a@example.com"])
+                    .args(args)
+                    .current_dir(&path)
+                    .status()
+                    .unwrap();
+                assert!(status.success());
+            };
+            git(&["init", "--quiet"]);
+            git(&[
+                "commit",
+                "--quiet",
+                "--allow-empty",
+                "-m",
+                "feat: initial release",
+            ]);
+            git(&["tag", "1.0.0"]);
+            git(&[
+                "commit",
+                "--quiet",
+                "--allow-empty",
+                "-m",
+                "fix: handle empty responses",
+            ]);
+            fs::write(path.join("github.yml"), "sources: [github]\n").unwrap();
+            path
+        })
+    }
+
+    fn options(config_file: Option<&str>) -> CalculateBumpOptions {
+        CalculateBumpOptions {
+            repo_path: Some(fixture_repo().to_string_lossy().into_owned()),
+            config_file: config_file.map(str::to_owned),
+            commit_sha: None,
+        }
+    }
+
+    #[test]
+    fn version_bumps_follow_the_json_output() {
+        let output = calculate(options(None)).unwrap();
+        let version_bump = &output["version_bumps"][0];
+        assert_eq!(version_bump["scope"], "");
+        assert_eq!(version_bump["old_version"], "1.0.0");
+        assert_eq!(version_bump["new_version"], "1.0.1");
+        assert_eq!(version_bump["increment_kind"], "patch");
+        assert_eq!(
+            version_bump["provenance"][0]["subject"],
+            "fix: handle empty responses"
+        );
+        assert_eq!(output["skipped_commits"], json!([]));
+    }
+
+    #[test]
+    fn sources_other_than_git_are_rejected() {
+        let error = calculate(options(Some("github.yml"))).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Other);
+        assert!(error
+            .to_string()
+            .contains("the `github` source is not supported"));
+    }
+}
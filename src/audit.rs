@@ -0,0 +1,349 @@
+//! This module provides the tag audit, used when onboarding legacy repositories. The existing tags of the local git
+//! repository are validated against the tag pattern of the configuration, reporting the tags that do not parse,
+//! duplicated versions, gaps in the version sequence of each scope and scopes that are not configured.
+//!
+//! When many tags do not parse, a set of common tag patterns is evaluated and the one that parses the most tags is
+//! suggested.
+//!
+
+use crate::config::{Config, ScopeCase};
+use crate::error::{Error, ErrorKind};
+use crate::parsing::{parse_tag_details, TagDetails};
+use crate::source::git::get_tag_names;
+use semver::Version;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Ratio of tags that do not parse from which a tag pattern is suggested.
+const SUGGESTION_UNPARSED_RATIO: f64 = 0.5;
+
+/// Common tag patterns evaluated when suggesting a tag pattern.
+const CANDIDATE_TAG_PATTERNS: [&str; 4] = [
+    r"^v?(?<version>\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?)$",
+    r"^(?<scope>[^/@]+)/v?(?<version>\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?)$",
+    r"^(?<scope>[^/@]+)@v?(?<version>\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?)$",
+    r"^(?<scope>[A-Za-z0-9_.]+)-v?(?<version>\d+\.\d+\.\d+(?:-[0-9A-Za-z.-]+)?(?:\+[0-9A-Za-z.-]+)?)$",
+];
+
+/// Type that represents the result of a tag audit.
+#[derive(Debug, Serialize)]
+pub struct TagAudit {
+    /// Number of audited tags.
+    pub tag_count: usize,
+    /// Names of the tags that do not match the tag pattern or do not contain a valid semantic version.
+    pub unparsed_tags: Vec<String>,
+    /// Versions referenced by more than one tag.
+    pub duplicated_versions: Vec<DuplicatedVersion>,
+    /// Gaps in the version sequence of each scope.
+    pub version_gaps: Vec<VersionGap>,
+    /// Scopes of the tags that do not match the configured version scopes.
+    pub scope_issues: Vec<ScopeIssue>,
+    /// Tag pattern that parses more tags than the configured one, if many tags do not parse.
+    pub suggested_tag_pattern: Option<String>,
+}
+
+impl TagAudit {
+    /// Returns `true` if the audit found at least one issue.
+    pub fn has_issues(&self) -> bool {
+        !self.unparsed_tags.is_empty()
+            || !self.duplicated_versions.is_empty()
+            || !self.version_gaps.is_empty()
+            || !self.scope_issues.is_empty()
+    }
+}
+
+/// Type that represents a version referenced by more than one tag.
+#[derive(Debug, Serialize)]
+pub struct DuplicatedVersion {
+    /// Scope of the version.
+    pub scope: String,
+    /// Duplicated version.
+    pub version: String,
+    /// Names of the tags that reference the version.
+    pub tag_names: Vec<String>,
+}
+
+/// Type that represents a gap between two consecutive release versions of a scope.
+#[derive(Debug, Serialize)]
+pub struct VersionGap {
+    /// Scope of the versions.
+    pub scope: String,
+    /// Version before the gap.
+    pub previous_version: String,
+    /// Version after the gap.
+    pub version: String,
+}
+
+/// Type that represents a tag scope that does not match the configured version scopes.
+#[derive(Debug, Serialize)]
+pub struct ScopeIssue {
+    /// Scope of the tags.
+    pub scope: String,
+    /// Names of the tags with the scope.
+    pub tag_names: Vec<String>,
+    /// Description of the issue.
+    pub message: String,
+}
+
+/// Audits the tags of the local git repository against the tag pattern and version scopes of the given configuration.
+/// Pre-release versions are not considered when looking for gaps in the version sequences.
+///
+/// # Arguments
+///
+/// * `config` - Tag Track configuration.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidRegexPattern` if the tag pattern is not a valid
+/// regex pattern.
+///
+/// Returns the errors of `source::git::get_tag_names`.
+///
+pub fn audit_tags(config: &Config) -> Result<TagAudit, Error> {
+    audit_tag_names(get_tag_names(None)?, config)
+}
+
+/// Audits the given tag names against the tag pattern and version scopes of the given configuration, as done by
+/// `audit_tags`.
+///
+/// # Arguments
+///
+/// * `tag_names` - Names of the audited tags.
+///
+/// * `config` - Tag Track configuration.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidRegexPattern` if the tag pattern is not a valid
+/// regex pattern.
+///
+fn audit_tag_names(tag_names: Vec<String>, config: &Config) -> Result<TagAudit, Error> {
+    let mut unparsed_tags = vec![];
+    let mut scope_tags: BTreeMap<String, Vec<(&str, Version)>> = BTreeMap::new();
+    for name in &tag_names {
+        match parse_tag(name, &config.tag_pattern, config.scope_rules.case)? {
+            Some(details) => scope_tags
+                .entry(details.scope.unwrap_or_default())
+                .or_default()
+                .push((name.as_str(), details.version)),
+            None => unparsed_tags.push(name.clone()),
+        }
+    }
+
+    let mut duplicated_versions = vec![];
+    let mut version_gaps = vec![];
+    let mut scope_issues = vec![];
+    for (scope, tags) in &scope_tags {
+        let mut versions: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for (name, version) in tags {
+            versions
+                .entry(version.to_string())
+                .or_default()
+                .push(name.to_string());
+        }
+        for (version, tag_names) in versions {
+            if tag_names.len() > 1 {
+                duplicated_versions.push(DuplicatedVersion {
+                    scope: scope.clone(),
+                    version,
+                    tag_names,
+                });
+            }
+        }
+
+        let mut releases: Vec<&Version> = tags
+            .iter()
+            .map(|(_, version)| version)
+            .filter(|version| version.pre.is_empty())
+            .collect();
+        releases.sort();
+        releases.dedup_by(|a, b| (a.major, a.minor, a.patch) == (b.major, b.minor, b.patch));
+        for pair in releases.windows(2) {
+            if !is_sequential(pair[0], pair[1]) {
+                version_gaps.push(VersionGap {
+                    scope: scope.clone(),
+                    previous_version: pair[0].to_string(),
+                    version: pair[1].to_string(),
+                });
+            }
+        }
+
+        if config.version_scopes.contains(scope) {
+            continue;
+        }
+        let message = match config
+            .version_scopes
+            .iter()
+            .find(|version_scope| version_scope.eq_ignore_ascii_case(scope))
+        {
+            Some(version_scope) => format!(
+                "the scope differs in casing from the version scope `{}`",
+                version_scope
+            ),
+            None => "the scope is not configured in `version_scopes`".to_owned(),
+        };
+        scope_issues.push(ScopeIssue {
+            scope: scope.clone(),
+            tag_names: tags.iter().map(|(name, _)| name.to_string()).collect(),
+            message,
+        });
+    }
+
+    let suggested_tag_pattern = match !tag_names.is_empty()
+        && unparsed_tags.len() as f64 / tag_names.len() as f64 > SUGGESTION_UNPARSED_RATIO
+    {
+        true => suggest_tag_pattern(&tag_names, tag_names.len() - unparsed_tags.len())?,
+        false => None,
+    };
+
+    Ok(TagAudit {
+        tag_count: tag_names.len(),
+        unparsed_tags,
+        duplicated_versions,
+        version_gaps,
+        scope_issues,
+        suggested_tag_pattern,
+    })
+}
+
+/// Extracts the tag details from a tag name. Contrary to `parsing::parse_tag_details`, tag names matching the tag
+/// pattern without a valid semantic version are returned as `None` instead of failing.
+///
+/// # Arguments
+///
+/// * `tag_name` - Tag name that will be parsed.
+///
+/// * `tag_pattern` - Pattern that will be used to parse the tag.
+///
+/// * `scope_case` - Case applied to the parsed scope.
+///
+/// # Errors
+///
+/// Returns the regex errors of `parsing::parse_tag_details`.
+///
+fn parse_tag(
+    tag_name: &str,
+    tag_pattern: &str,
+    scope_case: ScopeCase,
+) -> Result<Option<TagDetails>, Error> {
+    match parse_tag_details(tag_name, tag_pattern, scope_case) {
+        Err(error) if error.kind == ErrorKind::Other => Ok(None),
+        result => result,
+    }
+}
+
+/// Returns the candidate tag pattern that parses the most tags, or `None` if no candidate parses more tags than the
+/// given number of parsed tags.
+///
+/// # Arguments
+///
+/// * `tag_names` - Names of the tags.
+///
+/// * `parsed_count` - Number of tags parsed by the configured tag pattern.
+///
+fn suggest_tag_pattern(tag_names: &[String], parsed_count: usize) -> Result<Option<String>, Error> {
+    let mut suggestion = None;
+    let mut best_count = parsed_count;
+    for pattern in CANDIDATE_TAG_PATTERNS {
+        let mut count = 0;
+        for name in tag_names {
+            if parse_tag(name, pattern, ScopeCase::Preserve)?.is_some() {
+                count += 1;
+            }
+        }
+        if count > best_count {
+            best_count = count;
+            suggestion = Some(pattern.to_owned());
+        }
+    }
+    Ok(suggestion)
+}
+
+/// Returns `true` if the given version is the next major, minor or patch version of the previous version.
+///
+/// # Arguments
+///
+/// * `previous` - Previous release version.
+///
+/// * `version` - Release version that follows `previous`.
+///
+fn is_sequential(previous: &Version, version: &Version) -> bool {
+    (version.major == previous.major + 1 && version.minor == 0 && version.patch == 0)
+        || (version.major == previous.major
+            && version.minor == previous.minor + 1
+            && version.patch == 0)
+        || (version.major == previous.major
+            && version.minor == previous.minor
+            && version.patch == previous.patch + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Audits the given tag names with the tag pattern `<scope>/v<version>`, where the `v` is optional, and the
+    /// version scopes `api` and `cli`.
+    fn audit_names(tag_names: &[&str]) -> TagAudit {
+        let mut config = Config::new();
+        config.tag_pattern = r"^((?<scope>[a-z]+)/)?v?(?<version>\d+\.\d+\.\d+)$".to_owned();
+        config.version_scopes = vec!["".to_owned(), "api".to_owned(), "cli".to_owned()];
+        let tag_names = tag_names.iter().map(|name| name.to_string()).collect();
+        audit_tag_names(tag_names, &config).unwrap()
+    }
+
+    #[test]
+    fn version_gaps_and_duplicates_are_reported() {
+        let audit = audit_names(&[
+            "v1.0.0",
+            "v1.0.1",
+            "1.0.1",
+            "v1.2.0",
+            "api/v0.1.0",
+            "api/v0.2.0",
+        ]);
+        assert_eq!(audit.tag_count, 6);
+        assert!(audit.unparsed_tags.is_empty());
+        assert_eq!(audit.duplicated_versions.len(), 1);
+        assert_eq!(audit.duplicated_versions[0].version, "1.0.1");
+        assert_eq!(
+            audit.duplicated_versions[0].tag_names,
+            vec!["v1.0.1", "1.0.1"]
+        );
+        assert_eq!(audit.version_gaps.len(), 1);
+        assert_eq!(audit.version_gaps[0].scope, "");
+        assert_eq!(audit.version_gaps[0].previous_version, "1.0.1");
+        assert_eq!(audit.version_gaps[0].version, "1.2.0");
+        assert!(audit.has_issues());
+    }
+
+    #[test]
+    fn scopes_that_are_not_configured_are_reported() {
+        let audit = audit_names(&["api/v1.0.0", "web/v1.0.0", "v1.0.0"]);
+        let scopes: Vec<(&str, &str)> = audit
+            .scope_issues
+            .iter()
+            .map(|issue| (issue.scope.as_str(), issue.message.as_str()))
+            .collect();
+        assert_eq!(
+            scopes,
+            vec![("web", "the scope is not configured in `version_scopes`")]
+        );
+    }
+
+    #[test]
+    fn tag_pattern_is_suggested_when_most_tags_do_not_parse() {
+        let audit = audit_names(&["api@1.0.0", "api@1.1.0", "cli@2.0.0", "v1.0.0"]);
+        assert_eq!(
+            audit.unparsed_tags,
+            vec!["api@1.0.0", "api@1.1.0", "cli@2.0.0"]
+        );
+        assert_eq!(
+            audit.suggested_tag_pattern.as_deref(),
+            Some(CANDIDATE_TAG_PATTERNS[2])
+        );
+
+        let audit = audit_names(&["v1.0.0", "v1.1.0", "release-1.2.0"]);
+        assert_eq!(audit.unparsed_tags, vec!["release-1.2.0"]);
+        assert!(audit.suggested_tag_pattern.is_none());
+    }
+}
@@ -0,0 +1,1087 @@
+//! This module provides the calculation of the version bumps, the core of Tag Track.
+//!
+//! The references of a source are scanned from the given commit to the closest tag of each scope, and the greatest
+//! increment kind triggered by the commits of each scope is applied to the version of its closest tag. Scopes without
+//! tags start from the initial version of the configuration. Tags and releases are never created by this module.
+//!
+
+use crate::cancel::CancellationToken;
+use crate::config::{Config, NonConformingCommitBump, VersionEpoch};
+use crate::error::{Error, ErrorKind};
+use crate::git::{Commit, Tag};
+use crate::logging;
+use crate::parsing::TagDetails;
+use crate::run;
+use crate::source::{Reference, SourceActions};
+use crate::suggestion::{suggest_commit_rewrite, CommitSuggestion};
+use crate::version::{
+    calculate_triggered_increment, increment_major, increment_minor, increment_patch,
+    increment_prerelease, increment_prerelease_version, max_increment, parse_user_version,
+    reset_epoch, snapshot_version, IncrementKind,
+};
+use semver::Version;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+/// Default maximum number of version scopes of a run, above which the tag pattern is considered to match unrelated
+/// tags.
+pub const DEFAULT_MAX_SCOPES: usize = 100;
+
+/// Old version reported for the scopes without tags.
+pub const NO_VERSION: &str = "none";
+
+/// Number of tags listed in the error returned when the maximum number of version scopes is exceeded.
+const SCOPE_SAMPLE_SIZE: usize = 5;
+
+/// Type for storing a commit that contributed to the version bump of a scope.
+#[derive(Serialize, Debug, Clone)]
+pub struct CommitProvenance {
+    /// Commit SHA.
+    pub sha: String,
+    /// Type of the commit, `None` if the commit does not match the commit pattern.
+    pub commit_type: Option<String>,
+    /// First line of the commit message.
+    pub subject: String,
+    /// Position in the bump rules of the scope, starting at 1, of the rule that triggered the increment of the commit.
+    /// `None` if the commit does not match the commit pattern and was counted as a patch bump.
+    pub rule: Option<usize>,
+    /// Kind of increment produced by the commit.
+    pub increment_kind: IncrementKind,
+}
+
+impl CommitProvenance {
+    /// Creates a new `CommitProvenance` instance for the given commit.
+    ///
+    /// # Arguments
+    ///
+    /// * `commit` - Commit that contributed to the version bump.
+    ///
+    /// * `rule` - Position of the rule that triggered the increment of the commit, if any.
+    ///
+    /// * `increment_kind` - Kind of increment produced by the commit.
+    ///
+    pub fn new(commit: &Commit, rule: Option<usize>, increment_kind: IncrementKind) -> Self {
+        Self {
+            sha: commit.sha.clone(),
+            commit_type: commit
+                .details
+                .as_ref()
+                .map(|details| details.commit_type.clone()),
+            subject: commit.message.lines().next().unwrap_or_default().to_owned(),
+            rule,
+            increment_kind,
+        }
+    }
+}
+
+/// Type that defines why the version of a scope was not bumped.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NoBumpReason {
+    /// There are no commits of the scope since its closest tag.
+    NoCommits,
+    /// The commits of the scope since its closest tag do not increment the version.
+    OnlyNonBumpingCommits,
+    /// The only commits since the closest tag of the scope do not match the commit pattern.
+    OnlySkippedCommits,
+}
+
+impl NoBumpReason {
+    /// Returns the description of the reason used in the text output.
+    pub fn description(&self) -> &str {
+        use NoBumpReason::*;
+
+        match *self {
+            NoCommits => "no new commits",
+            OnlyNonBumpingCommits => "only commits that do not bump the version",
+            OnlySkippedCommits => "only commits that do not match the commit pattern",
+        }
+    }
+}
+
+/// Type for storing the result of scanning the references of a source.
+pub struct ScannedReferences {
+    /// Greatest increment kind found for each scope.
+    pub version_bumps: HashMap<String, Option<IncrementKind>>,
+    /// Reason why the version is not bumped, for each scope without increment kind. As their scope is unknown,
+    /// skipped commits are attributed to every scope whose closest tag was not found before them.
+    pub no_bump_reasons: HashMap<String, NoBumpReason>,
+    /// Closest tags of each scope.
+    pub closest_tags: Vec<Tag>,
+    /// Authors of the scanned commits grouped by scope.
+    pub contributors: HashMap<String, BTreeSet<String>>,
+    /// Scanned commits that increment the version, grouped by scope.
+    pub commits: HashMap<String, Vec<Commit>>,
+    /// Scanned commits that contributed to the version bump, grouped by scope.
+    pub provenance: HashMap<String, Vec<CommitProvenance>>,
+    /// Commits that only update dependency lockfiles.
+    pub dependency_updates: Vec<DependencyUpdate>,
+    /// Commits that were skipped due to pattern mismatch.
+    pub skipped_commits: Vec<String>,
+    /// Commits excluded from the version bump by the ignore rules of the configuration.
+    pub ignored_commits: Vec<IgnoredCommit>,
+    /// Skipped commits counted as a patch bump, grouped by scope. Only filled if `non_conforming_commit_bump` is
+    /// `patch`.
+    pub non_conforming_commits: HashMap<String, Vec<String>>,
+    /// Scopes whose patch bump was forced by `patch_bump_commit_threshold`.
+    pub commit_threshold_scopes: HashSet<String>,
+    /// Rewrite suggestions for the skipped commits.
+    pub commit_suggestions: Vec<CommitSuggestion>,
+    /// If the scan stopped at the `stop_sha` of the scan options, so the commits from it were not scanned.
+    pub is_stopped: bool,
+}
+
+/// Type that holds the options used to scan the history of a commit. The default options scan the whole history of
+/// the commit and limit the number of version scopes to `DEFAULT_MAX_SCOPES`.
+#[derive(Debug)]
+pub struct ScanOptions {
+    /// SHA of the commit that starts the range of commits used to calculate the version bump. The commit and the
+    /// commits after it are only searched for the closest tags. If `None`, every commit is part of the range.
+    pub range_start: Option<String>,
+    /// Commit scanned before the history, such as the provisional commit of the uncommitted changes.
+    pub leading_commit: Option<Commit>,
+    /// SHA of the commit where the scan stops, excluding it. Used to only scan the commits added after the last
+    /// evaluated commit of a previous run.
+    pub stop_sha: Option<String>,
+    /// Maximum number of version scopes, `0` to disable the check.
+    pub max_scopes: usize,
+    /// Token used to interrupt the reading of the history.
+    pub cancel: CancellationToken,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            range_start: None,
+            leading_commit: None,
+            stop_sha: None,
+            max_scopes: DEFAULT_MAX_SCOPES,
+            cancel: CancellationToken::new(),
+        }
+    }
+}
+
+/// Type for storing commits that only update dependency lockfiles.
+#[derive(Serialize, Debug, Clone)]
+pub struct DependencyUpdate {
+    /// Commit SHA.
+    pub sha: String,
+    /// Commit message.
+    pub message: String,
+    /// Scope of the commit, empty if the commit does not match the commit pattern or has no scope.
+    pub scope: String,
+}
+
+/// Type that defines the ignore rule of the configuration that excluded a commit from the version bump.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IgnoreRule {
+    /// The commit message matches a pattern of `ignore_commit_patterns`.
+    CommitPattern,
+    /// The commit author is listed in `ignore_authors`.
+    Author,
+    /// The commit type is listed in `ignore_types`.
+    Type,
+}
+
+impl IgnoreRule {
+    /// Returns the description of the rule used in the text output, followed by the matched value.
+    pub fn description(&self) -> &str {
+        use IgnoreRule::*;
+
+        match *self {
+            CommitPattern => "its message matches the ignored pattern",
+            Author => "its author matches the ignored author",
+            Type => "its type is the ignored type",
+        }
+    }
+}
+
+/// Type for storing commits excluded from the version bump by the ignore rules of the configuration.
+#[derive(Serialize, Debug, Clone)]
+pub struct IgnoredCommit {
+    /// Commit SHA.
+    pub sha: String,
+    /// First line of the commit message.
+    pub subject: String,
+    /// Commit author in the format `name <email>`, if known.
+    pub author: Option<String>,
+    /// Ignore rule that matched the commit.
+    pub rule: IgnoreRule,
+    /// Pattern, author or type of the ignore rule that matched the commit.
+    pub matched: String,
+}
+
+impl IgnoredCommit {
+    /// Creates a new `IgnoredCommit` instance for the given commit.
+    ///
+    /// # Arguments
+    ///
+    /// * `commit` - Commit excluded from the version bump.
+    ///
+    /// * `rule` - Ignore rule that matched the commit.
+    ///
+    /// * `matched` - Pattern, author or type of the ignore rule that matched the commit.
+    ///
+    pub fn new(commit: &Commit, rule: IgnoreRule, matched: &str) -> Self {
+        Self {
+            sha: commit.sha.clone(),
+            subject: commit.message.lines().next().unwrap_or_default().to_owned(),
+            author: commit.author.clone(),
+            rule,
+            matched: matched.to_owned(),
+        }
+    }
+}
+
+/// Scans the given references, calculating the greatest increment kind of each scope and collecting the closest
+/// tags, contributors, dependency updates, skipped commits and ignored commits.
+///
+/// # Arguments
+///
+/// * `references` - References to scan, ordered from the newest commit to the oldest one.
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `max_scopes` - Maximum number of version scopes, `0` to disable the check.
+///
+/// # Errors
+///
+/// Returns the first error returned by the references.
+///
+/// Returns the errors of `check_scope_count` if the maximum number of version scopes is exceeded.
+///
+pub fn scan_references<I>(
+    references: I,
+    config: &Config,
+    max_scopes: usize,
+) -> Result<ScannedReferences, Error>
+where
+    I: Iterator<Item = Result<Reference, Error>>,
+{
+    let mut version_bumps: HashMap<String, Option<IncrementKind>> = HashMap::new();
+    for scope in &config.version_scopes {
+        version_bumps.insert(scope.clone(), None);
+    }
+
+    let mut contributors: HashMap<String, BTreeSet<String>> = HashMap::new();
+    let mut commits: HashMap<String, Vec<Commit>> = HashMap::new();
+    let mut provenance: HashMap<String, Vec<CommitProvenance>> = HashMap::new();
+    let mut scopes_with_commits: HashSet<String> = HashSet::new();
+    let mut scopes_with_skipped_commits: HashSet<String> = HashSet::new();
+    let mut non_conforming_commits: HashMap<String, Vec<String>> = HashMap::new();
+    let mut commit_counts: HashMap<String, usize> = HashMap::new();
+    let mut dependency_updates = vec![];
+    let mut skipped_commits = vec![];
+    let mut ignored_commits = vec![];
+    let mut commit_suggestions = vec![];
+    let mut closest_tags = vec![];
+    for r in references {
+        let r = r?;
+
+        if let Some(tags) = r.tags {
+            closest_tags.reserve(tags.len());
+            closest_tags.extend(tags);
+        }
+
+        if let Some(ignored_commit) = r.ignored_commit {
+            ignored_commits.push(ignored_commit);
+        }
+
+        if r.commit.is_none() {
+            continue;
+        }
+        let commit = r.commit.unwrap();
+
+        let is_dependency_update = match &config.dependency_updates {
+            Some(dependency_updates) => commit.is_dependency_update(&dependency_updates.lockfiles),
+            None => false,
+        };
+        if is_dependency_update {
+            dependency_updates.push(DependencyUpdate {
+                sha: commit.sha.clone(),
+                message: commit.message.clone(),
+                scope: commit
+                    .details
+                    .as_ref()
+                    .and_then(|details| details.scope.clone())
+                    .unwrap_or_default(),
+            });
+        }
+
+        let commit_details = match &commit.details {
+            Some(details) => details,
+            None => {
+                skipped_commits.push(commit.sha.clone());
+                for scope in &config.version_scopes {
+                    let is_tag_found = closest_tags.iter().any(|tag| {
+                        tag.details.as_ref().is_some_and(|details| {
+                            details.scope.as_deref().unwrap_or_default() == scope.as_str()
+                        })
+                    });
+                    if is_tag_found {
+                        continue;
+                    }
+                    scopes_with_skipped_commits.insert(scope.clone());
+                    *commit_counts.entry(scope.clone()).or_default() += 1;
+                    if config.non_conforming_commit_bump == NonConformingCommitBump::Patch {
+                        let prev_increment_kind = version_bumps.get(scope).copied().flatten();
+                        version_bumps.insert(
+                            scope.clone(),
+                            max_increment([prev_increment_kind, Some(IncrementKind::Patch)]),
+                        );
+                        non_conforming_commits
+                            .entry(scope.clone())
+                            .or_default()
+                            .push(commit.sha.clone());
+                        provenance
+                            .entry(scope.clone())
+                            .or_default()
+                            .push(CommitProvenance::new(&commit, None, IncrementKind::Patch));
+                    }
+                }
+                if let Some(suggestion) =
+                    suggest_commit_rewrite(&commit.sha, &commit.message, config)?
+                {
+                    commit_suggestions.push(suggestion);
+                }
+                continue;
+            }
+        };
+
+        let scope = commit_details.scope.clone().unwrap_or_default();
+        scopes_with_commits.insert(scope.clone());
+        *commit_counts.entry(scope.clone()).or_default() += 1;
+        if let Some(author) = &commit.author {
+            contributors
+                .entry(scope.clone())
+                .or_default()
+                .insert(author.clone());
+        }
+
+        if is_dependency_update
+            && config
+                .dependency_updates
+                .as_ref()
+                .is_some_and(|dependency_updates| !dependency_updates.bump)
+        {
+            continue;
+        }
+
+        let (increment_kind, rule) =
+            match calculate_triggered_increment(&commit, config.scope_bump_rules(&scope)) {
+                Some(increment) => increment,
+                None => continue,
+            };
+        provenance
+            .entry(scope.clone())
+            .or_default()
+            .push(CommitProvenance::new(&commit, Some(rule), increment_kind));
+
+        let prev_increment_kind = version_bumps.get(&scope).copied().flatten();
+        version_bumps.insert(
+            scope.clone(),
+            max_increment([prev_increment_kind, Some(increment_kind)]),
+        );
+        commits.entry(scope).or_default().push(commit);
+    }
+
+    check_scope_count(&closest_tags, &version_bumps, config, max_scopes)?;
+
+    let mut commit_threshold_scopes = HashSet::new();
+    if let Some(threshold) = config.patch_bump_commit_threshold {
+        for (scope, count) in commit_counts {
+            let increment_kind = version_bumps.entry(scope.clone()).or_default();
+            if count > threshold && increment_kind.is_none() {
+                *increment_kind = Some(IncrementKind::Patch);
+                commit_threshold_scopes.insert(scope);
+            }
+        }
+    }
+
+    let no_bump_reasons = version_bumps
+        .iter()
+        .filter(|(_, increment_kind)| increment_kind.is_none())
+        .map(|(scope, _)| {
+            let reason = if scopes_with_commits.contains(scope) {
+                NoBumpReason::OnlyNonBumpingCommits
+            } else if scopes_with_skipped_commits.contains(scope) {
+                NoBumpReason::OnlySkippedCommits
+            } else {
+                NoBumpReason::NoCommits
+            };
+            (scope.clone(), reason)
+        })
+        .collect();
+
+    Ok(ScannedReferences {
+        version_bumps,
+        no_bump_reasons,
+        closest_tags,
+        contributors,
+        commits,
+        provenance,
+        dependency_updates,
+        skipped_commits,
+        ignored_commits,
+        non_conforming_commits,
+        commit_threshold_scopes,
+        commit_suggestions,
+        is_stopped: false,
+    })
+}
+
+/// Checks that the number of version scopes found in the closest tags and the commits does not exceed the maximum.
+/// An abnormal number of scopes usually means that the tag pattern matches unrelated tags, which would create a tag
+/// for each bogus scope.
+///
+/// # Arguments
+///
+/// * `closest_tags` - Closest tags of each scope.
+///
+/// * `version_bumps` - Increment kind of each scope found in the commits.
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `max_scopes` - Maximum number of version scopes, `0` to disable the check.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::TooManyScopes` if the maximum is exceeded, listing a
+/// sample of the tags of scopes that are not declared in `version_scopes`.
+///
+fn check_scope_count(
+    closest_tags: &[Tag],
+    version_bumps: &HashMap<String, Option<IncrementKind>>,
+    config: &Config,
+    max_scopes: usize,
+) -> Result<(), Error> {
+    let mut tag_scopes: BTreeMap<&str, &str> = BTreeMap::new();
+    for tag in closest_tags {
+        let scope = tag
+            .details
+            .as_ref()
+            .and_then(|details| details.scope.as_deref())
+            .unwrap_or_default();
+        tag_scopes.entry(scope).or_insert(&tag.name);
+    }
+    let scope_count = version_bumps
+        .keys()
+        .map(String::as_str)
+        .chain(tag_scopes.keys().copied())
+        .collect::<HashSet<&str>>()
+        .len();
+    if max_scopes == 0 || scope_count <= max_scopes {
+        return Ok(());
+    }
+
+    let sample_tags: Vec<String> = tag_scopes
+        .iter()
+        .filter(|(scope, _)| {
+            !config
+                .version_scopes
+                .iter()
+                .any(|version_scope| version_scope == *scope)
+        })
+        .take(SCOPE_SAMPLE_SIZE)
+        .map(|(scope, name)| format!("'{}' (scope '{}')", name, scope))
+        .collect();
+    let sample = match sample_tags.is_empty() {
+        true => String::new(),
+        false => format!(", sample tags: {}", sample_tags.join(", ")),
+    };
+    Err(Error::new(
+        ErrorKind::TooManyScopes,
+        Some(&format!(
+            "found {} version scopes, more than the maximum of {}{}. The `tag_pattern` may match unrelated tags, \
+             tighten it so it only matches release tags, or raise `--max-scopes` if the scopes are expected",
+            scope_count, max_scopes, sample
+        )),
+    ))
+}
+
+/// Adds a closest tag with the initial version of the configuration for each version scope without a closest tag, so
+/// the version bump of these scopes is calculated from the whole history. The added tags have no name nor commit.
+///
+/// Returns the scopes of the added tags.
+///
+/// # Arguments
+///
+/// * `closest_tags` - Closest tags of each scope.
+///
+/// * `config` - Tag Track configuration, used to read the version scopes and the initial version.
+///
+/// # Errors
+///
+/// Returns the errors of `version::parse_user_version` if the initial version is not a valid semantic version.
+///
+pub fn add_initial_tags(
+    closest_tags: &mut Vec<Tag>,
+    config: &Config,
+) -> Result<HashSet<String>, Error> {
+    let mut initial_scopes = HashSet::new();
+    for scope in &config.version_scopes {
+        let is_tag_found = closest_tags.iter().any(|tag| {
+            tag.details
+                .as_ref()
+                .is_some_and(|details| details.scope.as_deref().unwrap_or_default() == scope)
+        });
+        if is_tag_found {
+            continue;
+        }
+
+        let version = parse_user_version(&config.initial_version, "initial_version")?;
+        closest_tags.push(Tag {
+            commit_sha: String::new(),
+            name: String::new(),
+            details: Some(TagDetails {
+                version,
+                scope: match scope.is_empty() {
+                    true => None,
+                    false => Some(scope.clone()),
+                },
+            }),
+        });
+        initial_scopes.insert(scope.clone());
+    }
+    Ok(initial_scopes)
+}
+
+/// Increments the given version with the given increment kind. Major, minor and patch increments of pre-release
+/// versions follow the pre-release strategy of the configuration. If the configuration uses the year as version
+/// epoch, the first increment of a year resets the version to `<year>.0.0`, and major increments are applied as
+/// minor increments within a year. Versions ahead of the current year, such as the ones created by a run with a skewed
+/// clock, are never reset back to the current year, so the new version always sorts after the previous one.
+///
+/// # Arguments
+///
+/// * `version` - Version to increment.
+///
+/// * `increment_kind` - Kind of increment to apply.
+///
+/// * `config` - Tag Track configuration, used to read the prerelease identifier and strategy and the version epoch.
+///
+/// # Errors
+///
+/// Returns the errors of `version::increment_prerelease` for prerelease increments.
+///
+pub fn increment_version(
+    version: &mut Version,
+    increment_kind: IncrementKind,
+    config: &Config,
+) -> Result<(), Error> {
+    let mut increment_kind = increment_kind;
+    if let Some(VersionEpoch::Year) = config.version_epoch {
+        let year = run::year(run::current().started_at);
+        if version.major > year {
+            logging::warn(&format!(
+                "the version {} is ahead of the current year {}, it is incremented within its year so it keeps \
+                 sorting after the previous releases",
+                version, year
+            ));
+        }
+        if year > version.major {
+            reset_epoch(version, year);
+            if increment_kind == IncrementKind::Prerelease {
+                increment_prerelease(version, &config.prerelease_identifier)?;
+                version.patch = 0;
+            }
+            return Ok(());
+        }
+        if increment_kind == IncrementKind::Major {
+            increment_kind = IncrementKind::Minor;
+        }
+    }
+
+    if !version.pre.is_empty() && increment_kind != IncrementKind::Prerelease {
+        return increment_prerelease_version(
+            version,
+            increment_kind,
+            config.prerelease_strategy,
+            &config.prerelease_identifier,
+        );
+    }
+
+    match increment_kind {
+        IncrementKind::Major => increment_major(version),
+        IncrementKind::Minor => increment_minor(version),
+        IncrementKind::Patch => increment_patch(version),
+        IncrementKind::Prerelease => increment_prerelease(version, &config.prerelease_identifier)?,
+    }
+    Ok(())
+}
+
+/// Type that represents the version bump of a scope.
+#[derive(Debug)]
+pub struct ScopeBump {
+    /// Scope of the version, empty for the empty scope.
+    pub scope: String,
+    /// Closest tag of the scope, `None` if the scope has no tag and starts from the initial version of the
+    /// configuration.
+    pub closest_tag: Option<Tag>,
+    /// Version of the closest tag of the scope, `None` if the scope has no tag.
+    pub old_version: Option<Version>,
+    /// Version after the bump, `None` if the version of the scope does not need to be bumped.
+    pub new_version: Option<Version>,
+    /// Greatest increment kind triggered by the commits of the scope, `None` if the version is not bumped.
+    pub increment_kind: Option<IncrementKind>,
+    /// Reason why the version is not bumped, `None` if the version is bumped.
+    pub reason: Option<NoBumpReason>,
+    /// Commits that do not match the commit pattern and were counted as a patch bump of the scope.
+    pub non_conforming_commits: Vec<String>,
+    /// If a patch bump was forced because the number of commits since the closest tag exceeds
+    /// `patch_bump_commit_threshold`.
+    pub commit_threshold_reached: bool,
+    /// Commits of the scope that increment the version.
+    pub commits: Vec<Commit>,
+    /// Authors of the commits of the scope.
+    pub contributors: BTreeSet<String>,
+    /// Commits that contributed to the version bump of the scope.
+    pub provenance: Vec<CommitProvenance>,
+}
+
+impl ScopeBump {
+    /// Returns the name of the tag of the new version of the scope, `None` if the version does not need to be bumped.
+    /// The name is rendered from `new_tag_name` if set, otherwise the version of the closest tag is replaced in its
+    /// name. Scopes without a closest tag render the name from the tag pattern of the scope.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Tag Track configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `Config::render_new_tag_name` and `Config::render_scope_tag_name`.
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::InvalidInitialTagName` if the scope has no closest tag
+    /// and the name can not be rendered from its tag pattern.
+    ///
+    pub fn new_tag_name(&self, config: &Config) -> Result<Option<String>, Error> {
+        let new_version = match &self.new_version {
+            Some(new_version) => new_version,
+            None => return Ok(None),
+        };
+        if let Some(tag_name) = config.render_new_tag_name(&self.scope, new_version)? {
+            return Ok(Some(tag_name));
+        }
+
+        if let (Some(tag), Some(old_version)) = (&self.closest_tag, &self.old_version) {
+            return Ok(Some(
+                tag.name
+                    .replace(&old_version.to_string(), &new_version.to_string()),
+            ));
+        }
+
+        match config.render_scope_tag_name(&self.scope, new_version, None)? {
+            Some(tag_name) => Ok(Some(tag_name)),
+            None => Err(Error::new(
+                ErrorKind::InvalidInitialTagName,
+                Some(&format!(
+                    "create the first tag of the scope '{}' manually, set `new_tag_name` or use a tag pattern \
+                     without regex syntax outside of its capturing groups",
+                    self.scope
+                )),
+            )),
+        }
+    }
+
+    /// Returns the snapshot version of the scope, built from the next version of the scope with the date of the run and
+    /// the given commit. Scopes whose version is not bumped use a patch increment as next version, and scopes without a
+    /// closest tag use the initial version of the configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Tag Track configuration.
+    ///
+    /// * `commit_sha` - SHA of the commit of the snapshot.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `increment_version`, `version::parse_user_version` and `version::snapshot_version`.
+    ///
+    pub fn snapshot_version(&self, config: &Config, commit_sha: &str) -> Result<Version, Error> {
+        let (current_version, next_version) = match &self.old_version {
+            Some(old_version) => {
+                let mut next_version = old_version.clone();
+                increment_version(
+                    &mut next_version,
+                    self.increment_kind.unwrap_or(IncrementKind::Patch),
+                    config,
+                )?;
+                (old_version.clone(), next_version)
+            }
+            None => {
+                let version = parse_user_version(&config.initial_version, "initial_version")?;
+                (version.clone(), version)
+            }
+        };
+        snapshot_version(
+            &current_version,
+            &next_version,
+            &run::format_date(run::current().started_at),
+            commit_sha,
+        )
+    }
+}
+
+/// Type that represents the version bumps calculated for a commit.
+#[derive(Debug)]
+pub struct BumpResult {
+    /// SHA of the commit whose version bumps were calculated.
+    pub commit_sha: String,
+    /// Version bump of each scope, ordered by scope.
+    pub version_bumps: Vec<ScopeBump>,
+    /// Commits that only update dependency lockfiles.
+    pub dependency_updates: Vec<DependencyUpdate>,
+    /// Commits that were skipped due to pattern mismatch.
+    pub skipped_commits: Vec<String>,
+    /// Commits excluded from the version bump by the ignore rules of the configuration.
+    pub ignored_commits: Vec<IgnoredCommit>,
+    /// Rewrite suggestions for the skipped commits.
+    pub commit_suggestions: Vec<CommitSuggestion>,
+}
+
+impl BumpResult {
+    /// Returns `true` if the version of any scope needs to be bumped.
+    pub fn is_bump_required(&self) -> bool {
+        self.version_bumps
+            .iter()
+            .any(|version_bump| version_bump.new_version.is_some())
+    }
+}
+
+/// Type that represents the version bump of a scope as reported by the JSON output of the CLI and by the bindings.
+#[derive(Serialize, Debug, Clone)]
+pub struct OutputVersionBump {
+    /// Scope of the version.
+    pub scope: String,
+    /// Old version number before bump.
+    pub old_version: String,
+    /// New version number after bump.
+    pub new_version: String,
+    /// Kind of increment that was applied.
+    pub increment_kind: Option<IncrementKind>,
+    /// Reason why the version was not bumped, if it was not.
+    pub reason: Option<NoBumpReason>,
+    /// Commits that do not match the commit pattern and were counted as a patch bump of the scope.
+    pub non_conforming_commits: Vec<String>,
+    /// If a patch bump was forced because the number of commits since the closest tag exceeds
+    /// `patch_bump_commit_threshold`.
+    pub commit_threshold_reached: bool,
+    /// Commits that contributed to the version bump, in history order from the newest commit.
+    pub provenance: Vec<CommitProvenance>,
+}
+
+impl OutputVersionBump {
+    /// Creates a new `OutputVersionBump` from the given version bump of a scope. Scopes without a closest tag use
+    /// `NO_VERSION` as old version, and versions that are not bumped keep the old version as new version.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope_bump` - Version bump of the scope.
+    ///
+    pub fn new(scope_bump: &ScopeBump) -> Self {
+        let old_version = match &scope_bump.old_version {
+            Some(old_version) => old_version.to_string(),
+            None => NO_VERSION.to_owned(),
+        };
+        Self {
+            scope: scope_bump.scope.clone(),
+            new_version: match &scope_bump.new_version {
+                Some(new_version) => new_version.to_string(),
+                None => old_version.clone(),
+            },
+            old_version,
+            increment_kind: scope_bump.increment_kind,
+            reason: scope_bump.reason,
+            non_conforming_commits: scope_bump.non_conforming_commits.clone(),
+            commit_threshold_reached: scope_bump.commit_threshold_reached,
+            provenance: scope_bump.provenance.clone(),
+        }
+    }
+}
+
+/// Scans the history of the given commit from the given source, following the given scan options.
+///
+/// # Arguments
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `source` - Source used to read the history of the commit.
+///
+/// * `sha` - SHA of the commit whose history is scanned.
+///
+/// * `options` - Options of the scan.
+///
+/// # Errors
+///
+/// Returns the errors of the source if the history can not be read.
+///
+/// Returns the errors of `scan_references`.
+///
+pub fn scan<'a, S: SourceActions<'a>>(
+    config: &Config,
+    source: &S,
+    sha: &'a str,
+    options: ScanOptions,
+) -> Result<ScannedReferences, Error> {
+    let references =
+        source.get_ref_iterator(sha, options.range_start.as_deref(), &options.cancel)?;
+    let leading_reference = options.leading_commit.map(|commit| Reference {
+        commit: Some(commit),
+        tags: None,
+        ignored_commit: None,
+    });
+
+    let stop_sha = options.stop_sha.as_deref();
+    let mut is_stopped = false;
+    let references = leading_reference
+        .into_iter()
+        .map(Ok)
+        .chain(references)
+        .take_while(|reference| {
+            let is_stop_commit = match (reference, stop_sha) {
+                (
+                    Ok(Reference {
+                        commit: Some(commit),
+                        ..
+                    }),
+                    Some(sha),
+                ) => commit.sha == sha,
+                _ => false,
+            };
+            is_stopped |= is_stop_commit;
+            !is_stop_commit
+        });
+    let mut scanned_references = scan_references(references, config, options.max_scopes)?;
+    scanned_references.is_stopped = is_stopped;
+    Ok(scanned_references)
+}
+
+/// Calculates the version bump of each scope from the references scanned in the history of the given commit. Scopes
+/// of the configuration without a closest tag start from the initial version of the configuration.
+///
+/// # Arguments
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `sha` - SHA of the commit whose history was scanned.
+///
+/// * `scanned_references` - References scanned in the history of the commit.
+///
+/// # Errors
+///
+/// Returns the errors of `add_initial_tags` and `increment_version`.
+///
+pub fn calculate_bumps(
+    config: &Config,
+    sha: &str,
+    scanned_references: ScannedReferences,
+) -> Result<BumpResult, Error> {
+    let ScannedReferences {
+        version_bumps,
+        no_bump_reasons,
+        mut closest_tags,
+        mut contributors,
+        mut commits,
+        mut provenance,
+        dependency_updates,
+        skipped_commits,
+        ignored_commits,
+        mut non_conforming_commits,
+        commit_threshold_scopes,
+        commit_suggestions,
+        ..
+    } = scanned_references;
+    let initial_scopes = add_initial_tags(&mut closest_tags, config)?;
+
+    let mut scope_bumps = vec![];
+    for tag in closest_tags {
+        let (scope, version) = match &tag.details {
+            Some(details) => (
+                details.scope.clone().unwrap_or_default(),
+                details.version.clone(),
+            ),
+            None => continue,
+        };
+        let is_initial = initial_scopes.contains(&scope);
+        let increment_kind = version_bumps.get(&scope).copied().flatten();
+        let new_version = match (increment_kind, is_initial) {
+            (None, _) => None,
+            (Some(_), true) => Some(version.clone()),
+            (Some(increment_kind), false) => {
+                let mut new_version = version.clone();
+                increment_version(&mut new_version, increment_kind, config)?;
+                Some(new_version)
+            }
+        };
+
+        scope_bumps.push(ScopeBump {
+            closest_tag: match is_initial {
+                true => None,
+                false => Some(tag),
+            },
+            old_version: match is_initial {
+                true => None,
+                false => Some(version),
+            },
+            new_version,
+            increment_kind,
+            reason: match increment_kind {
+                Some(_) => None,
+                None => no_bump_reasons.get(&scope).copied(),
+            },
+            non_conforming_commits: non_conforming_commits.remove(&scope).unwrap_or_default(),
+            commit_threshold_reached: commit_threshold_scopes.contains(&scope),
+            commits: commits.remove(&scope).unwrap_or_default(),
+            contributors: contributors.remove(&scope).unwrap_or_default(),
+            provenance: provenance.remove(&scope).unwrap_or_default(),
+            scope,
+        });
+    }
+    scope_bumps.sort_by(|a, b| a.scope.cmp(&b.scope));
+
+    Ok(BumpResult {
+        commit_sha: sha.to_owned(),
+        version_bumps: scope_bumps,
+        dependency_updates,
+        skipped_commits,
+        ignored_commits,
+        commit_suggestions,
+    })
+}
+
+/// Calculates the version bump of each scope at the given commit, reading its history from the given source. This is
+/// the calculation done by the CLI before creating tags, without the state file, the `--since`/`--until` windows and
+/// the release branches, which are specific to the CLI. The number of version scopes is limited to
+/// `DEFAULT_MAX_SCOPES`.
+///
+/// # Arguments
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `source` - Source used to read the history of the commit, any of the sources of `source::SourceKind` or a
+///   custom source implementing `SourceActions`.
+///
+/// * `sha` - SHA of the commit whose version bumps are calculated.
+///
+/// # Errors
+///
+/// Returns the errors of `scan` and `calculate_bumps`.
+///
+pub fn calculate<'a, S: SourceActions<'a>>(
+    config: &Config,
+    source: &S,
+    sha: &'a str,
+) -> Result<BumpResult, Error> {
+    let scanned_references = scan(config, source, sha, ScanOptions::default())?;
+    calculate_bumps(config, sha, scanned_references)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Applies the given increment to the given version with the year as version epoch.
+    fn increment_in_year_epoch(version: &str, increment_kind: IncrementKind) -> String {
+        let mut config = Config::new();
+        config.version_epoch = Some(VersionEpoch::Year);
+        config.prerelease_identifier = "rc".to_owned();
+        let mut version = Version::parse(version).unwrap();
+        increment_version(&mut version, increment_kind, &config).unwrap();
+        version.to_string()
+    }
+
+    #[test]
+    fn first_increment_of_a_year_resets_the_version() {
+        let year = run::year(run::current().started_at);
+        let last_year = format!("{}.4.1", year - 1);
+        assert_eq!(
+            increment_in_year_epoch(&last_year, IncrementKind::Patch),
+            format!("{}.0.0", year)
+        );
+        assert_eq!(
+            increment_in_year_epoch(&last_year, IncrementKind::Major),
+            format!("{}.0.0", year)
+        );
+        assert_eq!(
+            increment_in_year_epoch(&last_year, IncrementKind::Prerelease),
+            format!("{}.0.0-rc.1", year)
+        );
+    }
+
+    #[test]
+    fn increments_within_a_year_keep_the_year() {
+        let year = run::year(run::current().started_at);
+        let version = format!("{}.4.1", year);
+        assert_eq!(
+            increment_in_year_epoch(&version, IncrementKind::Major),
+            format!("{}.5.0", year)
+        );
+        assert_eq!(
+            increment_in_year_epoch(&version, IncrementKind::Patch),
+            format!("{}.4.2", year)
+        );
+
+        let ahead = format!("{}.2.0", year + 1);
+        assert_eq!(
+            increment_in_year_epoch(&ahead, IncrementKind::Minor),
+            format!("{}.3.0", year + 1)
+        );
+    }
+
+    /// Returns the version bump of the given scope from the version of the given closest tag to `1.3.0`.
+    fn bump_from_tag(scope: &str, closest_tag: &str, old_version: &str) -> ScopeBump {
+        ScopeBump {
+            scope: scope.to_owned(),
+            closest_tag: Some(Tag {
+                commit_sha: String::new(),
+                name: closest_tag.to_owned(),
+                details: None,
+            }),
+            old_version: Some(Version::parse(old_version).unwrap()),
+            new_version: Some(Version::new(1, 3, 0)),
+            increment_kind: Some(IncrementKind::Minor),
+            reason: None,
+            non_conforming_commits: vec![],
+            commit_threshold_reached: false,
+            commits: vec![],
+            contributors: BTreeSet::new(),
+            provenance: vec![],
+        }
+    }
+
+    #[test]
+    fn new_tag_name_template_replaces_the_closest_tag_name() {
+        let mut config = Config::new();
+        config.tag_pattern = r"^(?<scope>[a-z]+)[/@]v?(?<version>.*)$".to_owned();
+        let scope_bump = bump_from_tag("api", "api/v1.2.0", "1.2.0");
+        assert_eq!(
+            scope_bump.new_tag_name(&config).unwrap(),
+            Some("api/v1.3.0".to_owned())
+        );
+
+        config.new_tag_name = Some("{scope}@{version}".to_owned());
+        assert_eq!(
+            scope_bump.new_tag_name(&config).unwrap(),
+            Some("api@1.3.0".to_owned())
+        );
+    }
+
+    #[test]
+    fn new_tag_names_not_parsed_back_are_rejected() {
+        let mut config = Config::new();
+        config.tag_pattern = r"^(?<scope>[a-z]+)/v(?<version>.*)$".to_owned();
+        config.new_tag_name = Some("{scope}-{version}".to_owned());
+        let error = bump_from_tag("api", "api/v1.2.0", "1.2.0")
+            .new_tag_name(&config)
+            .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Other);
+
+        config.new_tag_name = Some("web/v{version}".to_owned());
+        let error = bump_from_tag("api", "api/v1.2.0", "1.2.0")
+            .new_tag_name(&config)
+            .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::Other);
+    }
+}
@@ -0,0 +1,77 @@
+//! This module provides the cancellation token used to interrupt long running source operations cooperatively.
+//!
+//! The token is given to the source operations, which check it between the processed commits and pages and stop
+//! with an `error::ErrorKind::Cancelled` error reporting their progress when it is cancelled. When the `http` cargo
+//! feature is enabled, the token of the run is cancelled on the first interrupt signal and the process exits on the
+//! second one.
+//!
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// Exit code used when the process is interrupted twice, following the `128 + SIGINT` convention.
+#[cfg(feature = "http")]
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Type that represents a cancellation token. Clones of the token share the same state, so cancelling a clone
+/// cancels all of them.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    /// If the token has been cancelled.
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// Returns a new token that has not been cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the token and all its clones.
+    #[cfg_attr(not(feature = "http"), allow(dead_code))]
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns `true` if the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Cancels the given token when the process receives the first interrupt signal, so the running source operation can
+/// stop and report its progress, and exits the process on the second interrupt signal. The signals are listened on a
+/// background thread. Does nothing if the `http` cargo feature is disabled.
+///
+/// # Arguments
+///
+/// * `cancel` - Token cancelled on the first interrupt signal.
+///
+pub fn cancel_on_interrupt(cancel: &CancellationToken) {
+    #[cfg(feature = "http")]
+    {
+        let cancel = cancel.clone();
+        std::thread::spawn(move || {
+            let runtime = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(runtime) => runtime,
+                Err(_) => return,
+            };
+            runtime.block_on(async {
+                if tokio::signal::ctrl_c().await.is_err() {
+                    return;
+                }
+                cancel.cancel();
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    std::process::exit(INTERRUPTED_EXIT_CODE);
+                }
+            });
+        });
+    }
+    #[cfg(not(feature = "http"))]
+    let _ = cancel;
+}
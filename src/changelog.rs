@@ -0,0 +1,361 @@
+//! This module provides the changelog verification. The release notes of the latest release of each scope are
+//! derived from the commits between its tag and the previous tag of the same scope, and compared with the section of
+//! the committed changelog file, so hand edits of released sections are detected.
+//!
+//! It also provides the rendering of release notes with the built-in format or with the user provided template of
+//! `release_notes_template`.
+//!
+
+use crate::config::Config;
+use crate::error::{Error, ErrorKind};
+use crate::git::{Commit, Tag};
+use crate::source::git::{get_all_tags, get_commits_in_range};
+use crate::template::Template;
+use crate::version::{calculate_increment, IncrementKind};
+use serde::Serialize;
+use std::{collections::BTreeSet, fs, path::Path};
+
+/// Default path of the changelog file.
+pub const DEFAULT_CHANGELOG_FILE: &str = "CHANGELOG.md";
+
+/// Length of the abbreviated commit SHAs included in the release notes and run tag names.
+pub const SHORT_SHA_LENGTH: usize = 7;
+
+/// Titles of the release notes groups, in the order they are rendered.
+const GROUPS: [(IncrementKind, &str); 4] = [
+    (IncrementKind::Major, "Breaking changes"),
+    (IncrementKind::Minor, "Features"),
+    (IncrementKind::Patch, "Bug fixes"),
+    (IncrementKind::Prerelease, "Pre-release changes"),
+];
+
+/// Type that represents the verification of the changelog section of a release.
+#[derive(Debug, Serialize)]
+pub struct ChangelogSection {
+    /// Scope of the release.
+    pub scope: String,
+    /// Name of the tag of the release.
+    pub tag_name: String,
+    /// Name of the tag of the previous release of the same scope, if any.
+    pub previous_tag_name: Option<String>,
+    /// Release notes derived from the released commits.
+    pub expected: String,
+    /// If the changelog section matches the derived release notes.
+    pub matches: bool,
+}
+
+/// Type that holds the data of a release available to the release notes templates.
+#[derive(Debug, Serialize)]
+pub struct ReleaseNotesData {
+    /// Scope of the release.
+    pub scope: String,
+    /// Name of the tag of the release.
+    pub tag_name: String,
+    /// Version of the closest tag of the scope, `none` if the scope had no tag.
+    pub old_version: String,
+    /// Version of the release.
+    pub new_version: String,
+    /// Commits of the release, from the newest to the oldest one.
+    pub commits: Vec<ReleaseNotesCommit>,
+    /// Authors of the commits of the release, in the format `name <email>`, sorted and without duplicates.
+    pub authors: Vec<String>,
+}
+
+/// Type that holds the data of a commit available to the release notes templates.
+#[derive(Debug, Serialize)]
+pub struct ReleaseNotesCommit {
+    /// Commit SHA.
+    pub sha: String,
+    /// Abbreviated commit SHA.
+    pub short_sha: String,
+    /// Type of the commit, `None` if the commit does not match the commit pattern.
+    pub commit_type: Option<String>,
+    /// Scope of the commit.
+    pub scope: Option<String>,
+    /// If the commit includes a breaking change.
+    pub breaking: bool,
+    /// First line of the description of the commit, or of the message if the commit does not match the commit
+    /// pattern.
+    pub subject: String,
+    /// Commit message.
+    pub message: String,
+    /// Commit author in the format `name <email>`, if known.
+    pub author: Option<String>,
+    /// Increment kind triggered by the commit, `None` if it does not trigger a bump.
+    pub increment_kind: Option<IncrementKind>,
+}
+
+impl ReleaseNotesData {
+    /// Returns the data of a release available to the release notes templates.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_name` - Name of the tag of the release.
+    ///
+    /// * `scope` - Scope of the release.
+    ///
+    /// * `old_version` - Version of the closest tag of the scope.
+    ///
+    /// * `new_version` - Version of the release.
+    ///
+    /// * `commits` - Commits of the release, from the newest to the oldest one.
+    ///
+    /// * `config` - Tag Track configuration.
+    ///
+    pub fn new(
+        tag_name: &str,
+        scope: &str,
+        old_version: &str,
+        new_version: &str,
+        commits: &[Commit],
+        config: &Config,
+    ) -> Self {
+        let authors: BTreeSet<String> = commits
+            .iter()
+            .filter_map(|commit| commit.author.clone())
+            .collect();
+
+        Self {
+            scope: scope.to_owned(),
+            tag_name: tag_name.to_owned(),
+            old_version: old_version.to_owned(),
+            new_version: new_version.to_owned(),
+            commits: commits
+                .iter()
+                .map(|commit| {
+                    let subject = match &commit.details {
+                        Some(details) => details.description.trim(),
+                        None => commit.message.trim(),
+                    };
+                    ReleaseNotesCommit {
+                        sha: commit.sha.clone(),
+                        short_sha: commit
+                            .sha
+                            .get(..SHORT_SHA_LENGTH)
+                            .unwrap_or(&commit.sha)
+                            .to_owned(),
+                        commit_type: commit
+                            .details
+                            .as_ref()
+                            .map(|details| details.commit_type.clone()),
+                        scope: commit
+                            .details
+                            .as_ref()
+                            .and_then(|details| details.scope.clone()),
+                        breaking: commit
+                            .details
+                            .as_ref()
+                            .is_some_and(|details| details.breaking),
+                        subject: subject.lines().next().unwrap_or_default().to_owned(),
+                        message: commit.message.clone(),
+                        author: commit.author.clone(),
+                        increment_kind: calculate_increment(commit, config.scope_bump_rules(scope)),
+                    }
+                })
+                .collect(),
+            authors: authors.into_iter().collect(),
+        }
+    }
+}
+
+/// Derives the release notes of the latest release of each scope from the local git repository and compares them with
+/// the sections of the given changelog. Scopes without tags are skipped.
+///
+/// # Arguments
+///
+/// * `changelog` - Contents of the changelog file.
+///
+/// * `config` - Tag Track configuration.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::MissingGitTags` if there are no tags in the repository.
+///
+/// Returns the errors of `source::git::get_all_tags` and `source::git::get_commits_in_range`.
+///
+pub fn verify_changelog(changelog: &str, config: &Config) -> Result<Vec<ChangelogSection>, Error> {
+    let mut tags = match get_all_tags(&config.tag_pattern, config.scope_rules.case)? {
+        Some(tags) => tags,
+        None => {
+            return Err(Error::new(
+                ErrorKind::MissingGitTags,
+                Some("no tags found for repository"),
+            ))
+        }
+    };
+
+    config.resolve_scope_tags(&mut tags)?;
+
+    let mut sections = vec![];
+    for scope in &config.version_scopes {
+        let mut scope_tags: Vec<&Tag> = tags
+            .iter()
+            .filter(|tag| {
+                tag.details.as_ref().is_some_and(|details| {
+                    details.scope.as_deref().unwrap_or_default() == scope.as_str()
+                })
+            })
+            .collect();
+        scope_tags.sort_by(|a, b| {
+            let a = &a.details.as_ref().unwrap().version;
+            let b = &b.details.as_ref().unwrap().version;
+            b.cmp(a)
+        });
+
+        let latest = match scope_tags.first() {
+            Some(tag) => tag,
+            None => continue,
+        };
+        let previous = scope_tags.get(1);
+
+        let mut commits = get_commits_in_range(
+            previous.map(|tag| tag.commit_sha.as_str()),
+            &latest.commit_sha,
+            &config.commit_pattern,
+            config.scope_rules.case,
+            config.max_commit_message_length,
+        )?;
+        for commit in &mut commits {
+            config.resolve_scope_commit(commit)?;
+        }
+        let expected = render_release_notes(&latest.name, scope, &commits, config);
+        let matches = find_release_section(changelog, &latest.name)
+            .is_some_and(|section| normalize(&section) == normalize(&expected));
+
+        sections.push(ChangelogSection {
+            scope: scope.clone(),
+            tag_name: latest.name.clone(),
+            previous_tag_name: previous.map(|tag| tag.name.clone()),
+            expected,
+            matches,
+        });
+    }
+
+    Ok(sections)
+}
+
+/// Renders the release notes of a release. Commits are grouped by the increment kind calculated with the bump rules
+/// of the configuration, and commits of other scopes or without increment are left out.
+///
+/// # Arguments
+///
+/// * `tag_name` - Name of the tag of the release, used as the section heading.
+///
+/// * `scope` - Scope of the release.
+///
+/// * `commits` - Commits of the release, from the newest to the oldest one.
+///
+/// * `config` - Tag Track configuration.
+///
+pub fn render_release_notes(
+    tag_name: &str,
+    scope: &str,
+    commits: &[Commit],
+    config: &Config,
+) -> String {
+    let mut notes = format!("## {}\n", tag_name);
+    let mut is_empty = true;
+    for (increment_kind, title) in GROUPS {
+        let entries: Vec<String> =
+            commits
+                .iter()
+                .filter(|commit| {
+                    commit.details.as_ref().is_some_and(|details| {
+                        details.scope.as_deref().unwrap_or_default() == scope
+                    }) && calculate_increment(commit, config.scope_bump_rules(scope))
+                        == Some(increment_kind)
+                })
+                .map(|commit| {
+                    let short_sha = commit.sha.get(..SHORT_SHA_LENGTH).unwrap_or(&commit.sha);
+                    let description = commit.details.as_ref().unwrap().description.trim();
+                    format!(
+                        "- {} ({})",
+                        description.lines().next().unwrap_or_default(),
+                        short_sha
+                    )
+                })
+                .collect();
+        if entries.is_empty() {
+            continue;
+        }
+
+        is_empty = false;
+        notes.push_str(&format!("\n### {}\n\n{}\n", title, entries.join("\n")));
+    }
+
+    if is_empty {
+        notes.push_str("\nNo notable changes.\n");
+    }
+    notes
+}
+
+/// Renders the release notes of a release with the given template.
+///
+/// # Arguments
+///
+/// * `template` - Release notes template.
+///
+/// * `data` - Data of the release available to the template.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the data can not be serialized.
+///
+pub fn render_templated_release_notes(
+    template: &Template,
+    data: &ReleaseNotesData,
+) -> Result<String, Error> {
+    Ok(template.render(&serde_json::to_value(data)?))
+}
+
+/// Reads and parses the release notes template of the given file.
+///
+/// # Arguments
+///
+/// * `path` - Path of the template file.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidTemplate` if the file can not be read.
+///
+/// Returns the errors of `template::Template::parse`.
+///
+pub fn read_release_notes_template(path: &Path) -> Result<Template, Error> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(error) => {
+            return Err(Error::new(
+                ErrorKind::InvalidTemplate,
+                Some(&format!("{}: {}", path.display(), error)),
+            ))
+        }
+    };
+    Template::parse(&contents)
+}
+
+/// Returns the section of the changelog that starts with the heading `## <tag_name>`, up to the next level two
+/// heading, or `None` if the section does not exist.
+///
+/// # Arguments
+///
+/// * `changelog` - Contents of the changelog file.
+///
+/// * `tag_name` - Name of the tag of the release.
+///
+fn find_release_section(changelog: &str, tag_name: &str) -> Option<String> {
+    let heading = format!("## {}", tag_name);
+    let mut lines = changelog
+        .lines()
+        .skip_while(|line| line.trim_end() != heading);
+    let first_line = lines.next()?;
+
+    let mut section = vec![first_line];
+    section.extend(lines.take_while(|line| !line.starts_with("## ")));
+    Some(section.join("\n"))
+}
+
+/// Normalizes a changelog section to compare it ignoring trailing whitespace.
+fn normalize(section: &str) -> String {
+    let lines: Vec<&str> = section.lines().map(|line| line.trim_end()).collect();
+    lines.join("\n").trim().to_string()
+}
@@ -0,0 +1,453 @@
+//! This module provides the handling of the CLI arguments of the `tag-track` binary: the environment variables that
+//! set them, the subcommands that are shortcuts of other arguments, the checks of `--read-only` and the options of
+//! the sources.
+//!
+
+use crate::{Args, Command, ConfigCommand, ENV_VAR_PREFIX, RELEASE_NOTES_STDOUT};
+use clap::{parser::ValueSource, Arg, ArgMatches, CommandFactory};
+use std::{env, ffi::OsString, path::PathBuf, time::Duration};
+use tag_track::config::Config;
+use tag_track::error::{Error, ErrorKind};
+use tag_track::logging;
+use tag_track::source;
+use tag_track::validate::unknown_field_message;
+
+/// Parses the given CLI arguments. Every argument of `Args` and of its subcommands can also be set with an
+/// environment variable named `TAG_TRACK_` followed by its long flag in upper snake case, such as
+/// `TAG_TRACK_OUTPUT_FORMAT` for `--output-format`, and arguments given in the command line take precedence over
+/// environment variables. `--no-env` is looked up in the command line before any environment variable is read, so
+/// invalid `TAG_TRACK_*` values are ignored when it is given. Exits with the clap error if the arguments can not be
+/// parsed.
+///
+/// # Arguments
+///
+/// * `cli_args` - CLI arguments, including the binary name.
+///
+pub(crate) fn parse_cli_args(cli_args: Vec<OsString>) -> ArgMatches {
+    let no_env = mut_all_args(Args::command(), without_env_var)
+        .ignore_errors(true)
+        .try_get_matches_from(cli_args.clone())
+        .is_ok_and(|matches| matches.get_one::<bool>("no_env") == Some(&true));
+    let command = match no_env {
+        true => mut_all_args(Args::command(), without_env_var),
+        false => mut_all_args(Args::command(), with_env_var),
+    };
+    command.get_matches_from(cli_args)
+}
+
+/// Returns the given command with `f` applied to its arguments and to the arguments of all its subcommands.
+///
+/// # Arguments
+///
+/// * `command` - Command whose arguments are modified.
+///
+/// * `f` - Function applied to every argument.
+///
+fn mut_all_args(command: clap::Command, f: fn(Arg) -> Arg) -> clap::Command {
+    let subcommands: Vec<String> = command
+        .get_subcommands()
+        .map(|subcommand| subcommand.get_name().to_owned())
+        .collect();
+    subcommands
+        .iter()
+        .fold(command.mut_args(f), |command, subcommand| {
+            command.mut_subcommand(subcommand, |subcommand| mut_all_args(subcommand, f))
+        })
+}
+
+/// Returns the given argument without the environment variable that sets it.
+///
+/// # Arguments
+///
+/// * `arg` - Argument of `Args`.
+///
+fn without_env_var(arg: Arg) -> Arg {
+    arg.env(None::<&str>)
+}
+
+/// Returns the given argument with the environment variable that sets it, named after its long flag with the
+/// `TAG_TRACK_` prefix. Arguments that already declare their environment variable, arguments without long flag and
+/// `--no-env` are returned unchanged. Values of the environment variables are never shown in the help, as some
+/// arguments are secrets.
+///
+/// # Arguments
+///
+/// * `arg` - Argument of `Args`.
+///
+fn with_env_var(arg: Arg) -> Arg {
+    if arg.get_env().is_some() || arg.get_id() == "no_env" {
+        return arg;
+    }
+    let name = match arg.get_long() {
+        Some(long) => format!(
+            "{}{}",
+            ENV_VAR_PREFIX,
+            long.to_uppercase().replace('-', "_")
+        ),
+        None => return arg,
+    };
+    arg.env(name).hide_env_values(true)
+}
+
+/// Fills the arguments that were not given with the values of the standard GitHub Actions environment variables:
+/// `GITHUB_TOKEN` for `--github-token`, `GITHUB_API_URL` for `--github-api-url` and `GITHUB_REPOSITORY` for
+/// `--github-repo`. The repository is only read if no other source was selected, and never for the `apply`
+/// subcommand as plans record their source. If `--no-env` is given, no environment variable is read.
+///
+/// # Arguments
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `matches` - Matches of the parsed CLI arguments, used to know which arguments were given.
+///
+pub(crate) fn read_env_fallbacks(args: &mut Args, matches: &ArgMatches) {
+    if args.no_env {
+        return;
+    }
+
+    if args.github_token.is_none() {
+        args.github_token = read_env_var(source::GITHUB_TOKEN);
+    }
+
+    if matches.value_source("github_api_url") == Some(ValueSource::DefaultValue) {
+        if let Some(api_url) = read_env_var(source::GITHUB_API_URL) {
+            args.github_api_url = api_url;
+        }
+    }
+
+    if cfg!(feature = "github")
+        && args.github_repo.is_none()
+        && args.git_remote.is_none()
+        && !args.auto_source
+        && !args.include_working_tree
+        && !matches!(args.command, Some(Command::Apply { .. }))
+    {
+        args.github_repo = read_env_var(source::GITHUB_REPOSITORY);
+    }
+}
+
+/// Returns the value of the given environment variable, or `None` if it is not set or empty.
+fn read_env_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+/// Rewrites the subcommands that are shortcuts of flags or of other subcommands into their equivalent arguments, so
+/// the rest of the run only handles the canonical invocation. `bump`, `check`, `tag` and `changelog` are replaced by
+/// the bare invocation with their flags, and `lint` by `config lint`.
+///
+/// # Arguments
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the subcommand is used with an argument that
+/// conflicts with its equivalent flag.
+///
+pub(crate) fn resolve_command_alias(args: &mut Args) -> Result<(), Error> {
+    let writes_release_notes = args
+        .release_notes_file
+        .as_ref()
+        .is_some_and(|path| path.as_os_str() != RELEASE_NOTES_STDOUT);
+    let (name, conflicts) = match args.command {
+        Some(Command::Bump) => ("bump", vec![]),
+        Some(Command::Check) => (
+            "check",
+            vec![
+                (args.create_tag, "--create-tag"),
+                (args.create_release, "--create-release"),
+                (args.all_release_branches, "--all-release-branches"),
+                (args.snapshot, "--snapshot"),
+            ],
+        ),
+        Some(Command::Tag) => (
+            "tag",
+            vec![
+                (args.check, "--check"),
+                (args.include_working_tree, "--include-working-tree"),
+                (args.all_release_branches, "--all-release-branches"),
+                (args.snapshot, "--snapshot"),
+                (args.until_date.is_some(), "--until-date"),
+            ],
+        ),
+        Some(Command::Changelog) => (
+            "changelog",
+            vec![(writes_release_notes, "--release-notes-file")],
+        ),
+        Some(Command::Lint) => {
+            args.command = Some(Command::Config {
+                command: ConfigCommand::Lint,
+            });
+            return Ok(());
+        }
+        _ => return Ok(()),
+    };
+
+    if let Some((_, flag)) = conflicts.iter().find(|(is_used, _)| *is_used) {
+        return Err(Error::new(
+            ErrorKind::Other,
+            Some(&format!(
+                "`{}` can not be used with the `{}` subcommand",
+                flag, name
+            )),
+        ));
+    }
+    match args.command {
+        Some(Command::Check) => args.check = true,
+        Some(Command::Tag) => args.create_tag = true,
+        Some(Command::Changelog) => {
+            args.release_notes_file = Some(PathBuf::from(RELEASE_NOTES_STDOUT))
+        }
+        _ => {}
+    }
+    args.command = None;
+    Ok(())
+}
+
+/// Prints a warning for each unknown field of the configuration file, suggesting the closest known field, or fails
+/// if `strict_config` is `true`.
+///
+/// # Arguments
+///
+/// * `config` - Loaded configuration.
+///
+/// * `strict_config` - Whether unknown fields fail instead of printing warnings.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::ConfigValidationFailed` listing the unknown fields if
+/// `strict_config` is `true` and there are unknown fields.
+///
+pub(crate) fn check_unknown_fields(config: &Config, strict_config: bool) -> Result<(), Error> {
+    let messages: Vec<String> = config
+        .unknown_fields
+        .iter()
+        .map(|field| unknown_field_message(field))
+        .collect();
+    match (messages.is_empty(), strict_config) {
+        (true, _) => Ok(()),
+        (false, true) => Err(Error::new(
+            ErrorKind::ConfigValidationFailed,
+            Some(&messages.join("; ")),
+        )),
+        (false, false) => {
+            for message in &messages {
+                logging::warn(message);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Checks that no action that writes to the repository or to files is requested if `--read-only` is given.
+///
+/// # Arguments
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::ReadOnly` listing the requested actions that write to
+/// the repository or to files.
+///
+pub(crate) fn check_read_only(args: &Args) -> Result<(), Error> {
+    if !args.read_only {
+        return Ok(());
+    }
+
+    let writes_release_notes = args
+        .release_notes_file
+        .as_ref()
+        .is_some_and(|path| path.as_os_str() != RELEASE_NOTES_STDOUT);
+    let mut actions: Vec<&str> = [
+        (args.create_tag, "`--create-tag`"),
+        (args.create_release, "`--create-release`"),
+        (args.auto_deepen, "`--auto-deepen`"),
+        (args.state_file.is_some(), "`--state-file`"),
+        (args.ledger_file.is_some(), "`--ledger-file`"),
+        (args.output_file.is_some(), "`--output-file`"),
+        (
+            args.release_metadata_dir.is_some(),
+            "`--release-metadata-dir`",
+        ),
+        (writes_release_notes, "`--release-notes-file`"),
+        (args.github_cache_dir.is_some(), "`--github-cache-dir`"),
+    ]
+    .into_iter()
+    .filter(|(requested, _)| *requested)
+    .map(|(_, action)| action)
+    .collect();
+    match (&args.command, args.dry_run) {
+        (Some(Command::Plan { .. }), _) => actions.push("the `plan` subcommand"),
+        (Some(Command::Approve { .. }), _) => actions.push("the `approve` subcommand"),
+        (Some(Command::Apply { .. }), false) => actions.push("the `apply` subcommand"),
+        (Some(Command::PromoteEnv { .. }), false) => actions.push("the `promote-env` subcommand"),
+        (Some(Command::Init { .. }), _) => actions.push("the `init` subcommand"),
+        _ => {}
+    }
+
+    match actions.is_empty() {
+        true => Ok(()),
+        false => Err(Error::new(
+            ErrorKind::ReadOnly,
+            Some(&format!(
+                "{} can not be used with `--read-only`",
+                actions.join(", ")
+            )),
+        )),
+    }
+}
+
+/// Returns the options used to create sources from the given user inputted CLI arguments.
+pub(crate) fn get_source_options(args: &Args) -> source::SourceOptions {
+    source::SourceOptions {
+        github_repo: args.github_repo.clone(),
+        github_api_url: validate_trailing_slash(&args.github_api_url),
+        github_token: args.github_token.clone(),
+        github_app_id: args.github_app_id.clone(),
+        github_app_private_key: args.github_app_private_key.clone(),
+        github_cache_dir: args.github_cache_dir.clone(),
+        github_concurrent_pages: args.github_concurrent_pages,
+        proxy: source::ProxyOptions {
+            http_proxy: args.http_proxy.clone(),
+            https_proxy: args.https_proxy.clone(),
+            use_env: !args.no_env,
+        },
+        tls_ca_cert: args.tls_ca_cert.clone(),
+        tls_insecure: args.tls_insecure,
+        request_timeout: match args.request_timeout {
+            0 => None,
+            seconds => Some(Duration::from_secs(seconds)),
+        },
+        request_retries: args.request_retries,
+        #[cfg(feature = "github")]
+        observer: match args.log_requests {
+            true => Some(std::sync::Arc::new(RequestLogger)),
+            false => None,
+        },
+        git_remote: args.git_remote.clone(),
+        git_backend: args.git_backend,
+        use_env: !args.no_env,
+        sign_tag: args.sign_tag,
+        auto_deepen: args.auto_deepen,
+        since_date: args.since_date.clone(),
+        until_date: args.until_date.clone(),
+        read_only: args.read_only,
+    }
+}
+
+/// Observer of the GitHub source requests that logs each request to stderr.
+#[cfg(feature = "github")]
+#[derive(Debug)]
+struct RequestLogger;
+
+#[cfg(feature = "github")]
+impl source::github::RequestObserver for RequestLogger {
+    fn on_request(&self, record: &source::github::RequestRecord) {
+        let status = match record.status {
+            Some(status) => status.to_string(),
+            None => "error".to_owned(),
+        };
+        let rate_limit = match record.rate_limit_remaining {
+            Some(remaining) => format!(" (rate limit remaining {})", remaining),
+            None => String::new(),
+        };
+        eprintln!(
+            "{} {} -> {} in {}ms{}",
+            record.method,
+            record.url,
+            status,
+            record.duration.as_millis(),
+            rate_limit
+        );
+    }
+}
+
+/// Validates the given URL and returns a valid URL without a trailing slash.
+///
+/// # Arguments
+///
+/// * `url` - URL to be validated.
+///
+pub(crate) fn validate_trailing_slash(url: &str) -> String {
+    let mut url = url.to_owned();
+    if url.ends_with('/') {
+        url.pop();
+    }
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// Returns the output format parsed from the given CLI arguments.
+    fn output_format(cli_args: &[&str]) -> String {
+        let cli_args = cli_args.iter().map(OsString::from).collect();
+        parse_cli_args(cli_args)
+            .get_one::<String>("output_format")
+            .unwrap()
+            .to_owned()
+    }
+
+    #[test]
+    fn env_vars_are_named_after_long_flags() {
+        let command = mut_all_args(Args::command(), with_env_var);
+        let env_var = |id: &str| {
+            let arg = command
+                .get_arguments()
+                .find(|arg| arg.get_id() == id)
+                .unwrap();
+            arg.get_env().map(|name| name.to_str().unwrap().to_owned())
+        };
+        assert_eq!(
+            env_var("output_format").as_deref(),
+            Some("TAG_TRACK_OUTPUT_FORMAT")
+        );
+        assert_eq!(
+            env_var("approval_key").as_deref(),
+            Some("TAG_TRACK_APPROVAL_KEY")
+        );
+        assert_eq!(env_var("no_env"), None);
+    }
+
+    #[test]
+    fn env_vars_are_overridden_by_cli_args() {
+        env::set_var("TAG_TRACK_OUTPUT_FORMAT", "json");
+        assert_eq!(output_format(&["tag-track"]), "json");
+        assert_eq!(
+            output_format(&["tag-track", "--output-format", "matrix"]),
+            "matrix"
+        );
+        assert_eq!(output_format(&["tag-track", "--no-env"]), "text");
+        env::remove_var("TAG_TRACK_OUTPUT_FORMAT");
+    }
+
+    /// Returns the result of `check_read_only` for the given CLI arguments, without the binary name.
+    fn read_only_check(cli_args: &[&str]) -> Result<(), String> {
+        let args =
+            Args::try_parse_from(["tag-track", "--read-only"].iter().chain(cli_args)).unwrap();
+        check_read_only(&args).map_err(|error| {
+            assert_eq!(error.kind, ErrorKind::ReadOnly);
+            error.to_string()
+        })
+    }
+
+    #[test]
+    fn read_only_rejects_actions_that_write() {
+        let error = read_only_check(&["--create-tag", "--state-file", "state.json"]).unwrap_err();
+        assert!(error.contains("`--create-tag`, `--state-file` can not be used with `--read-only`"));
+        let error = read_only_check(&["--release-notes-file", "notes.md"]).unwrap_err();
+        assert!(error.contains("`--release-notes-file`"));
+        let error = read_only_check(&["apply", "plan.json"]).unwrap_err();
+        assert!(error.contains("the `apply` subcommand"));
+    }
+
+    #[test]
+    fn read_only_allows_actions_that_only_read() {
+        assert!(read_only_check(&[]).is_ok());
+        assert!(read_only_check(&["--release-notes-file", RELEASE_NOTES_STDOUT]).is_ok());
+        assert!(read_only_check(&["--dry-run", "apply", "plan.json"]).is_ok());
+    }
+}
@@ -0,0 +1,713 @@
+//! This module provides the version bump calculation of the `tag-track` binary, used when it is invoked without a
+//! subcommand or with the `plan` subcommand, which reports the version bumps and creates or plans their tags.
+//!
+
+use crate::cli::output::{
+    print_error, print_matrix, print_output, print_provenance, render_output_template, Output,
+    OutputFormat, OutputReleaseBranch,
+};
+use crate::cli::plan::{create_tags, report_planned_tags};
+use crate::{Args, Command, CHECK_NO_BUMP_EXIT_CODE, RELEASE_NOTES_STDOUT};
+use std::{collections::BTreeSet, fs, path::Path, process::exit};
+use tag_track::bump::{
+    self, calculate_bumps, BumpResult, IgnoredCommit, OutputVersionBump, ScanOptions, ScopeBump,
+};
+use tag_track::cancel::CancellationToken;
+use tag_track::changelog::read_release_notes_template;
+use tag_track::config::{Config, NonConformingCommitBump};
+use tag_track::error::{Error, ErrorKind};
+use tag_track::git::{self, Branch, Commit};
+use tag_track::logging;
+use tag_track::metadata::{write_release_metadata_file, CommitRange, ReleaseMetadata};
+use tag_track::parsing::{normalize_scope, parse_release_line};
+use tag_track::plan::{plan_tags, write_plan_file, Plan, PlannedTag, TagPlan, TagPlanOptions};
+use tag_track::source::{SourceActions, SourceKind};
+use tag_track::state::{
+    get_scope_states, read_state_file, resume_from_state, write_state_file, ScopeState, State,
+};
+use tag_track::suggestion::CommitSuggestion;
+
+use crate::cli::args::validate_trailing_slash;
+
+/// Calculates the version bumps, prints them and creates or plans their tags as requested by the user inputted CLI
+/// arguments. Exits with code 1 if the calculation fails, or with `CHECK_NO_BUMP_EXIT_CODE` if `--check` is given
+/// and no scope requires a version bump.
+///
+/// # Arguments
+///
+/// * `source` - Source used to read the history and create the tags.
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `output_format` - Output format used to print the result.
+///
+/// * `cancel` - Token used to interrupt the reading of the history.
+///
+pub(crate) fn bump_versions(
+    source: &SourceKind,
+    args: &Args,
+    config: &Config,
+    output_format: &OutputFormat,
+    cancel: &CancellationToken,
+) {
+    let bump_required = match calculate_version_bumps(source, args, config, output_format, cancel) {
+        Ok(bump_required) => bump_required,
+        Err(error) => {
+            print_error(error, args, output_format, Some(config));
+            exit(1);
+        }
+    };
+    if args.check && !bump_required {
+        exit(CHECK_NO_BUMP_EXIT_CODE);
+    }
+}
+
+/// Calculates the version bumps, prints them and creates or plans their tags. Returns whether any scope requires a
+/// version bump.
+///
+/// # Arguments
+///
+/// * `source` - Source used to read the history and create the tags.
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `output_format` - Output format used to print the result.
+///
+/// * `cancel` - Token used to interrupt the reading of the history.
+///
+/// # Errors
+///
+/// Returns the errors of the resolution of the commit range, the calculation of the version bumps, the tag plan and
+/// the files written by the run.
+///
+fn calculate_version_bumps(
+    source: &SourceKind,
+    args: &Args,
+    config: &Config,
+    output_format: &OutputFormat,
+    cancel: &CancellationToken,
+) -> Result<bool, Error> {
+    let commit_sha = match (&args.commit_sha, &args.to_ref) {
+        (Some(commit_sha), _) => commit_sha.clone(),
+        (None, Some(to_ref)) => source.resolve_ref(to_ref)?,
+        (None, None) => source.get_latest_commit_sha()?,
+    };
+
+    let range_start = match &args.from_ref {
+        Some(from_ref) => Some(source.resolve_ref(from_ref)?),
+        None => None,
+    };
+    match &range_start {
+        Some(range_start) => logging::debug(&format!(
+            "calculating version bumps of the commits between {} and {}",
+            range_start, commit_sha
+        )),
+        None => logging::debug(&format!(
+            "calculating version bumps of the history of commit {}",
+            commit_sha
+        )),
+    }
+
+    let leading_commit = match args.include_working_tree {
+        true => Some(get_working_tree_commit(args, config)?),
+        false => None,
+    };
+
+    if let (true, Some(Command::Plan { .. })) = (args.snapshot, &args.command) {
+        return Err(Error::new(
+            ErrorKind::Other,
+            Some("`--snapshot` can not be used with the `plan` subcommand"),
+        ));
+    }
+
+    let state = match &args.state_file {
+        Some(_) if !config.scope_paths.is_empty() => {
+            return Err(Error::new(
+                ErrorKind::Other,
+                Some("`--state-file` can not be used with `scope_paths`"),
+            ))
+        }
+        Some(path) => read_state_file(path, config)?,
+        None => None,
+    };
+
+    let options = ScanOptions {
+        range_start,
+        leading_commit,
+        stop_sha: state.as_ref().map(|state| state.commit_sha.clone()),
+        max_scopes: args.max_scopes,
+        cancel: cancel.clone(),
+    };
+    let mut scanned_references = bump::scan(config, source, &commit_sha, options)?;
+    if let (true, Some(state)) = (scanned_references.is_stopped, &state) {
+        resume_from_state(&mut scanned_references, state)?;
+    }
+
+    let BumpResult {
+        version_bumps: scope_bumps,
+        dependency_updates,
+        skipped_commits: skipped_commits_sha,
+        ignored_commits,
+        commit_suggestions,
+        ..
+    } = calculate_bumps(config, &commit_sha, scanned_references)?;
+    let mut scope_states = get_scope_states(&scope_bumps);
+
+    report_skipped_commits(
+        config,
+        &skipped_commits_sha,
+        &commit_suggestions,
+        &ignored_commits,
+    );
+
+    let mut output = Output::new(args, Some(config), &skipped_commits_sha);
+    output.commit_suggestions = commit_suggestions;
+    output.ignored_commits = ignored_commits;
+
+    if let OutputFormat::Text = output_format {
+        if !dependency_updates.is_empty() {
+            println!("dependencies:");
+            for dependency_update in &dependency_updates {
+                println!(
+                    "- {} ({})",
+                    dependency_update.message, dependency_update.sha
+                );
+            }
+        }
+    }
+    output.dependency_updates = dependency_updates;
+
+    let print_release_notes = args
+        .release_notes_file
+        .as_ref()
+        .is_some_and(|path| path.as_os_str() == RELEASE_NOTES_STDOUT);
+    if let (true, OutputFormat::Json | OutputFormat::Matrix) = (print_release_notes, output_format)
+    {
+        return Err(Error::new(
+            ErrorKind::Other,
+            Some(&format!(
+                "the release notes can not be printed to stdout with the `{}` output format",
+                args.output_format
+            )),
+        ));
+    }
+    let release_notes_template = match &config.release_notes_template {
+        Some(path) => Some(read_release_notes_template(&git::repo_path().join(path))?),
+        None => None,
+    };
+
+    let selected_scopes: Vec<String> = args
+        .create_tag_for
+        .iter()
+        .map(|scope| normalize_scope(scope, config.scope_rules.case))
+        .collect();
+    for scope_bump in &scope_bumps {
+        let version_bump = report_scope_bump(scope_bump, &commit_sha, args, config, output_format)?;
+        output.version_bumps.push(version_bump);
+    }
+
+    let options = TagPlanOptions {
+        selected_scopes: &selected_scopes,
+        release_notes: args.release_notes_file.is_some(),
+        create_release: args.create_release,
+        aggregate_release: args.aggregate_release,
+        release_notes_template: release_notes_template.as_ref(),
+    };
+    let TagPlan {
+        tags: planned_tags,
+        release_notes,
+    } = match args.snapshot {
+        true => TagPlan {
+            tags: vec![],
+            release_notes: vec![],
+        },
+        false => plan_tags(config, &commit_sha, &scope_bumps, &options)?,
+    };
+
+    for scope in &selected_scopes {
+        let found = output
+            .version_bumps
+            .iter()
+            .any(|version_bump| &version_bump.scope == scope);
+        if !found {
+            logging::warn(&format!(
+                "scope '{}' of --create-tag-for was not found in the repository",
+                scope
+            ));
+        }
+    }
+
+    if let Some(dir) = &args.release_metadata_dir {
+        write_release_metadata(dir, &scope_bumps, &planned_tags, &commit_sha, config)?;
+    }
+
+    if let Some(path) = &args.release_notes_file {
+        let contents = release_notes.join("\n");
+        match print_release_notes {
+            true => print!("{}", contents),
+            false => {
+                fs::write(path, contents)?;
+                logging::info(&format!("release notes written to {}", path.display()));
+            }
+        }
+    }
+
+    match &args.command {
+        Some(Command::Plan { out }) => {
+            let plan = Plan::new(
+                args.github_repo.clone(),
+                validate_trailing_slash(&args.github_api_url),
+                args.git_remote.clone(),
+                commit_sha.clone(),
+                planned_tags.clone(),
+            );
+            write_plan_file(&plan, out)?;
+
+            logging::info(&format!(
+                "plan with {} tags written to {}",
+                plan.tags.len(),
+                out.display()
+            ));
+        }
+        _ => {
+            if args.dry_run {
+                report_planned_tags(&planned_tags, &mut output, output_format);
+            } else if args.create_tag {
+                create_tags(
+                    source,
+                    &planned_tags,
+                    args.ledger_file.as_deref(),
+                    &mut output,
+                )?;
+
+                for planned_tag in planned_tags.iter().filter(|tag| !tag.is_run_tag()) {
+                    let scope_state = scope_states
+                        .iter_mut()
+                        .find(|scope_state| scope_state.scope == planned_tag.scope);
+                    if let Some(scope_state) = scope_state {
+                        *scope_state = ScopeState {
+                            scope: planned_tag.scope.clone(),
+                            tag_name: planned_tag.name.clone(),
+                            tag_commit_sha: planned_tag.commit_sha.clone(),
+                            version: planned_tag.new_version.clone(),
+                            increment_kind: None,
+                            contributors: BTreeSet::new(),
+                        };
+                    }
+                }
+            }
+
+            if let (Some(path), false) = (&args.state_file, args.dry_run) {
+                let state = State::new(commit_sha.clone(), scope_states, config)?;
+                write_state_file(&state, path)?;
+            }
+        }
+    }
+
+    print_output(&mut output, args, output_format);
+    match output_format {
+        OutputFormat::Text | OutputFormat::Json => {}
+        OutputFormat::Matrix => print_matrix(&planned_tags),
+        OutputFormat::Template(template) => {
+            for version_bump in &output.version_bumps {
+                let tag_name = planned_tags
+                    .iter()
+                    .find(|planned_tag| {
+                        !planned_tag.is_run_tag() && planned_tag.scope == version_bump.scope
+                    })
+                    .map(|planned_tag| planned_tag.name.as_str())
+                    .unwrap_or_default();
+                println!(
+                    "{}",
+                    render_output_template(
+                        template,
+                        &version_bump.scope,
+                        &version_bump.old_version,
+                        &version_bump.new_version,
+                        tag_name,
+                    )
+                );
+            }
+        }
+    }
+
+    Ok(output
+        .version_bumps
+        .iter()
+        .any(|version_bump| version_bump.increment_kind.is_some()))
+}
+
+/// Prints a warning for each commit that does not match the commit pattern, with its rewrite suggestion if any, and
+/// the commits excluded by the ignore rules of the configuration.
+///
+/// # Arguments
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `skipped_commits` - SHAs of the commits that do not match the commit pattern.
+///
+/// * `commit_suggestions` - Rewrite suggestions for the skipped commits.
+///
+/// * `ignored_commits` - Commits excluded by the ignore rules.
+///
+fn report_skipped_commits(
+    config: &Config,
+    skipped_commits: &[String],
+    commit_suggestions: &[CommitSuggestion],
+    ignored_commits: &[IgnoredCommit],
+) {
+    for sha in skipped_commits {
+        match config.non_conforming_commit_bump {
+            NonConformingCommitBump::Patch => logging::warn(&format!(
+                "commit '{}' does not match the commit pattern, counted as a patch bump",
+                sha
+            )),
+            NonConformingCommitBump::None => logging::warn(&format!(
+                "commit '{}' does not match the commit pattern",
+                sha
+            )),
+        }
+        let suggestion = commit_suggestions
+            .iter()
+            .find(|suggestion| &suggestion.sha == sha);
+        if let Some(suggestion) = suggestion {
+            logging::info(&format!("  suggestion: {}", suggestion.suggestion));
+            for hint in &suggestion.hints {
+                logging::info(&format!("  - {}", hint));
+            }
+        }
+    }
+
+    for ignored_commit in ignored_commits {
+        logging::info(&format!(
+            "commit '{}' is ignored, {} '{}'",
+            ignored_commit.sha,
+            ignored_commit.rule.description(),
+            ignored_commit.matched
+        ));
+    }
+}
+
+/// Prints the version bump of the given scope in the text output format and returns it as an output entry. With
+/// `--snapshot`, the snapshot version of the scope is reported as its new version.
+///
+/// # Arguments
+///
+/// * `scope_bump` - Version bump of the scope.
+///
+/// * `commit_sha` - SHA of the commit used to calculate the version bump.
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `output_format` - Output format used to print the version bump.
+///
+/// # Errors
+///
+/// Returns the errors of `ScopeBump::snapshot_version`.
+///
+fn report_scope_bump(
+    scope_bump: &ScopeBump,
+    commit_sha: &str,
+    args: &Args,
+    config: &Config,
+    output_format: &OutputFormat,
+) -> Result<OutputVersionBump, Error> {
+    let scope = &scope_bump.scope;
+    match &scope_bump.closest_tag {
+        Some(tag) => logging::debug(&format!(
+            "closest tag of scope '{}' is {} at commit {}",
+            scope, tag.name, tag.commit_sha
+        )),
+        None => logging::debug(&format!(
+            "scope '{}' has no tag, the initial version {} is used",
+            scope, config.initial_version
+        )),
+    }
+
+    let mut version_bump = OutputVersionBump::new(scope_bump);
+    let is_text = matches!(output_format, OutputFormat::Text);
+
+    if args.snapshot {
+        version_bump.new_version = scope_bump.snapshot_version(config, commit_sha)?.to_string();
+        if is_text && scope.is_empty() {
+            println!(
+                "snapshot version for empty scope: {}",
+                version_bump.new_version
+            );
+        } else if is_text {
+            println!(
+                "snapshot version for scope {}: {}",
+                scope, version_bump.new_version
+            );
+        }
+        return Ok(version_bump);
+    }
+
+    if scope_bump.increment_kind.is_none() {
+        if is_text {
+            let reason = version_bump
+                .reason
+                .map(|reason| format!(": {}", reason.description()))
+                .unwrap_or_default();
+            if scope.is_empty() {
+                println!("version bump for empty scope is not required{}", reason);
+            } else {
+                println!("version bump for scope {} is not required{}", scope, reason);
+            }
+        }
+        return Ok(version_bump);
+    }
+
+    if is_text {
+        if scope.is_empty() {
+            println!(
+                "version bump for empty scope: {} -> {}",
+                version_bump.old_version, version_bump.new_version
+            );
+        } else {
+            println!(
+                "version bump for scope {}: {} -> {}",
+                scope, version_bump.old_version, version_bump.new_version
+            );
+        }
+        if version_bump.commit_threshold_reached {
+            println!(
+                "patch bump forced, more than {} commits since the last tag",
+                config.patch_bump_commit_threshold.unwrap_or_default()
+            );
+        }
+        if args.explain {
+            print_provenance(&version_bump.provenance);
+        }
+    }
+    Ok(version_bump)
+}
+
+/// Writes the release metadata file of each scope with a planned tag into the given directory.
+///
+/// # Arguments
+///
+/// * `dir` - Directory where the release metadata files are written.
+///
+/// * `scope_bumps` - Version bumps of the scopes.
+///
+/// * `planned_tags` - Tags planned for the version bumps.
+///
+/// * `commit_sha` - SHA of the commit used to calculate the version bumps.
+///
+/// * `config` - Tag Track configuration.
+///
+/// # Errors
+///
+/// Returns the errors of `ReleaseMetadata::new` and `metadata::write_release_metadata_file`.
+///
+fn write_release_metadata(
+    dir: &Path,
+    scope_bumps: &[ScopeBump],
+    planned_tags: &[PlannedTag],
+    commit_sha: &str,
+    config: &Config,
+) -> Result<(), Error> {
+    for scope_bump in scope_bumps {
+        let planned_tag = planned_tags
+            .iter()
+            .find(|planned_tag| !planned_tag.is_run_tag() && planned_tag.scope == scope_bump.scope);
+        let planned_tag = match planned_tag {
+            Some(planned_tag) => planned_tag,
+            None => continue,
+        };
+        let release_metadata = ReleaseMetadata::new(
+            planned_tag.scope.clone(),
+            planned_tag.name.clone(),
+            planned_tag.old_version.clone(),
+            planned_tag.new_version.clone(),
+            CommitRange {
+                from: scope_bump
+                    .closest_tag
+                    .as_ref()
+                    .map(|tag| tag.commit_sha.clone())
+                    .unwrap_or_default(),
+                to: commit_sha.to_owned(),
+            },
+            scope_bump.contributors.clone(),
+            config,
+        )?;
+        let path = write_release_metadata_file(&release_metadata, dir)?;
+        logging::info(&format!("release metadata written to {}", path.display()));
+    }
+    Ok(())
+}
+
+/// Returns a provisional commit representing the uncommitted changes of the working tree. The commit message is
+/// obtained from the `--message` argument or, if missing, from the `.git/COMMIT_EDITMSG` file.
+///
+/// # Arguments
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `config` - Tag Track configuration.
+///
+/// # Errors
+///
+/// Returns the errors from `git::read_commit_editmsg` if the message is read from the `.git/COMMIT_EDITMSG` file.
+///
+/// Returns the errors of `Config::resolve_scope_commit`.
+///
+fn get_working_tree_commit(args: &Args, config: &Config) -> Result<Commit, Error> {
+    let message = match &args.message {
+        Some(message) => message.clone(),
+        None => git::read_commit_editmsg()?,
+    };
+
+    let mut commit = Commit::new(
+        git::WORKING_TREE_COMMIT_SHA.to_owned(),
+        &message,
+        None,
+        &config.commit_pattern,
+        config.scope_rules.case,
+        config.max_commit_message_length,
+    )?;
+    config.resolve_scope_commit(&mut commit)?;
+    Ok(commit)
+}
+
+/// Calculates the version bumps of every branch of the source matching the release branch pattern of the
+/// configuration and prints a combined report. Branches are checked in name order. Tags are never created.
+///
+/// # Arguments
+///
+/// * `source` - Source used to read the branches and their history.
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `output_format` - Format used to print the report.
+///
+/// * `cancel` - Token used to interrupt the reading of the history of the branches.
+///
+pub(crate) fn check_release_branches(
+    source: &SourceKind,
+    args: &Args,
+    config: &Config,
+    output_format: &OutputFormat,
+    cancel: &CancellationToken,
+) {
+    let mut branches = match source.get_branches() {
+        Ok(branches) => branches,
+        Err(error) => {
+            print_error(error, args, output_format, Some(config));
+            exit(1);
+        }
+    };
+    branches.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let skipped_commits = vec![];
+    let mut output = Output::new(args, Some(config), &skipped_commits);
+    for branch in &branches {
+        let line = match parse_release_line(&branch.name, &config.release_branch_pattern) {
+            Ok(Some(line)) => line,
+            Ok(None) => continue,
+            Err(error) => {
+                print_error(error, args, output_format, Some(config));
+                exit(1);
+            }
+        };
+
+        match check_release_branch(source, branch, line, config, args.max_scopes, cancel) {
+            Ok(release_branch) => output.release_branches.push(release_branch),
+            Err(error) => {
+                print_error(error, args, output_format, Some(config));
+                exit(1);
+            }
+        }
+    }
+
+    if let OutputFormat::Text = output_format {
+        if output.release_branches.is_empty() {
+            println!(
+                "no branch matches the release branch pattern '{}'",
+                config.release_branch_pattern
+            );
+        }
+
+        for release_branch in &output.release_branches {
+            println!(
+                "release branch {} (line {}):",
+                release_branch.branch, release_branch.line
+            );
+            for version_bump in &release_branch.version_bumps {
+                let scope = match version_bump.scope.is_empty() {
+                    true => "empty scope".to_owned(),
+                    false => format!("scope {}", version_bump.scope),
+                };
+                match version_bump.increment_kind {
+                    Some(_) => println!(
+                        "- version bump for {}: {} -> {}",
+                        scope, version_bump.old_version, version_bump.new_version
+                    ),
+                    None => println!("- version bump for {} is not required", scope),
+                }
+                if args.explain {
+                    print_provenance(&version_bump.provenance);
+                }
+            }
+        }
+    }
+    print_output(&mut output, args, output_format);
+}
+
+/// Calculates the version bumps of the given release branch.
+///
+/// # Arguments
+///
+/// * `source` - Source used to read the history of the branch.
+///
+/// * `branch` - Release branch.
+///
+/// * `line` - Release line of the branch.
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `max_scopes` - Maximum number of version scopes, `0` to disable the check.
+///
+/// * `cancel` - Token used to interrupt the reading of the history of the branch.
+///
+/// # Errors
+///
+/// Returns the errors of `bump::scan` and `bump::calculate_bumps`.
+///
+fn check_release_branch(
+    source: &SourceKind,
+    branch: &Branch,
+    line: String,
+    config: &Config,
+    max_scopes: usize,
+    cancel: &CancellationToken,
+) -> Result<OutputReleaseBranch, Error> {
+    let options = ScanOptions {
+        max_scopes,
+        cancel: cancel.clone(),
+        ..ScanOptions::default()
+    };
+    let scanned_references = bump::scan(config, source, &branch.commit_sha, options)?;
+    let bump_result = calculate_bumps(config, &branch.commit_sha, scanned_references)?;
+
+    Ok(OutputReleaseBranch {
+        branch: branch.name.clone(),
+        line,
+        commit_sha: branch.commit_sha.clone(),
+        version_bumps: bump_result
+            .version_bumps
+            .iter()
+            .map(OutputVersionBump::new)
+            .collect(),
+        skipped_commits: bump_result.skipped_commits,
+    })
+}
@@ -0,0 +1,334 @@
+//! This module provides the subcommands of the `tag-track` binary that check the repository or the configuration
+//! without calculating version bumps: `verify-changelog`, `audit-tags`, `diff-report`, `config lint` and
+//! `config validate`.
+//!
+
+use crate::cli::output::{print_error, print_output, Output, OutputError, OutputFormat};
+use crate::Args;
+use std::{fs, path::Path, process::exit};
+use tag_track::audit::audit_tags;
+use tag_track::changelog::verify_changelog;
+use tag_track::config::Config;
+use tag_track::diff_report::{diff_report_files, ScopeDiffKind};
+use tag_track::error::{Error, ErrorKind};
+use tag_track::lint::lint_config;
+use tag_track::logging;
+use tag_track::validate::validate_config_file;
+
+/// Verifies the changelog sections of the latest releases and prints the result. Exits with an error if a section is
+/// missing or does not match the release notes derived from the released commits.
+///
+/// # Arguments
+///
+/// * `file` - Path of the changelog file.
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `output_format` - Output format used to print the result.
+///
+pub(crate) fn check_changelog(
+    file: &Path,
+    args: &Args,
+    config: &Config,
+    output_format: &OutputFormat,
+) {
+    let result = fs::read_to_string(file)
+        .map_err(Error::from)
+        .and_then(|changelog| verify_changelog(&changelog, config));
+    let sections = match result {
+        Ok(sections) => sections,
+        Err(error) => {
+            print_error(error, args, output_format, Some(config));
+            exit(1);
+        }
+    };
+
+    let mismatches: Vec<&str> = sections
+        .iter()
+        .filter(|section| !section.matches)
+        .map(|section| section.tag_name.as_str())
+        .collect();
+    let error = match mismatches.is_empty() {
+        true => None,
+        false => Some(Error::new(
+            ErrorKind::ChangelogMismatch,
+            Some(&format!(
+                "sections of tags {} are missing or were edited",
+                mismatches.join(", ")
+            )),
+        )),
+    };
+
+    if let OutputFormat::Text = output_format {
+        for section in &sections {
+            if section.matches {
+                println!("changelog section for tag {} matches", section.tag_name);
+            } else {
+                println!(
+                    "changelog section for tag {} does not match, expected:\n{}",
+                    section.tag_name, section.expected
+                );
+            }
+        }
+    }
+
+    let skipped_commits = vec![];
+    let mut output = Output::new(args, Some(config), &skipped_commits);
+    output.changelog_sections = sections;
+    if let Some(error) = &error {
+        output.error = Some(OutputError::new(error));
+    }
+    print_output(&mut output, args, output_format);
+
+    if let Some(error) = error {
+        logging::error(&error.to_string());
+        exit(1);
+    }
+}
+
+/// Audits the existing tags of the local git repository and prints the found issues. Exits with an error if any
+/// issue was found.
+///
+/// # Arguments
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `output_format` - Output format used to print the result.
+///
+pub(crate) fn check_tags(args: &Args, config: &Config, output_format: &OutputFormat) {
+    let audit = match audit_tags(config) {
+        Ok(audit) => audit,
+        Err(error) => {
+            print_error(error, args, output_format, Some(config));
+            exit(1);
+        }
+    };
+
+    let error = match audit.has_issues() {
+        true => Some(Error::new(
+            ErrorKind::TagAuditFailed,
+            Some(&format!("{} tags audited", audit.tag_count)),
+        )),
+        false => None,
+    };
+
+    if let OutputFormat::Text = output_format {
+        println!("{} tags audited", audit.tag_count);
+        for tag_name in &audit.unparsed_tags {
+            println!("tag {} does not match the tag pattern", tag_name);
+        }
+        for duplicate in &audit.duplicated_versions {
+            println!(
+                "version {} of scope \"{}\" is referenced by tags {}",
+                duplicate.version,
+                duplicate.scope,
+                duplicate.tag_names.join(", ")
+            );
+        }
+        for gap in &audit.version_gaps {
+            println!(
+                "scope \"{}\" jumps from version {} to version {}",
+                gap.scope, gap.previous_version, gap.version
+            );
+        }
+        for issue in &audit.scope_issues {
+            println!(
+                "scope \"{}\" of tags {}: {}",
+                issue.scope,
+                issue.tag_names.join(", "),
+                issue.message
+            );
+        }
+        if let Some(tag_pattern) = &audit.suggested_tag_pattern {
+            println!("suggested tag pattern: {}", tag_pattern);
+        }
+    }
+
+    let skipped_commits = vec![];
+    let mut output = Output::new(args, Some(config), &skipped_commits);
+    output.tag_audit = Some(audit);
+    if let Some(error) = &error {
+        output.error = Some(OutputError::new(error));
+    }
+    print_output(&mut output, args, output_format);
+
+    if let Some(error) = error {
+        logging::error(&error.to_string());
+        exit(1);
+    }
+}
+
+/// Compares the given report files and prints the scopes whose computed version changed in the given output format.
+///
+/// # Arguments
+///
+/// * `old_file` - Path of the report file used as baseline.
+///
+/// * `new_file` - Path of the report file compared against the baseline.
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `output_format` - Output format used to print the result.
+///
+pub(crate) fn check_diff_report(
+    old_file: &Path,
+    new_file: &Path,
+    args: &Args,
+    config: &Config,
+    output_format: &OutputFormat,
+) {
+    let report = match diff_report_files(old_file, new_file) {
+        Ok(report) => report,
+        Err(error) => {
+            print_error(error, args, output_format, Some(config));
+            exit(1);
+        }
+    };
+
+    if let OutputFormat::Text = output_format {
+        if !report.has_changes() {
+            println!("no computed version changed");
+        }
+        for scope_diff in &report.scope_diffs {
+            let scope = match scope_diff.scope.is_empty() {
+                true => "empty scope".to_owned(),
+                false => format!("scope {}", scope_diff.scope),
+            };
+            let old_version = scope_diff.old_computed_version.as_deref().unwrap_or("-");
+            let new_version = scope_diff.new_computed_version.as_deref().unwrap_or("-");
+            match scope_diff.kind {
+                ScopeDiffKind::Added => println!("{} added: {}", scope, new_version),
+                ScopeDiffKind::Removed => println!("{} removed: {}", scope, old_version),
+                ScopeDiffKind::Changed => {
+                    println!("{} changed: {} -> {}", scope, old_version, new_version)
+                }
+            }
+            for reason in &scope_diff.reasons {
+                println!("  - {}", reason);
+            }
+        }
+    }
+
+    let skipped_commits = vec![];
+    let mut output = Output::new(args, Some(config), &skipped_commits);
+    output.diff_report = Some(report);
+    print_output(&mut output, args, output_format);
+}
+
+/// Lints the bump rules of the configuration and prints the found issues in the given output format. Exits with an
+/// error if an issue is found.
+///
+/// # Arguments
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `output_format` - Output format used to print the result.
+///
+pub(crate) fn check_config(args: &Args, config: &Config, output_format: &OutputFormat) {
+    let issues = lint_config(config);
+    let error = match issues.is_empty() {
+        true => None,
+        false => Some(Error::new(
+            ErrorKind::ConfigLintFailed,
+            Some(&format!("{} issues found", issues.len())),
+        )),
+    };
+
+    if let OutputFormat::Text = output_format {
+        println!("{} bump rules linted", config.bump_rules.len());
+        for issue in &issues {
+            println!("{}", issue.message);
+        }
+    }
+
+    let skipped_commits = vec![];
+    let mut output = Output::new(args, Some(config), &skipped_commits);
+    if let Some(error) = &error {
+        output.error = Some(OutputError::new(error));
+    }
+    print_output(&mut output, args, output_format);
+
+    if let Some(error) = error {
+        logging::error(&error.to_string());
+        exit(1);
+    }
+}
+
+/// Validates the configuration file and prints the found issues in the given output format. Exits with an error if
+/// the file is not found or an issue is found.
+///
+/// # Arguments
+///
+/// * `path` - Path of the configuration file, `None` if no configuration file was found.
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `output_format` - Output format used to print the result.
+///
+pub(crate) fn validate_config(path: Option<&Path>, args: &Args, output_format: &OutputFormat) {
+    let path = match path {
+        Some(path) => path,
+        None => {
+            let error = Error::new(
+                ErrorKind::Other,
+                Some(
+                    "no configuration file was found, create a `track.yml` file in the repository or give its path \
+                     with `--config`",
+                ),
+            );
+            print_error(error, args, output_format, None);
+            exit(1);
+        }
+    };
+    let diagnostics = match validate_config_file(path) {
+        Ok(diagnostics) => diagnostics,
+        Err(error) => {
+            print_error(error, args, output_format, None);
+            exit(1);
+        }
+    };
+    let error = match diagnostics.is_empty() {
+        true => None,
+        false => Some(Error::new(
+            ErrorKind::ConfigValidationFailed,
+            Some(&format!(
+                "{} issues found in {}",
+                diagnostics.len(),
+                path.display()
+            )),
+        )),
+    };
+
+    if let OutputFormat::Text = output_format {
+        if diagnostics.is_empty() {
+            println!("configuration file {} validated", path.display());
+        }
+        for diagnostic in &diagnostics {
+            match diagnostic.line {
+                Some(line) => println!("{}:{}: {}", path.display(), line, diagnostic.message),
+                None => println!("{}: {}", path.display(), diagnostic.message),
+            }
+        }
+    }
+
+    let skipped_commits = vec![];
+    let mut output = Output::new(args, None, &skipped_commits);
+    output.config_diagnostics = diagnostics;
+    if let Some(error) = &error {
+        output.error = Some(OutputError::new(error));
+    }
+    print_output(&mut output, args, output_format);
+
+    if let Some(error) = error {
+        logging::error(&error.to_string());
+        exit(1);
+    }
+}
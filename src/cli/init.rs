@@ -0,0 +1,173 @@
+//! This module provides the `init` subcommand of the `tag-track` binary, which writes a commented configuration
+//! file discovered from the existing tags.
+//!
+
+use crate::cli::output::{print_output, Output, OutputError, OutputFormat};
+use crate::Args;
+use std::{fs, path::Path, process::exit};
+use tag_track::config::Config;
+use tag_track::error::{Error, ErrorKind};
+use tag_track::git;
+use tag_track::init::{build_tag_pattern, discover_tag_layout, prompt, render_config, TagLayout};
+use tag_track::logging;
+use tag_track::validate::validate_config_file;
+
+/// Type that holds the inputs of the `init` subcommand.
+pub(crate) struct InitOptions<'a> {
+    /// Whether an existing configuration file is overwritten.
+    pub(crate) force: bool,
+    /// Whether the fields are asked to the user.
+    pub(crate) interactive: bool,
+    /// Tag pattern given by the user.
+    pub(crate) tag_pattern: Option<&'a str>,
+    /// Version scopes given by the user.
+    pub(crate) scopes: &'a [String],
+}
+
+/// Runs the `init` subcommand, writing a commented configuration file to the given path, and prints the result.
+/// Exits with code 1 if the file can not be written.
+///
+/// # Arguments
+///
+/// * `path` - Path of the configuration file.
+///
+/// * `options` - Inputs of the `init` subcommand.
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `output_format` - Output format used to print the result.
+///
+pub(crate) fn init_config(
+    path: &Path,
+    options: InitOptions,
+    args: &Args,
+    output_format: &OutputFormat,
+) {
+    let result = write_init_config(path, &options, args);
+
+    if let (OutputFormat::Text, Ok(())) = (output_format, &result) {
+        println!("configuration file written to {}", path.display());
+    }
+
+    let skipped_commits = vec![];
+    let mut output = Output::new(args, None, &skipped_commits);
+    if let Err(error) = &result {
+        output.error = Some(OutputError::new(error));
+    }
+    print_output(&mut output, args, output_format);
+
+    if let Err(error) = result {
+        logging::error(&error.to_string());
+        exit(1);
+    }
+}
+
+/// Writes a commented configuration file to the given path and validates it. The version scopes and the tag pattern
+/// are discovered from the existing tags of the local git repository unless they are given by the user, and the
+/// initial version is read from `--initial-version`. If the tags can not be listed, a warning is printed and the
+/// whole repository is versioned as a single scope.
+///
+/// # Arguments
+///
+/// * `path` - Path of the configuration file.
+///
+/// * `options` - Inputs of the `init` subcommand.
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the file already exists and `--force` is not
+/// used, if the path is not a YAML file, or if the file can not be written.
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::ConfigValidationFailed` if the written file is not
+/// valid, for example because of an invalid tag pattern given by the user.
+///
+fn write_init_config(path: &Path, options: &InitOptions, args: &Args) -> Result<(), Error> {
+    if let Some(extension @ ("toml" | "json")) =
+        path.extension().and_then(|extension| extension.to_str())
+    {
+        return Err(Error::new(
+            ErrorKind::Other,
+            Some(&format!(
+                "the `init` subcommand only writes YAML configuration files, the extension `{}` of `{}` is not \
+                 supported",
+                extension,
+                path.display()
+            )),
+        ));
+    }
+    if path.exists() && !options.force {
+        return Err(Error::new(
+            ErrorKind::Other,
+            Some(&format!(
+                "the configuration file `{}` already exists, use `--force` to overwrite it",
+                path.display()
+            )),
+        ));
+    }
+
+    let layout = match git::get_tag_names().and_then(|tag_names| discover_tag_layout(&tag_names)) {
+        Ok(layout) => layout,
+        Err(error) => {
+            logging::warn(&format!(
+                "can not discover the version scopes from the existing tags: {}",
+                error
+            ));
+            TagLayout::default()
+        }
+    };
+    let mut scopes = match options.scopes.is_empty() {
+        true => layout.scopes,
+        false => options.scopes.to_vec(),
+    };
+    let mut initial_version = args
+        .initial_version
+        .clone()
+        .unwrap_or_else(|| Config::new().initial_version);
+
+    if options.interactive {
+        let answer = prompt(
+            "Version scopes separated by commas, empty to version the whole repository",
+            &scopes.join(","),
+        )?;
+        scopes = answer
+            .split(',')
+            .map(|scope| scope.trim().to_owned())
+            .filter(|scope| !scope.is_empty())
+            .collect();
+    }
+    let is_scoped = scopes.iter().any(|scope| !scope.is_empty());
+    let mut tag_pattern = match options.tag_pattern {
+        Some(tag_pattern) => tag_pattern.to_owned(),
+        None => build_tag_pattern(layout.has_version_prefix, is_scoped),
+    };
+    if options.interactive {
+        tag_pattern = prompt("Tag pattern", &tag_pattern)?;
+        initial_version = prompt("Initial version", &initial_version)?;
+    }
+    if scopes.is_empty() {
+        scopes.push(String::new());
+    }
+
+    fs::write(path, render_config(&tag_pattern, &scopes, &initial_version))?;
+
+    let diagnostics = validate_config_file(path)?;
+    match diagnostics.is_empty() {
+        true => Ok(()),
+        false => {
+            let messages: Vec<String> = diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.message.clone())
+                .collect();
+            Err(Error::new(
+                ErrorKind::ConfigValidationFailed,
+                Some(&format!(
+                    "the written configuration file {} is not valid: {}",
+                    path.display(),
+                    messages.join("; ")
+                )),
+            ))
+        }
+    }
+}
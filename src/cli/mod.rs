@@ -0,0 +1,17 @@
+//! This module includes the modules of the `tag-track` binary, which implement the CLI on top of the library.
+//!
+//! List of modules:
+//! - `args`: environment variables, subcommand aliases and checks of the CLI arguments.
+//! - `bump`: calculation of the version bumps and creation or planning of their tags.
+//! - `checks`: subcommands that check the repository or the configuration.
+//! - `init`: the `init` subcommand.
+//! - `output`: output printed in the different formats and written to `--output-file`.
+//! - `plan`: the `apply`, `approve` and `promote-env` subcommands.
+//!
+
+pub mod args;
+pub mod bump;
+pub mod checks;
+pub mod init;
+pub mod output;
+pub mod plan;
@@ -0,0 +1,590 @@
+//! This module provides the output of the `tag-track` binary, printed in the text, `json` and `matrix` formats or
+//! rendered from `--output-template`, and written to `--output-file`.
+//!
+
+use crate::{Args, Command};
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::to_string_pretty;
+use std::{fs, path::PathBuf, process::exit, time::SystemTime};
+use tag_track::audit::TagAudit;
+use tag_track::bump::{CommitProvenance, DependencyUpdate, IgnoredCommit, OutputVersionBump};
+use tag_track::changelog::{ChangelogSection, SHORT_SHA_LENGTH};
+use tag_track::config::Config;
+use tag_track::diff_report::DiffReport;
+use tag_track::error::{Error, ErrorKind};
+use tag_track::git::strip_url_userinfo;
+use tag_track::legacy::{deprecations, Deprecation};
+use tag_track::lint::{lint_config, LintIssue};
+use tag_track::logging;
+use tag_track::plan::PlannedTag;
+use tag_track::run;
+use tag_track::source::{self, SourceStats};
+use tag_track::suggestion::CommitSuggestion;
+use tag_track::validate::ConfigDiagnostic;
+
+use crate::cli::args::validate_trailing_slash;
+
+/// Type for the top-level fields of the JSON output that can be selected with `--output-fields`.
+#[derive(ValueEnum, Serialize, Debug, Clone, Copy, PartialEq)]
+#[value(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum OutputField {
+    Run,
+    Inputs,
+    Config,
+    TagCreated,
+    NewTags,
+    PlannedTags,
+    NewReleases,
+    VersionBumps,
+    ReleaseBranches,
+    ChangelogSections,
+    TagAudit,
+    DiffReport,
+    ConfigLints,
+    ConfigDiagnostics,
+    SkippedCommits,
+    IgnoredCommits,
+    CommitSuggestions,
+    DependencyUpdates,
+    Stats,
+    Warnings,
+}
+
+impl OutputField {
+    /// Returns the name of the field in the JSON output.
+    pub(crate) fn as_str(&self) -> &str {
+        use OutputField::*;
+
+        match *self {
+            Run => "run",
+            Inputs => "inputs",
+            Config => "config",
+            TagCreated => "tag_created",
+            NewTags => "new_tags",
+            PlannedTags => "planned_tags",
+            NewReleases => "new_releases",
+            VersionBumps => "version_bumps",
+            ReleaseBranches => "release_branches",
+            ChangelogSections => "changelog_sections",
+            TagAudit => "tag_audit",
+            DiffReport => "diff_report",
+            ConfigLints => "config_lints",
+            ConfigDiagnostics => "config_diagnostics",
+            SkippedCommits => "skipped_commits",
+            IgnoredCommits => "ignored_commits",
+            CommitSuggestions => "commit_suggestions",
+            DependencyUpdates => "dependency_updates",
+            Stats => "stats",
+            Warnings => "warnings",
+        }
+    }
+}
+
+/// Name of the JSON output field that is always included.
+const OUTPUT_ERROR_FIELD: &str = "error";
+
+/// Type for storing the required data that needs to be printed in the terminal in different formats.
+#[derive(Serialize, Debug)]
+pub(crate) struct Output<'a> {
+    /// Information of the run that generated the output.
+    run: OutputRun,
+    /// User inputted CLI arguments.
+    inputs: ReportedInputs<'a>,
+    /// Configuration that was used.
+    config: Option<&'a Config>,
+    /// If at least one tag was created.
+    pub(crate) tag_created: bool,
+    /// New tags that were created.
+    pub(crate) new_tags: Vec<String>,
+    /// Tags that would be created, reported instead of creating them when using `--dry-run`.
+    pub(crate) planned_tags: Vec<PlannedTag>,
+    /// URLs of the new releases that were created.
+    pub(crate) new_releases: Vec<String>,
+    /// Information on the version bump of a scope.
+    pub(crate) version_bumps: Vec<OutputVersionBump>,
+    /// Version bumps of each release branch when using `--all-release-branches`.
+    pub(crate) release_branches: Vec<OutputReleaseBranch>,
+    /// Changelog sections verified by the `verify-changelog` subcommand.
+    pub(crate) changelog_sections: Vec<ChangelogSection>,
+    /// Result of the `audit-tags` subcommand.
+    pub(crate) tag_audit: Option<TagAudit>,
+    /// Result of the `diff-report` subcommand.
+    pub(crate) diff_report: Option<DiffReport>,
+    /// Issues found in the bump rules of the configuration.
+    pub(crate) config_lints: Vec<LintIssue>,
+    /// Issues found in the configuration file by the `config validate` subcommand.
+    pub(crate) config_diagnostics: Vec<ConfigDiagnostic>,
+    /// Commits that were skipped during the version bump due to pattern mismatch.
+    skipped_commits: &'a Vec<String>,
+    /// Commits excluded from the version bump by the ignore rules of the configuration.
+    pub(crate) ignored_commits: Vec<IgnoredCommit>,
+    /// Rewrite suggestions for the skipped commits.
+    pub(crate) commit_suggestions: Vec<CommitSuggestion>,
+    /// Commits that only update dependency lockfiles.
+    pub(crate) dependency_updates: Vec<DependencyUpdate>,
+    /// Statistics of the requests sent by the source.
+    pub(crate) stats: SourceStats,
+    /// Deprecation warnings of the legacy flags that were used.
+    warnings: &'static [Deprecation],
+    /// Error that stopped the run, if any.
+    pub(crate) error: Option<OutputError>,
+    /// Top-level fields included in the JSON output.
+    #[serde(skip)]
+    output_fields: &'a Vec<OutputField>,
+}
+
+/// Type for storing the error of the output.
+#[derive(Serialize, Debug)]
+pub(crate) struct OutputError {
+    /// Machine readable code of the error kind, stable across releases.
+    kind: &'static str,
+    /// Human readable message of the error.
+    message: String,
+    /// Suggestion to fix the error, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<&'static str>,
+}
+
+impl OutputError {
+    /// Returns a new `OutputError` with the code, message and hint of the given error.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - Error to report.
+    ///
+    pub(crate) fn new(error: &Error) -> Self {
+        Self {
+            kind: error.kind.code(),
+            message: error.to_string(),
+            hint: error.kind.hint(),
+        }
+    }
+}
+
+/// Type for storing the information of the run that generated the output.
+#[derive(Serialize, Debug, Clone)]
+struct OutputRun {
+    /// Random identifier of the run.
+    id: String,
+    /// Version of Tag Track.
+    tool_version: String,
+    /// RFC 3339 UTC timestamp of the start of the run.
+    started_at: String,
+    /// RFC 3339 UTC timestamp of the end of the run, set when the output is serialized.
+    finished_at: String,
+}
+
+/// Type for the CLI arguments reported in the output. Secrets are never included, the GitHub token is replaced by
+/// whether it was given.
+#[derive(Serialize, Debug)]
+struct ReportedInputs<'a> {
+    /// If tags are created from the populated versions.
+    create_tag: bool,
+    /// If the tags are reported without creating them.
+    dry_run: bool,
+    /// Scopes whose tags are created, all scopes if empty.
+    create_tag_for: &'a Vec<String>,
+    /// If created tags are signed.
+    sign_tag: bool,
+    /// If lightweight tags are created.
+    lightweight: bool,
+    /// If the full history of shallow clones is fetched.
+    auto_deepen: bool,
+    /// If releases are created for the new tags.
+    create_release: bool,
+    /// If a single release is created for all new tags.
+    aggregate_release: bool,
+    /// Path of the file where the release notes of the new tags are written.
+    release_notes_file: &'a Option<PathBuf>,
+    /// Version of the first tag of the scopes without tags.
+    initial_version: &'a Option<String>,
+    /// Namespace of the tags.
+    tag_namespace: &'a Option<String>,
+    /// GitHub REST API base URL, without a trailing slash.
+    github_api_url: String,
+    /// GitHub repository identifier.
+    github_repo: &'a Option<String>,
+    /// URL of a git remote, without its userinfo.
+    git_remote: Option<String>,
+    /// If the source is detected from the URL of the `origin` git remote.
+    auto_source: bool,
+    /// If a provisional commit for the uncommitted changes is included.
+    include_working_tree: bool,
+    /// Message of the provisional commit.
+    message: &'a Option<String>,
+    /// If the version bumps of every release branch are calculated.
+    all_release_branches: bool,
+    /// If snapshot versions are calculated.
+    snapshot: bool,
+    /// If the exit code reports whether a version bump is required.
+    check: bool,
+    /// If the commits that contributed to each version bump are printed.
+    explain: bool,
+    /// Directory of the HTTP cache used for GitHub REST API requests.
+    github_cache_dir: &'a Option<PathBuf>,
+    /// Number of GitHub REST API pages requested concurrently.
+    github_concurrent_pages: u64,
+    /// Path of the file with additional CA certificates.
+    tls_ca_cert: &'a Option<PathBuf>,
+    /// If the verification of TLS certificates is disabled.
+    tls_insecure: bool,
+    /// Timeout in seconds of each GitHub REST API request.
+    request_timeout: u64,
+    /// Number of retries of GitHub REST API read requests.
+    request_retries: u32,
+    /// Maximum number of version scopes.
+    max_scopes: usize,
+    /// If actions that write to the repository or to files are disabled.
+    read_only: bool,
+    /// If the GitHub REST API requests are logged.
+    log_requests: bool,
+    /// If a proxy for HTTP connections was given.
+    http_proxy: bool,
+    /// If a proxy for HTTPS connections was given.
+    https_proxy: bool,
+    /// If a GitHub token was given.
+    github_token: bool,
+    /// Identifier of the GitHub App used to authenticate.
+    github_app_id: &'a Option<String>,
+    /// SHA of the commit used to calculate the version bump.
+    commit_sha: &'a Option<String>,
+    /// Reference of the commit that starts the range of commits used to calculate the version bump.
+    from_ref: &'a Option<String>,
+    /// Reference of the commit that ends the range of commits used to calculate the version bump.
+    to_ref: &'a Option<String>,
+    /// Date from which commits are requested.
+    since_date: &'a Option<String>,
+    /// Date until which commits are requested.
+    until_date: &'a Option<String>,
+    /// Backend used to read the local git repository.
+    git_backend: source::GitBackend,
+    /// Directory of the local git repository.
+    repo_path: &'a Option<PathBuf>,
+    /// Path of the configuration file.
+    config_file: &'a Option<PathBuf>,
+    /// If unknown fields of the configuration file fail the run.
+    strict_config: bool,
+    /// Directory where the release metadata files are written.
+    release_metadata_dir: &'a Option<PathBuf>,
+    /// Path of the extra tags file.
+    extra_tags_file: &'a Option<PathBuf>,
+    /// Path of the state file.
+    state_file: &'a Option<PathBuf>,
+    /// Path of the release ledger file.
+    ledger_file: &'a Option<PathBuf>,
+    /// Output format.
+    output_format: &'a String,
+    /// Template printed once for each scope.
+    output_template: &'a Option<String>,
+    /// Path of the file where the JSON output is written.
+    output_file: &'a Option<PathBuf>,
+    /// Top-level fields included in the JSON output.
+    output_fields: &'a Vec<OutputField>,
+    /// If progress messages and warnings are not printed.
+    quiet: bool,
+    /// If details of the calculation are printed.
+    verbose: bool,
+    /// If implicit environment variables are not read.
+    no_env: bool,
+    /// Subcommand that was run.
+    command: &'a Option<Command>,
+}
+
+impl<'a> ReportedInputs<'a> {
+    /// Creates a new `ReportedInputs` instance from the given CLI arguments.
+    fn new(args: &'a Args) -> Self {
+        Self {
+            create_tag: args.create_tag,
+            dry_run: args.dry_run,
+            create_tag_for: &args.create_tag_for,
+            sign_tag: args.sign_tag,
+            lightweight: args.lightweight,
+            auto_deepen: args.auto_deepen,
+            create_release: args.create_release,
+            aggregate_release: args.aggregate_release,
+            release_notes_file: &args.release_notes_file,
+            initial_version: &args.initial_version,
+            tag_namespace: &args.tag_namespace,
+            github_api_url: validate_trailing_slash(&args.github_api_url),
+            github_repo: &args.github_repo,
+            git_remote: args.git_remote.as_deref().map(strip_url_userinfo),
+            auto_source: args.auto_source,
+            include_working_tree: args.include_working_tree,
+            message: &args.message,
+            all_release_branches: args.all_release_branches,
+            snapshot: args.snapshot,
+            check: args.check,
+            explain: args.explain,
+            github_cache_dir: &args.github_cache_dir,
+            github_concurrent_pages: args.github_concurrent_pages,
+            tls_ca_cert: &args.tls_ca_cert,
+            tls_insecure: args.tls_insecure,
+            request_timeout: args.request_timeout,
+            request_retries: args.request_retries,
+            max_scopes: args.max_scopes,
+            read_only: args.read_only,
+            log_requests: args.log_requests,
+            http_proxy: args.http_proxy.is_some(),
+            https_proxy: args.https_proxy.is_some(),
+            github_token: args.github_token.is_some(),
+            github_app_id: &args.github_app_id,
+            commit_sha: &args.commit_sha,
+            from_ref: &args.from_ref,
+            to_ref: &args.to_ref,
+            since_date: &args.since_date,
+            until_date: &args.until_date,
+            git_backend: args.git_backend,
+            repo_path: &args.repo_path,
+            config_file: &args.config_file,
+            strict_config: args.strict_config,
+            release_metadata_dir: &args.release_metadata_dir,
+            extra_tags_file: &args.extra_tags_file,
+            state_file: &args.state_file,
+            ledger_file: &args.ledger_file,
+            output_format: &args.output_format,
+            output_template: &args.output_template,
+            output_file: &args.output_file,
+            output_fields: &args.output_fields,
+            quiet: args.quiet,
+            verbose: args.verbose,
+            no_env: args.no_env,
+            command: &args.command,
+        }
+    }
+}
+
+/// Type for storing the version bumps of a release branch.
+#[derive(Serialize, Debug, Clone)]
+pub(crate) struct OutputReleaseBranch {
+    /// Name of the branch.
+    pub(crate) branch: String,
+    /// Release line of the branch, obtained from the release branch pattern.
+    pub(crate) line: String,
+    /// SHA of the commit referenced by the branch.
+    pub(crate) commit_sha: String,
+    /// Information on the version bump of each scope of the branch.
+    pub(crate) version_bumps: Vec<OutputVersionBump>,
+    /// Commits of the branch that were skipped during the version bump due to pattern mismatch.
+    pub(crate) skipped_commits: Vec<String>,
+}
+
+impl<'a> Output<'a> {
+    /// Creates a new `Output` instance with the given `inputs`.
+    pub(crate) fn new(
+        inputs: &'a Args,
+        config: Option<&'a Config>,
+        skipped_commits: &'a Vec<String>,
+    ) -> Self {
+        let current_run = run::current();
+        Self {
+            run: OutputRun {
+                id: current_run.id.clone(),
+                tool_version: env!("CARGO_PKG_VERSION").to_owned(),
+                started_at: run::format_timestamp(current_run.started_at),
+                finished_at: "".to_owned(),
+            },
+            inputs: ReportedInputs::new(inputs),
+            config,
+            tag_created: false,
+            new_tags: vec![],
+            planned_tags: vec![],
+            new_releases: vec![],
+            version_bumps: vec![],
+            release_branches: vec![],
+            changelog_sections: vec![],
+            tag_audit: None,
+            diff_report: None,
+            config_lints: config.map(lint_config).unwrap_or_default(),
+            config_diagnostics: vec![],
+            skipped_commits,
+            ignored_commits: vec![],
+            commit_suggestions: vec![],
+            dependency_updates: vec![],
+            stats: SourceStats::default(),
+            warnings: deprecations(),
+            error: None,
+            output_fields: &inputs.output_fields,
+        }
+    }
+
+    /// Sets the end of the run to the current time and returns the prettified JSON representation of the output,
+    /// including only the top-level fields selected with `--output-fields` and the `error` field.
+    pub(crate) fn finish_json(&mut self) -> Result<String, serde_json::Error> {
+        self.run.finished_at = run::format_timestamp(SystemTime::now());
+        let mut value = serde_json::to_value(&*self)?;
+        let fields = self.output_fields;
+        if let (false, Some(object)) = (fields.is_empty(), value.as_object_mut()) {
+            object.retain(|key, _| {
+                key == OUTPUT_ERROR_FIELD || fields.iter().any(|field| field.as_str() == key)
+            });
+        }
+        to_string_pretty(&value)
+    }
+}
+
+/// Type for valid output formats.
+pub(crate) enum OutputFormat {
+    Text,
+    Json,
+    /// JSON array with an entry for each new version, used as a GitHub Actions job matrix.
+    Matrix,
+    /// User provided template printed once for each scope, given by `--output-template`.
+    Template(String),
+}
+
+/// Type for storing an entry of the `matrix` output format.
+#[derive(Serialize, Debug)]
+struct OutputMatrixEntry<'a> {
+    /// Scope of the version.
+    scope: &'a str,
+    /// New version number.
+    version: &'a str,
+    /// Name of the new tag.
+    tag: &'a str,
+}
+
+/// Prints the given error to stderr. In the `json` output format, or if `--output-file` is given, the error is also
+/// reported in the JSON output.
+///
+/// # Arguments
+///
+/// * `error` - Error to be displayed.
+///
+/// * `inputs` - User inputted cli arguments.
+///
+/// * `output_format` - Output format that will be used for printing the result. The output
+///   will be prettified before being printed.
+///
+pub(crate) fn print_error(
+    error: Error,
+    inputs: &Args,
+    output_format: &OutputFormat,
+    config: Option<&Config>,
+) {
+    logging::error(&error.to_string());
+
+    let skipped_commits = vec![];
+    let mut output = Output::new(inputs, config, &skipped_commits);
+    output.error = Some(OutputError::new(&error));
+    print_output(&mut output, inputs, output_format);
+}
+
+/// Writes the JSON output to the file given by `--output-file`, if any, and prints it if the output format is
+/// `json`. Exits with an error if the output file can not be written. The output file is not written with
+/// `--read-only`, which is reported with a warning.
+///
+/// # Arguments
+///
+/// * `output` - Output that will be written and printed.
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `output_format` - Output format used to print the result.
+///
+pub(crate) fn print_output(output: &mut Output, args: &Args, output_format: &OutputFormat) {
+    let json = output.finish_json();
+
+    if let (Some(path), true) = (&args.output_file, args.read_only) {
+        logging::warn(&format!(
+            "the output file {} is not written because `--read-only` is given",
+            path.display()
+        ));
+    }
+    if let (Some(path), false) = (&args.output_file, args.read_only) {
+        let result = match &json {
+            Ok(json_str) => fs::write(path, format!("{}\n", json_str)).map_err(Error::from),
+            Err(error) => Err(Error::new(ErrorKind::Other, Some(&error.to_string()))),
+        };
+        if let Err(error) = result {
+            logging::error(&format!(
+                "could not write the output file {}: {}",
+                path.display(),
+                error
+            ));
+            exit(1);
+        }
+    }
+
+    if let OutputFormat::Json = output_format {
+        match json {
+            Ok(json_str) => println!("{}", json_str),
+            Err(_) => println!("could not serialize {:?}", output),
+        }
+    }
+}
+
+/// Prints the `matrix` output format, a compact JSON array with the scope, version and tag name of each new tag, so
+/// it can be written as a single line to `GITHUB_OUTPUT` and used with `fromJSON()` as a job matrix. Run tags are
+/// not included as they are not associated with a version.
+///
+/// # Arguments
+///
+/// * `planned_tags` - New tags of the version bumps.
+///
+pub(crate) fn print_matrix(planned_tags: &[PlannedTag]) {
+    let entries: Vec<OutputMatrixEntry> = planned_tags
+        .iter()
+        .filter(|planned_tag| !planned_tag.is_run_tag())
+        .map(|planned_tag| OutputMatrixEntry {
+            scope: &planned_tag.scope,
+            version: &planned_tag.new_version,
+            tag: &planned_tag.name,
+        })
+        .collect();
+    match serde_json::to_string(&entries) {
+        Ok(json_str) => println!("{}", json_str),
+        Err(_) => println!("could not serialize {:?}", entries),
+    }
+}
+
+/// Renders the `--output-template` of a scope, replacing its placeholders.
+///
+/// # Arguments
+///
+/// * `template` - Template given by `--output-template`.
+///
+/// * `scope` - Scope of the version.
+///
+/// * `old_version` - Version number before bump.
+///
+/// * `new_version` - Version number after bump, equal to `old_version` if the version was not bumped.
+///
+/// * `tag_name` - Name of the new tag, empty if the scope has no new tag.
+///
+pub(crate) fn render_output_template(
+    template: &str,
+    scope: &str,
+    old_version: &str,
+    new_version: &str,
+    tag_name: &str,
+) -> String {
+    template
+        .replace("{scope}", scope)
+        .replace("{old_version}", old_version)
+        .replace("{new_version}", new_version)
+        .replace("{tag_name}", tag_name)
+}
+
+/// Prints the commits that contributed to a version bump, used by `--explain` in the text output.
+///
+/// # Arguments
+///
+/// * `provenance` - Commits that contributed to the version bump.
+///
+pub(crate) fn print_provenance(provenance: &[CommitProvenance]) {
+    for commit in provenance {
+        let rule = match commit.rule {
+            Some(rule) => format!("rule {}", rule),
+            None => "non-conforming commit".to_owned(),
+        };
+        println!(
+            "  - {} {} ({}, {})",
+            commit.sha.get(..SHORT_SHA_LENGTH).unwrap_or(&commit.sha),
+            commit.subject,
+            rule,
+            commit.increment_kind.as_str()
+        );
+    }
+}
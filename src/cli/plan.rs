@@ -0,0 +1,383 @@
+//! This module provides the subcommands of the `tag-track` binary that create tags outside of the version bump
+//! calculation: `apply`, which creates the tags of a plan file, `approve`, which signs plan files, and `promote-env`.
+//!
+
+use crate::cli::args::get_source_options;
+use crate::cli::output::{
+    print_error, print_matrix, print_output, render_output_template, Output, OutputFormat,
+};
+use crate::Args;
+use semver::Version;
+use std::{path::Path, process::exit};
+use tag_track::bump::OutputVersionBump;
+use tag_track::config::Config;
+use tag_track::error::{Error, ErrorKind};
+use tag_track::logging;
+use tag_track::plan::{
+    approve_plan_file, build_plan_source, create_planned_tags, read_approved_plan_file,
+    write_approval_file, CreatedTags, PlannedRelease, PlannedTag,
+};
+use tag_track::source::{SourceActions, SourceKind};
+use tag_track::version::parse_user_version;
+
+/// Reports the given planned tags, and their releases if any, without creating them. Used by `--dry-run`.
+///
+/// # Arguments
+///
+/// * `planned_tags` - Tags that would be created.
+///
+/// * `output` - Output where the planned tags are recorded.
+///
+/// * `output_format` - Output format used to print the planned tags.
+///
+pub(crate) fn report_planned_tags(
+    planned_tags: &[PlannedTag],
+    output: &mut Output,
+    output_format: &OutputFormat,
+) {
+    for planned_tag in planned_tags {
+        if let OutputFormat::Text = output_format {
+            println!(
+                "would create tag {} on commit {} with message '{}'",
+                planned_tag.name, planned_tag.commit_sha, planned_tag.message
+            );
+            if let Some(release) = &planned_tag.release {
+                println!("would create release '{}'", release.title);
+            }
+        }
+        output.planned_tags.push(planned_tag.clone());
+    }
+}
+
+/// Creates the given planned tags, and their releases if any, using the given source and records them in the output,
+/// including the tags created before a failure. If a ledger file is given, an entry is appended to it for each created
+/// tag with a version.
+///
+/// # Arguments
+///
+/// * `source` - Source used to create the tags.
+///
+/// * `planned_tags` - Tags that will be created.
+///
+/// * `ledger_file` - Path of the release ledger file.
+///
+/// * `output` - Output where the created tags are recorded.
+///
+/// # Errors
+///
+/// Returns the errors of `plan::create_planned_tags`.
+///
+pub(crate) fn create_tags(
+    source: &SourceKind,
+    planned_tags: &[PlannedTag],
+    ledger_file: Option<&Path>,
+    output: &mut Output,
+) -> Result<(), Error> {
+    let mut created = CreatedTags::default();
+    let result = create_planned_tags(source, planned_tags, ledger_file, &mut created);
+    output.stats = source.get_stats();
+    output.tag_created = !created.tags.is_empty();
+    output.new_tags.extend(created.tags);
+    output.new_releases.extend(created.releases);
+    result
+}
+
+/// Creates the tags described in the given plan file. The source is obtained from the plan, while the GitHub token
+/// is obtained from the user inputted CLI arguments as it is never stored in plan files.
+///
+/// If an approval file is given, or if the configuration requires plan approvals, the approval is verified before
+/// creating any tag.
+///
+/// # Arguments
+///
+/// * `plan_file` - Path of the plan file.
+///
+/// * `approval_file` - Path of the approval file.
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `output_format` - Output format used to print the result.
+///
+pub(crate) fn apply_plan(
+    plan_file: &Path,
+    approval_file: Option<&Path>,
+    args: &Args,
+    config: &Config,
+    output_format: &OutputFormat,
+) {
+    let plan = match read_approved_plan_file(
+        plan_file,
+        approval_file,
+        args.approval_key.as_deref(),
+        config.require_plan_approval,
+    ) {
+        Ok(plan) => plan,
+        Err(error) => {
+            print_error(error, args, output_format, Some(config));
+            exit(1);
+        }
+    };
+
+    let source = match build_plan_source(&plan, config, get_source_options(args)) {
+        Ok(source) => source,
+        Err(error) => {
+            print_error(error, args, output_format, Some(config));
+            exit(1);
+        }
+    };
+
+    let skipped_commits = vec![];
+    let mut output = Output::new(args, Some(config), &skipped_commits);
+    for planned_tag in &plan.tags {
+        output.version_bumps.push(OutputVersionBump {
+            scope: planned_tag.scope.clone(),
+            old_version: planned_tag.old_version.clone(),
+            new_version: planned_tag.new_version.clone(),
+            increment_kind: None,
+            reason: None,
+            non_conforming_commits: vec![],
+            commit_threshold_reached: false,
+            provenance: vec![],
+        });
+    }
+
+    if plan.tags.is_empty() {
+        logging::info("plan does not contain tags to create");
+    }
+
+    if args.dry_run {
+        report_planned_tags(&plan.tags, &mut output, output_format);
+    } else if let Err(error) = create_tags(
+        &source,
+        &plan.tags,
+        args.ledger_file.as_deref(),
+        &mut output,
+    ) {
+        print_error(error, args, output_format, Some(config));
+        exit(1);
+    }
+
+    print_output(&mut output, args, output_format);
+    match output_format {
+        OutputFormat::Text | OutputFormat::Json => {}
+        OutputFormat::Matrix => print_matrix(&plan.tags),
+        OutputFormat::Template(template) => {
+            for planned_tag in plan.tags.iter().filter(|tag| !tag.is_run_tag()) {
+                println!(
+                    "{}",
+                    render_output_template(
+                        template,
+                        &planned_tag.scope,
+                        &planned_tag.old_version,
+                        &planned_tag.new_version,
+                        &planned_tag.name,
+                    )
+                );
+            }
+        }
+    }
+}
+
+/// Approves the given plan file by writing an approval file signed with the approval key.
+///
+/// # Arguments
+///
+/// * `plan_file` - Path of the plan file.
+///
+/// * `out` - Path of the approval file.
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `output_format` - Output format used to print the result.
+///
+pub(crate) fn approve_plan(
+    plan_file: &Path,
+    out: &Path,
+    args: &Args,
+    config: &Config,
+    output_format: &OutputFormat,
+) {
+    let approval_key = match &args.approval_key {
+        Some(approval_key) => approval_key,
+        None => {
+            let error = Error::new(
+                ErrorKind::PlanNotApproved,
+                Some("missing approval key, use the `--approval-key` argument"),
+            );
+            print_error(error, args, output_format, Some(config));
+            exit(1);
+        }
+    };
+
+    let result = approve_plan_file(plan_file, approval_key)
+        .and_then(|approval| write_approval_file(&approval, out));
+    if let Err(error) = result {
+        print_error(error, args, output_format, Some(config));
+        exit(1);
+    }
+
+    logging::info(&format!("approval written to {}", out.display()));
+}
+
+/// Type that holds the inputs of the `promote-env` subcommand.
+pub(crate) struct Promotion<'a> {
+    /// Namespace of the existing tag.
+    pub(crate) from: &'a str,
+    /// Namespace of the new tag.
+    pub(crate) to: &'a str,
+    /// Version that is promoted.
+    pub(crate) version: &'a str,
+    /// Scope of the version.
+    pub(crate) scope: &'a str,
+}
+
+/// Promotes a version between tag namespaces, creating the tag of the target namespace, and its release if
+/// `--create-release` is used, on the commit referenced by the tag of the source namespace.
+///
+/// # Arguments
+///
+/// * `source` - Source used to resolve the existing tag and create the new one.
+///
+/// * `promotion` - Inputs of the `promote-env` subcommand.
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `output_format` - Output format used to print the result.
+///
+pub(crate) fn promote_env(
+    source: &SourceKind,
+    promotion: &Promotion,
+    args: &Args,
+    config: &Config,
+    output_format: &OutputFormat,
+) {
+    if args.tag_namespace.is_some() {
+        let error = Error::new(
+            ErrorKind::InvalidTagNamespace,
+            Some("`--tag-namespace` can not be used with `promote-env`, use `--from` and `--to` instead"),
+        );
+        print_error(error, args, output_format, Some(config));
+        exit(1);
+    }
+
+    let result = parse_user_version(promotion.version, "--version").and_then(|version| {
+        let from_tag =
+            render_namespace_tag_name(config, promotion.from, promotion.scope, &version)?;
+        let to_tag = render_namespace_tag_name(config, promotion.to, promotion.scope, &version)?;
+        Ok((version, from_tag, to_tag))
+    });
+    let (version, from_tag, to_tag) = match result {
+        Ok(result) => result,
+        Err(error) => {
+            print_error(error, args, output_format, Some(config));
+            exit(1);
+        }
+    };
+
+    let commit_sha = match source.resolve_ref(&from_tag) {
+        Ok(commit_sha) => commit_sha,
+        Err(_) => {
+            let error = Error::new(
+                ErrorKind::Other,
+                Some(&format!(
+                    "the tag {} of the version to promote does not exist",
+                    from_tag
+                )),
+            );
+            print_error(error, args, output_format, Some(config));
+            exit(1);
+        }
+    };
+    if source.resolve_ref(&to_tag).is_ok() {
+        let error = Error::new(
+            ErrorKind::Other,
+            Some(&format!("the tag {} already exists", to_tag)),
+        );
+        print_error(error, args, output_format, Some(config));
+        exit(1);
+    }
+
+    let version = version.to_string();
+    let message = config
+        .new_tag_message
+        .replace("{scope}", promotion.scope)
+        .replace("{version}", &version);
+    let release = match args.create_release {
+        true => Some(PlannedRelease {
+            title: config
+                .new_release_title
+                .replace("{scope}", promotion.scope)
+                .replace("{version}", &version),
+            body: format!("Promotion of {} to {}.\n", from_tag, to_tag),
+        }),
+        false => None,
+    };
+    let planned_tag = PlannedTag {
+        name: to_tag,
+        message,
+        commit_sha,
+        scope: promotion.scope.to_owned(),
+        old_version: version.clone(),
+        new_version: version,
+        release,
+    };
+
+    let skipped_commits = vec![];
+    let mut output = Output::new(args, Some(config), &skipped_commits);
+    if args.dry_run {
+        report_planned_tags(&[planned_tag], &mut output, output_format);
+    } else if let Err(error) = create_tags(
+        source,
+        &[planned_tag],
+        args.ledger_file.as_deref(),
+        &mut output,
+    ) {
+        print_error(error, args, output_format, Some(config));
+        exit(1);
+    }
+    print_output(&mut output, args, output_format);
+}
+
+/// Renders the name of the tag of the given namespace, scope and version from the tag pattern of the scope.
+///
+/// # Arguments
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `namespace` - Namespace of the tag.
+///
+/// * `scope` - Scope of the tag, empty for the empty scope.
+///
+/// * `version` - Version of the tag.
+///
+/// # Errors
+///
+/// Returns the errors of `Config::render_scope_tag_name`.
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the tag name can not be rendered from the tag
+/// pattern.
+///
+fn render_namespace_tag_name(
+    config: &Config,
+    namespace: &str,
+    scope: &str,
+    version: &Version,
+) -> Result<String, Error> {
+    match config.render_scope_tag_name(scope, version, Some(namespace))? {
+        Some(tag_name) => Ok(tag_name),
+        None => Err(Error::new(
+            ErrorKind::Other,
+            Some(&format!(
+                "the tag name of version {} can not be rendered from the tag pattern '{}'",
+                version,
+                config.scope_tag_pattern(scope)
+            )),
+        )),
+    }
+}
@@ -1,26 +1,47 @@
 //! This module provides functions for working with configuration files.
 //!
 //! The `Config` struct represents the structure of the configuration file.
-//! The `is_config_available` function checks if a configuration file exists in the current directory.
+//! The `is_config_available` function checks if a configuration file exists in the repository directory or in its
+//! parent directories up to the root of the git repository.
 //! The `parse_config_file` function parses a configuration file and returns a `Config` object.
 //!
 
-use crate::error::Error;
+use crate::bump::{IgnoreRule, IgnoredCommit};
+use crate::error::{Error, ErrorKind};
+use crate::git::{repo_path, Commit, Tag};
+use crate::parsing::{
+    matches_pattern, namespace_tag_pattern, normalize_scope, parse_commit_details,
+    parse_tag_details, render_tag_name, validate_scope, TagDetails,
+};
+use crate::source::SourceName;
 use crate::version::IncrementKind;
+use semver::Version;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
     fs::{self, File},
     io::Read,
     path::PathBuf,
     vec,
 };
 
+/// Names of the configuration files, in order of precedence when several of them are in the same directory.
+const CONFIG_FILE_NAMES: [&str; 4] = ["track.yml", "track.yaml", "track.toml", "track.json"];
+
+/// Name of the directory, or file for worktrees and submodules, that marks the root of a git repository.
+const GIT_DIR_NAME: &str = ".git";
+
+/// Start of the `scope` capturing group of the tag patterns.
+const SCOPE_GROUP_START: &str = "(?<scope>";
+
 /// Default Regex pattern used to validate tags and extract the version from it.
 const DEFAULT_TAG_PATTERN: &str = r"(?<version>.*)";
 
 /// Default Regex pattern used to validate conventional commits and extract the required fields from it.
+/// The scope can not contain parentheses or line breaks, so the breaking indicator (`!`) placed between the
+/// scope and the colon, as in `feat(api)!: description`, is always captured by the `breaking` group.
 const DEFAULT_COMMIT_PATTERN: &str =
-    r"^(?<type>[a-zA-Z]*)(?<scope>\(.*\))?(?<breaking>!)?:(?<description>[\s\S]*)$";
+    r"^(?<type>[a-zA-Z]+)(?<scope>\([^()\r\n]*\))?(?<breaking>!)?:(?<description>[\s\S]*)$";
 
 /// Default version scopes.
 const DEFAULT_VERSION_SCOPES: [String; 1] = [String::new()];
@@ -28,6 +49,52 @@ const DEFAULT_VERSION_SCOPES: [String; 1] = [String::new()];
 /// Default message used when creating a new tag.
 const DEFAULT_NEW_TAG_MESSAGE: &str = "Version {version}";
 
+/// Default title used when creating a new release.
+const DEFAULT_NEW_RELEASE_TITLE: &str = "Version {version}";
+
+/// Default identifier used for the pre-release section when a `prerelease` bump is applied to a release version.
+const DEFAULT_PRERELEASE_IDENTIFIER: &str = "rc";
+
+/// Default maximum length in bytes of commit messages. Longer messages are truncated.
+const DEFAULT_MAX_COMMIT_MESSAGE_LENGTH: usize = 4096;
+
+/// Default version of the first tag of the scopes without tags.
+const DEFAULT_INITIAL_VERSION: &str = "0.1.0";
+
+/// Default name of the tag created for the aggregated release when the empty scope has no new tag.
+const DEFAULT_RUN_TAG_NAME: &str = "release-{date}-{short_sha}";
+
+/// Default Regex pattern used to match release branches and extract their release line.
+const DEFAULT_RELEASE_BRANCH_PATTERN: &str = r"^release/(?<line>.+)$";
+
+/// Default Regex pattern that the version scopes must match.
+const DEFAULT_SCOPE_PATTERN: &str = r"^[A-Za-z0-9._-]*$";
+
+/// Default maximum length in bytes of the version scopes.
+const DEFAULT_SCOPE_MAX_LENGTH: usize = 64;
+
+/// Default lockfiles used to detect dependency update commits.
+const DEFAULT_DEPENDENCY_LOCKFILES: [&str; 2] = ["Cargo.lock", "package-lock.json"];
+
+fn get_default_dependency_lockfiles() -> Vec<String> {
+    DEFAULT_DEPENDENCY_LOCKFILES
+        .iter()
+        .map(|lockfile| lockfile.to_string())
+        .collect()
+}
+
+fn get_default_dependency_bump() -> bool {
+    true
+}
+
+fn get_default_scope_pattern() -> String {
+    DEFAULT_SCOPE_PATTERN.to_owned()
+}
+
+fn get_default_scope_max_length() -> usize {
+    DEFAULT_SCOPE_MAX_LENGTH
+}
+
 fn get_default_bump_rules() -> Vec<BumpRule> {
     vec![
         BumpRule {
@@ -78,12 +145,114 @@ pub struct ParsedConfig {
     /// The different commit scopes that have different versions.
     pub version_scopes: Option<Vec<String>>,
 
+    /// Directory of each version scope in the repository, used to select the commits of the scope by the paths they
+    /// change.
+    pub scope_paths: Option<BTreeMap<String, String>>,
+
+    /// Template of the names of the new tags.
+    pub new_tag_name: Option<String>,
+
     /// The tag name used when creating a new tag.
     pub new_tag_message: Option<String>,
+
+    /// The title used when creating a new release.
+    pub new_release_title: Option<String>,
+
+    /// Name of the tag created for the aggregated release when the empty scope has no new tag.
+    pub run_tag_name: Option<String>,
+
+    /// Version of the first tag of the scopes without tags.
+    pub initial_version: Option<String>,
+
+    /// Path of the template used to render release notes.
+    pub release_notes_template: Option<PathBuf>,
+
+    /// Maximum length in bytes of commit messages. Longer messages are truncated before being parsed.
+    pub max_commit_message_length: Option<usize>,
+
+    /// If `true`, the `apply` subcommand requires a valid approval file before creating tags.
+    pub require_plan_approval: Option<bool>,
+
+    /// Identifier used for the pre-release section when a `prerelease` bump is applied to a release version.
+    pub prerelease_identifier: Option<String>,
+
+    /// How major, minor and patch bumps are applied when the closest tag is a pre-release.
+    pub prerelease_strategy: Option<PrereleaseStrategy>,
+
+    /// Key used to sign tags created with `--sign-tag`.
+    pub signing_key: Option<String>,
+
+    /// Type of the created tags.
+    pub tag_type: Option<TagType>,
+
+    /// Calendar epoch used as the major section of the versions.
+    pub version_epoch: Option<VersionEpoch>,
+
+    /// Commits of the history used to calculate the version bump.
+    pub traversal: Option<Traversal>,
+
+    /// Ordered list of sources. If a source returns a retryable error, the next source is used.
+    pub sources: Option<Vec<SourceName>>,
+
+    /// Detection of commits that only update dependency lockfiles.
+    pub dependency_updates: Option<DependencyUpdates>,
+
+    /// The pattern used to match release branches and extract their release line from the `line` capture group.
+    pub release_branch_pattern: Option<String>,
+
+    /// Version bump triggered by commits that do not match the commit pattern.
+    pub non_conforming_commit_bump: Option<NonConformingCommitBump>,
+
+    /// Number of commits since the closest tag of a scope from which a patch bump is forced.
+    pub patch_bump_commit_threshold: Option<usize>,
+
+    /// Validation and normalization rules of the scopes.
+    pub scope_rules: Option<ScopeRules>,
+
+    /// Bump rules, commit pattern and tag pattern of each version scope that override the top-level ones.
+    pub scopes: Option<BTreeMap<String, ParsedScopeConfig>>,
+
+    /// Patterns of the messages of the commits that are ignored when calculating the version bump.
+    pub ignore_commit_patterns: Option<Vec<String>>,
+
+    /// Authors of the commits that are ignored when calculating the version bump.
+    pub ignore_authors: Option<Vec<String>>,
+
+    /// Types of the commits that are ignored when calculating the version bump.
+    pub ignore_types: Option<Vec<String>>,
+}
+
+/// Type used to parse the fields of a version scope that override the top-level fields of the configuration file.
+#[derive(Debug, Deserialize)]
+pub struct ParsedScopeConfig {
+    /// Bump rules used to calculate the version bumps of the scope.
+    pub bump_rules: Option<Vec<BumpRule>>,
+
+    /// Pattern used to parse the commits of the scope.
+    pub commit_pattern: Option<String>,
+
+    /// Pattern used to parse the tags of the scope.
+    pub tag_pattern: Option<String>,
+}
+
+/// Type to represent the fields of a version scope, with the top-level values of the configuration for the fields
+/// that the scope does not override.
+#[derive(Debug, Serialize, Clone)]
+pub struct ScopeConfig {
+    /// Bump rules used to calculate the version bumps of the scope.
+    pub bump_rules: Vec<BumpRule>,
+
+    /// Pattern used to parse the commits of the scope. Commits matching it belong to the scope, regardless of the
+    /// `scope` capturing group of the top-level commit pattern.
+    pub commit_pattern: String,
+
+    /// Pattern used to parse the tags of the scope. Tags matching it belong to the scope, regardless of the `scope`
+    /// capturing group of the top-level tag pattern.
+    pub tag_pattern: String,
 }
 
 /// Type to represent the rules for bumping the version number.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct BumpRule {
     /// Which version field should be bumped if the rule triggers.
     pub bump: IncrementKind,
@@ -101,6 +270,110 @@ pub struct BumpRule {
     pub if_breaking_description: Option<bool>,
 }
 
+/// Type to represent how commits that only update dependency lockfiles are detected and handled.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DependencyUpdates {
+    /// File names of the lockfiles. A commit is a dependency update if all the paths it changes have one of these
+    /// file names.
+    #[serde(default = "get_default_dependency_lockfiles")]
+    pub lockfiles: Vec<String>,
+
+    /// Use `false` if dependency update commits should not be used to calculate the version bump.
+    #[serde(default = "get_default_dependency_bump")]
+    pub bump: bool,
+}
+
+/// Type to represent the validation and normalization rules of the scopes. Scopes are embedded in tag names, file
+/// paths and release titles, so the version scopes must match the rules.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ScopeRules {
+    /// Pattern that the version scopes must match, after being normalized.
+    #[serde(default = "get_default_scope_pattern")]
+    pub pattern: String,
+
+    /// Maximum length in bytes of the version scopes.
+    #[serde(default = "get_default_scope_max_length")]
+    pub max_length: usize,
+
+    /// Case applied to the scopes of the configuration, commits and tags when they are parsed.
+    #[serde(default)]
+    pub case: ScopeCase,
+}
+
+impl Default for ScopeRules {
+    fn default() -> Self {
+        Self {
+            pattern: DEFAULT_SCOPE_PATTERN.to_owned(),
+            max_length: DEFAULT_SCOPE_MAX_LENGTH,
+            case: ScopeCase::Preserve,
+        }
+    }
+}
+
+/// Type to represent the case applied to the scopes when they are parsed.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ScopeCase {
+    /// Scopes keep their case, so `API` and `api` are different scopes.
+    #[default]
+    Preserve,
+    /// Scopes are converted to lowercase, so `API` and `api` are the same scope.
+    Lowercase,
+}
+
+/// Type to represent the version bump triggered by commits that do not match the commit pattern.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum NonConformingCommitBump {
+    /// Commits that do not match the commit pattern trigger a patch bump.
+    Patch,
+    /// Commits that do not match the commit pattern are ignored.
+    None,
+}
+
+/// Type to represent how major, minor and patch bumps are applied when the closest tag is a pre-release.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PrereleaseStrategy {
+    /// The bump is applied as with release versions, `1.4.0-rc.2` becomes `1.4.1` with a patch bump.
+    Ignore,
+    /// The pre-release series continues if the bump is already included in its release, `1.4.0-rc.2` becomes
+    /// `1.4.0-rc.3` with a patch or minor bump and `2.0.0-rc.1` with a major bump.
+    Continue,
+    /// The pre-release is finalized if the bump is already included in its release, `1.4.0-rc.2` becomes `1.4.0`
+    /// with a patch or minor bump and `2.0.0` with a major bump.
+    Finalize,
+}
+
+/// Type to represent the type of the created tags.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TagType {
+    /// Tag object with a message and a tagger, referenced by the tag ref.
+    Annotated,
+    /// Tag ref that references the commit directly, without a tag object.
+    Lightweight,
+}
+
+/// Type to represent the calendar epoch used as the major section of the versions.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum VersionEpoch {
+    /// The major section is the UTC year of the run. The first bump of a year resets the version to `<year>.0.0`,
+    /// and major bumps are applied as minor bumps within a year.
+    Year,
+}
+
+/// Type to represent the commits of the history used to calculate the version bump.
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Traversal {
+    /// Only the commits of the first-parent chain of the starting commit, such as the merge commits of the main line.
+    FirstParent,
+    /// Every commit reachable from the starting commit, including the commits of merged branches.
+    All,
+}
+
 /// Type used to add default fields to the missing configuration field fields.
 #[derive(Debug, Serialize)]
 pub struct Config {
@@ -121,8 +394,103 @@ pub struct Config {
     /// The different commit scopes that have different versions.
     pub version_scopes: Vec<String>,
 
+    /// Directory of each version scope in the repository. The GitHub source only requests the commits that changed
+    /// the directory of a scope to calculate its version bump, and those commits count for the scope regardless of
+    /// the scope of their message.
+    pub scope_paths: BTreeMap<String, String>,
+
+    /// Template of the names of the new tags, with the `{scope}` and `{version}` placeholders. If `None`, the old
+    /// version is replaced by the new one in the name of the closest tag of the scope.
+    pub new_tag_name: Option<String>,
+
     /// The tag name used when creating a new tag.
     pub new_tag_message: String,
+
+    /// The title used when creating a new release.
+    pub new_release_title: String,
+
+    /// Name of the tag created for the aggregated release when the empty scope has no new tag. It can contain the
+    /// `{date}` and `{short_sha}` placeholders.
+    pub run_tag_name: String,
+
+    /// Version of the first tag of the scopes without tags. When a scope has no tag, the whole history is used to
+    /// calculate its version bump.
+    pub initial_version: String,
+
+    /// Path of the template used to render release notes, relative to the repository directory. If `None`, release
+    /// notes are rendered with the built-in format.
+    pub release_notes_template: Option<PathBuf>,
+
+    /// Maximum length in bytes of commit messages. Longer messages are truncated before being parsed.
+    pub max_commit_message_length: usize,
+
+    /// If `true`, the `apply` subcommand requires a valid approval file before creating tags.
+    pub require_plan_approval: bool,
+
+    /// Identifier used for the pre-release section when a `prerelease` bump is applied to a release version.
+    pub prerelease_identifier: String,
+
+    /// How major, minor and patch bumps are applied when the closest tag is a pre-release.
+    pub prerelease_strategy: PrereleaseStrategy,
+
+    /// Key used to sign tags created with `--sign-tag`. If `None`, the `user.signingkey` git option is used.
+    pub signing_key: Option<String>,
+
+    /// Type of the created tags.
+    pub tag_type: TagType,
+
+    /// Calendar epoch used as the major section of the versions. If `None`, versions follow SemVer only.
+    pub version_epoch: Option<VersionEpoch>,
+
+    /// Commits of the history used to calculate the version bump.
+    pub traversal: Traversal,
+
+    /// Ordered list of sources. If a source returns a retryable error, the next source is used. If empty, the
+    /// source is selected from the CLI arguments.
+    pub sources: Vec<SourceName>,
+
+    /// Detection of commits that only update dependency lockfiles. If `None`, the changed paths of commits are not
+    /// requested from the sources and dependency updates are not detected.
+    pub dependency_updates: Option<DependencyUpdates>,
+
+    /// The pattern used to match release branches and extract their release line from the `line` capture group.
+    pub release_branch_pattern: String,
+
+    /// Version bump triggered by commits that do not match the commit pattern. As their scope is unknown, they count
+    /// for every scope whose closest tag is older than them.
+    pub non_conforming_commit_bump: NonConformingCommitBump,
+
+    /// If the number of commits since the closest tag of a scope is greater than this threshold, at least a patch bump
+    /// is applied. If `None`, no bump is forced.
+    pub patch_bump_commit_threshold: Option<usize>,
+
+    /// Validation and normalization rules of the scopes.
+    pub scope_rules: ScopeRules,
+
+    /// Fields of the version scopes that override the top-level bump rules, commit pattern or tag pattern. Use the
+    /// `scope_bump_rules`, `scope_commit_pattern` and `scope_tag_pattern` functions to read the fields of any scope.
+    pub scopes: BTreeMap<String, ScopeConfig>,
+
+    /// Regex patterns matched against the whole message of the commits. Matching commits are ignored when
+    /// calculating the version bump.
+    pub ignore_commit_patterns: Vec<String>,
+
+    /// Authors of the commits that are ignored when calculating the version bump, matched against the whole author
+    /// in the format `name <email>`, its name or its email.
+    pub ignore_authors: Vec<String>,
+
+    /// Types of the commits that are ignored when calculating the version bump.
+    pub ignore_types: Vec<String>,
+
+    /// Tags read from the extra tags file, merged with the tags of the sources. They are not part of the
+    /// configuration file.
+    #[serde(skip)]
+    pub extra_tags: Vec<Tag>,
+
+    /// Paths of the fields of the configuration file that are not known, such as `bump_ruls` or
+    /// `bump_rules.0.typo`. They are ignored when the configuration is loaded.
+    #[serde(skip)]
+    pub unknown_fields: Vec<String>,
 }
 
 impl From<ParsedConfig> for Config {
@@ -143,6 +511,26 @@ impl From<ParsedConfig> for Config {
             None => get_default_bump_rules(),
         };
 
+        let scopes = parsed_config
+            .scopes
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(scope, scope_config)| {
+                let scope_config = ScopeConfig {
+                    bump_rules: scope_config
+                        .bump_rules
+                        .unwrap_or_else(|| bump_rules.clone()),
+                    commit_pattern: scope_config
+                        .commit_pattern
+                        .unwrap_or_else(|| commit_pattern.clone()),
+                    tag_pattern: scope_config
+                        .tag_pattern
+                        .unwrap_or_else(|| tag_pattern.clone()),
+                };
+                (scope, scope_config)
+            })
+            .collect();
+
         let version_scopes: Vec<String> = match parsed_config.version_scopes {
             Some(version_scopes) => version_scopes,
             None => DEFAULT_VERSION_SCOPES.to_vec(),
@@ -153,17 +541,449 @@ impl From<ParsedConfig> for Config {
             None => DEFAULT_NEW_TAG_MESSAGE.to_owned(),
         };
 
+        let new_release_title = match parsed_config.new_release_title {
+            Some(new_release_title) => new_release_title,
+            None => DEFAULT_NEW_RELEASE_TITLE.to_owned(),
+        };
+
+        let max_commit_message_length = match parsed_config.max_commit_message_length {
+            Some(max_commit_message_length) => max_commit_message_length,
+            None => DEFAULT_MAX_COMMIT_MESSAGE_LENGTH,
+        };
+
+        let require_plan_approval = parsed_config.require_plan_approval.unwrap_or(false);
+
+        let prerelease_identifier = match parsed_config.prerelease_identifier {
+            Some(prerelease_identifier) => prerelease_identifier,
+            None => DEFAULT_PRERELEASE_IDENTIFIER.to_owned(),
+        };
+
+        let sources = parsed_config.sources.unwrap_or_default();
+
+        let release_branch_pattern = match parsed_config.release_branch_pattern {
+            Some(release_branch_pattern) => release_branch_pattern,
+            None => DEFAULT_RELEASE_BRANCH_PATTERN.to_owned(),
+        };
+
         Self {
             tag_pattern,
             commit_pattern,
             bump_rules,
             version_scopes,
+            scope_paths: parsed_config.scope_paths.unwrap_or_default(),
+            new_tag_name: parsed_config.new_tag_name,
             new_tag_message,
+            new_release_title,
+            run_tag_name: parsed_config
+                .run_tag_name
+                .unwrap_or(DEFAULT_RUN_TAG_NAME.to_owned()),
+            initial_version: parsed_config
+                .initial_version
+                .unwrap_or(DEFAULT_INITIAL_VERSION.to_owned()),
+            release_notes_template: parsed_config.release_notes_template,
+            max_commit_message_length,
+            require_plan_approval,
+            prerelease_identifier,
+            prerelease_strategy: parsed_config
+                .prerelease_strategy
+                .unwrap_or(PrereleaseStrategy::Ignore),
+            signing_key: parsed_config.signing_key,
+            tag_type: parsed_config.tag_type.unwrap_or(TagType::Annotated),
+            version_epoch: parsed_config.version_epoch,
+            traversal: parsed_config.traversal.unwrap_or(Traversal::All),
+            sources,
+            dependency_updates: parsed_config.dependency_updates,
+            release_branch_pattern,
+            non_conforming_commit_bump: parsed_config
+                .non_conforming_commit_bump
+                .unwrap_or(NonConformingCommitBump::None),
+            patch_bump_commit_threshold: parsed_config.patch_bump_commit_threshold,
+            scope_rules: parsed_config.scope_rules.unwrap_or_default(),
+            scopes,
+            ignore_commit_patterns: parsed_config.ignore_commit_patterns.unwrap_or_default(),
+            ignore_authors: parsed_config.ignore_authors.unwrap_or_default(),
+            ignore_types: parsed_config.ignore_types.unwrap_or_default(),
+            extra_tags: vec![],
+            unknown_fields: vec![],
         }
     }
 }
 
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Config {
+    /// Normalizes the scopes of the version scopes, scope paths and bump rules with the case of the scope rules, and
+    /// validates the version scopes and the scopes of the scope paths against the scope rules.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `parsing::validate_scope`.
+    ///
+    pub fn apply_scope_rules(&mut self) -> Result<(), Error> {
+        let case = self.scope_rules.case;
+        for scope in &mut self.version_scopes {
+            *scope = normalize_scope(scope, case);
+            validate_scope(scope, &self.scope_rules)?;
+        }
+
+        let mut scope_paths = BTreeMap::new();
+        for (scope, path) in std::mem::take(&mut self.scope_paths) {
+            let scope = normalize_scope(&scope, case);
+            validate_scope(&scope, &self.scope_rules)?;
+            scope_paths.insert(scope, path);
+        }
+        self.scope_paths = scope_paths;
+
+        let mut scopes = BTreeMap::new();
+        for (scope, scope_config) in std::mem::take(&mut self.scopes) {
+            let scope = normalize_scope(&scope, case);
+            validate_scope(&scope, &self.scope_rules)?;
+            scopes.insert(scope, scope_config);
+        }
+        self.scopes = scopes;
+
+        let bump_rules = self.bump_rules.iter_mut().chain(
+            self.scopes
+                .values_mut()
+                .flat_map(|scope_config| scope_config.bump_rules.iter_mut()),
+        );
+        for scopes in bump_rules.filter_map(|rule| rule.scopes.as_mut()) {
+            for scope in scopes {
+                *scope = normalize_scope(scope, case);
+            }
+        }
+        Ok(())
+    }
+
+    /// Restricts the tag pattern to the tags of the given namespace, so the tags of each namespace are parsed and
+    /// created independently.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - Namespace of the tags, such as `staging` or `prod`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `parsing::namespace_tag_pattern`.
+    ///
+    pub fn apply_tag_namespace(&mut self, namespace: &str) -> Result<(), Error> {
+        self.tag_pattern = namespace_tag_pattern(&self.tag_pattern, namespace)?;
+        for scope_config in self.scopes.values_mut() {
+            scope_config.tag_pattern = namespace_tag_pattern(&scope_config.tag_pattern, namespace)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the bump rules of the given scope, which are the top-level bump rules unless the scope overrides them.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - Version scope, empty for the empty scope.
+    ///
+    pub fn scope_bump_rules(&self, scope: &str) -> &[BumpRule] {
+        match self.scopes.get(scope) {
+            Some(scope_config) => &scope_config.bump_rules,
+            None => &self.bump_rules,
+        }
+    }
+
+    /// Returns the commit pattern of the given scope, which is the top-level commit pattern unless the scope
+    /// overrides it.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - Version scope, empty for the empty scope.
+    ///
+    pub fn scope_commit_pattern(&self, scope: &str) -> &str {
+        match self.scopes.get(scope) {
+            Some(scope_config) => &scope_config.commit_pattern,
+            None => &self.commit_pattern,
+        }
+    }
+
+    /// Returns the tag pattern of the given scope, which is the top-level tag pattern unless the scope overrides it.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - Version scope, empty for the empty scope.
+    ///
+    pub fn scope_tag_pattern(&self, scope: &str) -> &str {
+        match self.scopes.get(scope) {
+            Some(scope_config) => &scope_config.tag_pattern,
+            None => &self.tag_pattern,
+        }
+    }
+
+    /// Renders the name of the tag of the given scope and version from the tag pattern of the scope, as
+    /// `parsing::render_tag_name`. Tag patterns that a scope overrides may omit the `scope` capturing group, as the
+    /// tags matching them belong to the scope.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - Scope of the tag, empty for the empty scope.
+    ///
+    /// * `version` - Version of the tag.
+    ///
+    /// * `namespace` - Namespace of the tag, if the tag pattern is restricted to a namespace.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `parsing::namespace_tag_pattern` and `parsing::render_tag_name`.
+    ///
+    pub fn render_scope_tag_name(
+        &self,
+        scope: &str,
+        version: &Version,
+        namespace: Option<&str>,
+    ) -> Result<Option<String>, Error> {
+        let has_own_pattern = self.scope_tag_pattern(scope) != self.tag_pattern;
+        let tag_pattern = match namespace {
+            Some(namespace) => namespace_tag_pattern(self.scope_tag_pattern(scope), namespace)?,
+            None => self.scope_tag_pattern(scope).to_owned(),
+        };
+        let case = self.scope_rules.case;
+        match render_tag_name(&tag_pattern, scope, version, case)? {
+            None if has_own_pattern && !tag_pattern.contains(SCOPE_GROUP_START) => {
+                render_tag_name(&tag_pattern, "", version, case)
+            }
+            tag_name => Ok(tag_name),
+        }
+    }
+
+    /// Renders the name of the new tag of the given scope and version from the `new_tag_name` template, replacing its
+    /// `{scope}` and `{version}` placeholders. Returns `None` if the template is not set.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - Scope of the tag, empty for the empty scope.
+    ///
+    /// * `version` - Version of the tag.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the rendered name is not parsed back into
+    /// the given scope and version with the tag pattern of the scope, as the tag would not be found by the next runs.
+    ///
+    /// Returns the regex errors of `parsing::parse_tag_details`.
+    ///
+    pub fn render_new_tag_name(
+        &self,
+        scope: &str,
+        version: &Version,
+    ) -> Result<Option<String>, Error> {
+        let template = match &self.new_tag_name {
+            Some(template) => template,
+            None => return Ok(None),
+        };
+        let tag_name = template
+            .replace("{scope}", scope)
+            .replace("{version}", &version.to_string());
+
+        let tag_pattern = self.scope_tag_pattern(scope);
+        let tag_details = match parse_tag_details(&tag_name, tag_pattern, self.scope_rules.case) {
+            Ok(tag_details) => tag_details,
+            Err(error)
+                if error.kind == ErrorKind::InvalidRegexPattern
+                    || error.kind == ErrorKind::RegexLimitExceeded =>
+            {
+                return Err(error)
+            }
+            // The captured version is not a valid semantic version.
+            Err(_) => None,
+        };
+        let is_parsed_back = tag_details.is_some_and(|details| {
+            details.version == *version
+                && match details.scope {
+                    Some(captured) => captured == scope,
+                    None => scope.is_empty() || tag_pattern != self.tag_pattern,
+                }
+        });
+        match is_parsed_back {
+            true => Ok(Some(tag_name)),
+            false => Err(Error::new(
+                ErrorKind::Other,
+                Some(&format!(
+                    "the tag name '{}' rendered from `new_tag_name` is not parsed back into the scope '{}' and \
+                     version {} with the tag pattern '{}', change `tag_pattern` so it matches the names rendered \
+                     from `new_tag_name`",
+                    tag_name, scope, version, tag_pattern
+                )),
+            )),
+        }
+    }
+
+    /// Returns `true` if any version scope overrides the top-level tag pattern.
+    pub fn has_scope_tag_patterns(&self) -> bool {
+        self.scopes
+            .values()
+            .any(|scope_config| scope_config.tag_pattern != self.tag_pattern)
+    }
+
+    /// Parses the given tags again with the tag patterns of the scopes that override the top-level tag pattern. Tags
+    /// matching the tag pattern of such a scope belong to that scope, unless the pattern captures another scope, and
+    /// tags that the top-level tag pattern attributes to such a scope without matching its tag pattern lose their
+    /// details. Scopes are tried by name. The tags are left unchanged if no scope overrides the tag pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `tags` - Tags parsed with the top-level tag pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `parsing::parse_tag_details`.
+    ///
+    pub fn resolve_scope_tags(&self, tags: &mut [Tag]) -> Result<(), Error> {
+        let scope_patterns: Vec<(&String, &str)> = self
+            .scopes
+            .iter()
+            .filter(|(_, scope_config)| scope_config.tag_pattern != self.tag_pattern)
+            .map(|(scope, scope_config)| (scope, scope_config.tag_pattern.as_str()))
+            .collect();
+        if scope_patterns.is_empty() {
+            return Ok(());
+        }
+
+        'tags: for tag in tags {
+            for (scope, tag_pattern) in &scope_patterns {
+                let details =
+                    match parse_tag_details(&tag.name, tag_pattern, self.scope_rules.case)? {
+                        Some(details) => details,
+                        None => continue,
+                    };
+                if details
+                    .scope
+                    .as_ref()
+                    .is_some_and(|captured| captured != *scope)
+                {
+                    continue;
+                }
+                tag.details = Some(TagDetails {
+                    version: details.version,
+                    scope: Some((*scope).clone()).filter(|scope| !scope.is_empty()),
+                });
+                continue 'tags;
+            }
+
+            let has_own_pattern = tag.details.as_ref().is_some_and(|details| {
+                let scope = details.scope.as_deref().unwrap_or_default();
+                scope_patterns
+                    .iter()
+                    .any(|(pattern_scope, _)| *pattern_scope == scope)
+            });
+            if has_own_pattern {
+                tag.details = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Parses the given commit again with the commit patterns of the scopes that override the top-level commit
+    /// pattern. A commit matching the commit pattern of such a scope belongs to that scope, unless the pattern
+    /// captures another scope, and a commit that the top-level commit pattern attributes to such a scope without
+    /// matching its commit pattern loses its details, as it does not conform to the commit pattern of its scope.
+    /// Scopes are tried by name. The commit is left unchanged if no scope overrides the commit pattern.
+    ///
+    /// # Arguments
+    ///
+    /// * `commit` - Commit parsed with the top-level commit pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `parsing::parse_commit_details`.
+    ///
+    pub fn resolve_scope_commit(&self, commit: &mut Commit) -> Result<(), Error> {
+        let scope_patterns = self
+            .scopes
+            .iter()
+            .filter(|(_, scope_config)| scope_config.commit_pattern != self.commit_pattern);
+        let mut has_scope_patterns = false;
+        for (scope, scope_config) in scope_patterns {
+            has_scope_patterns = true;
+            let mut details = match parse_commit_details(
+                &commit.message,
+                &scope_config.commit_pattern,
+                self.scope_rules.case,
+            )? {
+                Some(details) => details,
+                None => continue,
+            };
+            if details
+                .scope
+                .as_ref()
+                .is_some_and(|captured| captured != scope)
+            {
+                continue;
+            }
+            details.scope = Some(scope.clone()).filter(|scope| !scope.is_empty());
+            commit.details = Some(details);
+            return Ok(());
+        }
+
+        let scope = commit
+            .details
+            .as_ref()
+            .map(|details| details.scope.as_deref().unwrap_or_default());
+        if has_scope_patterns
+            && scope.is_some_and(|scope| self.scope_commit_pattern(scope) != self.commit_pattern)
+        {
+            commit.details = None;
+        }
+        Ok(())
+    }
+
+    /// Returns the ignored commit of the given commit if it matches an ignore rule, or `None` if the commit counts
+    /// for the version bump. The rules are checked in order: `ignore_commit_patterns` against the whole message,
+    /// `ignore_authors` against the author, its name or its email, and `ignore_types` against the commit type.
+    ///
+    /// # Arguments
+    ///
+    /// * `commit` - Commit parsed with the commit pattern of its scope.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `parsing::matches_pattern`.
+    ///
+    pub fn find_ignored_commit(&self, commit: &Commit) -> Result<Option<IgnoredCommit>, Error> {
+        for pattern in &self.ignore_commit_patterns {
+            if matches_pattern(&commit.message, pattern)? {
+                return Ok(Some(IgnoredCommit::new(
+                    commit,
+                    IgnoreRule::CommitPattern,
+                    pattern,
+                )));
+            }
+        }
+
+        if let Some(author) = &commit.author {
+            let (name, email) = match author.rsplit_once(" <") {
+                Some((name, email)) => (name, email.trim_end_matches('>')),
+                None => (author.as_str(), author.as_str()),
+            };
+            let ignored_author = self.ignore_authors.iter().find(|ignored_author| {
+                [author.as_str(), name, email].contains(&ignored_author.as_str())
+            });
+            if let Some(ignored_author) = ignored_author {
+                return Ok(Some(IgnoredCommit::new(
+                    commit,
+                    IgnoreRule::Author,
+                    ignored_author,
+                )));
+            }
+        }
+
+        let commit_type = commit.details.as_ref().map(|details| &details.commit_type);
+        let ignored_type = self
+            .ignore_types
+            .iter()
+            .find(|ignored_type| Some(*ignored_type) == commit_type);
+        Ok(ignored_type
+            .map(|ignored_type| IgnoredCommit::new(commit, IgnoreRule::Type, ignored_type)))
+    }
+
     /// Create a new instance of `Config` with default values.
     pub fn new() -> Config {
         Self {
@@ -171,7 +991,33 @@ impl Config {
             commit_pattern: DEFAULT_COMMIT_PATTERN.to_owned(),
             bump_rules: get_default_bump_rules(),
             version_scopes: DEFAULT_VERSION_SCOPES.to_vec(),
+            scope_paths: BTreeMap::new(),
+            new_tag_name: None,
             new_tag_message: DEFAULT_NEW_TAG_MESSAGE.to_owned(),
+            new_release_title: DEFAULT_NEW_RELEASE_TITLE.to_owned(),
+            run_tag_name: DEFAULT_RUN_TAG_NAME.to_owned(),
+            initial_version: DEFAULT_INITIAL_VERSION.to_owned(),
+            release_notes_template: None,
+            max_commit_message_length: DEFAULT_MAX_COMMIT_MESSAGE_LENGTH,
+            require_plan_approval: false,
+            prerelease_identifier: DEFAULT_PRERELEASE_IDENTIFIER.to_owned(),
+            prerelease_strategy: PrereleaseStrategy::Ignore,
+            signing_key: None,
+            tag_type: TagType::Annotated,
+            version_epoch: None,
+            traversal: Traversal::All,
+            sources: vec![],
+            dependency_updates: None,
+            release_branch_pattern: DEFAULT_RELEASE_BRANCH_PATTERN.to_owned(),
+            non_conforming_commit_bump: NonConformingCommitBump::None,
+            patch_bump_commit_threshold: None,
+            scope_rules: ScopeRules::default(),
+            scopes: BTreeMap::new(),
+            ignore_commit_patterns: vec![],
+            ignore_authors: vec![],
+            ignore_types: vec![],
+            extra_tags: vec![],
+            unknown_fields: vec![],
         }
     }
 }
@@ -184,24 +1030,34 @@ fn read_file(path: &PathBuf) -> Result<String, Error> {
     Ok(contents)
 }
 
-/// Checks if a configuration file exists in the current directory.
+/// Checks if a configuration file exists in the repository directory, which is the current directory unless
+/// `--repo-path` is given, or in one of its parent directories up to the root of the git repository, so Tag Track can
+/// be run from a subdirectory of the repository. The closest configuration file is used. Parent directories are not
+/// checked if the directory is not inside a git repository.
 ///
 /// Returns the path to the configuration file if it exists, or `None` otherwise.
 pub fn is_config_available() -> Option<PathBuf> {
-    const CONFIG_FILE_NAMES: [&str; 2] = ["track.yml", "track.yaml"];
-
-    for file_name in &CONFIG_FILE_NAMES {
-        let path = PathBuf::from(file_name);
-        if let Ok(metadata) = fs::metadata(&path) {
-            if metadata.is_file() {
-                return Some(path);
+    let start = fs::canonicalize(repo_path()).unwrap_or_else(|_| repo_path().to_path_buf());
+    let git_root_depth = start
+        .ancestors()
+        .position(|dir| dir.join(GIT_DIR_NAME).exists())
+        .unwrap_or_default();
+    for dir in start.ancestors().take(git_root_depth + 1) {
+        for file_name in &CONFIG_FILE_NAMES {
+            let path = dir.join(file_name);
+            if let Ok(metadata) = fs::metadata(&path) {
+                if metadata.is_file() {
+                    return Some(path);
+                }
             }
         }
     }
     None
 }
 
-/// Parses a configuration file and returns a `Config` object.
+/// Parses a configuration file and returns a `Config` object. The format of the file is detected from its extension:
+/// `.toml` files are parsed as TOML, `.json` files as JSON and any other file as YAML. All formats share the same
+/// fields and default values. Unknown fields are ignored and their paths are stored in `Config::unknown_fields`.
 ///
 /// # Arguments
 ///
@@ -213,6 +1069,197 @@ pub fn is_config_available() -> Option<PathBuf> {
 ///
 pub fn parse_config_file(file_path: PathBuf) -> Result<Config, Error> {
     let contents = read_file(&file_path)?;
-    let parsed_config: ParsedConfig = serde_yaml::from_str(&contents)?;
-    Ok(Config::from(parsed_config))
+    let mut unknown_fields = vec![];
+    let on_unknown_field = |field: serde_ignored::Path| unknown_fields.push(field.to_string());
+    let parsed_config: ParsedConfig = match file_path
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("toml") => {
+            serde_ignored::deserialize(toml::Deserializer::new(&contents), on_unknown_field)?
+        }
+        Some("json") => {
+            let mut deserializer = serde_json::Deserializer::from_str(&contents);
+            let parsed_config: ParsedConfig =
+                serde_ignored::deserialize(&mut deserializer, on_unknown_field)?;
+            deserializer.end()?;
+            parsed_config
+        }
+        _ => serde_ignored::deserialize(
+            serde_yaml::Deserializer::from_str(&contents),
+            on_unknown_field,
+        )?,
+    };
+    let mut config = Config::from(parsed_config);
+    config.unknown_fields = unknown_fields;
+    config.apply_scope_rules()?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, process};
+
+    /// Configuration whose `web` scope overrides the tag and commit patterns and whose `api` scope overrides the
+    /// bump rules.
+    const SCOPED_CONFIG: &str = r#"
+tag_pattern: '^(?<scope>[a-z]+)/v(?<version>.*)$'
+version_scopes: [api, web]
+scopes:
+  api:
+    bump_rules:
+      - bump: major
+        types: [feat]
+  web:
+    tag_pattern: '^web-(?<version>.*)$'
+    commit_pattern: '^\[web\] (?<type>[a-z]+): (?<description>.*)$'
+"#;
+
+    /// Writes a configuration file with the given name and contents to the temporary directory and parses it.
+    fn parse_config(name: &str, contents: &str) -> Config {
+        let dir = env::temp_dir().join(format!("tag-track-config-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, contents).unwrap();
+        parse_config_file(path).unwrap()
+    }
+
+    /// Returns the commit of the given message parsed with the top-level commit pattern of the configuration.
+    fn commit(config: &Config, message: &str) -> Commit {
+        Commit::new(
+            String::new(),
+            message,
+            None,
+            &config.commit_pattern,
+            ScopeCase::Preserve,
+            usize::MAX,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn scopes_override_top_level_fields() {
+        let config = parse_config("scoped.yml", SCOPED_CONFIG);
+
+        let api_rules = config.scope_bump_rules("api");
+        assert_eq!(api_rules.len(), 1);
+        assert_eq!(api_rules[0].bump, IncrementKind::Major);
+        assert_eq!(
+            config.scope_bump_rules("web").len(),
+            config.bump_rules.len()
+        );
+        assert_eq!(
+            config.scope_bump_rules("cli").len(),
+            config.bump_rules.len()
+        );
+
+        assert_eq!(config.scope_tag_pattern("api"), config.tag_pattern);
+        assert_eq!(config.scope_tag_pattern("web"), "^web-(?<version>.*)$");
+        assert_eq!(config.scope_commit_pattern("api"), config.commit_pattern);
+        assert!(config.has_scope_tag_patterns());
+
+        let version = Version::new(1, 3, 0);
+        let tag_name = |scope| config.render_scope_tag_name(scope, &version, None).unwrap();
+        assert_eq!(tag_name("api"), Some("api/v1.3.0".to_owned()));
+        assert_eq!(tag_name("web"), Some("web-1.3.0".to_owned()));
+    }
+
+    #[test]
+    fn tags_are_parsed_with_the_tag_pattern_of_their_scope() {
+        let config = parse_config("scoped.yml", SCOPED_CONFIG);
+        let mut tags: Vec<Tag> = ["web-1.2.0", "web/v1.0.0", "api/v2.0.0"]
+            .into_iter()
+            .map(|name| Tag {
+                commit_sha: String::new(),
+                name: name.to_owned(),
+                details: parse_tag_details(name, &config.tag_pattern, ScopeCase::Preserve).unwrap(),
+            })
+            .collect();
+        config.resolve_scope_tags(&mut tags).unwrap();
+
+        let details: Vec<Option<(String, String)>> = tags
+            .iter()
+            .map(|tag| {
+                let details = tag.details.as_ref()?;
+                Some((details.scope.clone()?, details.version.to_string()))
+            })
+            .collect();
+        assert_eq!(
+            details,
+            vec![
+                Some(("web".to_owned(), "1.2.0".to_owned())),
+                None,
+                Some(("api".to_owned(), "2.0.0".to_owned())),
+            ]
+        );
+    }
+
+    #[test]
+    fn commits_are_parsed_with_the_commit_pattern_of_their_scope() {
+        let config = parse_config("scoped.yml", SCOPED_CONFIG);
+        let scope = |message| {
+            let mut commit = commit(&config, message);
+            config.resolve_scope_commit(&mut commit).unwrap();
+            commit
+                .details
+                .map(|details| (details.commit_type, details.scope))
+        };
+
+        assert_eq!(
+            scope("[web] feat: add a page"),
+            Some(("feat".to_owned(), Some("web".to_owned())))
+        );
+        assert_eq!(scope("feat(web): add a page"), None);
+        assert_eq!(
+            scope("fix(api): handle errors"),
+            Some(("fix".to_owned(), Some("api".to_owned())))
+        );
+    }
+
+    #[test]
+    fn ignore_rules_are_checked_in_order() {
+        let config = parse_config(
+            "ignore.yml",
+            "ignore_commit_patterns: ['^chore\\(release\\)']\n\
+             ignore_authors: ['dependabot[bot]', ci@example.com]\n\
+             ignore_types: [chore, docs]\n",
+        );
+        let ignored = |message, author: Option<&str>| {
+            let mut commit = commit(&config, message);
+            commit.author = author.map(str::to_owned);
+            config
+                .find_ignored_commit(&commit)
+                .unwrap()
+                .map(|ignored| (ignored.rule, ignored.matched))
+        };
+
+        assert_eq!(
+            ignored(
+                "chore(release): 1.0.0",
+                Some("dependabot[bot] <bot@example.com>")
+            ),
+            Some((IgnoreRule::CommitPattern, "^chore\\(release\\)".to_owned()))
+        );
+        assert_eq!(
+            ignored(
+                "chore: bump deps",
+                Some("dependabot[bot] <bot@example.com>")
+            ),
+            Some((IgnoreRule::Author, "dependabot[bot]".to_owned()))
+        );
+        assert_eq!(
+            ignored("feat: add a page", Some("CI <ci@example.com>")),
+            Some((IgnoreRule::Author, "ci@example.com".to_owned()))
+        );
+        assert_eq!(
+            ignored("docs: fix typo", None),
+            Some((IgnoreRule::Type, "docs".to_owned()))
+        );
+        assert_eq!(
+            ignored("feat: add a page", Some("Jane <jane@example.com>")),
+            None
+        );
+        assert_eq!(ignored("not conventional", None), None);
+    }
 }
@@ -0,0 +1,366 @@
+//! This module provides the comparison of two report files, used to validate configuration changes before merging
+//! them. A report file is either the JSON output of a run or a plan file generated by the `plan` subcommand.
+//!
+//! The computed version of each scope is compared between both files, and the scopes whose computed version changed
+//! are reported together with the differences that explain the change, such as a different closest version or a
+//! different increment kind. Plan files only contain the scopes with a new tag and do not include the increment
+//! kinds, so the reasons are less detailed when comparing them.
+//!
+
+use crate::error::{Error, ErrorKind};
+use crate::plan::PlannedTag;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+
+/// Type used to deserialize the fields of a report file required by the comparison. Output files contain the
+/// `version_bumps` field and plan files the `tags` field.
+#[derive(Debug, Deserialize)]
+struct ReportFile {
+    version_bumps: Option<Vec<ReportedVersionBump>>,
+    tags: Option<Vec<PlannedTag>>,
+}
+
+/// Type used to deserialize the version bumps of an output file.
+#[derive(Debug, Deserialize)]
+struct ReportedVersionBump {
+    scope: String,
+    old_version: String,
+    new_version: String,
+    increment_kind: Option<String>,
+    reason: Option<String>,
+    #[serde(default)]
+    non_conforming_commits: Vec<String>,
+    #[serde(default)]
+    commit_threshold_reached: bool,
+}
+
+/// Type that represents the result of a scope in a report file.
+#[derive(Debug)]
+struct ScopeResult {
+    /// Version of the closest tag of the scope.
+    old_version: String,
+    /// Computed version of the scope.
+    new_version: String,
+    /// Kind of increment that was applied, `None` if unknown or if the version was not bumped.
+    increment_kind: Option<String>,
+    /// Reason why the version was not bumped, if known.
+    reason: Option<String>,
+    /// Number of commits that do not match the commit pattern and were counted as a patch bump.
+    non_conforming_commits: usize,
+    /// If a patch bump was forced by `patch_bump_commit_threshold`.
+    commit_threshold_reached: bool,
+}
+
+/// Type that represents how the computed version of a scope differs between two report files.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScopeDiffKind {
+    /// The scope is only present in the new file.
+    Added,
+    /// The scope is only present in the old file.
+    Removed,
+    /// The computed version of the scope is different in both files.
+    Changed,
+}
+
+/// Type that represents a scope whose computed version differs between two report files.
+#[derive(Debug, Serialize)]
+pub struct ScopeDiff {
+    /// Scope of the version.
+    pub scope: String,
+    /// How the computed version differs.
+    pub kind: ScopeDiffKind,
+    /// Computed version of the old file, `None` if the scope is not present in it.
+    pub old_computed_version: Option<String>,
+    /// Computed version of the new file, `None` if the scope is not present in it.
+    pub new_computed_version: Option<String>,
+    /// Differences between both files that explain the change.
+    pub reasons: Vec<String>,
+}
+
+/// Type that represents the comparison of two report files.
+#[derive(Debug, Serialize)]
+pub struct DiffReport {
+    /// Scopes whose computed version differs, in scope order.
+    pub scope_diffs: Vec<ScopeDiff>,
+    /// Scopes with the same computed version in both files, in scope order.
+    pub unchanged_scopes: Vec<String>,
+}
+
+impl DiffReport {
+    /// Returns `true` if the computed version of at least one scope differs.
+    pub fn has_changes(&self) -> bool {
+        !self.scope_diffs.is_empty()
+    }
+}
+
+/// Compares the computed versions of the scopes of the given report files.
+///
+/// # Arguments
+///
+/// * `old_path` - Path of the report file used as baseline.
+///
+/// * `new_path` - Path of the report file compared against the baseline.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidReportFile` if a file is neither an output file
+/// with the `version_bumps` field nor a plan file.
+///
+pub fn diff_report_files(old_path: &Path, new_path: &Path) -> Result<DiffReport, Error> {
+    let old_results = read_report_file(old_path)?;
+    let mut new_results = read_report_file(new_path)?;
+
+    let mut scope_diffs = vec![];
+    let mut unchanged_scopes = vec![];
+    for (scope, old_result) in old_results {
+        let new_result = match new_results.remove(&scope) {
+            Some(new_result) => new_result,
+            None => {
+                scope_diffs.push(ScopeDiff {
+                    old_computed_version: Some(old_result.new_version),
+                    new_computed_version: None,
+                    reasons: vec!["the scope is not present in the new file".to_owned()],
+                    kind: ScopeDiffKind::Removed,
+                    scope,
+                });
+                continue;
+            }
+        };
+
+        if old_result.new_version == new_result.new_version {
+            unchanged_scopes.push(scope);
+            continue;
+        }
+        scope_diffs.push(ScopeDiff {
+            reasons: describe_changes(&old_result, &new_result),
+            old_computed_version: Some(old_result.new_version),
+            new_computed_version: Some(new_result.new_version),
+            kind: ScopeDiffKind::Changed,
+            scope,
+        });
+    }
+
+    for (scope, new_result) in new_results {
+        scope_diffs.push(ScopeDiff {
+            old_computed_version: None,
+            new_computed_version: Some(new_result.new_version),
+            reasons: vec!["the scope is not present in the old file".to_owned()],
+            kind: ScopeDiffKind::Added,
+            scope,
+        });
+    }
+    scope_diffs.sort_by(|a, b| a.scope.cmp(&b.scope));
+
+    Ok(DiffReport {
+        scope_diffs,
+        unchanged_scopes,
+    })
+}
+
+/// Reads the result of each scope of the given report file.
+///
+/// # Arguments
+///
+/// * `path` - Path of the report file.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidReportFile` if the file is neither an output file
+/// with the `version_bumps` field nor a plan file.
+///
+fn read_report_file(path: &Path) -> Result<BTreeMap<String, ScopeResult>, Error> {
+    let contents = fs::read_to_string(path)?;
+    let report: ReportFile = match serde_json::from_str(&contents) {
+        Ok(report) => report,
+        Err(error) => {
+            return Err(Error::new(
+                ErrorKind::InvalidReportFile,
+                Some(&format!("{}: {}", path.display(), error)),
+            ))
+        }
+    };
+
+    let mut results = BTreeMap::new();
+    match (report.version_bumps, report.tags) {
+        (Some(version_bumps), _) => {
+            for version_bump in version_bumps {
+                results.insert(
+                    version_bump.scope,
+                    ScopeResult {
+                        old_version: version_bump.old_version,
+                        new_version: version_bump.new_version,
+                        increment_kind: version_bump.increment_kind,
+                        reason: version_bump.reason,
+                        non_conforming_commits: version_bump.non_conforming_commits.len(),
+                        commit_threshold_reached: version_bump.commit_threshold_reached,
+                    },
+                );
+            }
+        }
+        (None, Some(tags)) => {
+            for tag in tags.into_iter().filter(|tag| !tag.is_run_tag()) {
+                results.insert(
+                    tag.scope,
+                    ScopeResult {
+                        old_version: tag.old_version,
+                        new_version: tag.new_version,
+                        increment_kind: None,
+                        reason: None,
+                        non_conforming_commits: 0,
+                        commit_threshold_reached: false,
+                    },
+                );
+            }
+        }
+        (None, None) => {
+            return Err(Error::new(
+                ErrorKind::InvalidReportFile,
+                Some(&format!(
+                    "{} contains neither the `version_bumps` field of an output file nor the `tags` field of a \
+                     plan file",
+                    path.display()
+                )),
+            ))
+        }
+    }
+    Ok(results)
+}
+
+/// Returns the differences between the results of a scope that explain why its computed version changed.
+///
+/// # Arguments
+///
+/// * `old_result` - Result of the scope in the old file.
+///
+/// * `new_result` - Result of the scope in the new file.
+///
+fn describe_changes(old_result: &ScopeResult, new_result: &ScopeResult) -> Vec<String> {
+    let mut reasons = vec![];
+    if old_result.old_version != new_result.old_version {
+        reasons.push(format!(
+            "the closest version changed from {} to {}",
+            old_result.old_version, new_result.old_version
+        ));
+    }
+    if old_result.increment_kind != new_result.increment_kind {
+        reasons.push(format!(
+            "the increment kind changed from {} to {}",
+            old_result.increment_kind.as_deref().unwrap_or("none"),
+            new_result.increment_kind.as_deref().unwrap_or("none")
+        ));
+    }
+    if old_result.reason != new_result.reason {
+        reasons.push(format!(
+            "the reason for not bumping the version changed from {} to {}",
+            old_result.reason.as_deref().unwrap_or("none"),
+            new_result.reason.as_deref().unwrap_or("none")
+        ));
+    }
+    if old_result.non_conforming_commits != new_result.non_conforming_commits {
+        reasons.push(format!(
+            "the non-conforming commits counted as a patch bump changed from {} to {}",
+            old_result.non_conforming_commits, new_result.non_conforming_commits
+        ));
+    }
+    if old_result.commit_threshold_reached != new_result.commit_threshold_reached {
+        reasons.push(match new_result.commit_threshold_reached {
+            true => "the commit threshold is only reached in the new file".to_owned(),
+            false => "the commit threshold is only reached in the old file".to_owned(),
+        });
+    }
+    if reasons.is_empty() {
+        reasons.push(format!(
+            "the computed version changed from {} to {}",
+            old_result.new_version, new_result.new_version
+        ));
+    }
+    reasons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::path::PathBuf;
+    use std::{env, process};
+
+    /// Writes an output file with the given version bumps, as `(scope, old version, new version, increment kind)`,
+    /// to the temporary directory and returns its path.
+    fn output_file(name: &str, version_bumps: &[(&str, &str, &str, Option<&str>)]) -> PathBuf {
+        let dir = env::temp_dir().join(format!("tag-track-diff-report-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let version_bumps: Vec<_> = version_bumps
+            .iter()
+            .map(|(scope, old_version, new_version, increment_kind)| {
+                json!({
+                    "scope": scope,
+                    "old_version": old_version,
+                    "new_version": new_version,
+                    "increment_kind": increment_kind,
+                })
+            })
+            .collect();
+        let path = dir.join(name);
+        fs::write(&path, json!({ "version_bumps": version_bumps }).to_string()).unwrap();
+        path
+    }
+
+    #[test]
+    fn changed_added_and_removed_scopes_are_reported() {
+        let old_path = output_file(
+            "old.json",
+            &[
+                ("", "1.0.0", "1.0.1", Some("patch")),
+                ("api", "0.1.0", "0.2.0", Some("minor")),
+                ("web", "2.0.0", "2.0.0", None),
+            ],
+        );
+        let new_path = output_file(
+            "new.json",
+            &[
+                ("", "1.0.0", "1.1.0", Some("minor")),
+                ("api", "0.1.0", "0.2.0", Some("minor")),
+                ("cli", "none", "0.1.0", Some("minor")),
+            ],
+        );
+
+        let report = diff_report_files(&old_path, &new_path).unwrap();
+        assert!(report.has_changes());
+        assert_eq!(report.unchanged_scopes, vec!["api"]);
+        let diffs: Vec<(&str, ScopeDiffKind, Option<&str>, Option<&str>)> = report
+            .scope_diffs
+            .iter()
+            .map(|diff| {
+                (
+                    diff.scope.as_str(),
+                    diff.kind,
+                    diff.old_computed_version.as_deref(),
+                    diff.new_computed_version.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            diffs,
+            vec![
+                ("", ScopeDiffKind::Changed, Some("1.0.1"), Some("1.1.0")),
+                ("cli", ScopeDiffKind::Added, None, Some("0.1.0")),
+                ("web", ScopeDiffKind::Removed, Some("2.0.0"), None),
+            ]
+        );
+        assert_eq!(
+            report.scope_diffs[0].reasons,
+            vec!["the increment kind changed from patch to minor"]
+        );
+    }
+
+    #[test]
+    fn files_without_version_bumps_or_tags_are_rejected() {
+        let old_path = output_file("valid.json", &[("", "1.0.0", "1.0.1", Some("patch"))]);
+        let new_path = old_path.with_file_name("invalid.json");
+        fs::write(&new_path, r#"{"tag_created": false}"#).unwrap();
+
+        let error = diff_report_files(&old_path, &new_path).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidReportFile);
+    }
+}
@@ -2,7 +2,7 @@
 //! to convert other errors to allow the application to return our own errors in all functions.
 //!
 
-use std::{env::VarError, fmt};
+use std::{env::VarError, fmt, num::ParseIntError};
 
 /// Describes what kind of errors the application can return.
 #[derive(Debug, PartialEq)]
@@ -14,15 +14,63 @@ pub enum ErrorKind {
     /// The application is being called outside a git working tree.
     NotGitWorkingTree,
     /// Error returned by the GitHub REST API.
+    #[cfg(feature = "github")]
     GithubRestError,
+    /// The GitHub REST API rate limit was exceeded.
+    #[cfg(feature = "github")]
+    RateLimited,
+    /// Error returned by gix while reading or writing the git repository.
+    GixError,
+    /// Error returned by libgit2 while reading or writing the git repository.
+    #[cfg(feature = "libgit2")]
+    Libgit2Error,
     /// Can not get tags from source.
     MissingGitTags,
+    /// The name of the first tag of a scope can not be derived from the tag pattern.
+    InvalidInitialTagName,
+    /// A version given by the user is not a valid semantic version.
+    InvalidVersion,
+    /// A version scope does not follow the scope rules of the configuration.
+    InvalidScope,
+    /// The repository is a shallow clone that does not contain the whole history.
+    ShallowClone,
     /// The user given output format is not valid.
     InvalidOutputFormat,
     /// The regex pattern is not valid.
     InvalidRegexPattern,
+    /// The regex pattern or the text matched against it exceed the regex limits.
+    RegexLimitExceeded,
     /// Authentication is required for the action you are trying to call.
+    #[cfg(feature = "github")]
     AuthenticationRequired,
+    /// The plan file is not valid.
+    InvalidPlanFile,
+    /// The plan file has not been approved or its approval is not valid.
+    PlanNotApproved,
+    /// The changelog does not match the notes derived from the released commits.
+    ChangelogMismatch,
+    /// The tag audit found issues in the existing tags.
+    TagAuditFailed,
+    /// The configuration lint found issues in the bump rules.
+    ConfigLintFailed,
+    /// The configuration file validation found issues.
+    ConfigValidationFailed,
+    /// The state file is not valid.
+    InvalidStateFile,
+    /// The extra tags file is not valid.
+    InvalidExtraTagsFile,
+    /// The report file compared by the `diff-report` subcommand is not valid.
+    InvalidReportFile,
+    /// The release notes template is not valid.
+    InvalidTemplate,
+    /// The tag namespace is not valid.
+    InvalidTagNamespace,
+    /// The number of version scopes exceeds the maximum, usually because the tag pattern matches unrelated tags.
+    TooManyScopes,
+    /// The action writes to the repository or to files and `--read-only` was given.
+    ReadOnly,
+    /// The operation was cancelled before finishing.
+    Cancelled,
     /// Unspecified found error. This error kind is also used for `From` implementation of
     /// other errors.
     Other,
@@ -37,16 +85,139 @@ impl ErrorKind {
             GenericCommandFailed => "shell command failed",
             MissingGit => "missing git installation",
             NotGitWorkingTree => "the current directory does not seem to be a git working tree",
+            #[cfg(feature = "github")]
             GithubRestError => "error while calling GitHub REST API",
+            #[cfg(feature = "github")]
+            RateLimited => "the GitHub REST API rate limit was exceeded",
+            GixError => "error while accessing the git repository with gix",
+            #[cfg(feature = "libgit2")]
+            Libgit2Error => "error while accessing the git repository with libgit2",
             MissingGitTags => "cannot get tags from source",
+            InvalidInitialTagName => {
+                "the name of the first tag can not be derived from the tag pattern"
+            }
+            InvalidVersion => "the version is not a valid semantic version",
+            InvalidScope => "the scope is not valid",
+            ShallowClone => "the repository is a shallow clone",
             InvalidOutputFormat => "the specified output format is not valid",
             InvalidRegexPattern => "the regex pattern is not valid",
+            RegexLimitExceeded => "the regex limits were exceeded",
+            #[cfg(feature = "github")]
             AuthenticationRequired => {
                 "authentication is required for the action you are trying to call"
             }
+            InvalidPlanFile => "the plan file is not valid",
+            PlanNotApproved => "the plan file is not approved",
+            ChangelogMismatch => "the changelog does not match the released commits",
+            TagAuditFailed => "the tag audit found issues in the existing tags",
+            ConfigLintFailed => "the configuration lint found issues in the bump rules",
+            ConfigValidationFailed => "the configuration file is not valid",
+            InvalidStateFile => "the state file is not valid",
+            InvalidExtraTagsFile => "the extra tags file is not valid",
+            InvalidReportFile => "the report file is not valid",
+            InvalidTemplate => "the release notes template is not valid",
+            InvalidTagNamespace => "the tag namespace is not valid",
+            TooManyScopes => "too many version scopes were found",
+            ReadOnly => "the action is not allowed in read-only mode",
+            Cancelled => "the operation was cancelled",
             Other => "other error",
         }
     }
+
+    /// Returns the machine readable code of the error kind, reported in the `error.kind` field of the JSON output.
+    /// Codes are stable across releases, so they can be matched by scripts and workflows.
+    pub fn code(&self) -> &'static str {
+        use ErrorKind::*;
+
+        match *self {
+            GenericCommandFailed => "command_failed",
+            MissingGit => "missing_git",
+            NotGitWorkingTree => "not_git_working_tree",
+            #[cfg(feature = "github")]
+            GithubRestError => "github_rest_error",
+            #[cfg(feature = "github")]
+            RateLimited => "rate_limited",
+            GixError => "gix_error",
+            #[cfg(feature = "libgit2")]
+            Libgit2Error => "libgit2_error",
+            MissingGitTags => "missing_git_tags",
+            InvalidInitialTagName => "invalid_initial_tag_name",
+            InvalidVersion => "invalid_version",
+            InvalidScope => "invalid_scope",
+            ShallowClone => "shallow_clone",
+            InvalidOutputFormat => "invalid_output_format",
+            InvalidRegexPattern => "invalid_regex_pattern",
+            RegexLimitExceeded => "regex_limit_exceeded",
+            #[cfg(feature = "github")]
+            AuthenticationRequired => "authentication_required",
+            InvalidPlanFile => "invalid_plan_file",
+            PlanNotApproved => "plan_not_approved",
+            ChangelogMismatch => "changelog_mismatch",
+            TagAuditFailed => "tag_audit_failed",
+            ConfigLintFailed => "config_lint_failed",
+            ConfigValidationFailed => "config_validation_failed",
+            InvalidStateFile => "invalid_state_file",
+            InvalidExtraTagsFile => "invalid_extra_tags_file",
+            InvalidReportFile => "invalid_report_file",
+            InvalidTemplate => "invalid_template",
+            InvalidTagNamespace => "invalid_tag_namespace",
+            TooManyScopes => "too_many_scopes",
+            ReadOnly => "read_only",
+            Cancelled => "cancelled",
+            Other => "other",
+        }
+    }
+
+    /// Returns a suggestion to fix the errors of this kind, reported in the `error.hint` field of the JSON output, or
+    /// `None` if there is no generic fix.
+    pub fn hint(&self) -> Option<&'static str> {
+        use ErrorKind::*;
+
+        match *self {
+            MissingGit => Some("install git and make sure it is available in the `PATH`"),
+            NotGitWorkingTree => {
+                Some("run Tag Track inside a git repository or give its directory with `--repo-path`")
+            }
+            #[cfg(feature = "github")]
+            RateLimited => Some(
+                "authenticate with `--github-token` or a GitHub App to raise the rate limit, or retry \
+                 after it resets",
+            ),
+            #[cfg(feature = "github")]
+            AuthenticationRequired => {
+                Some("give a GitHub token with `--github-token` or the credentials of a GitHub App")
+            }
+            ShallowClone => Some(
+                "fetch the whole history, for example with `fetch-depth: 0` in `actions/checkout`, or use \
+                 `--auto-deepen`",
+            ),
+            RegexLimitExceeded => Some("simplify the regex patterns of the configuration"),
+            PlanNotApproved => Some("approve the plan again after it was modified"),
+            ChangelogMismatch => Some("update the changelog with the expected sections"),
+            ConfigLintFailed => Some("fix the reported bump rules of the configuration"),
+            ConfigValidationFailed => Some("fix the reported lines of the configuration file"),
+            TooManyScopes => Some(
+                "tighten the `tag_pattern` so it only matches release tags, or raise `--max-scopes`",
+            ),
+            ReadOnly => Some("run the action in a trusted workflow without `--read-only`"),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the error is caused by the source being unavailable, so the action can be retried with a
+    /// different source.
+    pub fn is_retryable(&self) -> bool {
+        use ErrorKind::*;
+
+        match *self {
+            GenericCommandFailed | MissingGit | NotGitWorkingTree | GixError | ShallowClone => true,
+            #[cfg(feature = "github")]
+            GithubRestError | RateLimited => true,
+            #[cfg(feature = "libgit2")]
+            Libgit2Error => true,
+            _ => false,
+        }
+    }
 }
 
 impl fmt::Display for ErrorKind {
@@ -129,6 +300,15 @@ impl From<serde_yaml::Error> for Error {
     }
 }
 
+impl From<toml::de::Error> for Error {
+    fn from(error: toml::de::Error) -> Self {
+        Self {
+            kind: ErrorKind::Other,
+            message: error.to_string().replace('\n', " # "),
+        }
+    }
+}
+
 impl From<regex::Error> for Error {
     fn from(error: regex::Error) -> Self {
         Self {
@@ -137,3 +317,21 @@ impl From<regex::Error> for Error {
         }
     }
 }
+
+impl From<serde_json::Error> for Error {
+    fn from(error: serde_json::Error) -> Self {
+        Self {
+            kind: ErrorKind::Other,
+            message: error.to_string().replace('\n', " # "),
+        }
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(error: ParseIntError) -> Self {
+        Self {
+            kind: ErrorKind::Other,
+            message: error.to_string().replace('\n', " # "),
+        }
+    }
+}
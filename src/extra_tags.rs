@@ -0,0 +1,76 @@
+//! This module provides the extra tags file, used to supplement the tags of the sources with tags that are kept
+//! outside git, such as marketing versions.
+//!
+//! The extra tags file is a YAML or JSON list of tag records with the tag name and the SHA of the commit it
+//! references. Extra tags are merged with the tags of the source before the closest tag of each scope is resolved,
+//! and their names are parsed with the tag pattern of the configuration like any other tag.
+//!
+
+use crate::config::ScopeCase;
+use crate::error::{Error, ErrorKind};
+use crate::git::Tag;
+use crate::parsing::parse_tag_details;
+use serde::Deserialize;
+use std::{fs, path::Path};
+
+/// Type that represents a tag record of the extra tags file.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExtraTag {
+    /// Name of the tag.
+    pub name: String,
+    /// SHA of the commit referenced by the tag.
+    pub commit_sha: String,
+}
+
+/// Reads the tags of the given extra tags file. Tag details are extracted with the given tag pattern, tags that do
+/// not match it are kept without details, as done with the tags of the sources.
+///
+/// # Arguments
+///
+/// * `path` - Path of the extra tags file.
+///
+/// * `tag_pattern` - Pattern used to extract the tag details.
+///
+/// * `scope_case` - Case applied to the parsed scopes.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidExtraTagsFile` if the file is not a valid list of
+/// tag records.
+///
+/// Returns the errors of `parsing::parse_tag_details`.
+///
+pub fn read_extra_tags_file(
+    path: &Path,
+    tag_pattern: &str,
+    scope_case: ScopeCase,
+) -> Result<Vec<Tag>, Error> {
+    let contents = fs::read_to_string(path)?;
+    let extra_tags: Vec<ExtraTag> = match serde_yaml::from_str(&contents) {
+        Ok(extra_tags) => extra_tags,
+        Err(error) => {
+            return Err(Error::new(
+                ErrorKind::InvalidExtraTagsFile,
+                Some(&error.to_string()),
+            ))
+        }
+    };
+
+    let mut tags = Vec::with_capacity(extra_tags.len());
+    for extra_tag in extra_tags {
+        if extra_tag.name.is_empty() || extra_tag.commit_sha.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidExtraTagsFile,
+                Some("tag records require a non-empty `name` and `commit_sha`"),
+            ));
+        }
+
+        tags.push(Tag {
+            details: parse_tag_details(&extra_tag.name, tag_pattern, scope_case)?,
+            name: extra_tag.name,
+            commit_sha: extra_tag.commit_sha,
+        });
+    }
+    Ok(tags)
+}
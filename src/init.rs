@@ -0,0 +1,290 @@
+//! This module provides the scaffolding of the configuration file run by the `init` subcommand.
+//!
+//! The generated file is a commented `track.yml` with the default commit pattern and bump rules of Tag Track, and a
+//! tag pattern and version scopes that fit the tags already present in the repository. Tags named `<scope>/<version>`
+//! make the scaffolded configuration version each scope independently, and a `v` prefix is kept if most tags use it.
+//!
+
+use crate::config::{BumpRule, Config};
+use crate::error::Error;
+use crate::parsing::build_regex;
+use semver::Version;
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, Write};
+
+/// Pattern used to recognize the layout of existing tags, with an optional scope, an optional `v` prefix and a
+/// semantic version.
+const TAG_LAYOUT_PATTERN: &str =
+    r"^(?:(?<scope>[A-Za-z0-9._-]+)/)?(?<prefix>v?)(?<version>[0-9]+\.[0-9]+\.[0-9]+\S*)$";
+
+/// Pattern of the `scope` capturing group of the scaffolded tag pattern, matching the default scope rules.
+const SCOPE_GROUP_PATTERN: &str = r"(?<scope>[A-Za-z0-9._-]+)";
+
+/// Pattern of the `version` capturing group of the scaffolded tag pattern.
+const VERSION_GROUP_PATTERN: &str = r"(?<version>[0-9]+\.[0-9]+\.[0-9]+.*)";
+
+/// Prefix of the versions in the tag names.
+const VERSION_PREFIX: &str = "v";
+
+/// Type that represents the layout of the tags found in the repository.
+#[derive(Debug, Default)]
+pub struct TagLayout {
+    /// Whether most of the versioned tags prefix their version with `v`.
+    pub has_version_prefix: bool,
+    /// Scopes found in tags named `<scope>/<version>`, sorted by name.
+    pub scopes: Vec<String>,
+}
+
+/// Returns the layout of the given tags. Tags whose name does not end with a semantic version are ignored.
+///
+/// # Arguments
+///
+/// * `tag_names` - Names of the tags of the repository.
+///
+/// # Errors
+///
+/// Returns the regex errors of `parsing::build_regex`.
+///
+pub fn discover_tag_layout(tag_names: &[String]) -> Result<TagLayout, Error> {
+    let re = build_regex(TAG_LAYOUT_PATTERN)?;
+
+    let mut scopes = BTreeSet::new();
+    let mut prefixed_tags = 0;
+    let mut versioned_tags = 0;
+    for tag_name in tag_names {
+        let captures = match re.captures(tag_name) {
+            Some(captures) => captures,
+            None => continue,
+        };
+        if Version::parse(&captures["version"]).is_err() {
+            continue;
+        }
+
+        versioned_tags += 1;
+        if !captures["prefix"].is_empty() {
+            prefixed_tags += 1;
+        }
+        if let Some(scope) = captures.name("scope") {
+            scopes.insert(scope.as_str().to_owned());
+        }
+    }
+
+    Ok(TagLayout {
+        has_version_prefix: prefixed_tags * 2 > versioned_tags,
+        scopes: scopes.into_iter().collect(),
+    })
+}
+
+/// Returns the tag pattern that matches tags named `<version>`, or `<scope>/<version>` if `is_scoped` is `true`. The
+/// pattern only contains literal text outside of its capturing groups, so the names of new tags can be rendered
+/// from it.
+///
+/// # Arguments
+///
+/// * `has_version_prefix` - Whether the version is prefixed with `v`.
+///
+/// * `is_scoped` - Whether the tag names start with the scope.
+///
+pub fn build_tag_pattern(has_version_prefix: bool, is_scoped: bool) -> String {
+    let scope = match is_scoped {
+        true => format!("{}/", SCOPE_GROUP_PATTERN),
+        false => String::new(),
+    };
+    let prefix = match has_version_prefix {
+        true => VERSION_PREFIX,
+        false => "",
+    };
+    format!("^{}{}{}$", scope, prefix, VERSION_GROUP_PATTERN)
+}
+
+/// Returns the contents of a commented `track.yml` with the given fields, and the default commit pattern and bump
+/// rules.
+///
+/// # Arguments
+///
+/// * `tag_pattern` - Pattern used to parse the tags.
+///
+/// * `version_scopes` - Scopes versioned independently, the empty scope versions the whole repository.
+///
+/// * `initial_version` - Version of the first tag of the scopes without tags.
+///
+pub fn render_config(
+    tag_pattern: &str,
+    version_scopes: &[String],
+    initial_version: &str,
+) -> String {
+    let defaults = Config::new();
+
+    let mut contents = String::from(
+        "# Configuration of Tag Track, generated by `tag-track init`.\n\
+         # Read more about each field in https://github.com/dloez/tag-track#configuration\n\n",
+    );
+
+    contents.push_str(
+        "# Pattern used to parse the tags. It must expose the `version` capturing group, and the `scope` capturing\n\
+         # group if the repository has several version scopes.\n",
+    );
+    contents.push_str(&format!("tag_pattern: {}\n\n", yaml_string(tag_pattern)));
+
+    contents.push_str(
+        "# Pattern used to parse conventional commits. It must expose the `type` and `description` capturing\n\
+         # groups, and can expose the `scope` and `breaking` capturing groups.\n",
+    );
+    contents.push_str(&format!(
+        "commit_pattern: {}\n\n",
+        yaml_string(&defaults.commit_pattern)
+    ));
+
+    contents.push_str(
+        "# Scopes versioned independently, each one with its own tags. The empty scope versions the whole\n\
+         # repository.\n\
+         version_scopes:\n",
+    );
+    for scope in version_scopes {
+        contents.push_str(&format!("  - {}\n", yaml_string(scope)));
+    }
+    contents.push('\n');
+
+    contents.push_str(
+        "# Rules that decide the version bump of each commit. The biggest bump of the rules triggered by the\n\
+         # commits is applied.\n\
+         bump_rules:\n",
+    );
+    for rule in &defaults.bump_rules {
+        contents.push_str(&render_bump_rule(rule));
+    }
+    contents.push('\n');
+
+    contents.push_str("# Version of the first tag of the scopes without tags.\n");
+    contents.push_str(&format!(
+        "initial_version: {}\n",
+        yaml_string(initial_version)
+    ));
+
+    contents
+}
+
+/// Returns the YAML list item of the given bump rule.
+///
+/// # Arguments
+///
+/// * `rule` - Bump rule to render.
+///
+fn render_bump_rule(rule: &BumpRule) -> String {
+    let mut item = format!("  - bump: {}\n", rule.bump.as_str());
+    let lists = [("types", &rule.types), ("scopes", &rule.scopes)];
+    for (field, values) in lists {
+        if let Some(values) = values {
+            let values: Vec<String> = values.iter().map(|value| yaml_string(value)).collect();
+            item.push_str(&format!("    {}: [{}]\n", field, values.join(", ")));
+        }
+    }
+    let flags = [
+        ("if_breaking_field", rule.if_breaking_field),
+        ("if_breaking_description", rule.if_breaking_description),
+    ];
+    for (field, value) in flags {
+        if let Some(value) = value {
+            item.push_str(&format!("    {}: {}\n", field, value));
+        }
+    }
+    item
+}
+
+/// Returns the given value as a single quoted YAML string, so values such as regex patterns or versions are never
+/// read as other YAML types.
+///
+/// # Arguments
+///
+/// * `value` - Value to quote.
+///
+fn yaml_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Prints the given question to stderr and returns the line answered in stdin, or the given default value if the
+/// answer is empty.
+///
+/// # Arguments
+///
+/// * `question` - Question printed to the user.
+///
+/// * `default` - Value returned for empty answers, printed after the question.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if stderr can not be written or stdin can not be
+/// read.
+///
+pub fn prompt(question: &str, default: &str) -> Result<String, Error> {
+    let mut stderr = io::stderr();
+    write!(stderr, "{} [{}]: ", question, default)?;
+    stderr.flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    let answer = answer.trim();
+    match answer.is_empty() {
+        true => Ok(default.to_owned()),
+        false => Ok(answer.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::parse_config_file;
+    use crate::validate::validate_config_file;
+    use std::{env, fs, process};
+
+    /// Returns the given tag names as owned strings.
+    fn tag_names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn scoped_and_prefixed_tags_are_discovered() {
+        let layout = discover_tag_layout(&tag_names(&[
+            "web/v1.0.0",
+            "api/v0.2.0",
+            "api/v0.3.0",
+            "v2.0.0",
+            "1.0.0",
+            "nightly",
+            "api/v1.0",
+        ]))
+        .unwrap();
+        assert!(layout.has_version_prefix);
+        assert_eq!(layout.scopes, vec!["api", "web"]);
+    }
+
+    #[test]
+    fn unprefixed_tags_are_discovered() {
+        let layout = discover_tag_layout(&tag_names(&["1.0.0", "1.1.0", "v1.2.0"])).unwrap();
+        assert!(!layout.has_version_prefix);
+        assert!(layout.scopes.is_empty());
+    }
+
+    #[test]
+    fn rendered_configuration_is_valid() {
+        let dir = env::temp_dir().join(format!("tag-track-init-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("track.yml");
+        let scopes = tag_names(&["api", "web"]);
+        fs::write(
+            &path,
+            render_config(&build_tag_pattern(true, true), &scopes, "0.1.0"),
+        )
+        .unwrap();
+
+        assert!(validate_config_file(&path).unwrap().is_empty());
+        let config = parse_config_file(path).unwrap();
+        assert_eq!(config.version_scopes, scopes);
+        assert_eq!(config.bump_rules.len(), Config::new().bump_rules.len());
+        let version = Version::new(1, 2, 3);
+        assert_eq!(
+            config.render_scope_tag_name("api", &version, None).unwrap(),
+            Some("api/v1.2.3".to_owned())
+        );
+    }
+}
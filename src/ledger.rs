@@ -0,0 +1,134 @@
+//! This module provides the release ledger, an append-only JSON Lines file with one entry for each release created by
+//! Tag Track. The ledger gives a queryable release history that does not depend on enumerating the tags of the
+//! repository, and it is intended to be committed to the repository or uploaded as a workflow artifact.
+//!
+
+use crate::error::Error;
+use crate::plan::PlannedTag;
+use crate::run;
+use serde::Serialize;
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::Path,
+    time::SystemTime,
+};
+
+/// Version of the ledger entry format.
+pub const LEDGER_FORMAT_VERSION: u64 = 1;
+
+/// Type that represents an entry of the release ledger.
+#[derive(Debug, Serialize)]
+pub struct LedgerEntry<'a> {
+    /// Version of the ledger entry format.
+    pub format_version: u64,
+    /// Scope of the version.
+    pub scope: &'a str,
+    /// Version number of the release.
+    pub version: &'a str,
+    /// Name of the created tag.
+    pub tag_name: &'a str,
+    /// SHA of the commit referenced by the tag.
+    pub commit_sha: &'a str,
+    /// URL of the created release, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub release_url: Option<&'a str>,
+    /// Time when the tag was created, as an RFC 3339 UTC timestamp.
+    pub created_at: String,
+    /// Identifier of the run that created the tag.
+    pub run_id: &'a str,
+}
+
+impl<'a> LedgerEntry<'a> {
+    /// Returns a new `LedgerEntry` for the given created tag, timestamped with the current time.
+    ///
+    /// # Arguments
+    ///
+    /// * `planned_tag` - Tag that was created.
+    ///
+    /// * `release_url` - URL of the release created for the tag, if any.
+    ///
+    pub fn new(planned_tag: &'a PlannedTag, release_url: Option<&'a str>) -> Self {
+        Self {
+            format_version: LEDGER_FORMAT_VERSION,
+            scope: &planned_tag.scope,
+            version: &planned_tag.new_version,
+            tag_name: &planned_tag.name,
+            commit_sha: &planned_tag.commit_sha,
+            release_url,
+            created_at: run::format_timestamp(SystemTime::now()),
+            run_id: &run::current().id,
+        }
+    }
+}
+
+/// Appends the given entry as a single JSON line to the ledger file. The file and its missing parent directories are
+/// created if they do not exist. Existing entries are never modified.
+///
+/// # Arguments
+///
+/// * `entry` - Entry that will be appended.
+///
+/// * `path` - Path of the ledger file.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the entry cannot be serialized or the file
+/// cannot be written.
+///
+pub fn append_ledger_entry(entry: &LedgerEntry, path: &Path) -> Result<(), Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let line = serde_json::to_string(entry)? + "\n";
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, process};
+
+    /// Returns the created tag of the given scope and version.
+    fn planned_tag(scope: &str, version: &str) -> PlannedTag {
+        PlannedTag {
+            name: format!("{}/{}", scope, version),
+            message: format!("Version {}", version),
+            commit_sha: "c1".to_owned(),
+            scope: scope.to_owned(),
+            old_version: "0.1.0".to_owned(),
+            new_version: version.to_owned(),
+            release: None,
+        }
+    }
+
+    #[test]
+    fn entries_are_appended_as_json_lines() {
+        let path = env::temp_dir()
+            .join(format!("tag-track-ledger-{}", process::id()))
+            .join("releases.jsonl");
+        let api_tag = planned_tag("api", "1.0.0");
+        let web_tag = planned_tag("web", "0.2.0");
+        append_ledger_entry(&LedgerEntry::new(&api_tag, None), &path).unwrap();
+        append_ledger_entry(
+            &LedgerEntry::new(&web_tag, Some("https://example.com/r/1")),
+            &path,
+        )
+        .unwrap();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let entries: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["format_version"], LEDGER_FORMAT_VERSION);
+        assert_eq!(entries[0]["tag_name"], "api/1.0.0");
+        assert_eq!(entries[0]["run_id"], run::current().id.as_str());
+        assert!(entries[0].get("release_url").is_none());
+        assert_eq!(entries[1]["version"], "0.2.0");
+        assert_eq!(entries[1]["release_url"], "https://example.com/r/1");
+    }
+}
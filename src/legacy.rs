@@ -0,0 +1,254 @@
+//! This module provides the compatibility layer for the legacy flat CLI flags that were replaced by subcommands.
+//!
+//! Legacy flags are rewritten into their subcommands before the CLI arguments are parsed, and a deprecation warning
+//! with the exact replacement invocation is recorded for each of them. Legacy flags are accepted for one major release
+//! cycle after their subcommand was introduced.
+//!
+
+use serde::Serialize;
+use std::{ffi::OsString, sync::OnceLock};
+
+/// Name of the binary used in the replacement invocations.
+const BINARY_NAME: &str = "tag-track";
+
+/// Kind of the deprecation warnings of legacy flags.
+const DEPRECATED_FLAG_KIND: &str = "deprecated_flag";
+
+/// Type that represents a legacy flag and the subcommand that replaces it.
+struct LegacyFlag {
+    /// Legacy flag, including the leading dashes.
+    flag: &'static str,
+    /// Subcommand, and its nested subcommands, that replaces the flag.
+    subcommand: &'static [&'static str],
+    /// How the value of the legacy flag is passed to the subcommand, `None` if the flag does not take a value.
+    value: Option<LegacyValue>,
+}
+
+/// Type that represents how the value of a legacy flag is passed to its subcommand.
+enum LegacyValue {
+    /// The value is passed as a positional argument.
+    Positional,
+    /// The value is passed with the given flag.
+    Flag(&'static str),
+}
+
+/// Legacy flags that are still accepted.
+const LEGACY_FLAGS: &[LegacyFlag] = &[
+    LegacyFlag {
+        flag: "--plan",
+        subcommand: &["plan"],
+        value: Some(LegacyValue::Flag("--out")),
+    },
+    LegacyFlag {
+        flag: "--apply",
+        subcommand: &["apply"],
+        value: Some(LegacyValue::Positional),
+    },
+    LegacyFlag {
+        flag: "--verify-changelog",
+        subcommand: &["verify-changelog"],
+        value: None,
+    },
+    LegacyFlag {
+        flag: "--audit-tags",
+        subcommand: &["audit-tags"],
+        value: None,
+    },
+    LegacyFlag {
+        flag: "--lint-config",
+        subcommand: &["config", "lint"],
+        value: None,
+    },
+];
+
+/// Type that represents the deprecation warning of a legacy flag that was used.
+#[derive(Serialize, Debug, Clone)]
+pub struct Deprecation {
+    /// Kind of the warning, `deprecated_flag`.
+    pub kind: &'static str,
+    /// Legacy flag that was used.
+    pub flag: String,
+    /// Invocation that replaces the legacy one.
+    pub replacement: String,
+    /// Human readable message of the warning.
+    pub message: String,
+}
+
+/// Deprecation warnings of the run, set by `migrate_args`.
+static DEPRECATIONS: OnceLock<Vec<Deprecation>> = OnceLock::new();
+
+/// Rewrites the legacy flags of the given CLI arguments into their subcommands and records a deprecation warning for
+/// each of them. Only the first legacy flag is rewritten, as a run can only execute one subcommand.
+///
+/// # Arguments
+///
+/// * `args` - CLI arguments, including the binary name.
+///
+pub fn migrate_args(args: Vec<OsString>) -> Vec<OsString> {
+    let (migrated, deprecation) = migrate_legacy_flag(args);
+    let _ = DEPRECATIONS.set(deprecation.into_iter().collect());
+    migrated
+}
+
+/// Rewrites the first legacy flag of the given CLI arguments into its subcommand, and returns the rewritten arguments
+/// and the deprecation warning of the flag, `None` if no legacy flag was used.
+///
+/// # Arguments
+///
+/// * `args` - CLI arguments, including the binary name.
+///
+fn migrate_legacy_flag(args: Vec<OsString>) -> (Vec<OsString>, Option<Deprecation>) {
+    let position = args.iter().skip(1).position(|arg| {
+        LEGACY_FLAGS
+            .iter()
+            .any(|legacy_flag| legacy_flag_name(arg) == Some(legacy_flag.flag))
+    });
+    let index = match position {
+        Some(position) => position + 1,
+        None => return (args, None),
+    };
+
+    let legacy_arg = args[index].to_string_lossy();
+    let flag = legacy_flag_name(&args[index])
+        .unwrap_or_default()
+        .to_owned();
+    let legacy_flag = LEGACY_FLAGS
+        .iter()
+        .find(|legacy_flag| legacy_flag.flag == flag)
+        .unwrap();
+
+    let mut migrated = args[..index].to_vec();
+    let mut rest = args[index + 1..].iter();
+    let value = match (&legacy_flag.value, legacy_arg.split_once('=')) {
+        (None, _) => None,
+        (Some(_), Some((_, value))) => Some(OsString::from(value)),
+        (Some(_), None) => rest.next().cloned(),
+    };
+    migrated.extend(rest.cloned());
+    migrated.extend(legacy_flag.subcommand.iter().map(OsString::from));
+    match (&legacy_flag.value, value) {
+        (Some(LegacyValue::Positional), Some(value)) => migrated.push(value),
+        (Some(LegacyValue::Flag(value_flag)), Some(value)) => {
+            migrated.push(OsString::from(value_flag));
+            migrated.push(value);
+        }
+        _ => {}
+    }
+
+    let replacement = std::iter::once(BINARY_NAME.to_owned())
+        .chain(
+            migrated
+                .iter()
+                .skip(1)
+                .map(|arg| quote_arg(&arg.to_string_lossy())),
+        )
+        .collect::<Vec<String>>()
+        .join(" ");
+    let message = format!(
+        "`{}` is deprecated and will be removed in the next major release, use `{}` instead",
+        flag, replacement
+    );
+    let deprecation = Deprecation {
+        kind: DEPRECATED_FLAG_KIND,
+        flag,
+        replacement,
+        message,
+    };
+    (migrated, Some(deprecation))
+}
+
+/// Returns the deprecation warnings of the run, empty if `migrate_args` was not called.
+pub fn deprecations() -> &'static [Deprecation] {
+    DEPRECATIONS.get().map(Vec::as_slice).unwrap_or_default()
+}
+
+/// Returns the name of the given CLI argument, without the value given with `=`, or `None` if the argument is not
+/// valid UTF-8.
+///
+/// # Arguments
+///
+/// * `arg` - CLI argument.
+///
+fn legacy_flag_name(arg: &OsString) -> Option<&str> {
+    let arg = arg.to_str()?;
+    Some(arg.split_once('=').map(|(name, _)| name).unwrap_or(arg))
+}
+
+/// Quotes the given CLI argument with single quotes if it contains characters interpreted by shells.
+///
+/// # Arguments
+///
+/// * `arg` - CLI argument.
+///
+fn quote_arg(arg: &str) -> String {
+    let is_plain = !arg.is_empty()
+        && arg.chars().all(|char| {
+            char.is_ascii_alphanumeric() || matches!(char, '-' | '_' | '.' | '/' | '=' | ':' | ',')
+        });
+    match is_plain {
+        true => arg.to_owned(),
+        false => format!("'{}'", arg.replace('\'', r"'\''")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the rewritten arguments and the replacement of the deprecation warning of the given CLI arguments.
+    fn migrate(args: &[&str]) -> (Vec<String>, Option<String>) {
+        let args = args.iter().map(OsString::from).collect();
+        let (migrated, deprecation) = migrate_legacy_flag(args);
+        let migrated = migrated
+            .iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect();
+        (
+            migrated,
+            deprecation.map(|deprecation| deprecation.replacement),
+        )
+    }
+
+    #[test]
+    fn legacy_flags_are_rewritten_into_subcommands() {
+        assert_eq!(
+            migrate(&["tag-track", "--plan", "plan.json", "--verbose"]),
+            (
+                vec!["tag-track", "--verbose", "plan", "--out", "plan.json"]
+                    .into_iter()
+                    .map(str::to_owned)
+                    .collect(),
+                Some("tag-track --verbose plan --out plan.json".to_owned())
+            )
+        );
+        assert_eq!(
+            migrate(&["tag-track", "--apply=my plan.json"]).1,
+            Some("tag-track apply 'my plan.json'".to_owned())
+        );
+        assert_eq!(
+            migrate(&["tag-track", "--lint-config"]).1,
+            Some("tag-track config lint".to_owned())
+        );
+    }
+
+    #[test]
+    fn deprecation_warnings_name_the_replacement() {
+        let args = vec![OsString::from("tag-track"), OsString::from("--audit-tags")];
+        let deprecation = migrate_legacy_flag(args).1.unwrap();
+        assert_eq!(deprecation.kind, DEPRECATED_FLAG_KIND);
+        assert_eq!(deprecation.flag, "--audit-tags");
+        assert_eq!(
+            deprecation.message,
+            "`--audit-tags` is deprecated and will be removed in the next major release, use \
+             `tag-track audit-tags` instead"
+        );
+    }
+
+    #[test]
+    fn arguments_without_legacy_flags_are_unchanged() {
+        let args = ["tag-track", "plan", "--out", "plan.json"];
+        let expected = args.iter().map(|arg| arg.to_string()).collect();
+        assert_eq!(migrate(&args), (expected, None));
+        assert_eq!(quote_arg("it's"), r"'it'\''s'");
+    }
+}
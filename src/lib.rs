@@ -0,0 +1,158 @@
+//! Tag Track calculates the semantic version bumps of a repository from its conventional commits and creates the
+//! resulting tags and releases.
+//!
+//! This library exposes the version bump calculation used by the `tag-track` CLI, so release tools can embed it
+//! instead of running the CLI and parsing its JSON output:
+//!
+//! ```no_run
+//! use std::path::PathBuf;
+//! use tag_track::config::parse_config_file;
+//! use tag_track::source::{git::GitSource, SourceActions, SourceKind};
+//!
+//! let config = parse_config_file(PathBuf::from("track.yml")).unwrap();
+//! let source = SourceKind::Git(GitSource::new(&config, false, false));
+//! let sha = source.get_latest_commit_sha().unwrap();
+//! let result = tag_track::calculate(&config, &source, &sha).unwrap();
+//! for version_bump in &result.version_bumps {
+//!     println!("{}: {:?}", version_bump.scope, version_bump.new_version);
+//! }
+//! ```
+//!
+//! The public API is made of the `bump`, `cancel`, `config`, `error`, `git`, `parsing`, `source` and `version`
+//! modules. The rest of the modules implement the features of the CLI and are not covered by semantic versioning.
+//!
+//! # Custom sources
+//!
+//! The history can be read from other stores, such as a database that mirrors the repository, by implementing
+//! `SourceActions`. `get_ref_iterator` returns a `Reference` for each commit, from the given commit to the oldest
+//! one, and stops once the closest tag of every version scope was returned. The commit of a closest tag is not part of
+//! the version bump, so its reference only carries the tags:
+//!
+//! ```
+//! use tag_track::cancel::CancellationToken;
+//! use tag_track::config::Config;
+//! use tag_track::git::{Branch, Commit, Tag};
+//! use tag_track::parsing::parse_tag_details;
+//! use tag_track::{Error, ErrorKind, Reference, SourceActions};
+//!
+//! /// Source reading the history from the rows of a database, ordered from the newest commit to the oldest one. Each
+//! /// row holds the SHA, the message and the tag name of a commit.
+//! struct DatabaseSource<'a> {
+//!     config: &'a Config,
+//!     rows: Vec<(&'static str, &'static str, Option<&'static str>)>,
+//! }
+//!
+//! impl<'a> SourceActions<'a> for DatabaseSource<'a> {
+//!     fn get_ref_iterator(
+//!         &self,
+//!         sha: &'a str,
+//!         _range_start: Option<&str>,
+//!         _cancel: &CancellationToken,
+//!     ) -> Result<Box<dyn Iterator<Item = Result<Reference, Error>> + '_>, Error> {
+//!         let config = self.config;
+//!         let mut references = vec![];
+//!         for (commit_sha, message, tag_name) in self.rows.iter().skip_while(|row| row.0 != sha) {
+//!             if let Some(tag_name) = tag_name {
+//!                 let tag = Tag {
+//!                     name: tag_name.to_string(),
+//!                     commit_sha: commit_sha.to_string(),
+//!                     details: parse_tag_details(tag_name, &config.tag_pattern, config.scope_rules.case)?,
+//!                 };
+//!                 references.push(Ok(Reference { commit: None, tags: Some(vec![tag]), ignored_commit: None }));
+//!                 break;
+//!             }
+//!
+//!             let commit = Commit::new(
+//!                 commit_sha.to_string(),
+//!                 message,
+//!                 None,
+//!                 &config.commit_pattern,
+//!                 config.scope_rules.case,
+//!                 config.max_commit_message_length,
+//!             )?;
+//!             references.push(Ok(Reference { commit: Some(commit), tags: None, ignored_commit: None }));
+//!         }
+//!         Ok(Box::new(references.into_iter()))
+//!     }
+//!
+//!     fn get_latest_commit_sha(&self) -> Result<String, Error> {
+//!         Ok(self.rows[0].0.to_owned())
+//!     }
+//!
+//!     fn resolve_ref(&self, reference: &str) -> Result<String, Error> {
+//!         Ok(reference.to_owned())
+//!     }
+//!
+//!     fn get_branches(&self) -> Result<Vec<Branch>, Error> {
+//!         Ok(vec![])
+//!     }
+//!
+//!     fn create_tag(&self, tag_name: &str, _tag_message: &str, _commit_sha: &str) -> Result<(), Error> {
+//!         Err(Error::new(
+//!             ErrorKind::Other,
+//!             Some(&format!("can not create tag '{}', the database is read-only", tag_name)),
+//!         ))
+//!     }
+//! }
+//!
+//! let config = Config::default();
+//! let source = DatabaseSource {
+//!     config: &config,
+//!     rows: vec![
+//!         ("c3", "feat: export the release notes", None),
+//!         ("c2", "fix: handle empty scopes", None),
+//!         ("c1", "chore: release", Some("0.1.0")),
+//!     ],
+//! };
+//! let sha = source.get_latest_commit_sha().unwrap();
+//! let result = tag_track::calculate(&config, &source, &sha).unwrap();
+//! assert_eq!(result.version_bumps[0].new_version.as_ref().unwrap().to_string(), "0.2.0");
+//! ```
+//!
+
+pub mod bump;
+pub mod cancel;
+pub mod config;
+pub mod error;
+pub mod git;
+pub mod parsing;
+pub mod source;
+pub mod version;
+
+#[doc(hidden)]
+pub mod audit;
+#[doc(hidden)]
+pub mod changelog;
+#[doc(hidden)]
+pub mod diff_report;
+#[doc(hidden)]
+pub mod extra_tags;
+#[doc(hidden)]
+pub mod init;
+#[doc(hidden)]
+pub mod ledger;
+#[doc(hidden)]
+pub mod legacy;
+#[doc(hidden)]
+pub mod lint;
+#[doc(hidden)]
+pub mod logging;
+#[doc(hidden)]
+pub mod metadata;
+#[doc(hidden)]
+pub mod plan;
+#[doc(hidden)]
+pub mod run;
+#[doc(hidden)]
+pub mod state;
+#[doc(hidden)]
+pub mod suggestion;
+#[doc(hidden)]
+pub mod template;
+#[doc(hidden)]
+pub mod validate;
+
+pub use bump::{calculate, BumpResult, ScopeBump};
+pub use error::{Error, ErrorKind};
+pub use git::{Commit, Tag};
+pub use source::{Reference, SourceActions};
@@ -0,0 +1,292 @@
+//! This module provides the linting of the bump rules of the configuration, reporting rules that can never fire,
+//! rules shadowed by other rules, contradictory rules for the same commit type and rules that reference scopes that
+//! are not declared in `version_scopes`.
+//!
+//! Every matching rule contributes to the version bump of a commit and the biggest bump is applied, so a rule is
+//! shadowed if another rule matches every commit it matches and bumps the version at least as much.
+//!
+
+use crate::config::{BumpRule, Config};
+use serde::Serialize;
+
+/// Type that represents the kind of issue found in a bump rule.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum LintIssueKind {
+    /// The rule can never match a commit.
+    NeverFires,
+    /// Every commit matched by the rule is also matched by another rule with a bump at least as big.
+    Shadowed,
+    /// Rules with the same conditions apply different bumps to the same commit type.
+    Contradictory,
+    /// The rule references a scope that is not declared in `version_scopes`.
+    UndeclaredScope,
+}
+
+/// Type that represents an issue found in a bump rule.
+#[derive(Debug, Serialize, Clone)]
+pub struct LintIssue {
+    /// Kind of the issue.
+    pub kind: LintIssueKind,
+    /// Position of the rule in `bump_rules`, starting at 1.
+    pub rule: usize,
+    /// Description of the issue.
+    pub message: String,
+}
+
+/// Lints the bump rules of the given configuration. Rules are referenced by their position in `bump_rules`,
+/// starting at 1.
+///
+/// # Arguments
+///
+/// * `config` - Tag Track configuration.
+///
+pub fn lint_config(config: &Config) -> Vec<LintIssue> {
+    let rules = &config.bump_rules;
+    let mut issues = vec![];
+    let mut ignored = vec![false; rules.len()];
+
+    for (index, rule) in rules.iter().enumerate() {
+        if let Some(reason) = never_fires_reason(rule) {
+            ignored[index] = true;
+            issues.push(LintIssue {
+                kind: LintIssueKind::NeverFires,
+                rule: index + 1,
+                message: format!("rule {} never fires, {}", index + 1, reason),
+            });
+        }
+    }
+
+    for (index, rule) in rules.iter().enumerate() {
+        if ignored[index] {
+            continue;
+        }
+        let shadowing_index = rules.iter().enumerate().position(|(other_index, other)| {
+            other_index != index
+                && !ignored[other_index]
+                && is_covered_by(rule, other)
+                && match other_index < index {
+                    true => other.bump >= rule.bump,
+                    false => other.bump > rule.bump,
+                }
+        });
+        if let Some(other_index) = shadowing_index {
+            ignored[index] = true;
+            issues.push(LintIssue {
+                kind: LintIssueKind::Shadowed,
+                rule: index + 1,
+                message: format!(
+                    "rule {} ({}) is shadowed by rule {} ({}), every commit matched by rule {} is also matched by rule {}",
+                    index + 1,
+                    rule.bump.as_str(),
+                    other_index + 1,
+                    rules[other_index].bump.as_str(),
+                    index + 1,
+                    other_index + 1
+                ),
+            });
+        }
+    }
+
+    for (index, rule) in rules.iter().enumerate() {
+        if ignored[index] {
+            continue;
+        }
+        for (other_index, other) in rules.iter().enumerate().skip(index + 1) {
+            if ignored[other_index] || rule.bump == other.bump || !has_same_conditions(rule, other)
+            {
+                continue;
+            }
+            let shared_types: Vec<&str> = match (&rule.types, &other.types) {
+                (Some(types), Some(other_types)) => types
+                    .iter()
+                    .filter(|commit_type| other_types.contains(commit_type))
+                    .map(|commit_type| commit_type.as_str())
+                    .collect(),
+                _ => continue,
+            };
+            for commit_type in shared_types {
+                issues.push(LintIssue {
+                    kind: LintIssueKind::Contradictory,
+                    rule: other_index + 1,
+                    message: format!(
+                        "rules {} ({}) and {} ({}) both match the type `{}`, the {} bump is applied",
+                        index + 1,
+                        rule.bump.as_str(),
+                        other_index + 1,
+                        other.bump.as_str(),
+                        commit_type,
+                        rule.bump.max(other.bump).as_str()
+                    ),
+                });
+            }
+        }
+    }
+
+    for (index, rule) in rules.iter().enumerate() {
+        for scope in rule.scopes.iter().flatten() {
+            if config.version_scopes.contains(scope) {
+                continue;
+            }
+            issues.push(LintIssue {
+                kind: LintIssueKind::UndeclaredScope,
+                rule: index + 1,
+                message: format!(
+                    "rule {} references the scope `{}`, which is not declared in `version_scopes`",
+                    index + 1,
+                    scope
+                ),
+            });
+        }
+    }
+
+    issues.sort_by_key(|issue| issue.rule);
+    issues
+}
+
+/// Returns why the given rule can never match a commit, or `None` if it can.
+///
+/// # Arguments
+///
+/// * `rule` - Bump rule to check.
+///
+fn never_fires_reason(rule: &BumpRule) -> Option<&'static str> {
+    if rule.types.is_none()
+        && rule.scopes.is_none()
+        && rule.if_breaking_field.is_none()
+        && rule.if_breaking_description.is_none()
+    {
+        return Some("it has no conditions");
+    }
+    if rule.types.as_ref().is_some_and(|types| types.is_empty()) {
+        return Some("its `types` list is empty");
+    }
+    if rule.scopes.as_ref().is_some_and(|scopes| scopes.is_empty()) {
+        return Some("its `scopes` list is empty");
+    }
+    if rule.if_breaking_field == Some(false) {
+        return Some("`if_breaking_field: false` never matches");
+    }
+    if rule.if_breaking_description == Some(false) {
+        return Some("`if_breaking_description: false` never matches");
+    }
+    None
+}
+
+/// Returns `true` if every commit matched by `rule` is also matched by `other`.
+///
+/// # Arguments
+///
+/// * `rule` - Bump rule that may be covered.
+///
+/// * `other` - Bump rule that may cover `rule`.
+///
+fn is_covered_by(rule: &BumpRule, other: &BumpRule) -> bool {
+    let is_list_covered =
+        |list: &Option<Vec<String>>, other_list: &Option<Vec<String>>| match (list, other_list) {
+            (_, None) => true,
+            (Some(list), Some(other_list)) => list.iter().all(|value| other_list.contains(value)),
+            (None, Some(_)) => false,
+        };
+    let is_flag_covered = |flag: Option<bool>, other_flag: Option<bool>| {
+        other_flag.is_none() || (flag == Some(true) && other_flag == Some(true))
+    };
+
+    is_list_covered(&rule.types, &other.types)
+        && is_list_covered(&rule.scopes, &other.scopes)
+        && is_flag_covered(rule.if_breaking_field, other.if_breaking_field)
+        && is_flag_covered(rule.if_breaking_description, other.if_breaking_description)
+}
+
+/// Returns `true` if the given rules have the same conditions, except for their commit types.
+///
+/// # Arguments
+///
+/// * `rule` - First bump rule.
+///
+/// * `other` - Second bump rule.
+///
+fn has_same_conditions(rule: &BumpRule, other: &BumpRule) -> bool {
+    let sorted = |list: &Option<Vec<String>>| {
+        list.as_ref().map(|list| {
+            let mut list = list.clone();
+            list.sort();
+            list
+        })
+    };
+
+    sorted(&rule.scopes) == sorted(&other.scopes)
+        && rule.if_breaking_field == other.if_breaking_field
+        && rule.if_breaking_description == other.if_breaking_description
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::version::IncrementKind;
+
+    fn rule(bump: IncrementKind, types: Option<&[&str]>, scopes: Option<&[&str]>) -> BumpRule {
+        let list = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+        BumpRule {
+            bump,
+            types: types.map(list),
+            scopes: scopes.map(list),
+            if_breaking_field: None,
+            if_breaking_description: None,
+        }
+    }
+
+    /// Lints the given bump rules with the version scopes `api` and `cli`, returning the kind and rule of each issue.
+    fn lint(rules: Vec<BumpRule>) -> Vec<(LintIssueKind, usize)> {
+        let mut config = Config::new();
+        config.version_scopes = vec!["api".to_owned(), "cli".to_owned()];
+        config.bump_rules = rules;
+        lint_config(&config)
+            .iter()
+            .map(|issue| (issue.kind, issue.rule))
+            .collect()
+    }
+
+    #[test]
+    fn default_rules_have_no_issues() {
+        assert!(lint_config(&Config::new()).is_empty());
+    }
+
+    #[test]
+    fn rules_that_never_fire_or_are_shadowed_are_reported() {
+        use IncrementKind::*;
+
+        let issues = lint(vec![
+            rule(Minor, Some(&["feat"]), None),
+            rule(Patch, Some(&["feat"]), Some(&["api"])),
+            rule(Major, Some(&[]), None),
+            rule(Patch, None, None),
+        ]);
+        assert_eq!(
+            issues,
+            vec![
+                (LintIssueKind::Shadowed, 2),
+                (LintIssueKind::NeverFires, 3),
+                (LintIssueKind::NeverFires, 4),
+            ]
+        );
+    }
+
+    #[test]
+    fn contradictory_rules_and_undeclared_scopes_are_reported() {
+        use IncrementKind::*;
+
+        let issues = lint(vec![
+            rule(Patch, Some(&["fix", "docs"]), None),
+            rule(Minor, Some(&["fix", "perf"]), None),
+            rule(Major, Some(&["feat"]), Some(&["web"])),
+        ]);
+        assert_eq!(
+            issues,
+            vec![
+                (LintIssueKind::Contradictory, 2),
+                (LintIssueKind::UndeclaredScope, 3),
+            ]
+        );
+    }
+}
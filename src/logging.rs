@@ -0,0 +1,83 @@
+//! This module provides the logging of the diagnostic messages meant for humans, such as progress messages and
+//! warnings.
+//!
+//! Diagnostic messages are always printed to stderr, so the output printed to stdout can be parsed in every output
+//! format. The amount of messages is selected with `--quiet` and `--verbose`.
+//!
+
+use std::sync::OnceLock;
+
+/// Type that represents the amount of diagnostic messages that are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Only errors are printed.
+    Quiet,
+    /// Progress messages and warnings are printed.
+    Normal,
+    /// Details of the calculation are also printed.
+    Verbose,
+}
+
+/// Verbosity of the run, set by `set_verbosity`.
+static VERBOSITY: OnceLock<Verbosity> = OnceLock::new();
+
+/// Sets the verbosity of the run. Only the first call has effect.
+///
+/// # Arguments
+///
+/// * `verbosity` - Amount of diagnostic messages that are printed.
+///
+pub fn set_verbosity(verbosity: Verbosity) {
+    let _ = VERBOSITY.set(verbosity);
+}
+
+/// Returns the verbosity of the run, `Verbosity::Normal` if it was not set.
+fn verbosity() -> Verbosity {
+    VERBOSITY.get().copied().unwrap_or(Verbosity::Normal)
+}
+
+/// Prints a warning, unless the verbosity is `Verbosity::Quiet`.
+///
+/// # Arguments
+///
+/// * `message` - Message of the warning.
+///
+pub fn warn(message: &str) {
+    if verbosity() >= Verbosity::Normal {
+        eprintln!("warning: {}", message);
+    }
+}
+
+/// Prints a progress message, unless the verbosity is `Verbosity::Quiet`.
+///
+/// # Arguments
+///
+/// * `message` - Message that will be printed.
+///
+pub fn info(message: &str) {
+    if verbosity() >= Verbosity::Normal {
+        eprintln!("{}", message);
+    }
+}
+
+/// Prints a message with details of the calculation, only if the verbosity is `Verbosity::Verbose`.
+///
+/// # Arguments
+///
+/// * `message` - Message that will be printed.
+///
+pub fn debug(message: &str) {
+    if verbosity() >= Verbosity::Verbose {
+        eprintln!("{}", message);
+    }
+}
+
+/// Prints an error. Errors are printed with every verbosity.
+///
+/// # Arguments
+///
+/// * `message` - Message of the error.
+///
+pub fn error(message: &str) {
+    eprintln!("{}", message);
+}
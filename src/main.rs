@@ -1,124 +1,677 @@
-use clap::Parser;
-use config::{is_config_available, parse_config_file, Config};
-use error::{Error, ErrorKind};
-use serde::Serialize;
-use serde_json::to_string_pretty;
-use source::SourceActions;
-use std::{collections::HashMap, process::exit};
-use version::{
-    calculate_increment, increment_major, increment_minor, increment_patch, IncrementKind,
-};
+mod cli;
 
-mod config;
-mod error;
-mod git;
-mod parsing;
-mod source;
-mod version;
+use clap::{FromArgMatches, Parser, Subcommand};
+use cli::args::{
+    check_read_only, check_unknown_fields, get_source_options, parse_cli_args, read_env_fallbacks,
+    resolve_command_alias,
+};
+use cli::bump::{bump_versions, check_release_branches};
+use cli::checks::{check_changelog, check_config, check_diff_report, check_tags, validate_config};
+use cli::init::{init_config, InitOptions};
+use cli::output::{print_error, OutputField, OutputFormat};
+use cli::plan::{apply_plan, approve_plan, promote_env, Promotion};
+use serde::Serialize;
+use std::{path::PathBuf, process::exit};
+use tag_track::bump::DEFAULT_MAX_SCOPES;
+use tag_track::cancel::{cancel_on_interrupt, CancellationToken};
+use tag_track::changelog::DEFAULT_CHANGELOG_FILE;
+use tag_track::config::{is_config_available, parse_config_file, Config, TagType};
+use tag_track::error::{Error, ErrorKind};
+use tag_track::extra_tags::read_extra_tags_file;
+use tag_track::git;
+use tag_track::legacy::{deprecations, migrate_args};
+use tag_track::lint::lint_config;
+use tag_track::logging::{self, Verbosity};
+use tag_track::parsing::normalize_scope;
+use tag_track::run;
+use tag_track::source;
+use tag_track::version::parse_user_version;
+
+/// Name of the git remote inspected to detect the source with `--auto-source`.
+const AUTO_SOURCE_REMOTE: &str = "origin";
+
+/// Name of the configuration file written by the `init` subcommand if `--config` is not used.
+const INIT_CONFIG_FILE_NAME: &str = "track.yml";
+
+/// Prefix of the environment variables that set the CLI arguments.
+const ENV_VAR_PREFIX: &str = "TAG_TRACK_";
+
+/// Exit code of `--check` when no scope requires a version bump.
+const CHECK_NO_BUMP_EXIT_CODE: i32 = 2;
+
+/// Value of `--release-notes-file` that prints the release notes to stdout.
+const RELEASE_NOTES_STDOUT: &str = "-";
+
+/// Version printed by `--version`, including the TLS backend used by the sources that call REST APIs.
+#[cfg(all(feature = "http", feature = "rustls"))]
+const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), " (tls: rustls)");
+/// Version printed by `--version`, including the TLS backend used by the sources that call REST APIs.
+#[cfg(all(feature = "http", feature = "native-tls", not(feature = "rustls")))]
+const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), " (tls: native-tls)");
+/// Version printed by `--version`, including the TLS backend used by the sources that call REST APIs.
+#[cfg(not(all(feature = "http", any(feature = "rustls", feature = "native-tls"))))]
+const VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), " (tls: none)");
 
 /// Type that defines CLI arguments.
-#[derive(Parser, Debug, Serialize, Clone)]
-#[command(author, version, about, long_about = None)]
+#[derive(Parser, Debug, Clone)]
+#[command(author, version = VERSION, about, long_about = None)]
 struct Args {
-    /// Create git annotated tag from populated version.
-    #[arg(long, default_value = "false", default_missing_value = "true")]
+    /// Create git tag from populated version. Tags are annotated unless '--lightweight' or the 'tag_type' of the
+    /// configuration selects lightweight tags.
+    #[arg(
+        long,
+        global = true,
+        default_value = "false",
+        default_missing_value = "true"
+    )]
     create_tag: bool,
 
-    /// GitHub URL. Defaults to 'https://api.github.com'.
+    /// Report the tags and releases that would be created, with their names, messages and commits, without creating
+    /// them. Also supported by the 'apply' and 'promote-env' subcommands. The state file is not written.
+    #[arg(
+        long,
+        global = true,
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    dry_run: bool,
+
+    /// Only create tags and releases for the given scope, while the version bumps of all scopes are still
+    /// calculated. Can be used multiple times. Use an empty value for the empty scope. Defaults to all scopes.
+    #[arg(long, global = true)]
+    create_tag_for: Vec<String>,
+
+    /// Sign the created tags with the 'signing_key' of the configuration or the 'user.signingkey' git option. Only
+    /// supported by the git source with the 'cli' backend.
+    #[arg(
+        long,
+        global = true,
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    sign_tag: bool,
+
+    /// Fetch the full history and the tags with 'git fetch --unshallow' when the repository is a shallow clone,
+    /// instead of failing. Only supported by the git source with the 'cli' backend.
+    #[arg(
+        long,
+        global = true,
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    auto_deepen: bool,
+
+    /// Create lightweight tags, without a tag object, instead of annotated tags. Overrides the 'tag_type' of the
+    /// configuration.
+    #[arg(
+        long,
+        global = true,
+        default_value = "false",
+        default_missing_value = "true",
+        conflicts_with = "sign_tag"
+    )]
+    lightweight: bool,
+
+    /// Create a release for each new tag, titled with the 'new_release_title' of the configuration and with release
+    /// notes generated from the commits of the version bump. Used with '--create-tag' or stored in plan files by the
+    /// 'plan' subcommand. Only supported by the GitHub source.
+    #[arg(
+        long,
+        global = true,
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    create_release: bool,
+
+    /// Create a single release for all new tags instead of one release per tag, with a section of release notes per
+    /// scope. The release is created for the tag of the empty scope, or for a run tag named with the 'run_tag_name' of
+    /// the configuration if the empty scope has no new tag. Requires '--create-release'.
+    #[arg(
+        long,
+        global = true,
+        default_value = "false",
+        default_missing_value = "true",
+        requires = "create_release"
+    )]
+    aggregate_release: bool,
+
+    /// Path of the file where the release notes of the new tags are written, rendered with the 'release_notes_template'
+    /// of the configuration or the built-in format. Use '-' to print them to stdout.
+    #[arg(long, global = true)]
+    release_notes_file: Option<PathBuf>,
+
+    /// Version of the first tag of the scopes without tags, whose version bump is calculated from the whole history.
+    /// Overrides the 'initial_version' of the configuration.
+    #[arg(long, global = true)]
+    initial_version: Option<String>,
+
+    /// Only parse and create the tags of the given namespace, whose names are the namespace followed by a '/' and a
+    /// name matching the 'tag_pattern' of the configuration, such as 'staging/v1.4.0'. Each namespace has its own
+    /// version line.
+    #[arg(long, global = true)]
+    tag_namespace: Option<String>,
+
+    /// GitHub URL. Defaults to the environment variable 'GITHUB_API_URL' or 'https://api.github.com'.
     #[arg(
         long,
-        default_value = source::github::GITHUB_API_BASE_URL,
-        default_missing_value = source::github::GITHUB_API_BASE_URL
+        global = true,
+        default_value = source::GITHUB_API_BASE_URL,
+        default_missing_value = source::GITHUB_API_BASE_URL
     )]
     github_api_url: String,
 
     /// GitHub repository identifier (owner/repo_name).
-    /// If present, this will use GitHub as the source to calculate a version bump.
-    #[arg(long)]
+    /// If present, this will use GitHub as the source to calculate a version bump. Defaults to the environment
+    /// variable 'GITHUB_REPOSITORY' if no other source is selected.
+    #[arg(long, global = true)]
     github_repo: Option<String>,
 
-    /// Token to authenticate  GitHub REST API calls.
-    #[arg(long)]
+    /// URL of a git remote. If present, this will use the git remote as the source to calculate a version bump,
+    /// fetching only the required commits.
+    #[arg(long, global = true, conflicts_with = "github_repo")]
+    git_remote: Option<String>,
+
+    /// Detect the source from the URL of the 'origin' git remote. If the remote is hosted on GitHub, GitHub is used as
+    /// the source, otherwise the local git history is used.
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["github_repo", "git_remote"],
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    auto_source: bool,
+
+    /// Include a provisional commit for the uncommitted changes of the working tree to preview the version bump
+    /// they would produce once committed. The commit message is read from '--message' or '.git/COMMIT_EDITMSG'.
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["github_repo", "git_remote", "auto_source", "create_tag"],
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    include_working_tree: bool,
+
+    /// Message of the provisional commit used with '--include-working-tree'.
+    #[arg(long, global = true, requires = "include_working_tree")]
+    message: Option<String>,
+
+    /// Calculate the version bumps of every branch matching the release branch pattern of the configuration instead
+    /// of the current commit, so all maintained release lines can be checked in a single run.
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["create_tag", "include_working_tree", "commit_sha", "release_metadata_dir"],
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    all_release_branches: bool,
+
+    /// Print snapshot versions, such as '1.4.0-nightly.20240601+sha.abc1234', based on the next version of each
+    /// scope, for nightly artifact pipelines. Tags are never created.
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["create_tag", "create_release", "all_release_branches", "release_metadata_dir"],
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    snapshot: bool,
+
+    /// Calculate the version bumps without creating tags and exit with code 0 if at least one scope requires a
+    /// version bump, 2 if no scope requires a version bump and 1 on errors.
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["create_tag", "create_release", "all_release_branches", "snapshot"],
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    check: bool,
+
+    /// Print the commits that contributed to each version bump, with the bump rule that each commit triggered and
+    /// the resulting increment. The commits are always included in the 'provenance' field of the JSON output.
+    #[arg(
+        long,
+        global = true,
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    explain: bool,
+
+    /// Directory of the on-disk HTTP cache used for GitHub REST API requests. Cached responses are revalidated with
+    /// conditional requests, so unchanged responses do not consume the rate limit.
+    #[arg(long, global = true)]
+    github_cache_dir: Option<PathBuf>,
+
+    /// Number of GitHub REST API pages requested concurrently when paginating tags, branches and commits.
+    #[arg(
+        long,
+        global = true,
+        default_value_t = source::DEFAULT_CONCURRENT_PAGES,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    github_concurrent_pages: u64,
+
+    /// URL of the proxy used for HTTP connections of the GitHub and git remote sources. Proxy credentials can be given
+    /// in the URL. Defaults to the proxy environment variables, such as 'HTTP_PROXY'.
+    #[arg(long, global = true)]
+    http_proxy: Option<String>,
+
+    /// URL of the proxy used for HTTPS connections of the GitHub and git remote sources. Proxy credentials can be
+    /// given in the URL. Defaults to the proxy environment variables, such as 'HTTPS_PROXY'.
+    #[arg(long, global = true)]
+    https_proxy: Option<String>,
+
+    /// Path of a PEM file with additional CA certificates trusted by the GitHub source, for GitHub Enterprise Server
+    /// instances that use an internal CA. The file can contain several certificates.
+    #[arg(long, global = true)]
+    tls_ca_cert: Option<PathBuf>,
+
+    /// DANGEROUS: disable the verification of TLS certificates in the GitHub source. Any certificate is accepted, so
+    /// the GitHub token and the responses can be intercepted by a man-in-the-middle. Prefer '--tls-ca-cert'.
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "tls_ca_cert",
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    tls_insecure: bool,
+
+    /// Timeout in seconds of each request sent by the GitHub source. A value of '0' disables the timeout.
+    #[arg(
+        long,
+        global = true,
+        default_value_t = source::DEFAULT_REQUEST_TIMEOUT.as_secs()
+    )]
+    request_timeout: u64,
+
+    /// Number of retries of GitHub source read requests that time out, fail to connect or get a '5xx' response.
+    #[arg(long, global = true, default_value_t = source::DEFAULT_REQUEST_RETRIES)]
+    request_retries: u32,
+
+    /// Maximum number of version scopes found in the tags and commits. The run is aborted if it is exceeded, as a
+    /// tag pattern that matches unrelated tags can create hundreds of scopes. A value of '0' disables the check.
+    #[arg(long, global = true, default_value_t = DEFAULT_MAX_SCOPES)]
+    max_scopes: usize,
+
+    /// Log every request sent by the GitHub source to stderr, with its status, duration and remaining rate limit.
+    #[arg(
+        long,
+        global = true,
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    log_requests: bool,
+
+    /// Token to authenticate  GitHub REST API calls. Defaults to the environment variable 'GITHUB_TOKEN'.
+    #[arg(long, global = true)]
     github_token: Option<String>,
 
+    /// Identifier of a GitHub App installed on the repository. The GitHub source exchanges a token signed with the
+    /// private key of the app for an installation token scoped to the repository, used instead of '--github-token'.
+    #[arg(long, global = true, requires = "github_app_private_key")]
+    github_app_id: Option<String>,
+
+    /// Path of the PEM encoded private key of the GitHub App of '--github-app-id'.
+    #[arg(long, global = true, requires = "github_app_id")]
+    github_app_private_key: Option<PathBuf>,
+
     /// All commits between the oldest tag and the one specified
     /// by this SHA will be used to calculate the version bump. Useful when using
     /// a remote repository with different git history as the local repository.
-    #[arg(long)]
+    #[arg(long, global = true)]
     commit_sha: Option<String>,
 
-    /// Output format, possible values are: 'text', 'json'. Default value is 'text'.
-    #[arg(long, default_value = "text", default_missing_value = "text")]
+    /// Reference of the commit that starts the range of commits used to calculate the version bump, such as the
+    /// branch point of the last release branch. The commit and the commits older than it do not count for the
+    /// version bump, but are still searched for the closest tags. Accepts commit SHAs, branches and tags.
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["all_release_branches", "state_file"]
+    )]
+    from_ref: Option<String>,
+
+    /// Reference of the commit that ends the range of commits used to calculate the version bump, used instead of
+    /// the latest commit. New tags reference this commit. Accepts commit SHAs, branches and tags.
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["commit_sha", "all_release_branches", "include_working_tree"]
+    )]
+    to_ref: Option<String>,
+
+    /// Only request the commits committed at or after this date, in the 'YYYY-MM-DDTHH:MM:SSZ' format, so the GitHub
+    /// REST API stops paginating at the date. Tags of older commits are still used as closest tags. Only supported
+    /// by the GitHub source.
+    #[arg(long, global = true, conflicts_with = "state_file")]
+    since_date: Option<String>,
+
+    /// Only request the commits committed at or before this date, in the 'YYYY-MM-DDTHH:MM:SSZ' format. Only
+    /// supported by the GitHub source.
+    #[arg(long, global = true, conflicts_with = "create_tag")]
+    until_date: Option<String>,
+
+    /// Backend used to read the local git repository when GitHub is not used as the source.
+    #[arg(long, global = true, value_enum, default_value_t = source::GitBackend::Cli)]
+    git_backend: source::GitBackend,
+
+    /// Directory of the local git repository. Git commands are invoked in this directory and the configuration file
+    /// is discovered from it. Defaults to the current directory.
+    #[arg(long, global = true)]
+    repo_path: Option<PathBuf>,
+
+    /// Path of the configuration file, relative to the current directory. Can also be set with the environment
+    /// variable 'TAG_TRACK_CONFIG'. Defaults to the closest 'track.yml', 'track.yaml', 'track.toml' or 'track.json'
+    /// in the directory of the repository or its parents.
+    #[arg(long = "config", global = true, env = "TAG_TRACK_CONFIG")]
+    config_file: Option<PathBuf>,
+
+    /// Fail if the configuration file has unknown fields, such as misspelled keys, instead of printing a warning for
+    /// each of them.
+    #[arg(
+        long,
+        global = true,
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    strict_config: bool,
+
+    /// Directory where a 'release-metadata.json' file is written for each bumped scope.
+    #[arg(long, global = true)]
+    release_metadata_dir: Option<PathBuf>,
+
+    /// Path of a YAML or JSON file with a list of additional tags, with their 'name' and 'commit_sha', merged with the
+    /// tags of the source. Useful for versions kept outside git, such as marketing versions.
+    #[arg(long, global = true)]
+    extra_tags_file: Option<PathBuf>,
+
+    /// Path of a state file recording the last evaluated commit and the closest tags of each scope. If present, only
+    /// the commits added after the last evaluated commit are scanned. Not written by the 'plan' subcommand.
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["all_release_branches", "include_working_tree"]
+    )]
+    state_file: Option<PathBuf>,
+
+    /// Path of a JSON Lines file where an entry is appended for each created tag, with its scope, version, commit,
+    /// release URL, creation time and run identifier. Not written by the 'plan' subcommand or with '--dry-run'.
+    #[arg(long, global = true)]
+    ledger_file: Option<PathBuf>,
+
+    /// Output format, possible values are: 'text', 'json', 'matrix'. Default value is 'text'.
+    #[arg(
+        long,
+        global = true,
+        default_value = "text",
+        default_missing_value = "text"
+    )]
     output_format: String,
-}
 
-/// Type for storing the required data that needs to be printed in the terminal in different formats.
-#[derive(Serialize, Debug)]
-struct Output<'a> {
-    /// User inputted CLI arguments.
-    inputs: &'a Args,
-    /// Configuration that was used.
-    config: Option<&'a Config>,
-    /// If at least one tag was created.
-    tag_created: bool,
-    /// New tags that were created.
-    new_tags: Vec<String>,
-    /// Information on the version bump of a scope.
-    version_bumps: Vec<OutputVersionBump<'a>>,
-    /// Commits that were skipped during the version bump due to pattern mismatch.
-    skipped_commits: &'a Vec<String>,
-    /// Error message if any.
-    error: String,
-}
+    /// Template printed once for each scope instead of the text output, with the placeholders '{scope}',
+    /// '{old_version}', '{new_version}' and '{tag_name}'. Only supported with the 'text' output format.
+    #[arg(long, global = true)]
+    output_template: Option<String>,
+
+    /// Write the JSON output to the given file, regardless of the output format.
+    #[arg(long, global = true)]
+    output_file: Option<PathBuf>,
 
-/// Type for storing scope versions.
-#[derive(Serialize, Debug, Clone)]
-struct OutputVersionBump<'a> {
-    /// Scope of the version.
-    scope: String,
-    /// Old version number before bump.
-    old_version: String,
-    /// New version number after bump.
-    new_version: String,
-    /// Kind of increment that was applied.
-    increment_kind: &'a Option<IncrementKind>,
+    /// Comma separated list of top-level fields included in the JSON output. The 'error' field is always included.
+    /// Defaults to all fields.
+    #[arg(long, global = true, value_enum, value_delimiter = ',')]
+    output_fields: Vec<OutputField>,
+
+    /// Do not print progress messages and warnings to stderr. Errors are still printed.
+    #[arg(
+        long,
+        global = true,
+        default_value = "false",
+        default_missing_value = "true",
+        conflicts_with = "verbose"
+    )]
+    quiet: bool,
+
+    /// Print details of the calculation to stderr, such as the source and the closest tag of each scope.
+    #[arg(
+        long,
+        global = true,
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    verbose: bool,
+
+    /// Do not read implicit environment variables, such as 'GITHUB_TOKEN', 'GITHUB_REPOSITORY', 'GITHUB_API_URL',
+    /// 'GITHUB_SHA' or the 'TAG_TRACK_*' variables of the arguments.
+    #[arg(
+        long,
+        global = true,
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    no_env: bool,
+
+    /// Shared key used to sign and verify plan approvals. Can also be set with the environment variable
+    /// 'TAG_TRACK_APPROVAL_KEY'.
+    #[arg(
+        long,
+        global = true,
+        env = "TAG_TRACK_APPROVAL_KEY",
+        hide_env_values = true
+    )]
+    approval_key: Option<String>,
+
+    /// Disable every action that writes to the repository or to files, such as creating tags and releases or writing
+    /// the state file, regardless of the other arguments and the configuration. Runs that request such an action
+    /// fail. Used to run Tag Track on untrusted code, such as pull requests from forks.
+    #[arg(
+        long,
+        global = true,
+        default_value = "false",
+        default_missing_value = "true"
+    )]
+    read_only: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
 }
 
-impl<'a> Output<'a> {
-    /// Creates a new `Output` instance with the given `inputs`.
-    fn new(inputs: &'a Args, config: Option<&'a Config>, skipped_commits: &'a Vec<String>) -> Self {
-        Self {
-            inputs,
-            config,
-            tag_created: false,
-            new_tags: vec![],
-            version_bumps: vec![],
-            skipped_commits,
-            error: "".to_owned(),
-        }
-    }
+/// Type that defines CLI subcommands.
+#[derive(Subcommand, Debug, Serialize, Clone)]
+enum Command {
+    /// Calculate the version bumps. This is the default subcommand, used when Tag Track is invoked without one.
+    Bump,
+    /// Calculate the version bumps without creating tags and exit with code 2 if no scope requires a version bump,
+    /// same as '--check'.
+    Check,
+    /// Calculate the version bumps and create a tag for each new version, same as '--create-tag'.
+    Tag,
+    /// Calculate the version bumps and print the release notes of the new versions to stdout, same as
+    /// '--release-notes-file -'.
+    Changelog,
+    /// Lint the bump rules of the configuration, same as 'config lint'.
+    Lint,
+    /// Calculate the version bumps and write the tags that would be created into a plan file without creating them.
+    Plan {
+        /// Path of the plan file.
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Create the tags described in a plan file generated by the `plan` subcommand.
+    Apply {
+        /// Path of the plan file.
+        plan_file: PathBuf,
+
+        /// Path of the approval file generated by the `approve` subcommand. If present, the approval is
+        /// verified before creating any tag.
+        #[arg(long)]
+        approval: Option<PathBuf>,
+    },
+    /// Approve a plan file by signing its hash with the approval key.
+    Approve {
+        /// Path of the plan file.
+        plan_file: PathBuf,
+
+        /// Path of the approval file.
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Verify that the changelog sections of the latest releases match the release notes derived from the local git
+    /// history, failing if a released section was edited by hand.
+    VerifyChangelog {
+        /// Path of the changelog file.
+        #[arg(long, default_value = DEFAULT_CHANGELOG_FILE)]
+        file: PathBuf,
+    },
+    /// Validate the existing tags of the local git repository against the tag pattern, reporting tags that do not
+    /// parse, duplicated versions, gaps in version sequences and scope inconsistencies.
+    AuditTags,
+    /// Compare two output or plan files, for example from runs on different branches, reporting the scopes whose
+    /// computed version changed and why.
+    DiffReport {
+        /// Path of the output or plan file used as baseline.
+        old_file: PathBuf,
+
+        /// Path of the output or plan file compared against the baseline.
+        new_file: PathBuf,
+    },
+    /// Promote a version from a tag namespace to another one, creating the tag of the target namespace on the commit
+    /// referenced by the tag of the source namespace. A release is also created if '--create-release' is used.
+    PromoteEnv {
+        /// Namespace of the existing tag, such as 'staging'.
+        #[arg(long)]
+        from: String,
+
+        /// Namespace of the new tag, such as 'prod'.
+        #[arg(long)]
+        to: String,
+
+        /// Version that is promoted.
+        #[arg(long)]
+        version: String,
+
+        /// Scope of the version. Defaults to the empty scope.
+        #[arg(long, default_value = "")]
+        scope: String,
+    },
+    /// Inspect the configuration file.
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+    /// Write a commented configuration file with the default commit pattern and bump rules, and a tag pattern and
+    /// version scopes discovered from the existing tags. The file is written to '--config' or to 'track.yml' in the
+    /// repository.
+    Init {
+        /// Overwrite the configuration file if it already exists.
+        #[arg(long, default_value = "false", default_missing_value = "true")]
+        force: bool,
+
+        /// Ask for the tag pattern, version scopes and initial version, using the discovered values as defaults.
+        #[arg(long, default_value = "false", default_missing_value = "true")]
+        interactive: bool,
+
+        /// Tag pattern of the configuration. Overrides the tag pattern discovered from the existing tags.
+        #[arg(long)]
+        tag_pattern: Option<String>,
+
+        /// Scope versioned independently, can be used multiple times. Overrides the scopes discovered from the
+        /// existing tags.
+        #[arg(long = "scope")]
+        scopes: Vec<String>,
+    },
 }
 
-/// Type for valid output formats.
-enum OutputFormat {
-    Text,
-    Json,
+/// Type that defines the subcommands of the `config` subcommand.
+#[derive(Subcommand, Debug, Serialize, Clone)]
+enum ConfigCommand {
+    /// Lint the bump rules, reporting rules that can never fire, rules shadowed by other rules, contradictory rules
+    /// for the same commit type and rules that reference scopes not declared in `version_scopes`.
+    Lint,
+    /// Validate the configuration file, reporting unknown fields, regex patterns that do not compile or do not expose
+    /// their required capturing groups, and invalid versions and scopes, with the line where they are found.
+    Validate,
 }
 
 fn main() {
-    let args = Args::parse();
-
-    let output_format = match args.output_format.as_str() {
-        "text" => OutputFormat::Text,
-        "json" => OutputFormat::Json,
-        value => {
-            let error = Error::new(ErrorKind::InvalidOutputFormat, Some(value));
-            println!("{}", error);
+    run::current();
+    let matches = parse_cli_args(migrate_args(std::env::args_os().collect()));
+    let mut args = match Args::from_arg_matches(&matches) {
+        Ok(args) => args,
+        Err(error) => error.exit(),
+    };
+    read_env_fallbacks(&mut args, &matches);
+    logging::set_verbosity(match (args.quiet, args.verbose) {
+        (true, _) => Verbosity::Quiet,
+        (_, true) => Verbosity::Verbose,
+        _ => Verbosity::Normal,
+    });
+    for deprecation in deprecations() {
+        logging::warn(&deprecation.message);
+    }
+    let cancel = CancellationToken::new();
+    cancel_on_interrupt(&cancel);
+
+    let output_format = match get_output_format(&args) {
+        Ok(output_format) => output_format,
+        Err(error) => {
+            logging::error(&error.to_string());
             exit(1);
         }
     };
 
-    let config = match is_config_available() {
+    if let Err(error) = check_args(&mut args, &output_format) {
+        print_error(error, &args, &output_format, None);
+        exit(1);
+    }
+
+    if let Some(Command::Init {
+        force,
+        interactive,
+        tag_pattern,
+        scopes,
+    }) = &args.command
+    {
+        let path = match &args.config_file {
+            Some(path) => path.clone(),
+            None => git::repo_path().join(INIT_CONFIG_FILE_NAME),
+        };
+        let options = InitOptions {
+            force: *force,
+            interactive: *interactive,
+            tag_pattern: tag_pattern.as_deref(),
+            scopes,
+        };
+        init_config(&path, options, &args, &output_format);
+        return;
+    }
+    let config_file_path = match &args.config_file {
+        Some(path) if !path.is_file() => {
+            let error = Error::new(
+                ErrorKind::Other,
+                Some(&format!(
+                    "the configuration file `{}` does not exist",
+                    path.display()
+                )),
+            );
+            print_error(error, &args, &output_format, None);
+            exit(1);
+        }
+        Some(path) => Some(path.clone()),
+        None => is_config_available(),
+    };
+    if let Some(Command::Config {
+        command: ConfigCommand::Validate,
+    }) = &args.command
+    {
+        validate_config(config_file_path.as_deref(), &args, &output_format);
+        return;
+    }
+    let mut config = match config_file_path {
         Some(config_file_path) => match parse_config_file(config_file_path) {
             Ok(config) => config,
             Err(error) => {
@@ -128,245 +681,231 @@ fn main() {
         },
         None => Config::new(),
     };
-
-    // TODO: This will not work when we have more sources
-    let source: source::SourceKind = match args.github_repo.clone() {
-        Some(repo) => source::SourceKind::Github(source::github::GithubSource::new(
-            &config,
-            repo,
-            validate_trailing_slash(&args.github_api_url),
-            args.github_token.clone(),
-        )),
-        None => {
-            if let Err(error) = git::verify_git() {
-                print_error(error, &args, &output_format, Some(&config));
-                exit(1);
-            }
-            source::SourceKind::Git(source::git::GitSource::new(&config))
-        }
-    };
-
-    let commit_sha = match &args.commit_sha {
-        Some(commit_sha) => commit_sha.clone(),
-        None => match source.get_latest_commit_sha() {
-            Ok(commit_sha) => commit_sha,
-            Err(error) => {
-                print_error(error, &args, &output_format, Some(&config));
-                exit(1);
-            }
-        },
-    };
-
-    let ref_iterator = match source.get_ref_iterator(&commit_sha) {
-        Ok(ref_iterator) => ref_iterator,
-        Err(error) => {
-            print_error(error, &args, &output_format, Some(&config));
-            exit(1);
-        }
-    };
-
-    let mut version_bumps: HashMap<String, Option<IncrementKind>> = HashMap::new();
-    for scope in &config.version_scopes {
-        version_bumps.insert(scope.clone(), None);
+    if let Err(error) = apply_args_to_config(&mut args, &mut config) {
+        print_error(error, &args, &output_format, Some(&config));
+        exit(1);
     }
 
-    let mut skipped_commits_sha = vec![];
-    let mut closest_tags = vec![];
-    for r in ref_iterator {
-        let r = match r {
-            Ok(refs) => refs,
-            Err(error) => {
-                print_error(error, &args, &output_format, Some(&config));
-                exit(1);
-            }
-        };
+    run_command(&args, &config, &output_format, &cancel);
+}
 
-        if let Some(tags) = r.tags {
-            closest_tags.reserve(tags.len());
-            closest_tags.extend(tags);
-        }
+/// Returns the output format selected by `--output-format` and `--output-template`.
+///
+/// # Arguments
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidOutputFormat` if the output format is not valid
+/// or if `--output-template` is used with an output format other than `text`.
+///
+fn get_output_format(args: &Args) -> Result<OutputFormat, Error> {
+    match (args.output_format.as_str(), &args.output_template) {
+        ("text", Some(template)) => Ok(OutputFormat::Template(template.clone())),
+        (_, Some(_)) => Err(Error::new(
+            ErrorKind::InvalidOutputFormat,
+            Some("`--output-template` is only supported with the `text` output format"),
+        )),
+        ("text", None) => Ok(OutputFormat::Text),
+        ("json", None) => Ok(OutputFormat::Json),
+        ("matrix", None) => Ok(OutputFormat::Matrix),
+        (value, None) => Err(Error::new(ErrorKind::InvalidOutputFormat, Some(value))),
+    }
+}
 
-        if r.commit.is_none() {
-            continue;
+/// Resolves the subcommand aliases and checks the user inputted CLI arguments that do not depend on the
+/// configuration: the output formats supported by the subcommand, `--read-only` and `--repo-path`, which is set as
+/// the repository path of the run.
+///
+/// # Arguments
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `output_format` - Output format used to print the result.
+///
+/// # Errors
+///
+/// Returns the errors of `resolve_command_alias`, `check_read_only` and `git::set_repo_path`.
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidOutputFormat` if the `matrix` output format or
+/// `--output-template` are used without calculating or applying version bumps.
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the repository path is not a directory.
+///
+fn check_args(args: &mut Args, output_format: &OutputFormat) -> Result<(), Error> {
+    resolve_command_alias(args)?;
+
+    if let OutputFormat::Matrix | OutputFormat::Template(_) = output_format {
+        let supported = matches!(
+            args.command,
+            None | Some(Command::Plan { .. }) | Some(Command::Apply { .. })
+        );
+        if !supported || args.all_release_branches {
+            return Err(Error::new(
+                ErrorKind::InvalidOutputFormat,
+                Some(
+                    "the `matrix` output format and `--output-template` are only supported when calculating or \
+                     applying version bumps",
+                ),
+            ));
         }
-        let commit = r.commit.unwrap();
-
-        let commit_details = match &commit.details {
-            Some(details) => details,
-            None => {
-                skipped_commits_sha.push(commit.sha.clone());
-
-                if let OutputFormat::Text = output_format {
-                    println!("commit '{}' does not match the commit pattern", commit.sha);
-                }
-
-                continue;
-            }
-        };
-
-        let increment_kind = match calculate_increment(&commit, &config.bump_rules) {
-            Some(increment_kind) => increment_kind,
-            None => continue,
-        };
+    }
 
-        if let Some(prev_increment_kind) = version_bumps
-            .get(commit_details.scope.as_ref().unwrap_or(&String::new()))
-            .unwrap()
-        {
-            match prev_increment_kind {
-                IncrementKind::Major => continue,
-                IncrementKind::Minor => {
-                    if increment_kind == IncrementKind::Major {
-                        version_bumps.insert(
-                            commit_details.scope.clone().unwrap_or_default(),
-                            Some(increment_kind),
-                        );
-                    }
-                }
-                IncrementKind::Patch => {
-                    if increment_kind != IncrementKind::Patch {
-                        version_bumps.insert(
-                            commit_details.scope.clone().unwrap_or_default(),
-                            Some(increment_kind),
-                        );
-                    }
-                }
-            }
-        } else {
-            version_bumps.insert(
-                commit_details.scope.clone().unwrap_or_default(),
-                Some(increment_kind),
-            );
+    check_read_only(args)?;
+
+    if let Some(repo_path) = &args.repo_path {
+        if !repo_path.is_dir() {
+            return Err(Error::new(
+                ErrorKind::Other,
+                Some(&format!(
+                    "the repository path `{}` is not a directory",
+                    repo_path.display()
+                )),
+            ));
         }
+        git::set_repo_path(repo_path.clone())?;
     }
+    Ok(())
+}
 
-    let version_bumps = version_bumps;
-    let mut output = Output::new(&args, Some(&config), &skipped_commits_sha);
-
-    let empty_scope = String::new();
-    for tag in &mut closest_tags {
-        let tag_details = tag.details.as_mut().unwrap();
-        let scope = tag_details.scope.as_ref().unwrap_or(&empty_scope);
-
-        let bump = version_bumps.get(scope).unwrap();
+/// Applies the user inputted CLI arguments that override the configuration, validates the result and prints the
+/// warnings of the configuration. If `--auto-source` is given, the GitHub repository is detected from the `origin`
+/// git remote.
+///
+/// # Arguments
+///
+/// * `args` - User inputted CLI arguments.
+///
+/// * `config` - Tag Track configuration.
+///
+/// # Errors
+///
+/// Returns the errors of `check_unknown_fields`, `version::parse_user_version`, `Config::apply_tag_namespace`,
+/// `extra_tags::read_extra_tags_file` and `git::get_remote_url`.
+///
+fn apply_args_to_config(args: &mut Args, config: &mut Config) -> Result<(), Error> {
+    check_unknown_fields(config, args.strict_config)?;
 
-        let mut version_bump = OutputVersionBump {
-            scope: scope.clone(),
-            old_version: tag_details.version.to_string(),
-            new_version: tag_details.version.to_string(),
-            increment_kind: bump,
-        };
+    if args.lightweight {
+        config.tag_type = TagType::Lightweight;
+    }
 
-        if bump.is_none() {
-            output.version_bumps.push(version_bump);
+    if let Some(initial_version) = &args.initial_version {
+        config.initial_version = initial_version.clone();
+    }
+    let initial_version_name = match args.initial_version {
+        Some(_) => "--initial-version",
+        None => "initial_version",
+    };
+    parse_user_version(&config.initial_version, initial_version_name)?;
 
-            if let OutputFormat::Text = output_format {
-                if scope.is_empty() {
-                    println!("version bump for empty scope is not required");
-                } else {
-                    println!("version bump for scope {} is not required", scope);
-                }
-            }
-            continue;
-        }
+    if let Some(namespace) = &args.tag_namespace {
+        config.apply_tag_namespace(namespace)?;
+    }
 
-        match bump.as_ref().unwrap() {
-            IncrementKind::Major => {
-                increment_major(&mut tag_details.version);
-            }
-            IncrementKind::Minor => {
-                increment_minor(&mut tag_details.version);
-            }
-            IncrementKind::Patch => {
-                increment_patch(&mut tag_details.version);
-            }
-        }
-        version_bump.new_version = tag_details.version.to_string();
-        if let OutputFormat::Text = output_format {
-            if scope.is_empty() {
-                println!(
-                    "version bump for empty scope: {} -> {}",
-                    version_bump.old_version, version_bump.new_version
-                );
-            } else {
-                println!(
-                    "version bump for scope {}: {} -> {}",
-                    scope, version_bump.old_version, version_bump.new_version
-                );
-            }
-        }
-        output.version_bumps.push(version_bump.clone());
-
-        if args.create_tag {
-            let new_tag_name = tag
-                .name
-                .replace(&version_bump.old_version, &version_bump.new_version);
-            let new_tag_message = &config.new_tag_message.replace("{scope}", scope);
-            let new_tag_message = new_tag_message.replace("{version}", &version_bump.new_version);
-            if let Err(error) = source.create_tag(&new_tag_name, &new_tag_message, &commit_sha) {
-                print_error(error, &args, &output_format, Some(&config));
-                exit(1);
-            }
-            output.tag_created = true;
-            output.new_tags.push(new_tag_name.to_owned());
+    if let Some(path) = &args.extra_tags_file {
+        config.extra_tags =
+            read_extra_tags_file(path, &config.tag_pattern, config.scope_rules.case)?;
+    }
 
-            if let OutputFormat::Text = output_format {
-                println!("created tag {}", new_tag_name);
-            }
+    if !matches!(args.command, Some(Command::Config { .. })) {
+        for issue in lint_config(config) {
+            logging::warn(&issue.message);
         }
     }
 
-    if let OutputFormat::Json = output_format {
-        if let Ok(json_str) = to_string_pretty(&output) {
-            println!("{}", json_str);
-        } else {
-            println!("could not serialize {:?}", output);
+    if args.auto_source {
+        if let Some(url) = git::get_remote_url(AUTO_SOURCE_REMOTE)? {
+            if cfg!(feature = "github") {
+                args.github_repo = source::detect_github_repo(&url, &args.github_api_url);
+            }
         }
     }
+    Ok(())
 }
 
-/// Print the given error in the given output format.
+/// Runs the subcommand of the given user inputted CLI arguments, or calculates the version bumps if no subcommand
+/// was given.
 ///
 /// # Arguments
 ///
-/// * `error` - Error to be displayed.
+/// * `args` - User inputted CLI arguments.
+///
+/// * `config` - Tag Track configuration.
 ///
-/// * `inputs` - User inputted cli arguments.
+/// * `output_format` - Output format used to print the result.
 ///
-/// * `output_format` - Output format that will be used for printing the result. The output
-/// will be prettified before being printed.
+/// * `cancel` - Token used to interrupt the reading of the history.
 ///
-fn print_error(
-    error: error::Error,
-    inputs: &Args,
+fn run_command(
+    args: &Args,
+    config: &Config,
     output_format: &OutputFormat,
-    config: Option<&Config>,
+    cancel: &CancellationToken,
 ) {
-    match output_format {
-        OutputFormat::Text => println!("{}", error),
-        OutputFormat::Json => {
-            let skipped_commits = vec![];
-            let mut output = Output::new(inputs, config, &skipped_commits);
-            output.error = format!("{}", error);
-            if let Ok(json_str) = to_string_pretty(&output) {
-                println!("{}", json_str);
-            } else {
-                println!("could not serialize {:?}", output);
-            }
+    match &args.command {
+        Some(Command::Apply {
+            plan_file,
+            approval,
+        }) => {
+            apply_plan(plan_file, approval.as_deref(), args, config, output_format);
+            return;
+        }
+        Some(Command::Approve { plan_file, out }) => {
+            approve_plan(plan_file, out, args, config, output_format);
+            return;
+        }
+        Some(Command::VerifyChangelog { file }) => {
+            check_changelog(file, args, config, output_format);
+            return;
+        }
+        Some(Command::AuditTags) => {
+            check_tags(args, config, output_format);
+            return;
+        }
+        Some(Command::DiffReport { old_file, new_file }) => {
+            check_diff_report(old_file, new_file, args, config, output_format);
+            return;
         }
+        Some(Command::Config {
+            command: ConfigCommand::Lint,
+        }) => {
+            check_config(args, config, output_format);
+            return;
+        }
+        _ => {}
     }
-}
 
-/// Validates the given URL and returns a valid URL without a trailing slash.
-///
-/// # Arguments
-///
-/// * `url` - URL to be validated.
-///
-fn validate_trailing_slash(url: &str) -> String {
-    let mut url = url.to_owned();
-    if url.ends_with('/') {
-        url.pop();
+    let source = match source::build_source(config, &get_source_options(args)) {
+        Ok(source) => source,
+        Err(error) => {
+            print_error(error, args, output_format, Some(config));
+            exit(1);
+        }
+    };
+
+    if args.all_release_branches {
+        check_release_branches(&source, args, config, output_format, cancel);
+        return;
     }
-    url
+
+    if let Some(Command::PromoteEnv {
+        from,
+        to,
+        version,
+        scope,
+    }) = &args.command
+    {
+        let promotion = Promotion {
+            from,
+            to,
+            version,
+            scope: &normalize_scope(scope, config.scope_rules.case),
+        };
+        promote_env(&source, &promotion, args, config, output_format);
+        return;
+    }
+
+    bump_versions(&source, args, config, output_format, cancel);
 }
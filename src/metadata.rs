@@ -0,0 +1,196 @@
+//! This module provides the release metadata files. A release metadata file is written for each bumped scope and it
+//! describes how the new version was calculated: the commit range, the contributors and the hash of the
+//! configuration used. The file is intended to be uploaded next to build artifacts, so provenance travels with them.
+//!
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::plan::encode_hex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Version of the release metadata file format.
+pub const RELEASE_METADATA_FORMAT_VERSION: u64 = 1;
+
+/// Name of the release metadata file.
+pub const RELEASE_METADATA_FILE_NAME: &str = "release-metadata.json";
+
+/// Type that represents a release metadata file.
+#[derive(Debug, Serialize)]
+pub struct ReleaseMetadata {
+    /// Version of the release metadata file format.
+    pub format_version: u64,
+    /// Version of Tag Track that generated the file.
+    pub tool_version: String,
+    /// Scope of the version.
+    pub scope: String,
+    /// Name of the new tag.
+    pub tag_name: String,
+    /// Version number before bump.
+    pub old_version: String,
+    /// Version number after bump.
+    pub new_version: String,
+    /// Range of commits used to calculate the version bump.
+    pub commit_range: CommitRange,
+    /// Sorted list of authors of the commits used to calculate the version bump, in the format `name <email>`.
+    pub contributors: BTreeSet<String>,
+    /// Hex encoded SHA-256 hash of the configuration used to calculate the version bump.
+    pub config_sha256: String,
+}
+
+/// Type that represents the range of commits used to calculate a version bump.
+#[derive(Debug, Serialize)]
+pub struct CommitRange {
+    /// SHA of the commit referenced by the previous tag, excluded from the range. Empty if the scope had no tag, in
+    /// which case the range starts at the first commit.
+    pub from: String,
+    /// SHA of the commit that the new tag references, included in the range.
+    pub to: String,
+}
+
+impl ReleaseMetadata {
+    /// Returns a new `ReleaseMetadata` with the current format and tool versions.
+    ///
+    /// # Arguments
+    ///
+    /// * `scope` - Scope of the version.
+    ///
+    /// * `tag_name` - Name of the new tag.
+    ///
+    /// * `old_version` - Version number before bump.
+    ///
+    /// * `new_version` - Version number after bump.
+    ///
+    /// * `commit_range` - Range of commits used to calculate the version bump.
+    ///
+    /// * `contributors` - Authors of the commits used to calculate the version bump.
+    ///
+    /// * `config` - Tag Track configuration used to calculate the version bump.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the configuration cannot be serialized.
+    ///
+    pub fn new(
+        scope: String,
+        tag_name: String,
+        old_version: String,
+        new_version: String,
+        commit_range: CommitRange,
+        contributors: BTreeSet<String>,
+        config: &Config,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            format_version: RELEASE_METADATA_FORMAT_VERSION,
+            tool_version: env!("CARGO_PKG_VERSION").to_owned(),
+            scope,
+            tag_name,
+            old_version,
+            new_version,
+            commit_range,
+            contributors,
+            config_sha256: hash_config(config)?,
+        })
+    }
+}
+
+/// Writes the given release metadata into a JSON file inside the given directory. Metadata of the empty scope is
+/// written to `<dir>/release-metadata.json`, while metadata of other scopes is written to
+/// `<dir>/<scope>/release-metadata.json`. Missing directories are created. Returns the path of the written file.
+///
+/// # Arguments
+///
+/// * `metadata` - Release metadata that will be written.
+///
+/// * `dir` - Directory where the release metadata files are written.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the metadata cannot be serialized or written.
+///
+pub fn write_release_metadata_file(
+    metadata: &ReleaseMetadata,
+    dir: &Path,
+) -> Result<PathBuf, Error> {
+    let dir = match metadata.scope.is_empty() {
+        true => dir.to_path_buf(),
+        false => dir.join(&metadata.scope),
+    };
+    fs::create_dir_all(&dir)?;
+
+    let path = dir.join(RELEASE_METADATA_FILE_NAME);
+    let contents = serde_json::to_string_pretty(metadata)?;
+    fs::write(&path, contents + "\n")?;
+    Ok(path)
+}
+
+/// Returns the hex encoded SHA-256 hash of the JSON representation of the given configuration.
+pub fn hash_config(config: &Config) -> Result<String, Error> {
+    let contents = serde_json::to_string(config)?;
+    Ok(encode_hex(&Sha256::digest(contents)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{env, process};
+
+    /// Returns the release metadata of the given scope from `1.0.0` to `1.1.0`.
+    fn release_metadata(scope: &str, tag_name: &str, config: &Config) -> ReleaseMetadata {
+        ReleaseMetadata::new(
+            scope.to_owned(),
+            tag_name.to_owned(),
+            "1.0.0".to_owned(),
+            "1.1.0".to_owned(),
+            CommitRange {
+                from: "c1".to_owned(),
+                to: "c2".to_owned(),
+            },
+            BTreeSet::from(["Jane <jane@example.com>".to_owned()]),
+            config,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn metadata_files_are_written_to_the_directory_of_their_scope() {
+        let dir = env::temp_dir().join(format!("tag-track-metadata-{}", process::id()));
+        let config = Config::new();
+
+        let path =
+            write_release_metadata_file(&release_metadata("", "1.1.0", &config), &dir).unwrap();
+        assert_eq!(path, dir.join(RELEASE_METADATA_FILE_NAME));
+        let path =
+            write_release_metadata_file(&release_metadata("api", "api/1.1.0", &config), &dir)
+                .unwrap();
+        assert_eq!(path, dir.join("api").join(RELEASE_METADATA_FILE_NAME));
+
+        let metadata: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(metadata["format_version"], RELEASE_METADATA_FORMAT_VERSION);
+        assert_eq!(metadata["tag_name"], "api/1.1.0");
+        assert_eq!(metadata["commit_range"]["from"], "c1");
+        assert_eq!(metadata["contributors"][0], "Jane <jane@example.com>");
+        assert_eq!(
+            metadata["config_sha256"],
+            hash_config(&config).unwrap().as_str()
+        );
+    }
+
+    #[test]
+    fn config_hash_changes_with_the_configuration() {
+        let config = Config::new();
+        let hash = hash_config(&config).unwrap();
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_config(&Config::new()).unwrap());
+
+        let mut changed_config = Config::new();
+        changed_config.tag_pattern = "^v(?<version>.*)$".to_owned();
+        assert_ne!(hash, hash_config(&changed_config).unwrap());
+    }
+}
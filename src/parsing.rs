@@ -1,20 +1,176 @@
 //! This module includes utilities to parse conventional commits and tags.
 //!
 
+use crate::config::{ScopeCase, ScopeRules};
 use crate::error::{Error, ErrorKind};
-use regex::Regex;
+use regex::{Captures, Regex, RegexBuilder};
 use semver::Version;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
 
 /// Regex capturing group name for the type of the commit.
-const TYPE_CAPTURING_GROUP_NAME: &str = "type";
+pub const TYPE_CAPTURING_GROUP_NAME: &str = "type";
 /// Regex capturing group name for the scope of the commit.
 const SCOPE_CAPTURING_GROUP_NAME: &str = "scope";
 /// Regex capturing group name for the breaking change indicator of the commit.
 const BREAKING_CAPTURING_GROUP_NAME: &str = "breaking";
 /// Regex capturing group name for the description of the commit.
-const DESCRIPTION_CAPTURING_GROUP_NAME: &str = "description";
+pub const DESCRIPTION_CAPTURING_GROUP_NAME: &str = "description";
 /// Regex capturing group name for the version inside a tag.
-const VERSION_CAPTURING_GROUP_NAME: &str = "version";
+pub const VERSION_CAPTURING_GROUP_NAME: &str = "version";
+/// Regex capturing group name for the release line inside a branch name.
+pub const LINE_CAPTURING_GROUP_NAME: &str = "line";
+
+/// Maximum size in bytes of a compiled regex pattern.
+const REGEX_SIZE_LIMIT: usize = 1024 * 1024;
+/// Maximum size in bytes of the lazy DFA cache used while matching a regex pattern.
+const REGEX_DFA_SIZE_LIMIT: usize = 2 * 1024 * 1024;
+/// Maximum length in bytes of the text that a regex pattern can be matched against.
+const REGEX_MAX_INPUT_LENGTH: usize = 1024 * 1024;
+
+/// Normalizes a scope, removing its surrounding whitespace and applying the given case.
+///
+/// # Arguments
+///
+/// * `scope` - Scope that will be normalized.
+///
+/// * `case` - Case applied to the scope.
+///
+pub fn normalize_scope(scope: &str, case: ScopeCase) -> String {
+    match case {
+        ScopeCase::Preserve => scope.trim().to_string(),
+        ScopeCase::Lowercase => scope.trim().to_lowercase(),
+    }
+}
+
+/// Validates a version scope against the given scope rules.
+///
+/// # Arguments
+///
+/// * `scope` - Normalized scope that will be validated.
+///
+/// * `rules` - Scope rules of the configuration.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidScope` if the scope is longer than the maximum
+/// length or if it does not match the pattern of the rules.
+///
+/// Returns the errors of `build_regex` if the pattern of the rules is not valid.
+///
+pub fn validate_scope(scope: &str, rules: &ScopeRules) -> Result<(), Error> {
+    if scope.len() > rules.max_length {
+        return Err(Error::new(
+            ErrorKind::InvalidScope,
+            Some(&format!(
+                "'{}' is longer than the maximum length of {} bytes",
+                scope, rules.max_length
+            )),
+        ));
+    }
+
+    let re = build_regex(&rules.pattern)?;
+    if captures_within_limits(&re, scope)?.is_none() {
+        return Err(Error::new(
+            ErrorKind::InvalidScope,
+            Some(&format!(
+                "'{}' does not match the scope pattern '{}', change the scope or allow it with the `pattern` of \
+                 `scope_rules`",
+                scope, rules.pattern
+            )),
+        ));
+    }
+    Ok(())
+}
+
+/// Normalizes the scope captured by a commit or tag pattern, removing its parentheses and applying the given case.
+///
+/// # Arguments
+///
+/// * `captures` - Captures of the commit or tag pattern.
+///
+/// * `case` - Case applied to the scope.
+///
+fn captured_scope(captures: &Captures, case: ScopeCase) -> Option<String> {
+    captures
+        .name(SCOPE_CAPTURING_GROUP_NAME)
+        .map(|found_match| normalize_scope(&found_match.as_str().replace(['(', ')'], ""), case))
+}
+
+/// Compiles the given regex pattern applying the size limits used by Tag Track. Compiled patterns are cached for the
+/// rest of the run, as the same patterns are matched against every commit and tag.
+///
+/// # Arguments
+///
+/// * `pattern` - Regex pattern to compile.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::RegexLimitExceeded` if the compiled pattern exceeds
+/// the size limits.
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidRegexPattern` if the given `pattern` is not a
+/// valid regex pattern.
+///
+pub fn build_regex(pattern: &str) -> Result<Regex, Error> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Regex>>> = OnceLock::new();
+    let cache = CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(re) = cache.lock().unwrap().get(pattern) {
+        return Ok(re.clone());
+    }
+
+    let result = RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_DFA_SIZE_LIMIT)
+        .build();
+
+    match result {
+        Ok(re) => {
+            cache.lock().unwrap().insert(pattern.to_owned(), re.clone());
+            Ok(re)
+        }
+        Err(regex::Error::CompiledTooBig(limit)) => Err(Error::new(
+            ErrorKind::RegexLimitExceeded,
+            Some(&format!(
+                "{} - compiled pattern exceeds the size limit of {} bytes",
+                pattern, limit
+            )),
+        )),
+        Err(error) => Err(Error::new(
+            ErrorKind::InvalidRegexPattern,
+            Some(format!("{} - {}", pattern, error.to_string().as_str()).as_str()),
+        )),
+    }
+}
+
+/// Matches the given regex against the given text, enforcing the input length limit. Matching runs in linear time
+/// on the input length, so the input length limit and the size limits of `build_regex` bound the cost of a match.
+///
+/// # Arguments
+///
+/// * `re` - Compiled regex.
+///
+/// * `text` - Text that will be matched.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::RegexLimitExceeded` if the text is longer than the
+/// input length limit.
+///
+fn captures_within_limits<'t>(re: &Regex, text: &'t str) -> Result<Option<Captures<'t>>, Error> {
+    if text.len() > REGEX_MAX_INPUT_LENGTH {
+        return Err(Error::new(
+            ErrorKind::RegexLimitExceeded,
+            Some(&format!(
+                "input of {} bytes exceeds the limit of {} bytes",
+                text.len(),
+                REGEX_MAX_INPUT_LENGTH
+            )),
+        ));
+    }
+
+    Ok(re.captures(text))
+}
 
 /// Type to represent the sections of a conventional commit message.
 #[derive(Debug)]
@@ -40,26 +196,24 @@ pub struct CommitDetails {
 ///
 /// * `commit_pattern` - Pattern that will be used to parse the conventional commit.
 ///
+/// * `scope_case` - Case applied to the parsed scope, `scope_rules.case` of the configuration.
+///
 /// # Errors
 ///
 /// Returns `error::Error` with a kind of `error::ErrorKind::InvalidRegexPattern` if the given `commit_pattern`
 /// is not a valid regex pattern.
 ///
+/// Returns `error::Error` with a kind of `error::ErrorKind::RegexLimitExceeded` if the pattern or the commit message
+/// exceed the regex limits.
+///
 pub fn parse_commit_details(
     commit_message: &str,
     commit_pattern: &str,
+    scope_case: ScopeCase,
 ) -> Result<Option<CommitDetails>, Error> {
-    let re = match Regex::new(commit_pattern) {
-        Ok(re) => re,
-        Err(error) => {
-            return Err(Error::new(
-                ErrorKind::InvalidRegexPattern,
-                Some(format!("{} - {}", commit_pattern, error.to_string().as_str()).as_str()),
-            ))
-        }
-    };
+    let re = build_regex(commit_pattern)?;
 
-    let captures = match re.captures(commit_message) {
+    let captures = match captures_within_limits(&re, commit_message)? {
         Some(captures) => captures,
         None => return Ok(None),
     };
@@ -69,15 +223,7 @@ pub fn parse_commit_details(
         None => return Ok(None),
     };
 
-    let scope = captures
-        .name(SCOPE_CAPTURING_GROUP_NAME)
-        .map(|found_match| {
-            found_match
-                .as_str()
-                .replace(['(', ')'], "")
-                .trim()
-                .to_string()
-        });
+    let scope = captured_scope(&captures, scope_case);
 
     let breaking = captures.name(BREAKING_CAPTURING_GROUP_NAME).is_some();
 
@@ -117,23 +263,24 @@ pub struct TagDetails {
 ///
 /// * `tag_pattern` - Pattern that will be used to parse the tag.
 ///
+/// * `scope_case` - Case applied to the parsed scope, `scope_rules.case` of the configuration.
+///
 /// # Errors
 ///
 /// Returns `error::Error` with a kind of `error::ErrorKind::InvalidRegexPattern` if the given `tag_pattern`
 /// is not a valid regex pattern.
 ///
-pub fn parse_tag_details(tag_name: &str, tag_pattern: &str) -> Result<Option<TagDetails>, Error> {
-    let re = match Regex::new(tag_pattern) {
-        Ok(re) => re,
-        Err(error) => {
-            return Err(Error::new(
-                ErrorKind::InvalidRegexPattern,
-                Some(format!("{} - {}", tag_pattern, error.to_string().as_str()).as_str()),
-            ))
-        }
-    };
+/// Returns `error::Error` with a kind of `error::ErrorKind::RegexLimitExceeded` if the pattern or the tag name
+/// exceed the regex limits.
+///
+pub fn parse_tag_details(
+    tag_name: &str,
+    tag_pattern: &str,
+    scope_case: ScopeCase,
+) -> Result<Option<TagDetails>, Error> {
+    let re = build_regex(tag_pattern)?;
 
-    let captures = match re.captures(tag_name) {
+    let captures = match captures_within_limits(&re, tag_name)? {
         Some(captures) => captures,
         None => return Ok(None),
     };
@@ -147,15 +294,366 @@ pub fn parse_tag_details(tag_name: &str, tag_pattern: &str) -> Result<Option<Tag
         None => return Ok(None),
     };
 
-    let scope = captures
-        .name(SCOPE_CAPTURING_GROUP_NAME)
-        .map(|found_match| {
-            found_match
-                .as_str()
-                .replace(['(', ')'], "")
-                .trim()
-                .to_string()
-        });
+    let scope = captured_scope(&captures, scope_case);
 
     Ok(Some(TagDetails { version, scope }))
 }
+
+/// Returns `true` if the given pattern matches the given text.
+///
+/// # Arguments
+///
+/// * `text` - Text that will be matched.
+///
+/// * `pattern` - Regex pattern to match.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidRegexPattern` if the given `pattern` is not a
+/// valid regex pattern.
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::RegexLimitExceeded` if the pattern or the text exceed the
+/// regex limits.
+///
+pub fn matches_pattern(text: &str, pattern: &str) -> Result<bool, Error> {
+    let re = build_regex(pattern)?;
+    Ok(captures_within_limits(&re, text)?.is_some())
+}
+
+/// Renders the name of a tag with the given scope and version from a tag pattern, replacing its `scope` and
+/// `version` capturing groups and removing its anchors. Returns `None` if the pattern contains other named capturing
+/// groups or if the rendered name is not parsed back into the given scope and version, which happens when the
+/// pattern contains regex syntax outside of its capturing groups.
+///
+/// # Arguments
+///
+/// * `tag_pattern` - Pattern used to parse tags.
+///
+/// * `scope` - Scope of the tag, empty for the empty scope.
+///
+/// * `version` - Version of the tag.
+///
+/// * `scope_case` - Case applied to the scope parsed back from the rendered name.
+///
+/// # Errors
+///
+/// Returns the regex errors of `parse_tag_details`.
+///
+pub fn render_tag_name(
+    tag_pattern: &str,
+    scope: &str,
+    version: &Version,
+    scope_case: ScopeCase,
+) -> Result<Option<String>, Error> {
+    let mut rest = tag_pattern.strip_prefix('^').unwrap_or(tag_pattern);
+    rest = rest.strip_suffix('$').unwrap_or(rest);
+
+    let mut tag_name = String::new();
+    while let Some(start) = rest.find("(?<") {
+        tag_name.push_str(&rest[..start]);
+        let group = &rest[start + 3..];
+        let value = match group.split_once('>') {
+            Some((VERSION_CAPTURING_GROUP_NAME, _)) => version.to_string(),
+            Some((SCOPE_CAPTURING_GROUP_NAME, _)) => scope.to_owned(),
+            _ => return Ok(None),
+        };
+        tag_name.push_str(&value);
+
+        let mut depth = 0;
+        let mut is_escaped = false;
+        let mut end = None;
+        for (index, char) in rest[start..].char_indices() {
+            match (is_escaped, char) {
+                (true, _) => is_escaped = false,
+                (false, '\\') => is_escaped = true,
+                (false, '(') => depth += 1,
+                (false, ')') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(start + index + 1);
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+        match end {
+            Some(end) => rest = &rest[end..],
+            None => return Ok(None),
+        }
+    }
+    tag_name.push_str(rest);
+
+    let details = match parse_tag_details(&tag_name, tag_pattern, scope_case) {
+        Ok(details) => details,
+        Err(error) if error.kind == ErrorKind::Other => return Ok(None),
+        Err(error) => return Err(error),
+    };
+    match details {
+        Some(details)
+            if &details.version == version
+                && details.scope.as_deref().unwrap_or_default() == scope =>
+        {
+            Ok(Some(tag_name))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Parses the release line of the given branch name. Returns `None` if the branch name does not match the release
+/// branch pattern. The release line is the value of the `line` capturing group or, if the pattern does not expose
+/// it, the whole branch name.
+///
+/// # Arguments
+///
+/// * `branch_name` - Name of the branch.
+///
+/// * `release_branch_pattern` - Pattern used to match release branches.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidRegexPattern` if the given
+/// `release_branch_pattern` is not a valid regex pattern.
+///
+pub fn parse_release_line(
+    branch_name: &str,
+    release_branch_pattern: &str,
+) -> Result<Option<String>, Error> {
+    let re = build_regex(release_branch_pattern)?;
+    let captures = match captures_within_limits(&re, branch_name)? {
+        Some(captures) => captures,
+        None => return Ok(None),
+    };
+
+    let line = match captures.name(LINE_CAPTURING_GROUP_NAME) {
+        Some(line) => line.as_str(),
+        None => branch_name,
+    };
+    Ok(Some(line.to_owned()))
+}
+
+/// Returns a tag pattern that only matches the tags of the given namespace, which are the tags whose name is the
+/// namespace followed by a `/` and a name matching the given tag pattern. Each namespace has its own version line.
+///
+/// # Arguments
+///
+/// * `tag_pattern` - Pattern used to parse the tags without namespace.
+///
+/// * `namespace` - Namespace of the tags, such as `staging` or `prod`.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidTagNamespace` if the namespace is empty, starts or
+/// ends with `/` or contains characters other than alphanumeric characters, `/`, `_` and `-`.
+///
+pub fn namespace_tag_pattern(tag_pattern: &str, namespace: &str) -> Result<String, Error> {
+    let is_valid = !namespace.is_empty()
+        && !namespace.starts_with('/')
+        && !namespace.ends_with('/')
+        && namespace
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || matches!(char, '/' | '_' | '-'));
+    if !is_valid {
+        return Err(Error::new(
+            ErrorKind::InvalidTagNamespace,
+            Some(&format!(
+                "'{}' must only contain alphanumeric characters, '/', '_' and '-', and must not start or \
+                 end with '/'",
+                namespace
+            )),
+        ));
+    }
+
+    let pattern = tag_pattern.strip_prefix('^').unwrap_or(tag_pattern);
+    match pattern.contains('|') {
+        true => Ok(format!("^{}/(?:{})", namespace, pattern)),
+        false => Ok(format!("^{}/{}", namespace, pattern)),
+    }
+}
+
+/// Returns the literal prefix that every tag name matching the given tag pattern starts with, or an empty string if
+/// the pattern is not anchored to the start of the name or does not start with literal characters. Only alphanumeric
+/// characters and `.`, `/`, `_` and `-` are included in the prefix, so it can be used in request paths.
+///
+/// # Arguments
+///
+/// * `tag_pattern` - Pattern used to parse the tags.
+///
+pub fn get_literal_prefix(tag_pattern: &str) -> String {
+    let mut prefix = String::new();
+    let pattern = match tag_pattern.strip_prefix('^') {
+        Some(pattern) if !pattern.contains('|') => pattern,
+        _ => return prefix,
+    };
+
+    let mut chars = pattern.chars().peekable();
+    while let Some(char) = chars.next() {
+        let literal = match char {
+            '\\' => match chars.peek() {
+                Some(escaped) if matches!(escaped, '.' | '/' | '_' | '-') => {
+                    let escaped = *escaped;
+                    chars.next();
+                    escaped
+                }
+                _ => break,
+            },
+            char if char.is_ascii_alphanumeric() || matches!(char, '/' | '_' | '-') => char,
+            _ => break,
+        };
+
+        if matches!(chars.peek(), Some('?' | '*' | '{')) {
+            break;
+        }
+        prefix.push(literal);
+    }
+    prefix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn parse(commit_message: &str, scope_case: ScopeCase) -> Option<CommitDetails> {
+        parse_commit_details(commit_message, &Config::new().commit_pattern, scope_case).unwrap()
+    }
+
+    #[test]
+    fn parse_commit_details_reads_type_scope_and_description() {
+        let details = parse("feat(api): add the users endpoint", ScopeCase::Preserve).unwrap();
+        assert_eq!(details.commit_type, "feat");
+        assert_eq!(details.scope.as_deref(), Some("api"));
+        assert!(!details.breaking);
+        assert_eq!(details.description, "add the users endpoint");
+
+        let details = parse("fix: handle empty responses", ScopeCase::Preserve).unwrap();
+        assert_eq!(details.commit_type, "fix");
+        assert_eq!(details.scope, None);
+    }
+
+    #[test]
+    fn parse_commit_details_detects_breaking_indicator() {
+        let details = parse("feat!: drop the v1 endpoints", ScopeCase::Preserve).unwrap();
+        assert!(details.breaking);
+        assert_eq!(details.scope, None);
+
+        let details = parse(
+            "refactor(api)!: rename the users endpoint",
+            ScopeCase::Preserve,
+        )
+        .unwrap();
+        assert!(details.breaking);
+        assert_eq!(details.scope.as_deref(), Some("api"));
+    }
+
+    #[test]
+    fn parse_commit_details_applies_scope_case() {
+        let details = parse("feat(API): add the users endpoint", ScopeCase::Preserve).unwrap();
+        assert_eq!(details.scope.as_deref(), Some("API"));
+
+        let details = parse("feat( API ): add the users endpoint", ScopeCase::Lowercase).unwrap();
+        assert_eq!(details.scope.as_deref(), Some("api"));
+    }
+
+    #[test]
+    fn parse_commit_details_keeps_footers_in_description() {
+        let message = "feat(api): add the users endpoint\n\nThe old endpoint is removed.\n\nBREAKING CHANGE: \
+                       clients must use /users";
+        let details = parse(message, ScopeCase::Preserve).unwrap();
+        assert!(!details.breaking);
+        assert!(details.description.starts_with("add the users endpoint"));
+        assert!(details
+            .description
+            .ends_with("BREAKING CHANGE: clients must use /users"));
+    }
+
+    #[test]
+    fn parse_commit_details_rejects_non_conforming_messages() {
+        assert!(parse("update readme", ScopeCase::Preserve).is_none());
+        assert!(parse("feat(api):", ScopeCase::Preserve).is_none());
+        assert!(parse("feat(a(p)i): nested parentheses", ScopeCase::Preserve).is_none());
+    }
+
+    #[test]
+    fn parse_tag_details_reads_scope_and_version() {
+        let tag_pattern = "^((?<scope>[^/]+)/)?v(?<version>.+)$";
+        let details = parse_tag_details("API/v1.2.3-rc.1", tag_pattern, ScopeCase::Lowercase)
+            .unwrap()
+            .unwrap();
+        assert_eq!(details.scope.as_deref(), Some("api"));
+        assert_eq!(details.version, Version::parse("1.2.3-rc.1").unwrap());
+
+        let details = parse_tag_details("v0.1.0", tag_pattern, ScopeCase::Preserve)
+            .unwrap()
+            .unwrap();
+        assert_eq!(details.scope, None);
+        assert!(
+            parse_tag_details("release-1", tag_pattern, ScopeCase::Preserve)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn normalize_scope_trims_and_applies_case() {
+        assert_eq!(normalize_scope(" Web-UI ", ScopeCase::Preserve), "Web-UI");
+        assert_eq!(normalize_scope(" Web-UI ", ScopeCase::Lowercase), "web-ui");
+    }
+
+    #[test]
+    fn validate_scope_checks_length_and_pattern() {
+        let rules = ScopeRules::default();
+        assert!(validate_scope("", &rules).is_ok());
+        assert!(validate_scope("web-ui.v2_beta", &rules).is_ok());
+        assert_eq!(
+            validate_scope("web ui", &rules).unwrap_err().kind,
+            ErrorKind::InvalidScope
+        );
+        assert_eq!(
+            validate_scope(&"a".repeat(rules.max_length + 1), &rules)
+                .unwrap_err()
+                .kind,
+            ErrorKind::InvalidScope
+        );
+
+        let rules = ScopeRules {
+            pattern: "^(api|web)?$".to_owned(),
+            max_length: 3,
+            case: ScopeCase::Preserve,
+        };
+        assert!(validate_scope("api", &rules).is_ok());
+        assert_eq!(
+            validate_scope("cli", &rules).unwrap_err().kind,
+            ErrorKind::InvalidScope
+        );
+        assert_eq!(
+            validate_scope(
+                "api",
+                &ScopeRules {
+                    pattern: "(".to_owned(),
+                    ..rules
+                }
+            )
+            .unwrap_err()
+            .kind,
+            ErrorKind::InvalidRegexPattern
+        );
+    }
+
+    #[test]
+    fn apply_scope_rules_normalizes_and_validates_configured_scopes() {
+        let mut config = Config::new();
+        config.scope_rules.case = ScopeCase::Lowercase;
+        config.version_scopes = vec![" API ".to_owned(), "Web".to_owned()];
+        config.bump_rules[0].scopes = Some(vec!["API".to_owned()]);
+        config.apply_scope_rules().unwrap();
+        assert_eq!(config.version_scopes, vec!["api", "web"]);
+        assert_eq!(config.bump_rules[0].scopes, Some(vec!["api".to_owned()]));
+
+        config.version_scopes.push("my scope".to_owned());
+        assert_eq!(
+            config.apply_scope_rules().unwrap_err().kind,
+            ErrorKind::InvalidScope
+        );
+    }
+}
@@ -0,0 +1,1005 @@
+//! This module provides the plan file used to split the execution in two steps.
+//!
+//! The `plan` step calculates the version bumps and stores the tags that would be created in a plan file,
+//! without mutating the source. The `apply` step reads a plan file and creates the tags described on it. This
+//! allows humans or approval gates to review the planned releases between both steps.
+//!
+//! Plans can be approved by generating an approval file, which contains the SHA-256 hash of the plan file signed
+//! with a shared approval key using HMAC-SHA256. The `apply` step can verify the approval file before creating tags.
+//!
+
+use crate::bump::{ScopeBump, NO_VERSION};
+use crate::changelog::{
+    render_release_notes, render_templated_release_notes, ReleaseNotesData, SHORT_SHA_LENGTH,
+};
+use crate::config::Config;
+use crate::error::{Error, ErrorKind};
+use crate::git::strip_url_userinfo;
+use crate::ledger::{append_ledger_entry, LedgerEntry};
+use crate::logging;
+use crate::run;
+use crate::source::{self, SourceActions, SourceKind, SourceName, SourceOptions};
+use crate::template::Template;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{fs, path::Path};
+
+/// Version of the plan file format. Plans with a different format version are rejected by the `apply` step.
+pub const PLAN_FORMAT_VERSION: u64 = 1;
+
+/// Type that represents a plan file.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Plan {
+    /// Version of the plan file format.
+    pub format_version: u64,
+    /// Version of Tag Track that generated the plan.
+    pub tool_version: String,
+    /// GitHub repository identifier (owner/repo_name) if the GitHub source was used.
+    pub github_repo: Option<String>,
+    /// GitHub REST API base URL used with the GitHub source.
+    pub github_api_url: String,
+    /// URL of the git remote if the git remote source was used.
+    #[serde(default)]
+    pub git_remote: Option<String>,
+    /// SHA of the commit that the new tags will reference.
+    pub commit_sha: String,
+    /// Tags that will be created by the `apply` step.
+    pub tags: Vec<PlannedTag>,
+}
+
+/// Type that represents a tag that will be created by the `apply` step.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PlannedTag {
+    /// Name of the tag.
+    pub name: String,
+    /// Message of the tag.
+    pub message: String,
+    /// SHA of the commit referenced by the tag.
+    pub commit_sha: String,
+    /// Scope of the version.
+    pub scope: String,
+    /// Version number before bump.
+    pub old_version: String,
+    /// Version number after bump.
+    pub new_version: String,
+    /// Release created for the tag, if releases were requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release: Option<PlannedRelease>,
+}
+
+/// Type that represents a release that will be created for a planned tag.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PlannedRelease {
+    /// Title of the release.
+    pub title: String,
+    /// Body of the release, rendered from the commits of the version bump.
+    pub body: String,
+}
+
+impl PlannedTag {
+    /// Returns `true` if the tag is a run tag, created to reference the aggregated release of `--aggregate-release`
+    /// when the empty scope has no new tag. Run tags are not associated with a version.
+    pub fn is_run_tag(&self) -> bool {
+        self.new_version.is_empty()
+    }
+}
+
+/// Type that holds the options used to plan the tags and releases of the version bumps.
+#[derive(Debug, Default)]
+pub struct TagPlanOptions<'a> {
+    /// Scopes whose tags are planned. If it is empty, the tags of every bumped scope are planned.
+    pub selected_scopes: &'a [String],
+    /// If the release notes of the tags are rendered.
+    pub release_notes: bool,
+    /// If releases are planned for the tags.
+    pub create_release: bool,
+    /// If a single release with the release notes of every tag is planned, instead of a release per tag.
+    pub aggregate_release: bool,
+    /// Template used to render the release notes. If it is `None`, the default release notes are rendered.
+    pub release_notes_template: Option<&'a Template>,
+}
+
+/// Type that represents the tags and releases planned for the version bumps of a commit.
+#[derive(Debug)]
+pub struct TagPlan {
+    /// Tags that will be created, in scope order. The run tag of an aggregated release, if any, is the last one.
+    pub tags: Vec<PlannedTag>,
+    /// Release notes of the planned tags, in scope order. Empty if release notes were not requested.
+    pub release_notes: Vec<String>,
+}
+
+impl Plan {
+    /// Returns a new `Plan` with the current format and tool versions.
+    ///
+    /// # Arguments
+    ///
+    /// * `github_repo` - GitHub repository identifier if the GitHub source was used.
+    ///
+    /// * `github_api_url` - GitHub REST API base URL.
+    ///
+    /// * `git_remote` - URL of the git remote if the git remote source was used. Its userinfo is removed, so the
+    ///   credentials of the URL are not written to the plan file.
+    ///
+    /// * `commit_sha` - SHA of the commit that the new tags will reference.
+    ///
+    /// * `tags` - Tags that will be created.
+    ///
+    pub fn new(
+        github_repo: Option<String>,
+        github_api_url: String,
+        git_remote: Option<String>,
+        commit_sha: String,
+        tags: Vec<PlannedTag>,
+    ) -> Self {
+        Self {
+            format_version: PLAN_FORMAT_VERSION,
+            tool_version: env!("CARGO_PKG_VERSION").to_owned(),
+            github_repo,
+            github_api_url,
+            git_remote: git_remote.as_deref().map(strip_url_userinfo),
+            commit_sha,
+            tags,
+        }
+    }
+
+    /// Returns the name of the source that calculated the plan: the GitHub source if a GitHub repository was used,
+    /// the git remote source if a git remote was used, or the git source otherwise.
+    pub fn source_name(&self) -> SourceName {
+        match (&self.github_repo, &self.git_remote) {
+            (Some(_), _) => SourceName::Github,
+            (None, Some(_)) => SourceName::GitRemote,
+            (None, None) => SourceName::Git,
+        }
+    }
+}
+
+/// Creates the source that calculated the given plan, used by the `apply` step to create the planned tags. The GitHub
+/// repository, GitHub REST API URL and git remote are taken from the plan. As plans do not store the credentials of
+/// the git remote, the git remote of the options is used instead if it is the same remote with credentials.
+///
+/// Before returning the source, the commit of the plan is resolved with it, so tags are never created for a commit
+/// that the source does not know about.
+///
+/// # Arguments
+///
+/// * `plan` - Plan whose source is created.
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `options` - Options used to create the source. The GitHub repository and git remote, if given, must match the
+///   plan.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidPlanFile` if the options or the configured
+/// sources select a different source than the plan, if a planned tag references a different commit than the plan,
+/// or if the source resolves the commit of the plan to a different commit.
+///
+/// Returns the errors from `source::build_source_with_name` and `SourceActions::resolve_ref`.
+///
+pub fn build_plan_source<'a>(
+    plan: &Plan,
+    config: &'a Config,
+    options: SourceOptions,
+) -> Result<SourceKind<'a>, Error> {
+    let source_name = plan.source_name();
+    if !config.sources.is_empty() && !config.sources.contains(&source_name) {
+        return Err(Error::new(
+            ErrorKind::InvalidPlanFile,
+            Some(&format!(
+                "the plan was calculated with the `{}` source, which is not one of the configured `sources`",
+                source_name.as_str()
+            )),
+        ));
+    }
+
+    if options.github_repo.is_some() && options.github_repo != plan.github_repo {
+        return Err(Error::new(
+            ErrorKind::InvalidPlanFile,
+            Some("the `--github-repo` argument does not match the GitHub repository of the plan"),
+        ));
+    }
+    let git_remote = match &options.git_remote {
+        Some(url) if Some(strip_url_userinfo(url)) == plan.git_remote => Some(url.clone()),
+        Some(_) => {
+            return Err(Error::new(
+                ErrorKind::InvalidPlanFile,
+                Some("the `--git-remote` argument does not match the git remote of the plan"),
+            ))
+        }
+        None => plan.git_remote.clone(),
+    };
+
+    if let Some(tag) = plan
+        .tags
+        .iter()
+        .find(|tag| tag.commit_sha != plan.commit_sha)
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidPlanFile,
+            Some(&format!(
+                "the tag {} references commit {} instead of the plan commit {}",
+                tag.name, tag.commit_sha, plan.commit_sha
+            )),
+        ));
+    }
+
+    let options = SourceOptions {
+        github_repo: plan.github_repo.clone(),
+        github_api_url: plan.github_api_url.clone(),
+        git_remote,
+        ..options
+    };
+    let source = source::build_source_with_name(source_name, config, &options)?;
+    let commit_sha = source.resolve_ref(&plan.commit_sha)?;
+    if commit_sha != plan.commit_sha {
+        return Err(Error::new(
+            ErrorKind::InvalidPlanFile,
+            Some(&format!(
+                "the commit {} of the plan resolves to {} in the `{}` source",
+                plan.commit_sha,
+                commit_sha,
+                source_name.as_str()
+            )),
+        ));
+    }
+    Ok(source)
+}
+
+/// Type that holds the tags and releases created by `create_planned_tags`.
+#[derive(Debug, Default)]
+pub struct CreatedTags {
+    /// Names of the created tags.
+    pub tags: Vec<String>,
+    /// URLs of the created releases.
+    pub releases: Vec<String>,
+}
+
+/// Creates the given planned tags, and their releases if any, using the given source. If a ledger file is given, an
+/// entry is appended to it for each created tag with a version. The created tags and releases are recorded in
+/// `created` as they are created, so the tags created before a failure are still reported.
+///
+/// # Arguments
+///
+/// * `source` - Source used to create the tags.
+///
+/// * `planned_tags` - Tags that will be created.
+///
+/// * `ledger_file` - Path of the release ledger file.
+///
+/// * `created` - Tags and releases that were created.
+///
+/// # Errors
+///
+/// Returns the error of the first tag or release that could not be created, or the error of
+/// `ledger::append_ledger_entry` if the ledger file cannot be written.
+///
+pub fn create_planned_tags(
+    source: &SourceKind,
+    planned_tags: &[PlannedTag],
+    ledger_file: Option<&Path>,
+    created: &mut CreatedTags,
+) -> Result<(), Error> {
+    for planned_tag in planned_tags {
+        source.create_tag(
+            &planned_tag.name,
+            &planned_tag.message,
+            &planned_tag.commit_sha,
+        )?;
+        created.tags.push(planned_tag.name.clone());
+        logging::info(&format!("created tag {}", planned_tag.name));
+
+        let release_url = match &planned_tag.release {
+            Some(release) => {
+                let url =
+                    source.create_release(&planned_tag.name, &release.title, &release.body)?;
+                logging::info(&format!("created release {}", url));
+                created.releases.push(url.clone());
+                Some(url)
+            }
+            None => None,
+        };
+
+        if let (Some(path), false) = (ledger_file, planned_tag.is_run_tag()) {
+            append_ledger_entry(&LedgerEntry::new(planned_tag, release_url.as_deref()), path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Plans the tags of the given version bumps, with their release notes and releases if requested. Scopes whose
+/// version is not bumped or that are not selected are not tagged. If an aggregated release is requested, it is
+/// attached to the tag of the empty scope, or to a run tag named from `run_tag_name` if the empty scope is not tagged.
+///
+/// # Arguments
+///
+/// * `config` - Tag Track configuration.
+///
+/// * `commit_sha` - SHA of the commit that the new tags will reference.
+///
+/// * `scope_bumps` - Version bumps of the scopes.
+///
+/// * `options` - Options used to plan the tags.
+///
+/// # Errors
+///
+/// Returns the errors of `ScopeBump::new_tag_name` and `changelog::render_templated_release_notes`.
+///
+pub fn plan_tags(
+    config: &Config,
+    commit_sha: &str,
+    scope_bumps: &[ScopeBump],
+    options: &TagPlanOptions,
+) -> Result<TagPlan, Error> {
+    let mut tags: Vec<PlannedTag> = vec![];
+    let mut release_notes: Vec<String> = vec![];
+    for scope_bump in scope_bumps {
+        let scope = &scope_bump.scope;
+        let new_version = match (&scope_bump.increment_kind, &scope_bump.new_version) {
+            (Some(_), Some(new_version)) => new_version.to_string(),
+            _ => continue,
+        };
+        let old_version = match &scope_bump.old_version {
+            Some(old_version) => old_version.to_string(),
+            None => NO_VERSION.to_owned(),
+        };
+
+        if !options.selected_scopes.is_empty() && !options.selected_scopes.contains(scope) {
+            match scope.is_empty() {
+                true => logging::info(
+                    "tag for empty scope is not created, not selected by --create-tag-for",
+                ),
+                false => logging::info(&format!(
+                    "tag for scope {} is not created, not selected by --create-tag-for",
+                    scope
+                )),
+            }
+            continue;
+        }
+
+        let name = match scope_bump.new_tag_name(config)? {
+            Some(name) => name,
+            None => continue,
+        };
+        let message = config
+            .new_tag_message
+            .replace("{scope}", scope)
+            .replace("{version}", &new_version);
+
+        if options.create_release || options.release_notes {
+            let notes = match options.release_notes_template {
+                Some(template) => render_templated_release_notes(
+                    template,
+                    &ReleaseNotesData::new(
+                        &name,
+                        scope,
+                        &old_version,
+                        &new_version,
+                        &scope_bump.commits,
+                        config,
+                    ),
+                )?,
+                None => render_release_notes(&name, scope, &scope_bump.commits, config),
+            };
+            release_notes.push(notes);
+        }
+
+        let release = match options.create_release && !options.aggregate_release {
+            true => Some(PlannedRelease {
+                title: config
+                    .new_release_title
+                    .replace("{scope}", scope)
+                    .replace("{version}", &new_version),
+                body: release_notes.last().cloned().unwrap_or_default(),
+            }),
+            false => None,
+        };
+
+        tags.push(PlannedTag {
+            name,
+            message,
+            commit_sha: commit_sha.to_owned(),
+            scope: scope.clone(),
+            old_version,
+            new_version,
+            release,
+        });
+    }
+
+    if options.aggregate_release && !tags.is_empty() {
+        let body = release_notes.join("\n");
+        match tags.iter_mut().find(|tag| tag.scope.is_empty()) {
+            Some(tag) => {
+                tag.release = Some(PlannedRelease {
+                    title: config
+                        .new_release_title
+                        .replace("{scope}", "")
+                        .replace("{version}", &tag.new_version),
+                    body,
+                });
+            }
+            None => {
+                let short_sha = commit_sha.get(..SHORT_SHA_LENGTH).unwrap_or(commit_sha);
+                let run_tag_name = config
+                    .run_tag_name
+                    .replace("{date}", &run::format_date(run::current().started_at))
+                    .replace("{short_sha}", short_sha);
+                tags.push(PlannedTag {
+                    name: run_tag_name.clone(),
+                    message: run_tag_name.clone(),
+                    commit_sha: commit_sha.to_owned(),
+                    scope: String::new(),
+                    old_version: String::new(),
+                    new_version: String::new(),
+                    release: Some(PlannedRelease {
+                        title: run_tag_name,
+                        body,
+                    }),
+                });
+            }
+        }
+    }
+
+    Ok(TagPlan {
+        tags,
+        release_notes,
+    })
+}
+
+/// Writes the given plan into a JSON file.
+///
+/// # Arguments
+///
+/// * `plan` - Plan that will be written.
+///
+/// * `path` - Path of the plan file.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the plan cannot be serialized or written.
+///
+pub fn write_plan_file(plan: &Plan, path: &Path) -> Result<(), Error> {
+    let contents = serde_json::to_string_pretty(plan)?;
+    fs::write(path, contents + "\n")?;
+    Ok(())
+}
+
+/// Reads a plan from a JSON file.
+///
+/// # Arguments
+///
+/// * `path` - Path of the plan file.
+///
+/// # Errors
+///
+/// Returns the errors from `parse_plan`.
+///
+pub fn read_plan_file(path: &Path) -> Result<Plan, Error> {
+    parse_plan(&fs::read(path)?)
+}
+
+/// Reads a plan from a JSON file and verifies its approval. The file is read once, so the approved contents are the
+/// parsed contents even if the file is replaced while it is being verified.
+///
+/// # Arguments
+///
+/// * `plan_path` - Path of the plan file.
+///
+/// * `approval_path` - Path of the approval file, if given.
+///
+/// * `approval_key` - Shared key used to sign the plan hash, if given.
+///
+/// * `require_approval` - If the approval is verified even if no approval file is given.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::PlanNotApproved` if an approval is required and the
+/// approval file or the approval key are missing.
+///
+/// Returns the errors from `parse_plan` and `verify_approval`.
+///
+pub fn read_approved_plan_file(
+    plan_path: &Path,
+    approval_path: Option<&Path>,
+    approval_key: Option<&str>,
+    require_approval: bool,
+) -> Result<Plan, Error> {
+    let contents = fs::read(plan_path)?;
+    let plan = parse_plan(&contents)?;
+    if approval_path.is_none() && !require_approval {
+        return Ok(plan);
+    }
+
+    match (approval_path, approval_key) {
+        (Some(approval_path), Some(approval_key)) => {
+            verify_approval(&contents, approval_path, approval_key)?
+        }
+        (None, _) => {
+            return Err(Error::new(
+                ErrorKind::PlanNotApproved,
+                Some("an approval file is required, use the `--approval` argument"),
+            ))
+        }
+        (_, None) => {
+            return Err(Error::new(
+                ErrorKind::PlanNotApproved,
+                Some("missing approval key, use the `--approval-key` argument"),
+            ))
+        }
+    };
+    logging::info("plan approval verified");
+    Ok(plan)
+}
+
+/// Parses a plan from the contents of a plan file.
+///
+/// # Arguments
+///
+/// * `contents` - Contents of the plan file.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidPlanFile` if the contents are not a valid plan or
+/// if its format version is not supported.
+///
+fn parse_plan(contents: &[u8]) -> Result<Plan, Error> {
+    let plan: Plan = match serde_json::from_slice(contents) {
+        Ok(plan) => plan,
+        Err(error) => {
+            return Err(Error::new(
+                ErrorKind::InvalidPlanFile,
+                Some(&error.to_string()),
+            ))
+        }
+    };
+
+    if plan.format_version != PLAN_FORMAT_VERSION {
+        return Err(Error::new(
+            ErrorKind::InvalidPlanFile,
+            Some(&format!(
+                "unsupported plan format version {}, expected {}",
+                plan.format_version, PLAN_FORMAT_VERSION
+            )),
+        ));
+    }
+
+    Ok(plan)
+}
+
+/// Type that represents an approval file for a plan.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Approval {
+    /// Hex encoded SHA-256 hash of the approved plan file.
+    pub plan_sha256: String,
+    /// Hex encoded HMAC-SHA256 signature of `plan_sha256` using the approval key.
+    pub signature: String,
+}
+
+/// Generates an approval for the given plan file. The file is read once, so the approved contents are the validated
+/// contents even if the file is replaced while it is being approved.
+///
+/// # Arguments
+///
+/// * `plan_path` - Path of the plan file that will be approved.
+///
+/// * `approval_key` - Shared key used to sign the plan hash.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the plan file cannot be read or if the approval
+/// key is empty.
+///
+/// Returns the errors from `parse_plan`.
+///
+pub fn approve_plan_file(plan_path: &Path, approval_key: &str) -> Result<Approval, Error> {
+    let contents = fs::read(plan_path)?;
+    parse_plan(&contents)?;
+    let plan_sha256 = encode_hex(&Sha256::digest(&contents));
+    let signature = encode_hex(&sign(&plan_sha256, approval_key)?.finalize().into_bytes());
+    Ok(Approval {
+        plan_sha256,
+        signature,
+    })
+}
+
+/// Writes the given approval into a JSON file.
+///
+/// # Arguments
+///
+/// * `approval` - Approval that will be written.
+///
+/// * `path` - Path of the approval file.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the approval cannot be serialized or written.
+///
+pub fn write_approval_file(approval: &Approval, path: &Path) -> Result<(), Error> {
+    let contents = serde_json::to_string_pretty(approval)?;
+    fs::write(path, contents + "\n")?;
+    Ok(())
+}
+
+/// Verifies that the given approval file approves the given plan file contents.
+///
+/// # Arguments
+///
+/// * `plan_contents` - Contents of the plan file.
+///
+/// * `approval_path` - Path of the approval file.
+///
+/// * `approval_key` - Shared key used to sign the plan hash.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::PlanNotApproved` if the approval file does not match
+/// the plan file or if its signature is not valid.
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the approval key is empty.
+///
+fn verify_approval(
+    plan_contents: &[u8],
+    approval_path: &Path,
+    approval_key: &str,
+) -> Result<(), Error> {
+    let contents = fs::read_to_string(approval_path)?;
+    let approval: Approval = match serde_json::from_str(&contents) {
+        Ok(approval) => approval,
+        Err(error) => {
+            return Err(Error::new(
+                ErrorKind::PlanNotApproved,
+                Some(&format!("invalid approval file: {}", error)),
+            ))
+        }
+    };
+
+    let plan_sha256 = encode_hex(&Sha256::digest(plan_contents));
+    if approval.plan_sha256 != plan_sha256 {
+        return Err(Error::new(
+            ErrorKind::PlanNotApproved,
+            Some("the plan file has changed since it was approved"),
+        ));
+    }
+
+    let signature = match decode_hex(&approval.signature) {
+        Some(signature) => signature,
+        None => {
+            return Err(Error::new(
+                ErrorKind::PlanNotApproved,
+                Some("the approval signature is not valid hex"),
+            ))
+        }
+    };
+
+    if sign(&plan_sha256, approval_key)?
+        .verify_slice(&signature)
+        .is_err()
+    {
+        return Err(Error::new(
+            ErrorKind::PlanNotApproved,
+            Some("the approval signature does not match the approval key"),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns an HMAC-SHA256 instance with the given key that has already consumed the given plan hash. Empty keys are
+/// rejected, as anyone could sign plans with them.
+fn sign(plan_sha256: &str, approval_key: &str) -> Result<Hmac<Sha256>, Error> {
+    if approval_key.is_empty() {
+        return Err(Error::new(
+            ErrorKind::Other,
+            Some("the approval key can not be empty"),
+        ));
+    }
+    let mut mac = match Hmac::<Sha256>::new_from_slice(approval_key.as_bytes()) {
+        Ok(mac) => mac,
+        Err(error) => return Err(Error::new(ErrorKind::Other, Some(&error.to_string()))),
+    };
+    mac.update(plan_sha256.as_bytes());
+    Ok(mac)
+}
+
+/// Encodes the given bytes as a lowercase hex string.
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Decodes the given hex string. Returns `None` if the string is not valid hex.
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TagType;
+    use crate::source::GITHUB_API_BASE_URL;
+    use crate::version::IncrementKind;
+    use semver::Version;
+    use std::collections::BTreeSet;
+    use std::env;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::process::{self, Command, Stdio};
+
+    fn config() -> Config {
+        let mut config = Config::new();
+        config.tag_pattern = r"^(?<scope>[a-z]+)@(?<version>\d+\.\d+\.\d+)$".to_owned();
+        config.new_tag_name = Some("{scope}@{version}".to_owned());
+        config
+    }
+
+    /// Creates a bare repository with a single commit on its `main` branch, used as the git remote of the plans.
+    /// Returns the `file://` URL of the repository and the SHA of the commit.
+    fn remote_repo(name: &str) -> (String, String) {
+        let path = env::temp_dir().join(format!("tag-track-plan-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&path);
+        let status = Command::new("git")
+            .args(["init", "--bare", "--quiet"])
+            .arg(&path)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let message = "feat: initial release";
+        let stream = format!(
+            "commit refs/heads/main\ncommitter A <a@example.com> 1700000000 +0000\ndata {}\n{}\n\n",
+            message.len(),
+            message
+        );
+        let mut child = Command::new("git")
+            .arg("--git-dir")
+            .arg(&path)
+            .args(["fast-import", "--quiet"])
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stream.as_bytes())
+            .unwrap();
+        assert!(child.wait().unwrap().success());
+
+        (
+            format!("file://{}", path.display()),
+            ls_remote(&path, "refs/heads/main").unwrap(),
+        )
+    }
+
+    /// Returns the SHA referenced by the given reference of the given repository.
+    fn ls_remote(path: &Path, reference: &str) -> Option<String> {
+        let output = Command::new("git")
+            .arg("ls-remote")
+            .arg(path)
+            .arg(reference)
+            .output()
+            .unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        stdout.split_whitespace().next().map(|sha| sha.to_owned())
+    }
+
+    fn planned_tag(name: &str, commit_sha: &str) -> PlannedTag {
+        PlannedTag {
+            name: name.to_owned(),
+            message: name.to_owned(),
+            commit_sha: commit_sha.to_owned(),
+            scope: String::new(),
+            old_version: NO_VERSION.to_owned(),
+            new_version: "1.0.0".to_owned(),
+            release: None,
+        }
+    }
+
+    fn source_options() -> SourceOptions {
+        SourceOptions {
+            use_env: false,
+            ..SourceOptions::default()
+        }
+    }
+
+    /// Writes a plan file and its approval signed with the given key into a new temporary directory, and returns
+    /// the paths of both files.
+    fn approved_plan(name: &str, approval_key: &str) -> (PathBuf, PathBuf) {
+        let dir = env::temp_dir().join(format!("tag-track-approval-{}-{}", name, process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let plan_path = dir.join("plan.json");
+        let approval_path = dir.join("approval.json");
+        let plan = Plan::new(
+            None,
+            GITHUB_API_BASE_URL.to_owned(),
+            None,
+            "c1".to_owned(),
+            vec![planned_tag("v1.0.0", "c1")],
+        );
+        write_plan_file(&plan, &plan_path).unwrap();
+        let approval = approve_plan_file(&plan_path, approval_key).unwrap();
+        write_approval_file(&approval, &approval_path).unwrap();
+        (plan_path, approval_path)
+    }
+
+    fn read_approved(
+        plan_path: &Path,
+        approval_path: &Path,
+        approval_key: &str,
+    ) -> Result<Plan, Error> {
+        read_approved_plan_file(plan_path, Some(approval_path), Some(approval_key), true)
+    }
+
+    fn scope_bump(scope: &str, new_version: Option<&str>) -> ScopeBump {
+        ScopeBump {
+            scope: scope.to_owned(),
+            closest_tag: None,
+            old_version: None,
+            new_version: new_version.map(|version| Version::parse(version).unwrap()),
+            increment_kind: new_version.map(|_| IncrementKind::Minor),
+            reason: None,
+            non_conforming_commits: vec![],
+            commit_threshold_reached: false,
+            commits: vec![],
+            contributors: BTreeSet::new(),
+            provenance: vec![],
+        }
+    }
+
+    #[test]
+    fn plan_tags_skips_scopes_that_are_not_bumped_or_selected() {
+        let scope_bumps = vec![
+            scope_bump("api", Some("0.1.0")),
+            scope_bump("cli", None),
+            scope_bump("web", Some("0.2.0")),
+        ];
+        let selected_scopes = vec!["api".to_owned(), "cli".to_owned()];
+        let options = TagPlanOptions {
+            selected_scopes: &selected_scopes,
+            ..TagPlanOptions::default()
+        };
+
+        let tag_plan = plan_tags(&config(), "c1", &scope_bumps, &options).unwrap();
+        assert_eq!(tag_plan.tags.len(), 1);
+        assert_eq!(tag_plan.tags[0].name, "api@0.1.0");
+        assert_eq!(tag_plan.tags[0].old_version, NO_VERSION);
+        assert!(tag_plan.tags[0].release.is_none());
+        assert!(tag_plan.release_notes.is_empty());
+    }
+
+    #[test]
+    fn plan_tags_attaches_aggregated_release_to_run_tag() {
+        let scope_bumps = vec![
+            scope_bump("api", Some("0.1.0")),
+            scope_bump("web", Some("0.2.0")),
+        ];
+        let options = TagPlanOptions {
+            create_release: true,
+            aggregate_release: true,
+            ..TagPlanOptions::default()
+        };
+
+        let tag_plan = plan_tags(&config(), "c1", &scope_bumps, &options).unwrap();
+        assert_eq!(tag_plan.tags.len(), 3);
+        assert!(tag_plan.tags[..2].iter().all(|tag| tag.release.is_none()));
+        let run_tag = &tag_plan.tags[2];
+        assert!(run_tag.is_run_tag());
+        assert_eq!(
+            run_tag.release.as_ref().unwrap().body,
+            tag_plan.release_notes.join("\n")
+        );
+        assert_eq!(tag_plan.release_notes.len(), 2);
+    }
+
+    #[test]
+    fn git_remote_plan_is_applied_with_the_remote_of_the_plan() {
+        let (url, sha) = remote_repo("apply");
+        let mut config = Config::new();
+        config.tag_type = TagType::Lightweight;
+        let plan = Plan::new(
+            None,
+            GITHUB_API_BASE_URL.to_owned(),
+            Some(url.clone()),
+            sha.clone(),
+            vec![planned_tag("v1.0.0", &sha)],
+        );
+
+        let source = build_plan_source(&plan, &config, source_options()).unwrap();
+        assert!(matches!(source, SourceKind::GitRemote(_)));
+        let mut created = CreatedTags::default();
+        create_planned_tags(&source, &plan.tags, None, &mut created).unwrap();
+        assert_eq!(created.tags, vec!["v1.0.0"]);
+
+        let path = PathBuf::from(url.trim_start_matches("file://"));
+        assert_eq!(ls_remote(&path, "refs/tags/v1.0.0"), Some(sha));
+    }
+
+    #[test]
+    fn plan_source_must_match_the_plan() {
+        let (url, sha) = remote_repo("mismatch");
+        let config = Config::new();
+        let plan = Plan::new(
+            None,
+            GITHUB_API_BASE_URL.to_owned(),
+            Some(url),
+            sha.clone(),
+            vec![planned_tag(
+                "v1.0.0",
+                "0000000000000000000000000000000000000000",
+            )],
+        );
+        let error = build_plan_source(&plan, &config, source_options())
+            .err()
+            .unwrap();
+        assert_eq!(error.kind, ErrorKind::InvalidPlanFile);
+
+        let plan = Plan {
+            tags: vec![planned_tag("v1.0.0", &sha)],
+            ..plan
+        };
+        let options = SourceOptions {
+            git_remote: Some("file:///tmp/other-remote".to_owned()),
+            ..source_options()
+        };
+        let error = build_plan_source(&plan, &config, options).err().unwrap();
+        assert_eq!(error.kind, ErrorKind::InvalidPlanFile);
+
+        let mut config = Config::new();
+        config.sources = vec![SourceName::Git];
+        let error = build_plan_source(&plan, &config, source_options())
+            .err()
+            .unwrap();
+        assert_eq!(error.kind, ErrorKind::InvalidPlanFile);
+    }
+
+    #[test]
+    fn approved_plan_is_read() {
+        let (plan_path, approval_path) = approved_plan("valid", "key");
+        let plan = read_approved(&plan_path, &approval_path, "key").unwrap();
+        assert_eq!(plan.tags[0].name, "v1.0.0");
+    }
+
+    #[test]
+    fn tampered_plan_is_not_approved() {
+        let (plan_path, approval_path) = approved_plan("tampered", "key");
+        let contents = fs::read_to_string(&plan_path).unwrap();
+        fs::write(&plan_path, contents.replace("v1.0.0", "v9.0.0")).unwrap();
+        let error = read_approved(&plan_path, &approval_path, "key").unwrap_err();
+        assert_eq!(error.kind, ErrorKind::PlanNotApproved);
+    }
+
+    #[test]
+    fn plan_approved_with_another_key_is_not_approved() {
+        let (plan_path, approval_path) = approved_plan("wrong-key", "key");
+        let error = read_approved(&plan_path, &approval_path, "other-key").unwrap_err();
+        assert_eq!(error.kind, ErrorKind::PlanNotApproved);
+    }
+
+    #[test]
+    fn approval_with_invalid_hex_signature_is_rejected() {
+        let (plan_path, approval_path) = approved_plan("bad-hex", "key");
+        let mut approval: Approval =
+            serde_json::from_str(&fs::read_to_string(&approval_path).unwrap()).unwrap();
+        approval.signature.replace_range(..2, "zz");
+        write_approval_file(&approval, &approval_path).unwrap();
+        let error = read_approved(&plan_path, &approval_path, "key").unwrap_err();
+        assert_eq!(error.kind, ErrorKind::PlanNotApproved);
+        assert!(error.to_string().contains("not valid hex"));
+    }
+
+    #[test]
+    fn empty_approval_key_is_rejected() {
+        let (plan_path, approval_path) = approved_plan("empty-key", "key");
+        assert!(approve_plan_file(&plan_path, "").is_err());
+        let error = read_approved(&plan_path, &approval_path, "").unwrap_err();
+        assert!(error.to_string().contains("can not be empty"));
+    }
+}
@@ -0,0 +1,180 @@
+//! This module provides the information of the current run.
+//!
+//! Every run is identified by a random UUID and the time it started, so the outputs emitted by the same run can be
+//! correlated downstream.
+//!
+
+use std::{
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use uuid::Uuid;
+
+/// Seconds of a day.
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Type that represents the current run.
+pub struct Run {
+    /// Random identifier of the run.
+    pub id: String,
+    /// Time when the run started.
+    pub started_at: SystemTime,
+}
+
+/// Current run, initialized on the first call to `current`.
+static RUN: OnceLock<Run> = OnceLock::new();
+
+/// Returns the current run. The run is created on the first call, so this function should be called when the run
+/// starts.
+pub fn current() -> &'static Run {
+    RUN.get_or_init(|| Run {
+        id: Uuid::new_v4().to_string(),
+        started_at: SystemTime::now(),
+    })
+}
+
+/// Formats the given time as an RFC 3339 UTC timestamp with second precision, example `2023-08-01T12:30:00Z`.
+/// Times before the UNIX epoch are formatted as the epoch.
+///
+/// # Arguments
+///
+/// * `time` - Time that will be formatted.
+///
+pub fn format_timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days(seconds / SECONDS_PER_DAY);
+    let seconds_of_day = seconds % SECONDS_PER_DAY;
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day % 3600 / 60,
+        seconds_of_day % 60
+    )
+}
+
+/// Formats the date of the given time in UTC as `YYYYMMDD`, example `20230801`. Times before the UNIX epoch are
+/// formatted as the epoch.
+///
+/// # Arguments
+///
+/// * `time` - Time that will be formatted.
+///
+pub fn format_date(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days(seconds / SECONDS_PER_DAY);
+
+    format!("{:04}{:02}{:02}", year, month, day)
+}
+
+/// Returns the UTC year of the given time. Times before the UNIX epoch return `1970`.
+///
+/// # Arguments
+///
+/// * `time` - Time whose year is returned.
+///
+pub fn year(time: SystemTime) -> u64 {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    civil_from_days(seconds / SECONDS_PER_DAY).0
+}
+
+/// Parses the given RFC 3339 UTC timestamp with second precision, example `2023-08-01T12:30:00Z`. Returns `None` if
+/// the timestamp is not in the `YYYY-MM-DDTHH:MM:SSZ` format, is not a valid date or is before the UNIX epoch.
+///
+/// # Arguments
+///
+/// * `timestamp` - Timestamp that will be parsed.
+///
+pub fn parse_timestamp(timestamp: &str) -> Option<SystemTime> {
+    let (date, time) = timestamp.strip_suffix('Z')?.split_once('T')?;
+    let date: Vec<&str> = date.split('-').collect();
+    let time: Vec<&str> = time.split(':').collect();
+    let is_valid_format = date.len() == 3
+        && time.len() == 3
+        && date
+            .iter()
+            .zip([4, 2, 2])
+            .all(|(part, len)| part.len() == len)
+        && time.iter().all(|part| part.len() == 2)
+        && date
+            .iter()
+            .chain(&time)
+            .all(|part| part.chars().all(|char| char.is_ascii_digit()));
+    if !is_valid_format {
+        return None;
+    }
+
+    let parse = |part: &str| part.parse::<u64>().ok();
+    let (year, month, day) = (parse(date[0])?, parse(date[1])?, parse(date[2])?);
+    let (hours, minutes, seconds) = (parse(time[0])?, parse(time[1])?, parse(time[2])?);
+    if year < 1970 || !(1..=12).contains(&month) || hours > 23 || minutes > 59 || seconds > 59 {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+    if day == 0 || civil_from_days(days) != (year, month, day) {
+        return None;
+    }
+
+    let seconds = days * SECONDS_PER_DAY + hours * 3600 + minutes * 60 + seconds;
+    Some(UNIX_EPOCH + std::time::Duration::from_secs(seconds))
+}
+
+/// Returns the year, month and day of the given number of days since the UNIX epoch, using the proleptic Gregorian
+/// calendar.
+///
+/// # Arguments
+///
+/// * `days` - Days since `1970-01-01`.
+///
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    (year, month, day)
+}
+
+/// Returns the number of days since the UNIX epoch of the given year, month and day, using the proleptic Gregorian
+/// calendar. Inverse of `civil_from_days` for dates from `1970-01-01`.
+///
+/// # Arguments
+///
+/// * `year` - Year, from `1970`.
+///
+/// * `month` - Month, from `1` to `12`.
+///
+/// * `day` - Day of the month, from `1`.
+///
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = year - u64::from(month <= 2);
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    (era * 146_097 + day_of_era).saturating_sub(719_468)
+}
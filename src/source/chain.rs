@@ -0,0 +1,170 @@
+//! This module includes the chain source. The chain source wraps an ordered list of sources and falls back to the
+//! next source when the previous one returns a retryable error, for example when the GitHub REST API rate-limits the
+//! requests but the git history is available locally.
+//!
+
+use std::cell::Cell;
+
+use crate::cancel::CancellationToken;
+use crate::error::Error;
+use crate::git::Branch;
+use crate::source::{Reference, SourceActions, SourceKind, SourceStats};
+
+/// Type that represents an ordered chain of sources.
+pub struct ChainSource<'a> {
+    /// Sources in the order they are tried.
+    sources: Vec<SourceKind<'a>>,
+    /// Index of the source that last completed an action. Tags are created with this source, so they are created
+    /// in the same source used to calculate the version bump.
+    active: Cell<usize>,
+}
+
+impl<'a> ChainSource<'a> {
+    /// Returns a new instance of a `ChainSource` source.
+    ///
+    /// # Arguments
+    ///
+    /// * `sources` - Sources in the order they are tried. Must not be empty.
+    ///
+    pub fn new(sources: Vec<SourceKind<'a>>) -> Self {
+        Self {
+            sources,
+            active: Cell::new(0),
+        }
+    }
+}
+
+/// Trait to describe all common actions that all sources need to implement.
+impl<'a> SourceActions<'a> for ChainSource<'a> {
+    /// Returns an Iterator that will return commits and their associated tags for version bump. All references are
+    /// read from a single source before being returned, so if a source fails in the middle of the iteration the next
+    /// source starts from scratch without returning duplicated references.
+    ///
+    /// # Arguments
+    ///
+    /// * `sha` - The commit sha to start the iteration from.
+    ///
+    /// * `range_start` - SHA of the commit that starts the range of commits used to calculate the version bump.
+    ///
+    /// * `cancel` - Token used to interrupt the iteration.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first non retryable error, or the error of the last source if all sources returned retryable
+    /// errors.
+    ///
+    fn get_ref_iterator(
+        &self,
+        sha: &'a str,
+        range_start: Option<&str>,
+        cancel: &CancellationToken,
+    ) -> Result<Box<dyn Iterator<Item = Result<Reference, Error>> + '_>, Error> {
+        let mut last_error = None;
+        for (index, source) in self.sources.iter().enumerate() {
+            let references: Result<Vec<Reference>, Error> = source
+                .get_ref_iterator(sha, range_start, cancel)
+                .and_then(|refs| refs.collect());
+            match references {
+                Ok(references) => {
+                    self.active.set(index);
+                    return Ok(Box::new(references.into_iter().map(Ok)));
+                }
+                Err(error) if error.kind.is_retryable() => last_error = Some(error),
+                Err(error) => return Err(error),
+            }
+        }
+        Err(last_error.unwrap())
+    }
+
+    /// Returns the latest commit sha from the first source that does not return a retryable error.
+    fn get_latest_commit_sha(&self) -> Result<String, Error> {
+        let mut last_error = None;
+        for (index, source) in self.sources.iter().enumerate() {
+            match source.get_latest_commit_sha() {
+                Ok(sha) => {
+                    self.active.set(index);
+                    return Ok(sha);
+                }
+                Err(error) if error.kind.is_retryable() => last_error = Some(error),
+                Err(error) => return Err(error),
+            }
+        }
+        Err(last_error.unwrap())
+    }
+
+    /// Returns the SHA of the commit referenced by the given reference from the first source that does not return a
+    /// retryable error.
+    fn resolve_ref(&self, reference: &str) -> Result<String, Error> {
+        let mut last_error = None;
+        for (index, source) in self.sources.iter().enumerate() {
+            match source.resolve_ref(reference) {
+                Ok(sha) => {
+                    self.active.set(index);
+                    return Ok(sha);
+                }
+                Err(error) if error.kind.is_retryable() => last_error = Some(error),
+                Err(error) => return Err(error),
+            }
+        }
+        Err(last_error.unwrap())
+    }
+
+    /// Returns the branches from the first source that does not return a retryable error.
+    fn get_branches(&self) -> Result<Vec<Branch>, Error> {
+        let mut last_error = None;
+        for (index, source) in self.sources.iter().enumerate() {
+            match source.get_branches() {
+                Ok(branches) => {
+                    self.active.set(index);
+                    return Ok(branches);
+                }
+                Err(error) if error.kind.is_retryable() => last_error = Some(error),
+                Err(error) => return Err(error),
+            }
+        }
+        Err(last_error.unwrap())
+    }
+
+    /// Creates a new annotated tag with the source used to calculate the version bump. Tag creation does not fall
+    /// back to other sources to avoid creating tags in a source different from the expected one.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_name` - Name of the tag.
+    ///
+    /// * `tag_message` - Message of the tag.
+    ///
+    /// * `commit_sha` - SHA of the commit that the tag will reference.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of the active source.
+    ///
+    fn create_tag(&self, tag_name: &str, tag_message: &str, commit_sha: &str) -> Result<(), Error> {
+        self.sources[self.active.get()].create_tag(tag_name, tag_message, commit_sha)
+    }
+
+    /// Returns the statistics of the source used to calculate the version bump, which is also used to create tags.
+    fn get_stats(&self) -> SourceStats {
+        self.sources[self.active.get()].get_stats()
+    }
+
+    /// Creates a new release with the source used to calculate the version bump. Release creation does not fall back
+    /// to other sources, as the release tag is created with the same source.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_name` - Name of the tag of the release.
+    ///
+    /// * `title` - Title of the release.
+    ///
+    /// * `body` - Body of the release.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of the active source.
+    ///
+    fn create_release(&self, tag_name: &str, title: &str, body: &str) -> Result<String, Error> {
+        self.sources[self.active.get()].create_release(tag_name, title, body)
+    }
+}
@@ -5,19 +5,26 @@
 //! This source is useful for local development.
 //!
 
-use std::process::Command;
-use std::vec;
+use std::collections::{HashSet, VecDeque};
 
-use crate::config::Config;
+use crate::cancel::CancellationToken;
+use crate::config::{Config, ScopeCase, TagType, Traversal};
 use crate::error::{Error, ErrorKind};
-use crate::git::{Commit, Tag};
-use crate::parsing::{parse_commit_details, parse_tag_details};
-use crate::source::{Reference, SourceActions};
+use crate::git::{
+    get_config_value, git_command, is_shallow_repository, parse_branch_list_output,
+    parse_diff_tree_output, parse_rev_list_commits, parse_show_ref_output, unshallow_repository,
+    Branch, Commit, Tag, DIFF_TREE_ARGS, FOR_EACH_REF_BRANCHES_ARGS, REV_LIST_FORMAT,
+};
+use crate::source::{Reference, ScopeTracker, SourceActions};
 
 /// Type that represents the Git as a source.
 pub struct GitSource<'a> {
     /// Tag Track configuration.
     config: &'a Config,
+    /// If created tags are signed.
+    sign_tag: bool,
+    /// If the full history is fetched when the repository is a shallow clone.
+    auto_deepen: bool,
 }
 
 impl<'a> GitSource<'a> {
@@ -27,42 +34,75 @@ impl<'a> GitSource<'a> {
     ///
     /// * `config` - Tag Track configuration.
     ///
-    pub fn new(config: &'a Config) -> Self {
-        Self { config }
+    /// * `sign_tag` - If created tags are signed with the `signing_key` of the configuration or the
+    ///   `user.signingkey` git option.
+    ///
+    /// * `auto_deepen` - If the full history is fetched when the repository is a shallow clone, instead of failing.
+    ///
+    pub fn new(config: &'a Config, sign_tag: bool, auto_deepen: bool) -> Self {
+        Self {
+            config,
+            sign_tag,
+            auto_deepen,
+        }
     }
 }
 
 /// Trait to describe all common actions that all sources need to implement.
 impl<'a> SourceActions<'a> for GitSource<'a> {
     /// Returns an Iterator that will return commits and their associated tags for version bump. This iterator may skipped not
-    /// required commits or tags which are not required to calculate the version bump.
+    /// required commits or tags which are not required to calculate the version bump. Only the tags reachable from the
+    /// given commit, listed with `git tag --merged`, are considered, so tags of diverged branches are never matched.
+    /// Tags of the extra tags file are always considered.
     ///
     /// # Arguments
     ///
     /// * `sha` - The commit sha to start the iteration from.
     ///
+    /// * `range_start` - SHA of the commit that starts the range of commits used to calculate the version bump.
+    ///
+    /// * `cancel` - Token used to interrupt the iteration.
+    ///
     /// # Errors
     ///
-    /// Returns `error::Error` with a kind of `error::ErrorKind::MissingGitTags` if there are no tags in the source.
+    /// Returns `error::Error` with a kind of `error::ErrorKind::ShallowClone` if the repository is a shallow clone
+    /// and the full history is not fetched automatically.
     ///
     fn get_ref_iterator(
         &self,
         sha: &'a str,
+        range_start: Option<&str>,
+        cancel: &CancellationToken,
     ) -> Result<Box<dyn Iterator<Item = Result<Reference, Error>> + '_>, Error> {
-        let tags = get_all_tags(&self.config.tag_pattern)?;
-        if tags.is_none() {
-            return Err(Error::new(
-                ErrorKind::MissingGitTags,
-                Some("no tags found for repository"),
-            ));
+        if is_shallow_repository()? {
+            match self.auto_deepen {
+                true => unshallow_repository()?,
+                false => return Err(Error::new(
+                    ErrorKind::ShallowClone,
+                    Some("the version bump can not be calculated from a partial history, fetch the full history with `fetch-depth: 0` in `actions/checkout` or `git fetch --unshallow --tags`, or use `--auto-deepen`"),
+                )),
+            }
         }
 
-        Ok(Box::new(RefIterator::new(sha, tags.unwrap(), self.config)))
+        let mut tags = get_all_tags(&self.config.tag_pattern, self.config.scope_rules.case)?
+            .unwrap_or_default();
+        let reachable_tag_names: HashSet<String> = get_tag_names(Some(sha))?.into_iter().collect();
+        tags.retain(|tag| reachable_tag_names.contains(&tag.name));
+        tags.extend(self.config.extra_tags.iter().cloned());
+        self.config.resolve_scope_tags(&mut tags)?;
+
+        Ok(Box::new(RefIterator::new(
+            sha,
+            tags,
+            self.config,
+            range_start,
+            cancel,
+        )))
     }
 
     /// Returns the latest commit sha.
     fn get_latest_commit_sha(&self) -> Result<String, Error> {
-        let output_result = Command::new("git").arg("rev-parse").arg("HEAD").output();
+        let output_result = git_command().arg("rev-parse").arg("HEAD").output();
 
         let output = match output_result {
             Ok(output) => output,
@@ -90,7 +130,89 @@ impl<'a> SourceActions<'a> for GitSource<'a> {
         Ok(stdout)
     }
 
-    /// Creates a new annotated tag with the given name, message and referencing the given commit sha.
+    /// Returns the SHA of the commit referenced by the given reference using `git rev-parse`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reference` - Reference that will be resolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GenericCommandFailed` if the `git` command fails.
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the reference does not reference a commit.
+    ///
+    fn resolve_ref(&self, reference: &str) -> Result<String, Error> {
+        let output_result = git_command()
+            .arg("rev-parse")
+            .arg("--verify")
+            .arg(format!("{}^{{commit}}", reference))
+            .output();
+
+        let output = match output_result {
+            Ok(output) => output,
+            Err(error) => {
+                return Err(Error::new(
+                    ErrorKind::GenericCommandFailed,
+                    Some(&error.to_string()),
+                ))
+            }
+        };
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            return Err(Error::new(
+                ErrorKind::Other,
+                Some(&format!(
+                    "can not resolve reference '{}', error code: \"{}\", stderr: \"{}\"",
+                    reference,
+                    output.status.code().unwrap(),
+                    stderr.trim(),
+                )),
+            ));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(stdout)
+    }
+
+    /// Returns the local and remote-tracking branches of the repository.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GenericCommandFailed` if the branches can not be
+    /// listed.
+    ///
+    fn get_branches(&self) -> Result<Vec<Branch>, Error> {
+        let output = match git_command().args(FOR_EACH_REF_BRANCHES_ARGS).output() {
+            Ok(output) => output,
+            Err(error) => {
+                return Err(Error::new(
+                    ErrorKind::GenericCommandFailed,
+                    Some(&error.to_string()),
+                ))
+            }
+        };
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+            return Err(Error::new(
+                ErrorKind::GenericCommandFailed,
+                Some(&format!(
+                    "can not list branches, error code: \"{}\", stderr: \"{}\"",
+                    output.status.code().unwrap_or(-1),
+                    stderr.trim(),
+                )),
+            ));
+        }
+
+        Ok(parse_branch_list_output(&String::from_utf8_lossy(
+            &output.stdout,
+        )))
+    }
+
+    /// Creates a new tag with the given name, message and referencing the given commit sha. Lightweight tags are
+    /// created without the message.
     ///
     /// # Arguments
     ///
@@ -106,13 +228,35 @@ impl<'a> SourceActions<'a> for GitSource<'a> {
     ///
     /// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the command output cannot be converted to a utf8 string.
     ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::Other` if tags are signed and no signing key is
+    /// configured.
+    ///
     fn create_tag(&self, tag_name: &str, tag_message: &str, commit_sha: &str) -> Result<(), Error> {
-        let output_result = Command::new("git")
-            .arg("tag")
-            .args(["-a", tag_name])
-            .arg(commit_sha)
-            .args(["-m", tag_message])
-            .output();
+        let mut command = git_command();
+        command.arg("tag");
+        if self.sign_tag {
+            let signing_key = match &self.config.signing_key {
+                Some(signing_key) => signing_key.clone(),
+                None => match get_config_value("user.signingkey")? {
+                    Some(signing_key) => signing_key,
+                    None => {
+                        return Err(Error::new(
+                            ErrorKind::Other,
+                            Some("no signing key configured, set `signing_key` in the configuration file or the `user.signingkey` git option"),
+                        ))
+                    }
+                },
+            };
+            command.args(["-u", &signing_key]);
+        } else if self.config.tag_type == TagType::Annotated {
+            command.arg("-a");
+        }
+
+        command.arg(tag_name).arg(commit_sha);
+        if self.sign_tag || self.config.tag_type == TagType::Annotated {
+            command.args(["-m", tag_message]);
+        }
+        let output_result = command.output();
 
         let output = match output_result {
             Ok(output) => output,
@@ -141,13 +285,20 @@ impl<'a> SourceActions<'a> for GitSource<'a> {
     }
 }
 
+/// Number of commits obtained by each `git rev-list` call of the `RefIterator`.
+const COMMIT_BATCH_SIZE: u64 = 200;
+
 /// Type used to iterate over GitHub references on the repository history.
 /// This type implements the `Iterator` trait and performs paginated requests to the GitHub REST API.
 pub struct RefIterator<'a> {
-    /// List of version scopes that have not been found yet in the commits.
-    version_scopes: Vec<String>,
-    /// Current commit index.
-    current_elem: u64,
+    /// Version scopes whose closest tag has not been found yet.
+    scope_tracker: ScopeTracker,
+    /// Commits of the current batch, obtained on batches of `COMMIT_BATCH_SIZE` elements.
+    commits: VecDeque<Commit>,
+    /// Number of commits requested so far.
+    requested_commits: u64,
+    /// If the last batch was obtained, so no more commits are requested.
+    is_last_batch: bool,
     /// If the iterator has finished iterating over the commits.
     is_finished: bool,
 
@@ -161,17 +312,56 @@ pub struct RefIterator<'a> {
 
 impl<'a> RefIterator<'a> {
     /// Returns a new instance of a `CommitIterator`.
-    fn new(sha: &'a str, tags: Vec<Tag>, config: &'a Config) -> Self {
+    fn new(
+        sha: &'a str,
+        tags: Vec<Tag>,
+        config: &'a Config,
+        range_start: Option<&str>,
+        cancel: &CancellationToken,
+    ) -> Self {
         RefIterator {
-            version_scopes: config.version_scopes.clone(),
+            scope_tracker: ScopeTracker::new(config.version_scopes.clone(), range_start, cancel),
+            commits: VecDeque::new(),
+            requested_commits: 0,
+            is_last_batch: false,
             is_finished: false,
-            current_elem: 0,
 
             sha,
             tags,
             config,
         }
     }
+
+    /// Returns the next commit, requesting the next batch of commits if the current one has been consumed. Returns
+    /// `None` if the whole history has been iterated.
+    fn next_commit(&mut self) -> Result<Option<Commit>, Error> {
+        if self.commits.is_empty() && !self.is_last_batch {
+            let commits = get_commits_from_commit_sha(
+                self.requested_commits,
+                COMMIT_BATCH_SIZE,
+                self.sha,
+                self.config.traversal == Traversal::FirstParent,
+                &self.config.commit_pattern,
+                self.config.scope_rules.case,
+                self.config.max_commit_message_length,
+            )?;
+            self.requested_commits += COMMIT_BATCH_SIZE;
+            self.is_last_batch = (commits.len() as u64) < COMMIT_BATCH_SIZE;
+            self.commits.extend(commits);
+        }
+        Ok(self.commits.pop_front())
+    }
+
+    /// Finishes the iteration with the given error, so no more references are returned after it.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - Error returned by the iterator.
+    ///
+    fn fail(&mut self, error: Error) -> Option<Result<Reference, Error>> {
+        self.is_finished = true;
+        Some(Err(error))
+    }
 }
 
 impl<'a> Iterator for RefIterator<'a> {
@@ -187,70 +377,48 @@ impl<'a> Iterator for RefIterator<'a> {
     /// field. If there is a tag that does not conform the given tag pattern, it will be skipped.
     ///
     fn next(&mut self) -> Option<Self::Item> {
-        if self.is_finished {
-            return None;
-        }
-
-        let commit = match get_n_commit_from_commit_sha(
-            self.current_elem,
-            self.sha,
-            &self.config.commit_pattern,
-        ) {
-            Ok(commit) => commit,
-            Err(error) => return Some(Err(error)),
-        };
-        self.current_elem += 1;
-        if commit.is_none() {
-            self.is_finished = true;
-            return None;
-        }
-        let commit = commit.unwrap();
+        loop {
+            if self.is_finished {
+                return None;
+            }
+            if let Some(error) = self.scope_tracker.cancellation_error() {
+                return self.fail(error);
+            }
 
-        let tags = match find_tags_from_commit_sha(&commit.sha, &self.tags, &self.version_scopes) {
-            Ok(tags) => tags,
-            Err(error) => return Some(Err(error)),
-        };
+            let mut commit = match self.next_commit() {
+                Ok(Some(commit)) => commit,
+                Ok(None) => {
+                    self.is_finished = true;
+                    return None;
+                }
+                Err(error) => return self.fail(error),
+            };
 
-        if tags.is_some() {
-            for tag in tags.as_ref().unwrap() {
-                let tag_details = match &tag.details {
-                    Some(details) => details,
-                    None => continue,
+            if self.config.dependency_updates.is_some() {
+                commit.changed_paths = match get_changed_paths(&commit.sha) {
+                    Ok(changed_paths) => Some(changed_paths),
+                    Err(error) => return self.fail(error),
                 };
-                self.version_scopes
-                    .retain(|scope| scope != tag_details.scope.as_ref().unwrap_or(&String::new()));
             }
 
-            if self.version_scopes.is_empty() {
+            if let Err(error) = self.config.resolve_scope_commit(&mut commit) {
+                return self.fail(error);
+            }
+            let ignored_commit = match self.config.find_ignored_commit(&commit) {
+                Ok(ignored_commit) => ignored_commit,
+                Err(error) => return self.fail(error),
+            };
+            let tags = self.scope_tracker.find_tags(&commit.sha, &self.tags);
+            let reference = self
+                .scope_tracker
+                .match_reference(commit, tags, ignored_commit);
+            if self.scope_tracker.is_finished() {
                 self.is_finished = true;
             }
-        }
-
-        let commit_details = match &commit.details {
-            Some(details) => details,
-            None => {
-                return Some(Ok(Reference {
-                    commit: Some(commit),
-                    tags,
-                }))
+            if let Some(reference) = reference {
+                return Some(Ok(reference));
             }
-        };
-
-        if self
-            .version_scopes
-            .contains(commit_details.scope.as_ref().unwrap_or(&String::new()))
-        {
-            return Some(Ok(Reference {
-                commit: Some(commit),
-                tags,
-            }));
-        }
-
-        if tags.is_none() {
-            return self.next();
         }
-
-        Some(Ok(Reference { commit: None, tags }))
     }
 }
 
@@ -258,19 +426,12 @@ impl<'a> Iterator for RefIterator<'a> {
 ///
 /// # Arguments
 ///
-/// * `repo_id` - GitHub repository identifier that will be used to query commits.
-///
-/// * `api_url` - GitHub REST API base URL.
+/// * `tag_pattern` - Pattern used to extract the tag details.
 ///
-/// * `token` - GitHub REST API authentication token. If it is `None`, requests will not be authenticated, if it has
-/// a value, requests will be authenticated.
+/// * `scope_case` - Case applied to the parsed scopes.
 ///
-fn get_all_tags(tag_pattern: &str) -> Result<Option<Vec<Tag>>, Error> {
-    // create code for a function that returns a vector of Tags obtained using the git CLI
-    // use the tag_pattern argument to parse the tag details
-    // use the Tag struct to represent the tag details
-    // return the vector
-    let output_result = Command::new("git")
+pub fn get_all_tags(tag_pattern: &str, scope_case: ScopeCase) -> Result<Option<Vec<Tag>>, Error> {
+    let output_result = git_command()
         .arg("show-ref")
         .arg("--tags")
         .arg("-d")
@@ -286,6 +447,11 @@ fn get_all_tags(tag_pattern: &str) -> Result<Option<Vec<Tag>>, Error> {
         }
     };
 
+    // `git show-ref` exits with code 1 and without output when no reference is found.
+    if output.status.code() == Some(1) && output.stdout.is_empty() {
+        return Ok(None);
+    }
+
     if !output.status.success() {
         let stderr = String::from_utf8_lossy(&output.stderr).to_string();
         return Err(Error::new(
@@ -299,53 +465,83 @@ fn get_all_tags(tag_pattern: &str) -> Result<Option<Vec<Tag>>, Error> {
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    if stdout.is_empty() {
+    let tags = parse_show_ref_output(&stdout, tag_pattern, scope_case)?;
+    if tags.is_empty() {
         return Ok(None);
     }
 
-    let mut tags: Vec<Tag> = vec![];
-    for (i, line) in stdout.split('\n').enumerate() {
-        if i % 2 == 0 {
-            continue;
-        }
-
-        let line = line.replace("refs/tags/", "").replace("^{}", "");
-        let mut sha = String::new();
-        let mut name = String::new();
-        let mut sha_done = false;
-        for c in line.chars() {
-            if !sha_done {
-                match c {
-                    ' ' => sha_done = true,
-                    _ => sha.push(c),
-                }
-                continue;
-            }
-            name.push(c);
-        }
+    Ok(Some(tags))
+}
 
-        tags.push(Tag {
-            details: parse_tag_details(&name, tag_pattern)?,
-            name,
-            commit_sha: sha,
-        });
+/// Obtains the names of all tags using the Git CLI, without extracting their details.
+///
+/// # Arguments
+///
+/// * `merged_into` - If it has a value, only the tags reachable from this commit SHA are returned.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GenericCommandFailed` if the git command can not be
+/// invoked.
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the git command fails.
+///
+pub fn get_tag_names(merged_into: Option<&str>) -> Result<Vec<String>, Error> {
+    let mut command = git_command();
+    command.arg("tag").arg("--list");
+    if let Some(sha) = merged_into {
+        command.args(["--merged", sha]);
     }
+    let output_result = command.output();
 
-    if tags.is_empty() {
-        return Ok(None);
+    let output = match output_result {
+        Ok(output) => output,
+        Err(error) => {
+            return Err(Error::new(
+                ErrorKind::GenericCommandFailed,
+                Some(&error.to_string()),
+            ))
+        }
+    };
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        return Err(Error::new(
+            ErrorKind::Other,
+            Some(&format!(
+                "can not get tags, error code: \"{}\", stderr: \"{}\"",
+                output.status.code().unwrap(),
+                stderr.trim(),
+            )),
+        ));
     }
 
-    Ok(Some(tags))
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_owned)
+        .collect())
 }
 
-/// Obtains all commits from a given commit SHA using the Git CLI.
+/// Obtains a batch of commits from a given commit SHA using the Git CLI, from the newest commit to the oldest one.
 ///
 /// # Arguments
 ///
-/// * `n`: Which commit should be returned where `0` is the latest commit.
+/// * `skip`: Number of commits skipped before the batch, where `0` starts the batch with the latest commit.
+///
+/// * `max_count`: Maximum number of commits of the batch.
+///
+/// * `commit_sha`: SHA of the commit from where the commits are listed.
+///
+/// * `first_parent`: If `true`, only the commits of the first-parent chain are listed.
 ///
 /// * `commit_pattern`: Pattern used to extract the commit details.
 ///
+/// * `scope_case`: Case applied to the parsed scopes.
+///
+/// * `max_message_length`: Maximum length in bytes of the commit message.
+///
 /// # Errors
 ///
 /// Returns `error::Error` with a kind of `error::ErrorKind::GenericCommandFailed` if the `git` command fails.
@@ -354,18 +550,26 @@ fn get_all_tags(tag_pattern: &str) -> Result<Option<Vec<Tag>>, Error> {
 ///
 /// Returns `error::Error` with a kind of `error::ErrorKind::InvalidRegexPattern` if the commit pattern is invalid.
 ///
-fn get_n_commit_from_commit_sha(
-    n: u64,
+fn get_commits_from_commit_sha(
+    skip: u64,
+    max_count: u64,
     commit_sha: &str,
+    first_parent: bool,
     commit_pattern: &str,
-) -> Result<Option<Commit>, Error> {
-    let output_result = Command::new("git")
+    scope_case: ScopeCase,
+    max_message_length: usize,
+) -> Result<Vec<Commit>, Error> {
+    let mut command = git_command();
+    command
         .arg("rev-list")
         .arg(commit_sha)
-        .arg("--max-count=1")
-        .arg(format!("--skip={}", n))
-        .arg("--format=%H %s")
-        .output();
+        .arg(format!("--max-count={}", max_count))
+        .arg(format!("--skip={}", skip))
+        .arg(REV_LIST_FORMAT);
+    if first_parent {
+        command.arg("--first-parent");
+    }
+    let output_result = command.output();
 
     let output = match output_result {
         Ok(output) => output,
@@ -389,105 +593,349 @@ fn get_n_commit_from_commit_sha(
         ));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    if stdout.is_empty() {
-        return Ok(None);
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    parse_rev_list_commits(&stdout, commit_pattern, scope_case, max_message_length)
+}
+
+/// Obtains the commits reachable from `to_sha` but not from `from_sha` using the Git CLI, from the newest commit to
+/// the oldest one. If `from_sha` is `None`, all commits reachable from `to_sha` are returned.
+///
+/// # Arguments
+///
+/// * `from_sha`: SHA of the commit that starts the range, excluded from the result.
+///
+/// * `to_sha`: SHA of the commit that ends the range, included in the result.
+///
+/// * `commit_pattern`: Pattern used to extract the commit details.
+///
+/// * `scope_case`: Case applied to the parsed scopes.
+///
+/// * `max_message_length`: Maximum length in bytes of the commit message.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GenericCommandFailed` if the `git` command fails.
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidRegexPattern` if the commit pattern is invalid.
+///
+pub fn get_commits_in_range(
+    from_sha: Option<&str>,
+    to_sha: &str,
+    commit_pattern: &str,
+    scope_case: ScopeCase,
+    max_message_length: usize,
+) -> Result<Vec<Commit>, Error> {
+    let mut command = git_command();
+    command.arg("rev-list").arg(to_sha).arg(REV_LIST_FORMAT);
+    if let Some(from_sha) = from_sha {
+        command.arg(format!("^{}", from_sha));
     }
 
-    let mut sha = String::new();
-    let mut message = String::new();
-    let mut sha_done = false;
-    let mut cleaned = false;
-    for c in stdout.chars() {
-        if !cleaned {
-            if c == '\n' {
-                cleaned = true;
-            }
-            continue;
+    let output = match command.output() {
+        Ok(output) => output,
+        Err(error) => {
+            return Err(Error::new(
+                ErrorKind::GenericCommandFailed,
+                Some(&error.to_string()),
+            ))
         }
+    };
 
-        if !sha_done {
-            match c {
-                ' ' => sha_done = true,
-                _ => sha.push(c),
-            }
-            continue;
-        }
-        message.push(c);
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        return Err(Error::new(
+            ErrorKind::GenericCommandFailed,
+            Some(&format!(
+                "can not get commits, error code: \"{}\", stderr: \"{}\"",
+                output.status.code().unwrap(),
+                stderr.trim(),
+            )),
+        ));
     }
-    Ok(Some(Commit {
-        sha,
-        details: parse_commit_details(&message, commit_pattern)?,
-        message,
-    }))
+
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    parse_rev_list_commits(&stdout, commit_pattern, scope_case, max_message_length)
 }
 
-/// From a given list of `GitHub` tag, find the list of tags referencing a commit SHA equal to the given `sha` argument.
-/// If a tag with the given SHA cannot be found, `None` will be returned. If there are multiple tags referencing the same
-/// commit SHA, the tag with the highest version will be returned. This is also true if scoped versioning is used and there
-/// are multiple tags with the same scope in the same commit.
+/// Returns the paths changed by the given commit compared to its first parent.
 ///
 /// # Arguments
 ///
-/// * `sha` - Commit SHA that will be searched inside the tags commits.
-///
-/// * `tags` - List of tags.
-///
-/// * `tag_pattern` - Pattern used to extract the tag details.
+/// * `commit_sha` - SHA of the commit.
 ///
 /// # Errors
 ///
-/// Returns `error::Error` with a kind of `error::ErrorKind::TagPatternError` if the tag pattern is invalid.
-///
-fn find_tags_from_commit_sha(
-    sha: &str,
-    tags: &[Tag],
-    valid_scopes: &[String],
-) -> Result<Option<Vec<Tag>>, Error> {
-    let mut found_tags: Vec<Tag> = vec![];
-    for tag in tags {
-        if tag.commit_sha != sha {
-            continue;
+/// Returns `error::Error` with a kind of `error::ErrorKind::GenericCommandFailed` if the `git diff-tree` command
+/// fails.
+///
+fn get_changed_paths(commit_sha: &str) -> Result<Vec<String>, Error> {
+    let output = match git_command().args(DIFF_TREE_ARGS).arg(commit_sha).output() {
+        Ok(output) => output,
+        Err(error) => {
+            return Err(Error::new(
+                ErrorKind::GenericCommandFailed,
+                Some(&error.to_string()),
+            ))
         }
+    };
 
-        let tag_details = match &tag.details {
-            Some(details) => details,
-            None => continue,
-        };
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        return Err(Error::new(
+            ErrorKind::GenericCommandFailed,
+            Some(&format!(
+                "can not get changed paths, error code: \"{}\", stderr: \"{}\"",
+                output.status.code().unwrap_or(-1),
+                stderr.trim(),
+            )),
+        ));
+    }
 
-        if !valid_scopes.contains(tag_details.scope.as_ref().unwrap_or(&String::new())) {
-            continue;
-        }
+    Ok(parse_diff_tree_output(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
 
-        if found_tags.is_empty() {
-            found_tags.push(tag.clone());
-            continue;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bump::{calculate, BumpResult};
+    use crate::git::set_repo_path;
+    use semver::Version;
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
+    use std::process::{Command, Stdio};
+    use std::sync::OnceLock;
+    use std::thread;
+    use std::{env, fs, process};
+
+    /// Stack size of the threads that walk long runs of skipped commits, which would overflow it if the commits were
+    /// skipped recursively.
+    const SMALL_STACK_SIZE: usize = 256 * 1024;
+
+    const TAG_PATTERN: &str = r"^((?<scope>[a-z]+)/)?v(?<version>\d+\.\d+\.\d+)$";
+
+    /// Stream of `git fast-import` commands, used to build long histories quickly.
+    #[derive(Default)]
+    struct FastImport {
+        stream: String,
+        marks: usize,
+    }
 
-        let mut found = false;
-        for found_tag in &mut found_tags {
-            let found_tag_details = match &found_tag.details {
-                Some(details) => details,
-                None => continue,
-            };
-            if found_tag_details.scope.as_ref().unwrap_or(&String::new())
-                == tag_details.scope.as_ref().unwrap_or(&String::new())
-                && tag_details.version > found_tag_details.version
-            {
-                *found_tag = tag.clone();
-                found = true;
-                break;
+    impl FastImport {
+        /// Adds a commit to the given branch and returns its mark. The first commit of a branch starts from `from`,
+        /// and `merge` adds a second parent.
+        fn commit(
+            &mut self,
+            branch: &str,
+            message: &str,
+            from: Option<usize>,
+            merge: Option<usize>,
+        ) -> usize {
+            self.marks += 1;
+            let date = 1_700_000_000 + self.marks;
+            self.stream += &format!(
+                "commit refs/heads/{}\nmark :{}\nauthor A <a@example.com> {} +0000\ncommitter A <a@example.com> {} \
+                 +0000\ndata {}\n{}\n",
+                branch,
+                self.marks,
+                date,
+                date,
+                message.len(),
+                message
+            );
+            if let Some(from) = from {
+                self.stream += &format!("from :{}\n", from);
             }
+            if let Some(merge) = merge {
+                self.stream += &format!("merge :{}\n", merge);
+            }
+            self.stream += "\n";
+            self.marks
         }
 
-        if !found {
-            found_tags.push(tag.clone());
+        /// Adds a lightweight tag referencing the commit of the given mark.
+        fn tag(&mut self, name: &str, mark: usize) {
+            self.stream += &format!("reset refs/tags/{}\nfrom :{}\n\n", name, mark);
         }
     }
 
-    if found_tags.is_empty() {
-        return Ok(None);
+    /// Returns the repository used by the tests, built once per process as the repository path can only be set once.
+    ///
+    /// The `main` branch has a tag on its first commit, and the `release` branch diverges from it with a newer tag.
+    /// The `merged` branch merges the `release` branch into `main`. The `deep` branch has a long history with the
+    /// closest tag 149 commits behind its head. The `skip` branch has a run of 2000 commits of the `api` scope between
+    /// the closest tag of the `api` scope and the closest tag of the empty scope.
+    fn fixture_repo() -> &'static Path {
+        static REPO: OnceLock<PathBuf> = OnceLock::new();
+        REPO.get_or_init(|| {
+            let path = env::temp_dir().join(format!("tag-track-git-source-{}", process::id()));
+            let _ = fs::remove_dir_all(&path);
+            fs::create_dir_all(&path).unwrap();
+            let status = Command::new("git")
+                .args(["init", "--quiet"])
+                .current_dir(&path)
+                .status()
+                .unwrap();
+            assert!(status.success());
+
+            let mut import = FastImport::default();
+            let initial = import.commit("main", "feat: initial release", None, None);
+            import.tag("v2.0.0", initial);
+            let fix = import.commit("main", "fix: handle empty responses", None, None);
+            let release = import.commit(
+                "release",
+                "feat: add the release branch",
+                Some(initial),
+                None,
+            );
+            import.tag("v2.1.0", release);
+            import.commit(
+                "merged",
+                "fix: merge the release branch",
+                Some(fix),
+                Some(release),
+            );
+
+            let first = import.commit("deep", "feat: first commit", None, None);
+            import.tag("v0.1.0", first);
+            for i in 0..2000 {
+                import.commit("deep", &format!("feat: add old feature {}", i), None, None);
+            }
+            let release = import.commit("deep", "feat: initial release", None, None);
+            import.tag("v1.0.0", release);
+            for i in 0..148 {
+                import.commit("deep", &format!("feat: add feature {}", i), None, None);
+            }
+            import.commit("deep", "fix: handle empty responses", None, None);
+
+            let first = import.commit("skip", "feat: first commit", None, None);
+            import.tag("v0.2.0", first);
+            for i in 0..2000 {
+                import.commit("skip", &format!("feat(api): change {}", i), None, None);
+            }
+            let api = import.commit("skip", "feat(api): add the api", None, None);
+            import.tag("api/v0.1.0", api);
+            import.commit("skip", "fix: handle empty responses", None, None);
+
+            let mut child = Command::new("git")
+                .args(["fast-import", "--quiet"])
+                .current_dir(&path)
+                .stdin(Stdio::piped())
+                .spawn()
+                .unwrap();
+            child
+                .stdin
+                .take()
+                .unwrap()
+                .write_all(import.stream.as_bytes())
+                .unwrap();
+            assert!(child.wait().unwrap().success());
+
+            set_repo_path(path.clone()).unwrap();
+            path
+        })
     }
 
-    Ok(Some(found_tags))
+    fn config(version_scopes: &[&str]) -> Config {
+        let mut config = Config::new();
+        config.tag_pattern = TAG_PATTERN.to_owned();
+        config.version_scopes = version_scopes
+            .iter()
+            .map(|scope| scope.to_string())
+            .collect();
+        config
+    }
+
+    fn calculate_bump(config: &Config, branch: &str) -> BumpResult {
+        fixture_repo();
+        let source = GitSource::new(config, false, false);
+        let sha = source
+            .resolve_ref(&format!("refs/heads/{}", branch))
+            .unwrap();
+        calculate(config, &source, &sha).unwrap()
+    }
+
+    /// Returns the references returned by the git source from the head of the given branch of the fixture repository.
+    fn references(config: &Config, branch: &str) -> Vec<Reference> {
+        fixture_repo();
+        let source = GitSource::new(config, false, false);
+        let sha = source
+            .resolve_ref(&format!("refs/heads/{}", branch))
+            .unwrap();
+        let references = source
+            .get_ref_iterator(&sha, None, &CancellationToken::new())
+            .unwrap();
+        references.collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn deep_history_is_walked_until_the_closest_tag() {
+        let result = calculate_bump(&config(&[""]), "deep");
+        let version_bump = &result.version_bumps[0];
+        assert_eq!(version_bump.closest_tag.as_ref().unwrap().name, "v1.0.0");
+        assert_eq!(version_bump.new_version, Some(Version::new(1, 1, 0)));
+        assert_eq!(version_bump.commits.len(), 149);
+        assert!(result.skipped_commits.is_empty());
+    }
+
+    #[test]
+    fn long_runs_of_skipped_commits_are_walked() {
+        let config = config(&["", "api"]);
+        let references = thread::scope(|scope| {
+            thread::Builder::new()
+                .stack_size(SMALL_STACK_SIZE)
+                .spawn_scoped(scope, || references(&config, "skip"))
+                .unwrap()
+                .join()
+                .unwrap()
+        });
+        let tag_names: Vec<Vec<&str>> = references
+            .iter()
+            .map(|reference| {
+                reference
+                    .tags
+                    .iter()
+                    .flatten()
+                    .map(|tag| tag.name.as_str())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(tag_names, vec![vec![], vec!["api/v0.1.0"], vec!["v0.2.0"]]);
+        assert!(references[0].commit.is_some());
+        assert!(references[1..]
+            .iter()
+            .all(|reference| reference.commit.is_none()));
+    }
+
+    #[test]
+    fn iteration_finishes_after_an_error() {
+        fixture_repo();
+        let mut config = config(&[""]);
+        config.ignore_commit_patterns = vec!["(".to_owned()];
+        let source = GitSource::new(&config, false, false);
+        let sha = source.resolve_ref("refs/heads/deep").unwrap();
+
+        let mut references = source
+            .get_ref_iterator(&sha, None, &CancellationToken::new())
+            .unwrap();
+        assert!(references.next().unwrap().is_err());
+        assert!(references.next().is_none());
+    }
+
+    #[test]
+    fn tags_of_diverged_branches_are_not_used() {
+        let result = calculate_bump(&config(&[""]), "main");
+        let version_bump = &result.version_bumps[0];
+        assert_eq!(version_bump.closest_tag.as_ref().unwrap().name, "v2.0.0");
+        assert_eq!(version_bump.new_version, Some(Version::new(2, 0, 1)));
+    }
+
+    #[test]
+    fn tags_of_merged_branches_are_used() {
+        let result = calculate_bump(&config(&[""]), "merged");
+        let version_bump = &result.version_bumps[0];
+        assert_eq!(version_bump.closest_tag.as_ref().unwrap().name, "v2.1.0");
+        assert_eq!(version_bump.new_version, Some(Version::new(2, 1, 1)));
+    }
 }
@@ -0,0 +1,504 @@
+//! This module includes the gix git source. The gix git source uses the pure-Rust `gix` crate (gitoxide)
+//! to read the local git history in-process, so it does not require a system `git` installation and does not
+//! spawn a process per commit.
+//!
+//! This source is useful for local development and for environments without a `git` binary.
+//!
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use crate::cancel::CancellationToken;
+use crate::config::{Config, ScopeCase, TagType, Traversal};
+use crate::error::{Error, ErrorKind};
+use crate::git::{repo_path, Branch, Commit, Tag};
+use crate::parsing::parse_tag_details;
+use crate::source::{Reference, ScopeTracker, SourceActions};
+use gix::refs::transaction::PreviousValue;
+use gix::revision::walk::Sorting;
+use gix::traverse::commit::simple::CommitTimeOrder;
+
+/// Type that represents the local git repository read with gix as a source.
+pub struct GixSource<'a> {
+    /// Tag Track configuration.
+    config: &'a Config,
+
+    /// Repository discovered from `git::repo_path`. It is boxed as `gix::Repository` is much bigger than
+    /// other sources.
+    repo: Box<gix::Repository>,
+}
+
+impl<'a> GixSource<'a> {
+    /// Returns a new instance of a `GixSource` source. The repository is discovered from `git::repo_path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Tag Track configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::NotGitWorkingTree` if a repository can not be
+    /// discovered from `git::repo_path`.
+    ///
+    pub fn new(config: &'a Config) -> Result<Self, Error> {
+        let repo = match gix::discover(repo_path()) {
+            Ok(repo) => Box::new(repo),
+            Err(error) => {
+                return Err(Error::new(
+                    ErrorKind::NotGitWorkingTree,
+                    Some(&error.to_string()),
+                ))
+            }
+        };
+
+        Ok(Self { config, repo })
+    }
+}
+
+/// Trait to describe all common actions that all sources need to implement.
+impl<'a> SourceActions<'a> for GixSource<'a> {
+    /// Returns an Iterator that will return commits and their associated tags for version bump. This iterator may skipped not
+    /// required commits or tags which are not required to calculate the version bump.
+    ///
+    /// # Arguments
+    ///
+    /// * `sha` - The commit sha to start the iteration from.
+    ///
+    /// * `range_start` - SHA of the commit that starts the range of commits used to calculate the version bump.
+    ///
+    /// * `cancel` - Token used to interrupt the iteration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GixError` if the repository can not be read.
+    ///
+    fn get_ref_iterator(
+        &self,
+        sha: &'a str,
+        range_start: Option<&str>,
+        cancel: &CancellationToken,
+    ) -> Result<Box<dyn Iterator<Item = Result<Reference, Error>> + '_>, Error> {
+        let mut tags = get_all_tags(
+            &self.repo,
+            &self.config.tag_pattern,
+            self.config.scope_rules.case,
+        )?;
+        tags.extend(self.config.extra_tags.iter().cloned());
+        self.config.resolve_scope_tags(&mut tags)?;
+
+        let start_id = self.repo.rev_parse_single(sha).map_err(gix_error)?;
+        let mut platform = self
+            .repo
+            .rev_walk([start_id])
+            .sorting(Sorting::ByCommitTime(CommitTimeOrder::NewestFirst));
+        if self.config.traversal == Traversal::FirstParent {
+            platform = platform.first_parent_only();
+        }
+        let walk = platform.all().map_err(gix_error)?;
+
+        Ok(Box::new(RefIterator::new(
+            walk,
+            tags,
+            self.config,
+            range_start,
+            cancel,
+        )))
+    }
+
+    /// Returns the latest commit sha.
+    fn get_latest_commit_sha(&self) -> Result<String, Error> {
+        let head_id = self.repo.head_id().map_err(gix_error)?;
+        Ok(head_id.to_string())
+    }
+
+    /// Returns the SHA of the commit referenced by the given reference, peeling tags to the commit they reference.
+    ///
+    /// # Arguments
+    ///
+    /// * `reference` - Reference that will be resolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GixError` if the reference can not be resolved to a
+    /// commit.
+    ///
+    fn resolve_ref(&self, reference: &str) -> Result<String, Error> {
+        let commit = self
+            .repo
+            .rev_parse_single(reference)
+            .map_err(gix_error)?
+            .object()
+            .map_err(gix_error)?
+            .peel_to_commit()
+            .map_err(gix_error)?;
+        Ok(commit.id.to_string())
+    }
+
+    /// Returns the local and remote-tracking branches of the repository. Local branches take precedence over
+    /// remote-tracking branches with the same name.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GixError` if the references can not be read.
+    ///
+    fn get_branches(&self) -> Result<Vec<Branch>, Error> {
+        let references = self.repo.references().map_err(gix_error)?;
+        let local = references.local_branches().map_err(gix_error)?;
+        let remote = references.remote_branches().map_err(gix_error)?;
+
+        let mut branches: Vec<Branch> = vec![];
+        for reference in local.chain(remote) {
+            let mut reference = reference.map_err(gix_error)?;
+            let full_name = reference.name().as_bstr().to_string();
+            let name = match full_name.strip_prefix("refs/heads/") {
+                Some(name) => name.to_owned(),
+                None => match full_name
+                    .strip_prefix("refs/remotes/")
+                    .and_then(|name| name.split_once('/'))
+                {
+                    Some((_, name)) if name != "HEAD" => name.to_owned(),
+                    _ => continue,
+                },
+            };
+            if branches.iter().any(|branch| branch.name == name) {
+                continue;
+            }
+
+            let commit_sha = reference.peel_to_id().map_err(gix_error)?.to_string();
+            branches.push(Branch { name, commit_sha });
+        }
+
+        Ok(branches)
+    }
+
+    /// Creates a new tag with the given name, message and referencing the given commit sha. The committer configured
+    /// in the repository is used as the tagger of annotated tags, lightweight tags only create the tag reference.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_name` - Name of the tag.
+    ///
+    /// * `tag_message` - Message of the tag.
+    ///
+    /// * `commit_sha` - SHA of the commit that the tag will reference.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GixError` if the tag can not be created or if
+    /// there is no committer configured in the repository.
+    ///
+    fn create_tag(&self, tag_name: &str, tag_message: &str, commit_sha: &str) -> Result<(), Error> {
+        let target = self.repo.rev_parse_single(commit_sha).map_err(gix_error)?;
+        if self.config.tag_type == TagType::Lightweight {
+            self.repo
+                .tag_reference(tag_name, target.detach(), PreviousValue::MustNotExist)
+                .map_err(gix_error)?;
+            return Ok(());
+        }
+
+        let tagger = match self.repo.committer() {
+            Some(tagger) => tagger.map_err(gix_error)?,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::GixError,
+                    Some("missing committer name and email in git configuration"),
+                ))
+            }
+        };
+
+        self.repo
+            .tag(
+                tag_name,
+                target,
+                gix::object::Kind::Commit,
+                Some(tagger),
+                tag_message,
+                PreviousValue::MustNotExist,
+            )
+            .map_err(gix_error)?;
+
+        Ok(())
+    }
+}
+
+/// Type used to iterate over references on the repository history.
+/// This type implements the `Iterator` trait and walks the commits in-process with gix.
+pub struct RefIterator<'a> {
+    /// Version scopes whose closest tag has not been found yet.
+    scope_tracker: ScopeTracker,
+    /// If the iterator has finished iterating over the commits.
+    is_finished: bool,
+
+    /// Revision walk over the repository history.
+    walk: gix::revision::Walk<'a>,
+    /// List of tags obtained from the repository.
+    tags: Vec<Tag>,
+    /// Tag Track configuration.
+    config: &'a Config,
+}
+
+impl<'a> RefIterator<'a> {
+    /// Returns a new instance of a `RefIterator`.
+    fn new(
+        walk: gix::revision::Walk<'a>,
+        tags: Vec<Tag>,
+        config: &'a Config,
+        range_start: Option<&str>,
+        cancel: &CancellationToken,
+    ) -> Self {
+        RefIterator {
+            scope_tracker: ScopeTracker::new(config.version_scopes.clone(), range_start, cancel),
+            is_finished: false,
+
+            walk,
+            tags,
+            config,
+        }
+    }
+
+    /// Returns the next commit of the revision walk, or `None` if there are no more commits.
+    fn next_commit(&mut self) -> Result<Option<Commit>, Error> {
+        let info = match self.walk.next() {
+            Some(info) => info.map_err(gix_error)?,
+            None => return Ok(None),
+        };
+
+        let commit = info.object().map_err(gix_error)?;
+        let message = commit.message().map_err(gix_error)?;
+        let author = commit.author().map_err(gix_error)?;
+        let mut result = Commit::new(
+            info.id.to_string(),
+            &message.summary().to_string(),
+            Some(format!("{} <{}>", author.name, author.email)),
+            &self.config.commit_pattern,
+            self.config.scope_rules.case,
+            self.config.max_commit_message_length,
+        )?;
+
+        if self.config.dependency_updates.is_some() {
+            let repo = commit.repo;
+            let tree_id = commit.tree_id().map_err(gix_error)?.detach();
+            let parent_tree_id = match commit.parent_ids().next() {
+                Some(parent_id) => Some(
+                    repo.find_commit(parent_id)
+                        .map_err(gix_error)?
+                        .tree_id()
+                        .map_err(gix_error)?
+                        .detach(),
+                ),
+                None => None,
+            };
+
+            let mut changed_paths = vec![];
+            diff_trees(repo, parent_tree_id, Some(tree_id), "", &mut changed_paths)?;
+            result.changed_paths = Some(changed_paths);
+        }
+
+        Ok(Some(result))
+    }
+}
+
+impl<'a> Iterator for RefIterator<'a> {
+    type Item = Result<Reference, Error>;
+
+    /// Returns the next commit and its associated tags until the required commits to calculate the version bump have
+    /// been returned. If using scoped versioning, commits with scopes which tag has been already returned will be skipped.
+    ///
+    /// If a tag is associated with multiple commits, the tag with the biggest version will be returned. This is also true
+    /// if scoped versioning is used and there are multiple tags with the same scope in the same commit.
+    ///
+    /// If there is a commit that does not conform the given commit pattern, it will be returned with `None` in the details
+    /// field. If there is a tag that does not conform the given tag pattern, it will be skipped.
+    ///
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.is_finished {
+                return None;
+            }
+            if let Some(error) = self.scope_tracker.cancellation_error() {
+                self.is_finished = true;
+                return Some(Err(error));
+            }
+
+            let mut commit = match self.next_commit() {
+                Ok(Some(commit)) => commit,
+                Ok(None) => {
+                    self.is_finished = true;
+                    return None;
+                }
+                Err(error) => {
+                    self.is_finished = true;
+                    return Some(Err(error));
+                }
+            };
+
+            if let Err(error) = self.config.resolve_scope_commit(&mut commit) {
+                self.is_finished = true;
+                return Some(Err(error));
+            }
+            let ignored_commit = match self.config.find_ignored_commit(&commit) {
+                Ok(ignored_commit) => ignored_commit,
+                Err(error) => {
+                    self.is_finished = true;
+                    return Some(Err(error));
+                }
+            };
+            let tags = self.scope_tracker.find_tags(&commit.sha, &self.tags);
+            let reference = self
+                .scope_tracker
+                .match_reference(commit, tags, ignored_commit);
+            if self.scope_tracker.is_finished() {
+                self.is_finished = true;
+            }
+            if let Some(reference) = reference {
+                return Some(Ok(reference));
+            }
+        }
+    }
+}
+
+/// Obtains all tags from the repository. Annotated tags are peeled to the commit they reference.
+///
+/// # Arguments
+///
+/// * `repo` - Repository to read the tags from.
+///
+/// * `tag_pattern` - Pattern used to extract the tag details.
+///
+/// * `scope_case` - Case applied to the parsed scopes.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GixError` if the references can not be read.
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidRegexPattern` if the tag pattern is invalid.
+///
+fn get_all_tags(
+    repo: &gix::Repository,
+    tag_pattern: &str,
+    scope_case: ScopeCase,
+) -> Result<Vec<Tag>, Error> {
+    let references = repo.references().map_err(gix_error)?;
+    let mut tags: Vec<Tag> = vec![];
+    for reference in references.tags().map_err(gix_error)? {
+        let mut reference = reference.map_err(gix_error)?;
+        let name = reference.name().shorten().to_string();
+        let commit_sha = reference.peel_to_id().map_err(gix_error)?.to_string();
+
+        tags.push(Tag {
+            details: parse_tag_details(&name, tag_pattern, scope_case)?,
+            name,
+            commit_sha,
+        });
+    }
+
+    Ok(tags)
+}
+
+/// Compares two trees and appends the paths of the files that differ between them to `changed_paths`. Subtrees
+/// are compared recursively.
+///
+/// # Arguments
+///
+/// * `repo` - Repository to read the trees from.
+///
+/// * `old_tree` - ID of the old tree, `None` to compare against an empty tree.
+///
+/// * `new_tree` - ID of the new tree, `None` to compare against an empty tree.
+///
+/// * `prefix` - Path of the trees inside the root tree.
+///
+/// * `changed_paths` - List where the changed paths are appended.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GixError` if the trees can not be read.
+///
+fn diff_trees(
+    repo: &gix::Repository,
+    old_tree: Option<gix::ObjectId>,
+    new_tree: Option<gix::ObjectId>,
+    prefix: &str,
+    changed_paths: &mut Vec<String>,
+) -> Result<(), Error> {
+    let old_entries = get_tree_entries(repo, old_tree)?;
+    let mut new_entries = get_tree_entries(repo, new_tree)?;
+
+    let mut entries: Vec<_> = old_entries
+        .into_iter()
+        .map(|(name, old)| {
+            let new = new_entries.remove(&name);
+            (name, Some(old), new)
+        })
+        .collect();
+    entries.extend(
+        new_entries
+            .into_iter()
+            .map(|(name, new)| (name, None, Some(new))),
+    );
+
+    for (name, old, new) in entries {
+        if old == new {
+            continue;
+        }
+
+        let path = format!("{}{}", prefix, name);
+        let old_subtree = old.filter(|(_, is_tree)| *is_tree).map(|(id, _)| id);
+        let new_subtree = new.filter(|(_, is_tree)| *is_tree).map(|(id, _)| id);
+        if old_subtree.is_some() || new_subtree.is_some() {
+            diff_trees(
+                repo,
+                old_subtree,
+                new_subtree,
+                &format!("{}/", path),
+                changed_paths,
+            )?;
+        }
+
+        let is_file =
+            |entry: Option<(gix::ObjectId, bool)>| entry.is_some_and(|(_, is_tree)| !is_tree);
+        if is_file(old) || is_file(new) {
+            changed_paths.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the entries of the given tree by name, with their object ID and if they are trees. Returns an empty map
+/// if `tree` is `None`.
+///
+/// # Arguments
+///
+/// * `repo` - Repository to read the tree from.
+///
+/// * `tree` - ID of the tree.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GixError` if the tree can not be read.
+///
+fn get_tree_entries(
+    repo: &gix::Repository,
+    tree: Option<gix::ObjectId>,
+) -> Result<BTreeMap<String, (gix::ObjectId, bool)>, Error> {
+    let mut entries = BTreeMap::new();
+    let tree = match tree {
+        Some(tree) => repo.find_tree(tree).map_err(gix_error)?,
+        None => return Ok(entries),
+    };
+
+    for entry in tree.decode().map_err(gix_error)?.entries.iter() {
+        entries.insert(
+            entry.filename.to_string(),
+            (entry.oid.to_owned(), entry.mode.is_tree()),
+        );
+    }
+
+    Ok(entries)
+}
+
+/// Converts an error returned by gix into an `error::Error` with a kind of `error::ErrorKind::GixError`.
+fn gix_error(error: impl Display) -> Error {
+    Error::new(ErrorKind::GixError, Some(&error.to_string()))
+}
@@ -0,0 +1,407 @@
+//! This module includes the libgit2 git source. The libgit2 git source uses the `git2` crate, which binds
+//! libgit2, to read the local git history in-process, so it does not require a system `git` installation.
+//!
+//! This source is only available if the `libgit2` cargo feature is enabled. It is useful for environments
+//! without a `git` binary, such as distroless containers.
+//!
+
+use std::fmt::Display;
+
+use crate::cancel::CancellationToken;
+use crate::config::{Config, ScopeCase, TagType, Traversal};
+use crate::error::{Error, ErrorKind};
+use crate::git::{repo_path, Branch, Commit, Tag};
+use crate::parsing::parse_tag_details;
+use crate::source::{Reference, ScopeTracker, SourceActions};
+use git2::{BranchType, Oid, Repository, Revwalk, Sort};
+
+/// Type that represents the local git repository read with libgit2 as a source.
+pub struct Libgit2Source<'a> {
+    /// Tag Track configuration.
+    config: &'a Config,
+
+    /// Repository discovered from `git::repo_path`.
+    repo: Repository,
+}
+
+impl<'a> Libgit2Source<'a> {
+    /// Returns a new instance of a `Libgit2Source` source. The repository is discovered from `git::repo_path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Tag Track configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::NotGitWorkingTree` if a repository can not be
+    /// discovered from `git::repo_path`.
+    ///
+    pub fn new(config: &'a Config) -> Result<Self, Error> {
+        let repo = match Repository::discover(repo_path()) {
+            Ok(repo) => repo,
+            Err(error) => {
+                return Err(Error::new(
+                    ErrorKind::NotGitWorkingTree,
+                    Some(&error.to_string()),
+                ))
+            }
+        };
+
+        Ok(Self { config, repo })
+    }
+}
+
+/// Trait to describe all common actions that all sources need to implement.
+impl<'a> SourceActions<'a> for Libgit2Source<'a> {
+    /// Returns an Iterator that will return commits and their associated tags for version bump. This iterator may skipped not
+    /// required commits or tags which are not required to calculate the version bump.
+    ///
+    /// # Arguments
+    ///
+    /// * `sha` - The commit sha to start the iteration from.
+    ///
+    /// * `range_start` - SHA of the commit that starts the range of commits used to calculate the version bump.
+    ///
+    /// * `cancel` - Token used to interrupt the iteration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::Libgit2Error` if the repository can not be read.
+    ///
+    fn get_ref_iterator(
+        &self,
+        sha: &'a str,
+        range_start: Option<&str>,
+        cancel: &CancellationToken,
+    ) -> Result<Box<dyn Iterator<Item = Result<Reference, Error>> + '_>, Error> {
+        let mut tags = get_all_tags(
+            &self.repo,
+            &self.config.tag_pattern,
+            self.config.scope_rules.case,
+        )?;
+        tags.extend(self.config.extra_tags.iter().cloned());
+        self.config.resolve_scope_tags(&mut tags)?;
+
+        let start_id = self
+            .repo
+            .revparse_single(sha)
+            .map_err(libgit2_error)?
+            .peel_to_commit()
+            .map_err(libgit2_error)?
+            .id();
+        let mut walk = self.repo.revwalk().map_err(libgit2_error)?;
+        walk.set_sorting(Sort::TIME).map_err(libgit2_error)?;
+        walk.push(start_id).map_err(libgit2_error)?;
+        if self.config.traversal == Traversal::FirstParent {
+            walk.simplify_first_parent().map_err(libgit2_error)?;
+        }
+
+        Ok(Box::new(RefIterator::new(
+            &self.repo,
+            walk,
+            tags,
+            self.config,
+            range_start,
+            cancel,
+        )))
+    }
+
+    /// Returns the latest commit sha.
+    fn get_latest_commit_sha(&self) -> Result<String, Error> {
+        let head = self.repo.head().map_err(libgit2_error)?;
+        let head_commit = head.peel_to_commit().map_err(libgit2_error)?;
+        Ok(head_commit.id().to_string())
+    }
+
+    /// Returns the SHA of the commit referenced by the given reference, peeling tags to the commit they reference.
+    ///
+    /// # Arguments
+    ///
+    /// * `reference` - Reference that will be resolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::Libgit2Error` if the reference can not be resolved
+    /// to a commit.
+    ///
+    fn resolve_ref(&self, reference: &str) -> Result<String, Error> {
+        let commit = self
+            .repo
+            .revparse_single(reference)
+            .map_err(libgit2_error)?
+            .peel_to_commit()
+            .map_err(libgit2_error)?;
+        Ok(commit.id().to_string())
+    }
+
+    /// Returns the local and remote-tracking branches of the repository. Local branches take precedence over
+    /// remote-tracking branches with the same name.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::Libgit2Error` if the branches can not be read.
+    ///
+    fn get_branches(&self) -> Result<Vec<Branch>, Error> {
+        let mut branches: Vec<Branch> = vec![];
+        for branch_type in [BranchType::Local, BranchType::Remote] {
+            for branch in self
+                .repo
+                .branches(Some(branch_type))
+                .map_err(libgit2_error)?
+            {
+                let (branch, _) = branch.map_err(libgit2_error)?;
+                let full_name = match branch.name().map_err(libgit2_error)? {
+                    Some(name) => name,
+                    None => continue,
+                };
+                let name = match branch_type {
+                    BranchType::Local => full_name,
+                    BranchType::Remote => match full_name.split_once('/') {
+                        Some((_, name)) if name != "HEAD" => name,
+                        _ => continue,
+                    },
+                };
+                if branches.iter().any(|branch| branch.name == name) {
+                    continue;
+                }
+
+                let commit = branch.get().peel_to_commit().map_err(libgit2_error)?;
+                branches.push(Branch {
+                    name: name.to_owned(),
+                    commit_sha: commit.id().to_string(),
+                });
+            }
+        }
+
+        Ok(branches)
+    }
+
+    /// Creates a new tag with the given name, message and referencing the given commit sha. The user configured in
+    /// the repository is used as the tagger of annotated tags, lightweight tags only create the tag reference.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_name` - Name of the tag.
+    ///
+    /// * `tag_message` - Message of the tag.
+    ///
+    /// * `commit_sha` - SHA of the commit that the tag will reference.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::Libgit2Error` if the tag can not be created or if
+    /// there is no user configured in the repository.
+    ///
+    fn create_tag(&self, tag_name: &str, tag_message: &str, commit_sha: &str) -> Result<(), Error> {
+        let target = self
+            .repo
+            .revparse_single(commit_sha)
+            .map_err(libgit2_error)?;
+        match self.config.tag_type {
+            TagType::Annotated => {
+                let tagger = self.repo.signature().map_err(libgit2_error)?;
+                self.repo
+                    .tag(tag_name, &target, &tagger, tag_message, false)
+                    .map_err(libgit2_error)?;
+            }
+            TagType::Lightweight => {
+                self.repo
+                    .tag_lightweight(tag_name, &target, false)
+                    .map_err(libgit2_error)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Type used to iterate over references on the repository history.
+/// This type implements the `Iterator` trait and walks the commits in-process with libgit2.
+pub struct RefIterator<'a> {
+    /// Version scopes whose closest tag has not been found yet.
+    scope_tracker: ScopeTracker,
+    /// If the iterator has finished iterating over the commits.
+    is_finished: bool,
+
+    /// Repository used to read the commits.
+    repo: &'a Repository,
+    /// Revision walk over the repository history.
+    walk: Revwalk<'a>,
+    /// List of tags obtained from the repository.
+    tags: Vec<Tag>,
+    /// Tag Track configuration.
+    config: &'a Config,
+}
+
+impl<'a> RefIterator<'a> {
+    /// Returns a new instance of a `RefIterator`.
+    fn new(
+        repo: &'a Repository,
+        walk: Revwalk<'a>,
+        tags: Vec<Tag>,
+        config: &'a Config,
+        range_start: Option<&str>,
+        cancel: &CancellationToken,
+    ) -> Self {
+        RefIterator {
+            scope_tracker: ScopeTracker::new(config.version_scopes.clone(), range_start, cancel),
+            is_finished: false,
+
+            repo,
+            walk,
+            tags,
+            config,
+        }
+    }
+
+    /// Returns the next commit of the revision walk, or `None` if there are no more commits.
+    fn next_commit(&mut self) -> Result<Option<Commit>, Error> {
+        let id: Oid = match self.walk.next() {
+            Some(id) => id.map_err(libgit2_error)?,
+            None => return Ok(None),
+        };
+
+        let commit = self.repo.find_commit(id).map_err(libgit2_error)?;
+        let mut result = Commit::new(
+            id.to_string(),
+            &String::from_utf8_lossy(commit.summary_bytes().unwrap_or_default()),
+            Some(format!(
+                "{} <{}>",
+                String::from_utf8_lossy(commit.author().name_bytes()),
+                String::from_utf8_lossy(commit.author().email_bytes())
+            )),
+            &self.config.commit_pattern,
+            self.config.scope_rules.case,
+            self.config.max_commit_message_length,
+        )?;
+
+        if self.config.dependency_updates.is_some() {
+            let tree = commit.tree().map_err(libgit2_error)?;
+            let parent_tree = match commit.parents().next() {
+                Some(parent) => Some(parent.tree().map_err(libgit2_error)?),
+                None => None,
+            };
+            let diff = self
+                .repo
+                .diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)
+                .map_err(libgit2_error)?;
+            result.changed_paths = Some(
+                diff.deltas()
+                    .filter_map(|delta| delta.new_file().path().or(delta.old_file().path()))
+                    .map(|path| path.to_string_lossy().to_string())
+                    .collect(),
+            );
+        }
+
+        Ok(Some(result))
+    }
+}
+
+impl<'a> Iterator for RefIterator<'a> {
+    type Item = Result<Reference, Error>;
+
+    /// Returns the next commit and its associated tags until the required commits to calculate the version bump have
+    /// been returned. If using scoped versioning, commits with scopes which tag has been already returned will be skipped.
+    ///
+    /// If a tag is associated with multiple commits, the tag with the biggest version will be returned. This is also true
+    /// if scoped versioning is used and there are multiple tags with the same scope in the same commit.
+    ///
+    /// If there is a commit that does not conform the given commit pattern, it will be returned with `None` in the details
+    /// field. If there is a tag that does not conform the given tag pattern, it will be skipped.
+    ///
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.is_finished {
+                return None;
+            }
+            if let Some(error) = self.scope_tracker.cancellation_error() {
+                self.is_finished = true;
+                return Some(Err(error));
+            }
+
+            let mut commit = match self.next_commit() {
+                Ok(Some(commit)) => commit,
+                Ok(None) => {
+                    self.is_finished = true;
+                    return None;
+                }
+                Err(error) => {
+                    self.is_finished = true;
+                    return Some(Err(error));
+                }
+            };
+
+            if let Err(error) = self.config.resolve_scope_commit(&mut commit) {
+                self.is_finished = true;
+                return Some(Err(error));
+            }
+            let ignored_commit = match self.config.find_ignored_commit(&commit) {
+                Ok(ignored_commit) => ignored_commit,
+                Err(error) => {
+                    self.is_finished = true;
+                    return Some(Err(error));
+                }
+            };
+            let tags = self.scope_tracker.find_tags(&commit.sha, &self.tags);
+            let reference = self
+                .scope_tracker
+                .match_reference(commit, tags, ignored_commit);
+            if self.scope_tracker.is_finished() {
+                self.is_finished = true;
+            }
+            if let Some(reference) = reference {
+                return Some(Ok(reference));
+            }
+        }
+    }
+}
+
+/// Obtains all tags from the repository. Annotated tags are peeled to the commit they reference, and tags that do
+/// not reference a commit or whose name is not valid UTF-8 are skipped.
+///
+/// # Arguments
+///
+/// * `repo` - Repository to read the tags from.
+///
+/// * `tag_pattern` - Pattern used to extract the tag details.
+///
+/// * `scope_case` - Case applied to the parsed scopes.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Libgit2Error` if the references can not be read.
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidRegexPattern` if the tag pattern is invalid.
+///
+fn get_all_tags(
+    repo: &Repository,
+    tag_pattern: &str,
+    scope_case: ScopeCase,
+) -> Result<Vec<Tag>, Error> {
+    let references = repo.references_glob("refs/tags/*").map_err(libgit2_error)?;
+    let mut tags: Vec<Tag> = vec![];
+    for reference in references {
+        let reference = reference.map_err(libgit2_error)?;
+        let name = match reference.shorthand() {
+            Ok(name) => name.to_owned(),
+            Err(_) => continue,
+        };
+        let commit_sha = match reference.peel_to_commit() {
+            Ok(commit) => commit.id().to_string(),
+            Err(_) => continue,
+        };
+
+        tags.push(Tag {
+            details: parse_tag_details(&name, tag_pattern, scope_case)?,
+            name,
+            commit_sha,
+        });
+    }
+
+    Ok(tags)
+}
+
+/// Converts an error returned by libgit2 into an `error::Error` with a kind of `error::ErrorKind::Libgit2Error`.
+fn libgit2_error(error: impl Display) -> Error {
+    Error::new(ErrorKind::Libgit2Error, Some(&error.to_string()))
+}
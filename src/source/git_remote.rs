@@ -0,0 +1,515 @@
+//! This module includes the git remote source. The git remote source talks directly to a git remote using the
+//! system `git` installation: tags are listed with `git ls-remote --tags` and only the required commits are fetched
+//! into a temporary bare repository using partial clone filters and incremental deepening.
+//!
+//! This source is useful in CI environments with shallow checkouts, without requiring provider specific APIs.
+//!
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{self, Command};
+
+use crate::cancel::CancellationToken;
+use crate::config::{Config, TagType, Traversal};
+use crate::error::{Error, ErrorKind};
+use crate::git::{
+    parse_branch_list_output, parse_diff_tree_output, parse_rev_list_output, parse_show_ref_output,
+    strip_url_userinfo, Branch, Commit, Tag, DIFF_TREE_ARGS, REV_LIST_FORMAT,
+};
+use crate::source::{ProxyOptions, Reference, ScopeTracker, SourceActions};
+
+/// Number of commits fetched from the remote each time more history is required.
+const FETCH_DEPTH: u64 = 100;
+/// Environment variables read by git to select the proxy of HTTP connections.
+const PROXY_ENV_VARS: [&str; 7] = [
+    "http_proxy",
+    "https_proxy",
+    "HTTPS_PROXY",
+    "all_proxy",
+    "ALL_PROXY",
+    "no_proxy",
+    "NO_PROXY",
+];
+
+/// Type that represents a git remote as a source.
+pub struct GitRemoteSource<'a> {
+    /// Tag Track configuration.
+    config: &'a Config,
+
+    /// URL of the git remote.
+    url: String,
+    /// Path of the temporary bare repository where commits are fetched.
+    git_dir: PathBuf,
+    /// URL of the proxy used to connect to the remote.
+    proxy: Option<String>,
+    /// If the proxy environment variables are honored.
+    use_proxy_env: bool,
+}
+
+impl<'a> GitRemoteSource<'a> {
+    /// Returns a new instance of a `GitRemoteSource` source. A temporary bare repository is created to fetch the
+    /// required commits, it is removed when the source is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Tag Track configuration.
+    ///
+    /// * `url` - URL of the git remote.
+    ///
+    /// * `proxy` - Proxies used to connect to the remote. The proxy is selected from the scheme of `url`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GenericCommandFailed` if the temporary repository
+    /// can not be created.
+    ///
+    pub fn new(config: &'a Config, url: String, proxy: &ProxyOptions) -> Result<Self, Error> {
+        let git_dir = env::temp_dir().join(format!("tag-track-remote-{}", process::id()));
+        let source = Self {
+            config,
+            proxy: proxy.for_url(&url).map(|proxy| proxy.to_owned()),
+            use_proxy_env: proxy.use_env,
+            url,
+            git_dir,
+        };
+
+        run_git(
+            Command::new("git")
+                .args(["init", "--bare", "--quiet"])
+                .arg(&source.git_dir),
+        )?;
+        Ok(source)
+    }
+
+    /// Returns a `git` command that connects to the remote through the configured proxy, which overrides the proxy
+    /// environment variables. If the proxy environment variables are not honored, they are removed from the command.
+    fn remote_git(&self) -> Command {
+        let mut command = Command::new("git");
+        if !self.use_proxy_env {
+            for name in PROXY_ENV_VARS {
+                command.env_remove(name);
+            }
+        }
+        if let Some(proxy) = &self.proxy {
+            command.arg("-c").arg(format!("http.proxy={}", proxy));
+        }
+        command
+    }
+
+    /// Returns a `git` command that operates on the temporary bare repository.
+    fn git(&self) -> Command {
+        let mut command = self.remote_git();
+        command.arg("--git-dir").arg(&self.git_dir);
+        command
+    }
+
+    /// Fetches the history of the given commit from the remote. If `deepen` is `false`, the latest `FETCH_DEPTH`
+    /// commits are fetched, otherwise the already fetched history is extended by `FETCH_DEPTH` commits. Blobs are
+    /// never fetched as only commit messages are required.
+    ///
+    /// # Arguments
+    ///
+    /// * `sha` - SHA of the commit whose history is fetched.
+    ///
+    /// * `deepen` - Extend the already fetched history instead of starting a new one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GenericCommandFailed` if the `git fetch` command fails.
+    ///
+    fn fetch(&self, sha: &str, deepen: bool) -> Result<(), Error> {
+        let depth = match deepen {
+            true => format!("--deepen={}", FETCH_DEPTH),
+            false => format!("--depth={}", FETCH_DEPTH),
+        };
+
+        run_git(
+            self.git()
+                .args(["fetch", "--quiet", "--no-tags", "--filter=blob:none"])
+                .arg(depth)
+                .arg(&self.url)
+                .arg(sha),
+        )?;
+        Ok(())
+    }
+
+    /// Returns the paths changed by the given commit compared to its first parent. Only trees are required, so the
+    /// partial fetches without blobs are enough.
+    ///
+    /// # Arguments
+    ///
+    /// * `sha` - SHA of the commit.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GenericCommandFailed` if the `git diff-tree` command
+    /// fails.
+    ///
+    fn changed_paths(&self, sha: &str) -> Result<Vec<String>, Error> {
+        let stdout = run_git(self.git().args(DIFF_TREE_ARGS).arg(sha))?;
+        Ok(parse_diff_tree_output(&stdout))
+    }
+
+    /// Returns the commits at the boundary of the fetched history, or an empty string if the whole history has
+    /// been fetched.
+    fn shallow_boundary(&self) -> String {
+        fs::read_to_string(self.git_dir.join("shallow")).unwrap_or_default()
+    }
+}
+
+impl<'a> Drop for GitRemoteSource<'a> {
+    /// Removes the temporary bare repository.
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.git_dir);
+    }
+}
+
+/// Trait to describe all common actions that all sources need to implement.
+impl<'a> SourceActions<'a> for GitRemoteSource<'a> {
+    /// Returns an Iterator that will return commits and their associated tags for version bump. This iterator may skipped not
+    /// required commits or tags which are not required to calculate the version bump.
+    ///
+    /// # Arguments
+    ///
+    /// * `sha` - The commit sha to start the iteration from.
+    ///
+    /// * `range_start` - SHA of the commit that starts the range of commits used to calculate the version bump.
+    ///
+    /// * `cancel` - Token used to interrupt the iteration.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GenericCommandFailed` if the commits can not be
+    /// fetched from the remote.
+    ///
+    fn get_ref_iterator(
+        &self,
+        sha: &'a str,
+        range_start: Option<&str>,
+        cancel: &CancellationToken,
+    ) -> Result<Box<dyn Iterator<Item = Result<Reference, Error>> + '_>, Error> {
+        let stdout = run_git(
+            self.remote_git()
+                .args(["ls-remote", "--tags"])
+                .arg(&self.url),
+        )?;
+        let mut tags = parse_show_ref_output(
+            &stdout,
+            &self.config.tag_pattern,
+            self.config.scope_rules.case,
+        )?;
+        tags.extend(self.config.extra_tags.iter().cloned());
+        self.config.resolve_scope_tags(&mut tags)?;
+
+        self.fetch(sha, false)?;
+        Ok(Box::new(RefIterator::new(
+            self,
+            sha,
+            tags,
+            range_start,
+            cancel,
+        )))
+    }
+
+    /// Returns the SHA of the commit referenced by `HEAD` in the remote.
+    fn get_latest_commit_sha(&self) -> Result<String, Error> {
+        let stdout = run_git(
+            self.remote_git()
+                .arg("ls-remote")
+                .arg(&self.url)
+                .arg("HEAD"),
+        )?;
+        match stdout.split_whitespace().next() {
+            Some(sha) => Ok(sha.to_owned()),
+            None => Err(Error::new(
+                ErrorKind::Other,
+                Some(&format!(
+                    "can not get HEAD commit from remote '{}'",
+                    strip_url_userinfo(&self.url)
+                )),
+            )),
+        }
+    }
+
+    /// Returns the SHA of the commit referenced by the given branch or tag of the remote, listed with
+    /// `git ls-remote`. Annotated tags are peeled to the commit they reference. Commit SHAs are returned as they are,
+    /// as the remote does not list them.
+    ///
+    /// # Arguments
+    ///
+    /// * `reference` - Reference that will be resolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GenericCommandFailed` if the references can not be
+    /// listed.
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the remote does not have the reference.
+    ///
+    fn resolve_ref(&self, reference: &str) -> Result<String, Error> {
+        let stdout = run_git(
+            self.remote_git()
+                .arg("ls-remote")
+                .arg(&self.url)
+                .arg(reference)
+                .arg(format!("{}^{{}}", reference)),
+        )?;
+
+        let mut sha = None;
+        for line in stdout.lines() {
+            let (line_sha, name) = match line.split_once('\t') {
+                Some(fields) => fields,
+                None => continue,
+            };
+            if name.ends_with("^{}") || sha.is_none() {
+                sha = Some(line_sha.to_owned());
+            }
+        }
+        match sha {
+            Some(sha) => Ok(sha),
+            None if reference.chars().all(|char| char.is_ascii_hexdigit()) => {
+                Ok(reference.to_owned())
+            }
+            None => Err(Error::new(
+                ErrorKind::Other,
+                Some(&format!(
+                    "can not resolve reference '{}' in remote '{}'",
+                    reference,
+                    strip_url_userinfo(&self.url)
+                )),
+            )),
+        }
+    }
+
+    /// Returns the branches of the remote listed with `git ls-remote --heads`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GenericCommandFailed` if the branches can not be
+    /// listed.
+    ///
+    fn get_branches(&self) -> Result<Vec<Branch>, Error> {
+        let stdout = run_git(
+            self.remote_git()
+                .args(["ls-remote", "--heads"])
+                .arg(&self.url),
+        )?;
+        Ok(parse_branch_list_output(&stdout))
+    }
+
+    /// Creates a new tag with the given name, message and referencing the given commit sha, and pushes it to the
+    /// remote. Lightweight tags are created without the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_name` - Name of the tag.
+    ///
+    /// * `tag_message` - Message of the tag.
+    ///
+    /// * `commit_sha` - SHA of the commit that the tag will reference.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GenericCommandFailed` if the tag can not be created
+    /// or pushed.
+    ///
+    fn create_tag(&self, tag_name: &str, tag_message: &str, commit_sha: &str) -> Result<(), Error> {
+        self.fetch(commit_sha, false)?;
+        match self.config.tag_type {
+            TagType::Annotated => run_git(
+                self.git()
+                    .args(["tag", "-a", tag_name, commit_sha])
+                    .args(["-m", tag_message]),
+            )?,
+            TagType::Lightweight => run_git(self.git().args(["tag", tag_name, commit_sha]))?,
+        };
+        run_git(
+            self.git()
+                .args(["push", "--quiet"])
+                .arg(&self.url)
+                .arg(format!("refs/tags/{}", tag_name)),
+        )?;
+        Ok(())
+    }
+}
+
+/// Type used to iterate over references on the remote repository history.
+/// This type implements the `Iterator` trait and fetches more history from the remote when required.
+pub struct RefIterator<'a> {
+    /// Version scopes whose closest tag has not been found yet.
+    scope_tracker: ScopeTracker,
+    /// Current commit index.
+    current_elem: u64,
+    /// If the iterator has finished iterating over the commits.
+    is_finished: bool,
+
+    /// Source used to fetch commits.
+    source: &'a GitRemoteSource<'a>,
+    /// Commit SHA from where the iteration will start.
+    sha: &'a str,
+    /// List of tags obtained from the remote.
+    tags: Vec<Tag>,
+}
+
+impl<'a> RefIterator<'a> {
+    /// Returns a new instance of a `RefIterator`.
+    fn new(
+        source: &'a GitRemoteSource<'a>,
+        sha: &'a str,
+        tags: Vec<Tag>,
+        range_start: Option<&str>,
+        cancel: &CancellationToken,
+    ) -> Self {
+        RefIterator {
+            scope_tracker: ScopeTracker::new(
+                source.config.version_scopes.clone(),
+                range_start,
+                cancel,
+            ),
+            current_elem: 0,
+            is_finished: false,
+
+            source,
+            sha,
+            tags,
+        }
+    }
+
+    /// Returns the next commit, fetching more history from the remote if the fetched history has been exhausted.
+    /// Returns `None` if the whole history has been iterated.
+    fn next_commit(&mut self) -> Result<Option<Commit>, Error> {
+        loop {
+            let mut command = self.source.git();
+            command
+                .arg("rev-list")
+                .arg(self.sha)
+                .arg("--max-count=1")
+                .arg(format!("--skip={}", self.current_elem))
+                .arg(REV_LIST_FORMAT);
+            if self.source.config.traversal == Traversal::FirstParent {
+                command.arg("--first-parent");
+            }
+            let stdout = run_git(&mut command)?;
+            let commit = parse_rev_list_output(
+                &stdout,
+                &self.source.config.commit_pattern,
+                self.source.config.scope_rules.case,
+                self.source.config.max_commit_message_length,
+            )?;
+            if let Some(mut commit) = commit {
+                self.current_elem += 1;
+                if self.source.config.dependency_updates.is_some() {
+                    commit.changed_paths = Some(self.source.changed_paths(&commit.sha)?);
+                }
+                return Ok(Some(commit));
+            }
+
+            let boundary = self.source.shallow_boundary();
+            if boundary.is_empty() {
+                return Ok(None);
+            }
+
+            self.source.fetch(self.sha, true)?;
+            if self.source.shallow_boundary() == boundary {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for RefIterator<'a> {
+    type Item = Result<Reference, Error>;
+
+    /// Returns the next commit and its associated tags until the required commits to calculate the version bump have
+    /// been returned. If using scoped versioning, commits with scopes which tag has been already returned will be skipped.
+    ///
+    /// If a tag is associated with multiple commits, the tag with the biggest version will be returned. This is also true
+    /// if scoped versioning is used and there are multiple tags with the same scope in the same commit.
+    ///
+    /// If there is a commit that does not conform the given commit pattern, it will be returned with `None` in the details
+    /// field. If there is a tag that does not conform the given tag pattern, it will be skipped.
+    ///
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.is_finished {
+                return None;
+            }
+            if let Some(error) = self.scope_tracker.cancellation_error() {
+                self.is_finished = true;
+                return Some(Err(error));
+            }
+
+            let mut commit = match self.next_commit() {
+                Ok(Some(commit)) => commit,
+                Ok(None) => {
+                    self.is_finished = true;
+                    return None;
+                }
+                Err(error) => {
+                    self.is_finished = true;
+                    return Some(Err(error));
+                }
+            };
+
+            if let Err(error) = self.source.config.resolve_scope_commit(&mut commit) {
+                self.is_finished = true;
+                return Some(Err(error));
+            }
+            let ignored_commit = match self.source.config.find_ignored_commit(&commit) {
+                Ok(ignored_commit) => ignored_commit,
+                Err(error) => {
+                    self.is_finished = true;
+                    return Some(Err(error));
+                }
+            };
+            let tags = self.scope_tracker.find_tags(&commit.sha, &self.tags);
+            let reference = self
+                .scope_tracker
+                .match_reference(commit, tags, ignored_commit);
+            if self.scope_tracker.is_finished() {
+                self.is_finished = true;
+            }
+            if let Some(reference) = reference {
+                return Some(Ok(reference));
+            }
+        }
+    }
+}
+
+/// Runs the given `git` command and returns its trimmed standard output.
+///
+/// # Arguments
+///
+/// * `command` - Command to run.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GenericCommandFailed` if the command can not be spawned
+/// or if it exits with a failure status.
+///
+fn run_git(command: &mut Command) -> Result<String, Error> {
+    let output = match command.output() {
+        Ok(output) => output,
+        Err(error) => {
+            return Err(Error::new(
+                ErrorKind::GenericCommandFailed,
+                Some(&error.to_string()),
+            ))
+        }
+    };
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        return Err(Error::new(
+            ErrorKind::GenericCommandFailed,
+            Some(&format!(
+                "error code: \"{}\", stderr: \"{}\"",
+                output.status.code().unwrap_or(-1),
+                stderr.trim(),
+            )),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -5,34 +5,93 @@
 //! nor partially available.
 //!
 
-use std::{env, vec};
-
-use crate::config::Config;
+use std::{
+    cell::Cell,
+    collections::{HashMap, HashSet, VecDeque},
+    env, fmt, fs,
+    future::Future,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+    vec,
+};
+
+use crate::cancel::CancellationToken;
+use crate::config::{Config, ScopeCase, TagType, Traversal};
 use crate::error::{Error, ErrorKind};
-use crate::git::{Commit, Tag};
-use crate::parsing::{parse_commit_details, parse_tag_details};
-use crate::source::{Reference, SourceActions};
+use crate::git::{Branch, Commit, Tag};
+use crate::logging;
+use crate::parsing::{get_literal_prefix, parse_tag_details};
+use crate::plan::encode_hex;
+use crate::run;
+use crate::source::github_app::{get_installation_token, GithubAppCredentials};
+use crate::source::{ProxyOptions, Reference, ScopeTracker, SourceActions, SourceStats};
+use futures_util::future::join_all;
 use reqwest;
-use serde::Deserialize;
-
-/// GitHub REST API base URL.
-pub const GITHUB_API_BASE_URL: &str = "https://api.github.com";
-/// GitHub REST API URI for querying tags. Must be used in combination with `GITHUB_BASE_URI`.
-const GITHUB_TAGS_URI: &str = "/tags";
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::{runtime::Runtime, task::JoinHandle};
+
+#[cfg(not(any(feature = "rustls", feature = "native-tls")))]
+compile_error!(
+    "the `github` feature requires a TLS backend, enable the `rustls` or `native-tls` feature"
+);
+
+/// GitHub REST API URI for querying tag references. Must be used in combination with `GITHUB_BASE_URI`.
+const GITHUB_GIT_TAG_REFS_URI: &str = "/git/matching-refs/tags";
+/// GitHub REST API URI for querying branches. Must be used in combination with `GITHUB_BASE_URI`.
+const GITHUB_BRANCHES_URI: &str = "/branches";
 /// GitHub REST API URI for querying commits. Must be used in combination with `GITHUB_BASE_URI`.
 const GITHUB_COMMITS_URI: &str = "/commits";
+/// GitHub REST API URI for comparing commits. Must be used in combination with `GITHUB_BASE_URI`.
+const GITHUB_COMPARE_URI: &str = "/compare";
 /// GitHub REST API URI for creating git tags. Must be used in combination with `GITHUB_BASE_URI`.
 const GITHUB_GIT_TAGS_URI: &str = "/git/tags";
 // GitHub REST API URI for creating git references. Must be used in combination with `GITHUB_BASE_URI`.
 const GITHUB_GIT_REFS_URI: &str = "/git/refs";
+/// GitHub REST API URI for querying a single git reference. Must be used in combination with `GITHUB_BASE_URI`.
+const GITHUB_GIT_REF_URI: &str = "/git/ref";
+/// GitHub REST API URI for creating releases. Must be used in combination with `GITHUB_BASE_URI`.
+const GITHUB_RELEASES_URI: &str = "/releases";
 /// Content for the `User-Agent` header.
 const USER_AGENT: &str = "tag-track";
-/// Name for the authorization header for authorizing GitHub REST API requests.
-const AUTH_HEADER: &str = "authorization";
 
 /// Default elements per page used for paginated requests.
 const DEFAULT_PER_PAGE: u64 = 100;
 
+/// Maximum number of retries of rate-limited requests.
+const RATE_LIMIT_MAX_RETRIES: u32 = 3;
+/// Initial wait time before retrying a rate-limited request without `Retry-After` or `X-RateLimit-Reset` headers.
+/// The wait time is doubled on each retry.
+const RATE_LIMIT_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// Maximum wait time before retrying a rate-limited request. If the rate limit resets later, the request fails.
+const RATE_LIMIT_MAX_WAIT: Duration = Duration::from_secs(60);
+
+/// Initial wait time before retrying a request that timed out, failed to connect or got a `5xx` response. The wait
+/// time is doubled on each retry.
+const RETRY_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Minimal permissions of the GitHub token to create tags and releases, included in the errors of the requests that
+/// are not authorized.
+const TOKEN_PERMISSIONS_HINT: &str = "the token needs the `contents: write` permission of the repository to create \
+     tags and releases, use a fine-grained personal access token, the `GITHUB_TOKEN` of a workflow with \
+     `permissions: contents: write` or a GitHub App with `--github-app-id` and `--github-app-private-key`. With a \
+     deploy key, create the tags with the git source and push them with git";
+
+/// Maximum number of retries of a tag reference creation that gets a `404` response.
+const TAG_REF_MAX_RETRIES: u32 = 4;
+/// Initial wait time before retrying a tag reference creation that got a `404` response. The wait time is doubled on
+/// each retry.
+const TAG_REF_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Line that ends a PEM encoded certificate.
+const PEM_CERTIFICATE_END: &str = "-----END CERTIFICATE-----";
+
+/// Extension of the HTTP cache files.
+const CACHE_FILE_EXTENSION: &str = "json";
+/// Version of the format of the HTTP cache files. Cache files with a different version are ignored.
+const CACHE_FORMAT_VERSION: u32 = 2;
+
 /// GitHub actions environment variable name to get the commit sha that triggered a workflow.
 const GITHUB_SHA: &str = "GITHUB_SHA";
 
@@ -41,12 +100,164 @@ pub struct GithubSource<'a> {
     /// Tag Track configuration.
     config: &'a Config,
 
+    /// GitHub REST API client of the repository.
+    api: GithubApi,
+    /// If the latest commit sha can be read from the `GITHUB_SHA` environment variable.
+    use_env: bool,
+    /// Number of pages requested concurrently when paginating.
+    concurrent_pages: u64,
+    /// Committer date window of the commits requested to calculate the version bump.
+    window: CommitWindow,
+    /// Number of retries of the tag reference creations.
+    tag_ref_retries: Cell<u32>,
+    /// Runtime used to send the asynchronous requests.
+    runtime: Runtime,
+}
+
+/// Type that holds the options of the HTTP client used to send requests to the GitHub REST API.
+#[derive(Debug, Clone, Default)]
+pub struct GithubClientOptions {
+    /// Directory of the HTTP cache used for conditional requests. If it is `None`, responses are not cached.
+    pub cache_dir: Option<PathBuf>,
+    /// Number of pages requested concurrently when paginating. Values lower than `1` are treated as `1`.
+    pub concurrent_pages: u64,
+    /// Proxies used to connect to the GitHub REST API.
+    pub proxy: ProxyOptions,
+    /// Path of a PEM file with additional CA certificates trusted when verifying the TLS certificate of the GitHub
+    /// REST API, for GitHub Enterprise Server instances that use an internal CA.
+    pub ca_cert: Option<PathBuf>,
+    /// Disables the verification of TLS certificates. Any certificate is accepted, which makes the connection
+    /// vulnerable to man-in-the-middle attacks.
+    pub insecure: bool,
+    /// Timeout of each request, from connecting until the response body is read. If it is `None`, requests do not
+    /// time out.
+    pub timeout: Option<Duration>,
+    /// Number of retries of idempotent requests that time out, fail to connect or get a `5xx` response.
+    pub retries: u32,
+    /// Committer date window of the commits requested to calculate the version bump.
+    pub window: CommitWindow,
+    /// Observer of the requests sent to the GitHub REST API, used to record them in logs or metrics.
+    pub observer: Option<Arc<dyn RequestObserver>>,
+    /// Credentials of the GitHub App whose installation token authorizes the requests, used instead of the token.
+    pub app: Option<GithubAppCredentials>,
+}
+
+/// Trait implemented by the observers of the requests sent to the GitHub REST API. The observer is called after each
+/// attempt of a request, including the attempts that are retried, so it can forward them to logging or telemetry.
+pub trait RequestObserver: fmt::Debug + Send + Sync {
+    /// Called after an attempt of a request, when the response headers are received or the request fails.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - Details of the attempt.
+    ///
+    fn on_request(&self, record: &RequestRecord);
+}
+
+/// Type that holds the details of an attempt of a request sent to the GitHub REST API.
+#[derive(Debug, Clone)]
+pub struct RequestRecord {
+    /// HTTP method of the request.
+    pub method: String,
+    /// URL of the request.
+    pub url: String,
+    /// Status code of the response, `None` if the request failed before receiving a response.
+    pub status: Option<u16>,
+    /// Time elapsed until the response headers were received or the request failed.
+    pub duration: Duration,
+    /// Value of the `X-RateLimit-Limit` header of the response.
+    pub rate_limit_limit: Option<u64>,
+    /// Value of the `X-RateLimit-Remaining` header of the response.
+    pub rate_limit_remaining: Option<u64>,
+    /// Value of the `X-RateLimit-Reset` header of the response, in seconds since the Unix epoch.
+    pub rate_limit_reset: Option<u64>,
+}
+
+impl RequestRecord {
+    /// Returns a new `RequestRecord` with the rate-limit headers of the given response.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP method of the request.
+    ///
+    /// * `url` - URL of the request.
+    ///
+    /// * `response` - Response of the request, `None` if the request failed.
+    ///
+    /// * `duration` - Time elapsed until the response headers were received or the request failed.
+    ///
+    fn new(
+        method: &reqwest::Method,
+        url: &str,
+        response: Option<&reqwest::Response>,
+        duration: Duration,
+    ) -> Self {
+        let header = |name: &str| -> Option<u64> {
+            response?
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse().ok())
+        };
+
+        Self {
+            method: method.to_string(),
+            url: url.to_owned(),
+            status: response.map(|response| response.status().as_u16()),
+            duration,
+            rate_limit_limit: header("x-ratelimit-limit"),
+            rate_limit_remaining: header("x-ratelimit-remaining"),
+            rate_limit_reset: header("x-ratelimit-reset"),
+        }
+    }
+}
+
+/// Type that holds the committer date window of the commits requested from the GitHub REST API. The dates are
+/// ISO 8601 timestamps in the `YYYY-MM-DDTHH:MM:SSZ` format, sent as the `since` and `until` query parameters, so
+/// the commits outside of the window are filtered by the GitHub REST API instead of being paginated.
+#[derive(Debug, Clone, Default)]
+pub struct CommitWindow {
+    /// Only commits committed at or after this date are requested.
+    pub since: Option<String>,
+    /// Only commits committed at or before this date are requested.
+    pub until: Option<String>,
+}
+
+/// Type that holds the data required to send requests to the GitHub REST API of a repository.
+#[derive(Clone)]
+struct GithubApi {
+    /// HTTP client shared by all requests.
+    client: reqwest::Client,
     /// GitHub repository identifier `org/repo-name`, example `dloez/tag-track`.
     repo_id: String,
     /// GitHub REST API base URL.
     api_url: String,
     /// GitHub REST API authentication token to authorize requests.
     token: Option<String>,
+    /// Directory of the HTTP cache used for conditional requests. If it is `None`, responses are not cached.
+    cache_dir: Option<PathBuf>,
+    /// Number of retries of idempotent requests that time out, fail to connect or get a `5xx` response.
+    retries: u32,
+    /// Observer of the requests sent to the GitHub REST API.
+    observer: Option<Arc<dyn RequestObserver>>,
+}
+
+impl GithubApi {
+    /// Returns a request to the given URI of the repository. The `User-Agent` and `Authorization` headers are set by
+    /// the client.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - HTTP method of the request.
+    ///
+    /// * `uri` - URI of the request, relative to the repository URL.
+    ///
+    fn request(&self, method: reqwest::Method, uri: &str) -> reqwest::RequestBuilder {
+        self.client.request(
+            method,
+            format!("{}/repos/{}{}", self.api_url, self.repo_id, uri),
+        )
+    }
 }
 
 impl<'a> GithubSource<'a> {
@@ -60,161 +271,405 @@ impl<'a> GithubSource<'a> {
     ///
     /// * `api_url` - GitHub REST API base URL.
     ///
-    /// * `token` - GitHub REST API authentication token to authorize requests.
+    /// * `token` - GitHub REST API authentication token to authorize requests. If the options include the credentials
+    ///   of a GitHub App, an installation token of the app is used instead.
+    ///
+    /// * `use_env` - If the latest commit sha can be read from the `GITHUB_SHA` environment variable.
+    ///
+    /// * `options` - Options of the HTTP client.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the asynchronous runtime or the HTTP client
+    /// can not be created, for example because a proxy URL or the CA certificates file are not valid.
+    ///
+    /// Returns the errors of `github_app::get_installation_token` if the installation token of the GitHub App can not
+    /// be obtained.
     ///
     pub fn new(
         config: &'a Config,
         repo_id: String,
         api_url: String,
         token: Option<String>,
-    ) -> Self {
-        Self {
+        use_env: bool,
+        options: GithubClientOptions,
+    ) -> Result<Self, Error> {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()?;
+
+        let token = match &options.app {
+            Some(credentials) => {
+                let client = build_client(&options, None)?;
+                Some(runtime.block_on(get_installation_token(
+                    &client,
+                    &api_url,
+                    &repo_id,
+                    credentials,
+                ))?)
+            }
+            None => token,
+        };
+
+        Ok(Self {
             config,
-            repo_id,
-            api_url,
-            token,
-        }
+            api: GithubApi {
+                client: build_client(&options, token.as_deref())?,
+                repo_id,
+                api_url,
+                token,
+                cache_dir: options.cache_dir,
+                retries: options.retries,
+                observer: options.observer,
+            },
+            use_env,
+            concurrent_pages: options.concurrent_pages.max(1),
+            window: options.window,
+            tag_ref_retries: Cell::new(0),
+            runtime,
+        })
     }
 }
 
 /// Trait to describe all common actions that all sources need to implement.
 impl<'a> SourceActions<'a> for GithubSource<'a> {
     /// Returns an Iterator that will return commits and their associated tags for version bump. This iterator may skipped not
-    /// required commits or tags which are not required to calculate the version bump.
+    /// required commits or tags which are not required to calculate the version bump. Tags are only matched on
+    /// commits that the compare endpoint reports as ancestors of the given commit, so tags of diverged branches are
+    /// never matched.
     ///
     /// # Arguments
     ///
     /// * `sha` - The commit sha to start the iteration from.
     ///
+    /// * `range_start` - SHA of the commit that starts the range of commits used to calculate the version bump. It
+    ///   does not apply to the scopes of `scope_paths`, whose commits are requested by path.
+    ///
+    /// * `cancel` - Token used to interrupt the iteration.
+    ///
     /// # Errors
     ///
-    /// Returns `error::Error` with a kind of `error::ErrorKind::MissingGitTags` if there are no tags in the source.
+    /// Returns `error::Error` with a kind of `error::ErrorKind::Other` if a version scope overrides the tag pattern.
     ///
     fn get_ref_iterator(
         &self,
         sha: &'a str,
+        range_start: Option<&str>,
+        cancel: &CancellationToken,
     ) -> Result<Box<dyn Iterator<Item = Result<Reference, Error>> + '_>, Error> {
-        let tags = get_all_tags(&self.repo_id, &self.api_url, &self.token)?;
-        if tags.is_none() {
+        if self.config.has_scope_tag_patterns() {
             return Err(Error::new(
-                ErrorKind::MissingGitTags,
-                Some("no tags found for repository"),
+                ErrorKind::Other,
+                Some(
+                    "the `tag_pattern` of `scopes` is not supported by the GitHub source, use a git source or the \
+                     top-level `tag_pattern`",
+                ),
             ));
         }
 
-        Ok(Box::new(RefIterator::new(
-            sha,
-            tags.unwrap(),
-            &self.repo_id,
-            &self.api_url,
-            &self.token,
-            self.config,
-        )))
+        let mut tags = self
+            .runtime
+            .block_on(get_all_tags(
+                &self.api,
+                &self.config.tag_pattern,
+                self.concurrent_pages,
+            ))?
+            .unwrap_or_default();
+        tags.extend(self.config.extra_tags.iter().map(|tag| GithubTag {
+            name: tag.name.clone(),
+            commit: GithubTagCommit {
+                sha: tag.commit_sha.clone(),
+            },
+        }));
+
+        let (path_scopes, version_scopes): (Vec<String>, Vec<String>) = self
+            .config
+            .version_scopes
+            .iter()
+            .cloned()
+            .partition(|scope| self.config.scope_paths.contains_key(scope));
+
+        let mut path_references = vec![];
+        for scope in &path_scopes {
+            path_references.extend(self.runtime.block_on(get_path_references(
+                &self.api,
+                sha,
+                scope,
+                &self.config.scope_paths[scope],
+                &tags,
+                &self.window,
+                self.concurrent_pages,
+                self.config,
+            ))?);
+        }
+        let path_references = path_references.into_iter().map(Ok);
+        if version_scopes.is_empty() {
+            return Ok(Box::new(path_references));
+        }
+
+        Ok(Box::new(
+            RefIterator::new(
+                sha,
+                tags,
+                ScopeTracker::new(version_scopes, range_start, cancel),
+                &self.api,
+                &self.runtime,
+                self.concurrent_pages,
+                &self.window,
+                self.config,
+            )
+            .chain(path_references),
+        ))
     }
 
-    /// Returns the latest commit sha.
+    /// Returns the latest commit sha from the `GITHUB_SHA` environment variable.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the environment variable is not set or if
+    /// environment reads are disabled.
+    ///
     fn get_latest_commit_sha(&self) -> Result<String, Error> {
+        if !self.use_env {
+            return Err(Error::new(
+                ErrorKind::Other,
+                Some("can not read the latest commit from the environment when using `--no-env`, use the `--commit-sha` argument"),
+            ));
+        }
+
         match env::var(GITHUB_SHA) {
             Ok(sha) => Ok(sha),
             Err(error) => Err(error.into()),
         }
     }
 
+    /// Returns the SHA of the commit referenced by the given reference.
+    ///
+    /// # Arguments
+    ///
+    /// * `reference` - Reference that will be resolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+    /// from the GitHub REST API, such as the reference not being found.
+    ///
+    fn resolve_ref(&self, reference: &str) -> Result<String, Error> {
+        self.runtime.block_on(get_commit_sha(&self.api, reference))
+    }
+
+    /// Returns the branches of the repository.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+    /// from the GitHub REST API.
+    ///
+    fn get_branches(&self) -> Result<Vec<Branch>, Error> {
+        self.runtime
+            .block_on(get_all_branches(&self.api, self.concurrent_pages))
+    }
+
+    /// Creates a new tag with the given name, message and referencing the given commit sha. The reference of an
+    /// annotated tag points to the created tag object instead of the commit. The tag reference creation is retried
+    /// if the GitHub REST API responds with `404`, which happens on some GitHub Enterprise Server instances when the
+    /// tag object of an annotated tag is not visible yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_name` - The name of the tag to create.
+    ///
+    /// * `tag_message` - The message of the tag to create.
+    ///
+    /// * `commit_sha` - SHA of the commit that the tag will reference.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::AuthenticationRequired` if a GitHub token was not
+    /// given.
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+    /// from the GitHub REST API, or if the tag already exists and references a different commit.
+    ///
     fn create_tag(&self, tag_name: &str, tag_message: &str, commit_sha: &str) -> Result<(), Error> {
-        if self.token.is_none() {
+        if self.api.token.is_none() {
             return Err(Error::new(
                 ErrorKind::AuthenticationRequired,
-                Some("missing GitHub token to create tag, use the `--github-token` to pass the token"),
+                Some(
+                    "missing GitHub token to create tag, use the `--github-token` to pass the token or \
+                     `--github-app-id` and `--github-app-private-key` to authenticate as a GitHub App",
+                ),
             ));
         }
 
-        let data = serde_json::json!({
-            "tag": tag_name,
-            "message": tag_message,
-            "object": commit_sha,
-            "type": "commit",
-        });
-        let client = reqwest::blocking::Client::new()
-            .post(format!(
-                "{}/repos/{}{}",
-                &self.api_url, &self.repo_id, GITHUB_GIT_TAGS_URI
-            ))
-            .json(&data)
-            .header(reqwest::header::USER_AGENT, USER_AGENT)
-            .header(
-                AUTH_HEADER,
-                format!("Bearer {}", self.token.as_ref().unwrap()),
-            );
-
-        let response = match client.send() {
-            Err(error) => {
-                return Err(Error::new(
-                    ErrorKind::GithubRestError,
-                    Some(&error.to_string()),
-                ))
+        self.runtime.block_on(async {
+            let mut ref_sha = commit_sha.to_owned();
+            if self.config.tag_type == TagType::Annotated {
+                let data = serde_json::json!({
+                    "tag": tag_name,
+                    "message": tag_message,
+                    "object": commit_sha,
+                    "type": "commit",
+                });
+                let request = self
+                    .api
+                    .request(reqwest::Method::POST, GITHUB_GIT_TAGS_URI)
+                    .json(&data);
+                let response = send_request(&self.api, request).await?;
+
+                let status = response.status().as_u16();
+                if status != 201 {
+                    return Err(unexpected_write_response(
+                        status,
+                        response.text().await.unwrap_or_default(),
+                    ));
+                }
+                ref_sha = match response.json::<GithubCreatedGitTag>().await {
+                    Ok(tag) => tag.sha,
+                    Err(error) => {
+                        return Err(Error::new(
+                            ErrorKind::GithubRestError,
+                            Some(&error.to_string()),
+                        ))
+                    }
+                };
             }
-            Ok(res) => res,
-        };
 
-        if response.status().as_u16() != 201 {
+            let retries = create_tag_ref(&self.api, tag_name, &ref_sha).await?;
+            self.tag_ref_retries
+                .set(self.tag_ref_retries.get() + retries);
+            Ok(())
+        })
+    }
+
+    /// Creates a new release for the given existing tag and returns the URL of its web page.
+    ///
+    /// # Arguments
+    ///
+    /// * `tag_name` - Name of the tag of the release.
+    ///
+    /// * `title` - Title of the release.
+    ///
+    /// * `body` - Body of the release, in Markdown.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::AuthenticationRequired` if a GitHub token was not
+    /// given.
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+    /// from the GitHub REST API.
+    ///
+    fn create_release(&self, tag_name: &str, title: &str, body: &str) -> Result<String, Error> {
+        if self.api.token.is_none() {
             return Err(Error::new(
-                ErrorKind::GithubRestError,
-                Some(&response.text().unwrap()),
+                ErrorKind::AuthenticationRequired,
+                Some(
+                    "missing GitHub token to create release, use the `--github-token` to pass the token or \
+                     `--github-app-id` and `--github-app-private-key` to authenticate as a GitHub App",
+                ),
             ));
         }
 
         let data = serde_json::json!({
-            "ref": format!("refs/tags/{}", tag_name),
-            "sha": commit_sha,
+            "tag_name": tag_name,
+            "name": title,
+            "body": body,
         });
-        let client = reqwest::blocking::Client::new()
-            .post(format!(
-                "{}/repos/{}{}",
-                &self.api_url, &self.repo_id, GITHUB_GIT_REFS_URI
-            ))
-            .json(&data)
-            .header(reqwest::header::USER_AGENT, USER_AGENT)
-            .header(
-                AUTH_HEADER,
-                format!("Bearer {}", self.token.as_ref().unwrap()),
-            );
-
-        let response = match client.send() {
-            Err(error) => {
-                return Err(Error::new(
+        let request = self
+            .api
+            .request(reqwest::Method::POST, GITHUB_RELEASES_URI)
+            .json(&data);
+
+        self.runtime.block_on(async {
+            let response = send_request(&self.api, request).await?;
+            let status = response.status().as_u16();
+            if status != 201 {
+                return Err(unexpected_write_response(
+                    status,
+                    response.text().await.unwrap_or_default(),
+                ));
+            }
+
+            match response.json::<GithubRelease>().await {
+                Ok(release) => Ok(release.html_url),
+                Err(error) => Err(Error::new(
                     ErrorKind::GithubRestError,
                     Some(&error.to_string()),
-                ))
+                )),
             }
-            Ok(res) => res,
-        };
+        })
+    }
 
-        if response.status().as_u16() != 201 {
-            return Err(Error::new(
-                ErrorKind::GithubRestError,
-                Some(&response.text().unwrap()),
-            ));
+    /// Returns the number of retries of the tag reference creations.
+    fn get_stats(&self) -> SourceStats {
+        SourceStats {
+            tag_ref_retries: self.tag_ref_retries.get(),
         }
-
-        Ok(())
     }
 }
 
-/// Used to deserialize responses from `https://api.github.com/repos/org/repo_name/tags`.
-/// Only the required fields by `tag-track` are included.
-#[derive(Debug, Deserialize, Clone)]
+/// Used to deserialize responses from `https://api.github.com/repos/org/repo_name/releases`.
+#[derive(Deserialize, Debug)]
+struct GithubRelease {
+    html_url: String,
+}
+
+/// Type that represents a tag of the repository with the SHA of the commit it points to. The SHA of annotated tags is
+/// peeled from the tag object, so it matches the commit seen by the git sources.
+#[derive(Debug, Clone)]
 struct GithubTag {
     name: String,
     commit: GithubTagCommit,
 }
 
-/// Used to deserialize responses from `https://api.github.com/repos/org/repo_name/tags`.
+/// Used to deserialize the commit of responses from `https://api.github.com/repos/org/repo_name/branches`.
 /// Only the required fields by `tag-track` are included.
 #[derive(Debug, Deserialize, Clone)]
 struct GithubTagCommit {
     sha: String,
 }
 
+/// Used to deserialize responses from `https://api.github.com/repos/org/repo_name/branches`.
+/// Only the required fields by `tag-track` are included.
+#[derive(Debug, Deserialize, Clone)]
+struct GithubBranch {
+    name: String,
+    commit: GithubTagCommit,
+}
+
+/// Used to deserialize responses from `https://api.github.com/repos/org/repo_name/git/matching-refs/tags`.
+/// Only the required fields by `tag-track` are included.
+#[derive(Debug, Deserialize, Clone)]
+struct GithubRef {
+    #[serde(rename = "ref")]
+    reference: String,
+    object: GithubGitObject,
+}
+
+/// Used to deserialize responses from `https://api.github.com/repos/org/repo_name/git/tags/{sha}`.
+/// Only the required fields by `tag-track` are included.
+#[derive(Debug, Deserialize, Clone)]
+struct GithubGitTag {
+    object: GithubGitObject,
+}
+
+/// Used to deserialize responses from the creation of annotated tag objects in
+/// `https://api.github.com/repos/org/repo_name/git/tags`. Only the required fields by `tag-track` are included.
+#[derive(Debug, Deserialize, Clone)]
+struct GithubCreatedGitTag {
+    sha: String,
+}
+
+/// Used to deserialize the git objects referenced by references and annotated tag objects.
+/// Only the required fields by `tag-track` are included.
+#[derive(Debug, Deserialize, Clone)]
+struct GithubGitObject {
+    sha: String,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
 impl GithubTag {
     /// Converts a `GithubTag` into a `Tag`. If the tag details cannot be extracted,
     /// the `details` struct will be `None`.
@@ -223,12 +678,14 @@ impl GithubTag {
     ///
     /// * `tag_pattern` - Pattern used to extract the tag details.
     ///
+    /// * `scope_case` - Case applied to the parsed scope.
+    ///
     /// # Errors
     ///
     /// Returns `error::Error` with a kind of `error::ErrorKind::TagPatternError` if the tag pattern is invalid.
     ///
-    fn convert_to_git_tag(self, tag_pattern: &str) -> Result<Tag, Error> {
-        let tag_details = parse_tag_details(&self.name, tag_pattern)?;
+    fn convert_to_git_tag(self, tag_pattern: &str, scope_case: ScopeCase) -> Result<Tag, Error> {
+        let tag_details = parse_tag_details(&self.name, tag_pattern, scope_case)?;
 
         Ok(Tag {
             name: self.name,
@@ -244,6 +701,15 @@ impl GithubTag {
 struct GithubCommitDetails {
     sha: String,
     commit: GithubCommit,
+    #[serde(default)]
+    parents: Vec<GithubCommitParent>,
+}
+
+/// Used to deserialize responses from `https://api.github.com/repos/org/repo_name/commits`.
+/// Only the required fields by `tag-track` are included.
+#[derive(Debug, Deserialize, Clone)]
+struct GithubCommitParent {
+    sha: String,
 }
 
 /// Used to deserialize responses from `https://api.github.com/repos/org/repo_name/commits`.
@@ -251,6 +717,65 @@ struct GithubCommitDetails {
 #[derive(Debug, Deserialize, Clone)]
 struct GithubCommit {
     message: String,
+    author: Option<GithubCommitAuthor>,
+}
+
+/// Used to deserialize responses from `https://api.github.com/repos/org/repo_name/commits`.
+/// Only the required fields by `tag-track` are included.
+#[derive(Debug, Deserialize, Clone)]
+struct GithubCommitAuthor {
+    name: String,
+    email: String,
+}
+
+/// Used to deserialize responses from `https://api.github.com/repos/org/repo_name/compare/base...head`.
+/// Only the required fields by `tag-track` are included.
+#[derive(Debug, Deserialize, Clone)]
+struct GithubComparison {
+    status: String,
+    base_commit: GithubComparisonCommit,
+}
+
+/// Used to deserialize responses from `https://api.github.com/repos/org/repo_name/compare/base...head`.
+/// Only the required fields by `tag-track` are included.
+#[derive(Debug, Deserialize, Clone)]
+struct GithubComparisonCommit {
+    commit: GithubComparisonCommitDetails,
+}
+
+/// Used to deserialize responses from `https://api.github.com/repos/org/repo_name/compare/base...head`.
+/// Only the required fields by `tag-track` are included.
+#[derive(Debug, Deserialize, Clone)]
+struct GithubComparisonCommitDetails {
+    committer: Option<GithubCommitCommitter>,
+}
+
+/// Used to deserialize responses from `https://api.github.com/repos/org/repo_name/compare/base...head`.
+/// Only the required fields by `tag-track` are included.
+#[derive(Debug, Deserialize, Clone)]
+struct GithubCommitCommitter {
+    date: String,
+}
+
+/// Used to deserialize responses from `https://api.github.com/repos/org/repo_name/commits/ref`.
+/// Only the required fields by `tag-track` are included.
+#[derive(Debug, Deserialize, Clone)]
+struct GithubCommitSha {
+    sha: String,
+}
+
+/// Used to deserialize responses from `https://api.github.com/repos/org/repo_name/commits/sha`.
+/// Only the required fields by `tag-track` are included.
+#[derive(Debug, Deserialize, Clone)]
+struct GithubCommitFiles {
+    files: Vec<GithubCommitFile>,
+}
+
+/// Used to deserialize responses from `https://api.github.com/repos/org/repo_name/commits/sha`.
+/// Only the required fields by `tag-track` are included.
+#[derive(Debug, Deserialize, Clone)]
+struct GithubCommitFile {
+    filename: String,
 }
 
 impl GithubCommitDetails {
@@ -261,373 +786,1738 @@ impl GithubCommitDetails {
     ///
     /// * `commit_pattern` - Pattern used to extract the commit details.
     ///
+    /// * `scope_case` - Case applied to the parsed scope.
+    ///
+    /// * `max_message_length` - Maximum length in bytes of the commit message.
+    ///
     /// # Errors
     ///
     /// Returns `error::Error` with a kind of `error::ErrorKind::CommitPatternError` if the commit pattern is invalid.
     ///
-    fn convert_to_git_commit(self, commit_pattern: &str) -> Result<Commit, Error> {
-        let commit_details = parse_commit_details(&self.commit.message, commit_pattern)?;
-
-        Ok(Commit {
-            sha: self.sha,
-            message: self.commit.message,
-            details: commit_details,
-        })
+    fn convert_to_git_commit(
+        self,
+        commit_pattern: &str,
+        scope_case: ScopeCase,
+        max_message_length: usize,
+    ) -> Result<Commit, Error> {
+        Commit::new(
+            self.sha,
+            &self.commit.message,
+            self.commit
+                .author
+                .map(|author| format!("{} <{}>", author.name, author.email)),
+            commit_pattern,
+            scope_case,
+            max_message_length,
+        )
     }
 }
 
 /// Type used to iterate over GitHub references on the repository history.
-/// This type implements the `Iterator` trait and performs paginated requests to the GitHub REST API.
+/// This type implements the `Iterator` trait and performs paginated requests to the GitHub REST API. The next pages
+/// are requested concurrently in the background while the iterator is consumed, up to the last page referenced by
+/// the `Link` header of the responses.
 pub struct RefIterator<'a> {
     /// List of commits obtained from the GitHub REST API. Commits are obtained on batches of 100 elements.
     commits: Vec<GithubCommitDetails>,
-    /// List of version scopes that have not been found yet in the commits.
-    version_scopes: Vec<String>,
-    /// Current GitHub REST API page number.
+    /// Version scopes whose closest tag has not been found yet.
+    scope_tracker: ScopeTracker,
+    /// Next GitHub REST API page number that will be requested.
     page: u64,
+    /// Number of the next page referenced by the `Link` header of the last consumed page, `None` if the last consumed
+    /// page was the last page.
+    next_page: Option<u64>,
+    /// Number of the last page referenced by the `Link` header, if known.
+    last_page: Option<u64>,
     /// Elements per page used for paginated requests.
     per_page: u64,
+    /// Number of pages requested concurrently.
+    concurrent_pages: u64,
+    /// Requests of the next pages, in page order.
+    pending_pages: VecDeque<JoinHandle<Result<Page<Vec<GithubCommitDetails>>, Error>>>,
     /// If the iterator has finished iterating over the commits.
     is_finished: bool,
     /// Current element index in the `commits` vector.
     current_elem: u64,
     /// Max element index in the `commits` vector.
     max_elem: u64,
+    /// SHA of the next commit of the first-parent chain, if the first-parent traversal is used. `None` until the
+    /// starting commit is returned.
+    first_parent_sha: Option<String>,
+    /// Listed commits that were not reached yet by the first-parent traversal, by commit SHA. Commits of merged
+    /// branches are never reached.
+    listed_commits: HashMap<String, GithubCommitDetails>,
+    /// If each tagged commit that was checked is an ancestor of the starting commit, by commit SHA.
+    ancestor_shas: HashMap<String, bool>,
+    /// Committer date window of the requested commits.
+    window: CommitWindow,
 
     /// Commit SHA from where the iteration will start.
     sha: &'a str,
     /// List of tags obtained from the GitHub REST API.
     tags: Vec<GithubTag>,
-    /// GitHub repository identifier `org/repo-name`, example `dloez/tag-track`.
-    repo_id: &'a String,
-    /// GitHub REST API base URL.
-    api_url: &'a String,
-    /// GitHub REST API authentication token to authorize requests.
-    github_token: &'a Option<String>,
+    /// GitHub REST API client of the repository.
+    api: &'a GithubApi,
+    /// Runtime used to send the asynchronous requests.
+    runtime: &'a Runtime,
     /// Tag Track configuration.
     config: &'a Config,
 }
 
 impl<'a> RefIterator<'a> {
-    /// Returns a new instance of a `CommitIterator`.
+    /// Returns a new instance of a `CommitIterator` that returns the commits and tags of the version scopes of the
+    /// given scope tracker.
+    #[allow(clippy::too_many_arguments)]
     fn new(
         sha: &'a str,
         tags: Vec<GithubTag>,
-        repo_id: &'a String,
-        api_url: &'a String,
-        github_token: &'a Option<String>,
+        scope_tracker: ScopeTracker,
+        api: &'a GithubApi,
+        runtime: &'a Runtime,
+        concurrent_pages: u64,
+        window: &CommitWindow,
         config: &'a Config,
     ) -> Self {
         RefIterator {
             commits: vec![],
-            version_scopes: config.version_scopes.clone(),
+            scope_tracker,
             page: 1,
+            next_page: Some(1),
+            last_page: None,
             per_page: DEFAULT_PER_PAGE,
+            concurrent_pages,
+            pending_pages: VecDeque::new(),
             is_finished: false,
             current_elem: 0,
             max_elem: 0,
+            first_parent_sha: None,
+            listed_commits: HashMap::new(),
+            ancestor_shas: HashMap::new(),
+            window: window.clone(),
 
             sha,
             tags,
-            repo_id,
-            api_url,
-            github_token,
+            api,
+            runtime,
             config,
         }
     }
-}
 
-impl<'a> Iterator for RefIterator<'a> {
-    type Item = Result<Reference, Error>;
+    /// Spawns the requests of the next pages until `concurrent_pages` pages are being requested. Only pages up to the
+    /// last page are requested, or up to the next page if the last page is not known.
+    fn request_pages(&mut self) {
+        let limit = match self.last_page.or(self.next_page) {
+            Some(limit) => limit,
+            None => return,
+        };
 
-    /// Returns the next commit and its associated tags until the required commits to calculate the version bump have
-    /// been returned. If using scoped versioning, commits with scopes which tag has been already returned will be skipped.
+        while (self.pending_pages.len() as u64) < self.concurrent_pages && self.page <= limit {
+            let api = self.api.clone();
+            let sha = self.sha.to_owned();
+            let window = self.window.clone();
+            let (page, per_page) = (self.page, self.per_page);
+            self.pending_pages.push_back(self.runtime.spawn(async move {
+                get_commits_from_commit_sha(&api, &sha, &window, &page, &per_page).await
+            }));
+            self.page += 1;
+        }
+    }
+
+    /// Returns the commits of the next page, or `None` if the last page was already consumed. Once the page is
+    /// received, the requests of the following pages are spawned so they are received in the background. If the page
+    /// contains the tags of all the version scopes that have not been found yet, no more pages are requested and the
+    /// pending requests are cancelled, as the iteration finishes within the page. With the first-parent traversal,
+    /// the tags of the page may reference commits of merged branches, so the next pages are always requested.
     ///
-    /// If a tag is associated with multiple commits, the tag with the biggest version will be returned. This is also true
-    /// if scoped versioning is used and there are multiple tags with the same scope in the same commit.
+    /// # Errors
     ///
-    /// If there is a commit that does not conform the given commit pattern, it will be returned with `None` in the details
-    /// field. If there is a tag that does not conform the given tag pattern, it will be skipped.
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+    /// from the GitHub REST API or if the request task failed.
     ///
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.is_finished {
-            return None;
+    fn next_page(&mut self) -> Result<Option<Vec<GithubCommitDetails>>, Error> {
+        if self.next_page.is_none() {
+            return Ok(None);
         }
+        self.request_pages();
 
-        if self.current_elem == self.max_elem {
-            self.commits = match get_commits_from_commit_sha(
-                self.repo_id,
-                self.api_url,
-                self.sha,
-                self.github_token,
-                &self.page,
-                &self.per_page,
-            ) {
-                Ok(commits) => commits,
-                Err(error) => {
-                    self.is_finished = true;
-                    return Some(Err(error));
-                }
-            };
-            self.max_elem = self.commits.len() as u64;
-            self.current_elem = 0;
-            self.page += 1;
+        let request = match self.pending_pages.pop_front() {
+            Some(request) => request,
+            None => return Ok(None),
+        };
+        let page = match self.runtime.block_on(request) {
+            Ok(page) => page?,
+            Err(error) => {
+                return Err(Error::new(
+                    ErrorKind::GithubRestError,
+                    Some(&error.to_string()),
+                ))
+            }
         };
 
-        let commit = self.commits.get(self.current_elem as usize);
-        self.current_elem += 1;
-        if commit.is_none() {
-            self.is_finished = true;
-            return None;
+        self.next_page = page.links.next;
+        if page.links.last.is_some() {
+            self.last_page = page.links.last;
         }
+        if self.config.traversal == Traversal::All && self.contains_scope_tags(&page.data)? {
+            self.next_page = None;
+            self.cancel_pages();
+        } else {
+            self.request_pages();
+        }
+        Ok(Some(page.data))
+    }
 
-        let commit: Commit = match commit
-            .unwrap()
-            .clone()
-            .convert_to_git_commit(&self.config.commit_pattern)
-        {
-            Ok(commit) => commit,
-            Err(error) => return Some(Err(error)),
-        };
-        let tags = match find_tags_from_commit_sha(
-            &commit.sha,
-            &self.tags,
-            &self.config.tag_pattern,
-            &self.version_scopes,
-        ) {
-            Ok(tags) => tags,
-            Err(error) => return Some(Err(error)),
-        };
-
-        if tags.is_some() {
-            for tag in tags.as_ref().unwrap() {
-                let tag_details = match &tag.details {
-                    Some(details) => details,
-                    None => continue,
-                };
-                self.version_scopes
-                    .retain(|scope| scope != tag_details.scope.as_ref().unwrap_or(&String::new()));
+    /// Returns `true` if the given commits are referenced by tags of all the version scopes that have not been found
+    /// yet. Only the tags of commits that are ancestors of the starting commit are considered, as checked by
+    /// `is_ancestor`.
+    ///
+    /// # Arguments
+    ///
+    /// * `commits` - Commits of a page.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::InvalidRegexPattern` if the tag pattern is invalid.
+    ///
+    /// Returns the errors of `is_ancestor`.
+    ///
+    fn contains_scope_tags(&mut self, commits: &[GithubCommitDetails]) -> Result<bool, Error> {
+        let shas: HashSet<&str> = commits.iter().map(|commit| commit.sha.as_str()).collect();
+        let mut version_scopes = self.scope_tracker.version_scopes().to_vec();
+        let mut scope_tags = vec![];
+        for tag in &self.tags {
+            if !shas.contains(tag.commit.sha.as_str()) {
+                continue;
             }
+            let tag = tag
+                .clone()
+                .convert_to_git_tag(&self.config.tag_pattern, self.config.scope_rules.case)?;
+            if let Some(details) = tag.details {
+                scope_tags.push((details.scope.unwrap_or_default(), tag.commit_sha));
+            }
+        }
+
+        for (scope, sha) in scope_tags {
+            if version_scopes.contains(&scope) && self.is_ancestor(&sha)? {
+                version_scopes.retain(|version_scope| *version_scope != scope);
+            }
+        }
+        Ok(version_scopes.is_empty())
+    }
+
+    /// Returns `true` if the given commit is the starting commit or one of its ancestors, checked with the compare
+    /// endpoint of the GitHub REST API. The tags endpoint lists the tags of every branch, so the commits of the found
+    /// tags are checked before the tags are matched, and a tag of a diverged branch is never used as a closest tag.
+    /// The result of each commit is kept, so each tagged commit is only compared once.
+    ///
+    /// # Arguments
+    ///
+    /// * `sha` - SHA of the tagged commit.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+    /// from the GitHub REST API.
+    ///
+    fn is_ancestor(&mut self, sha: &str) -> Result<bool, Error> {
+        if let Some(is_ancestor) = self.ancestor_shas.get(sha) {
+            return Ok(*is_ancestor);
+        }
+        let is_ancestor = self
+            .runtime
+            .block_on(get_ancestor_date(self.api, sha, self.sha))?
+            .is_some();
+        self.ancestor_shas.insert(sha.to_owned(), is_ancestor);
+        Ok(is_ancestor)
+    }
+
+    /// Returns the reference with the closest tags of the version scopes that have not been found, if the commits are
+    /// requested since a date and the tags of these scopes reference commits committed before it. The closest tag of
+    /// each scope is the tag with the greatest version that references an ancestor of the starting commit. Returns
+    /// `None` if there is no window start or if no closest tag is found.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+    /// from the GitHub REST API.
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::InvalidRegexPattern` if the tag pattern is invalid.
+    ///
+    fn get_window_reference(&self) -> Result<Option<Reference>, Error> {
+        if self.window.since.is_none() {
+            return Ok(None);
+        }
+
+        let mut tags = vec![];
+        for scope in self.scope_tracker.version_scopes() {
+            let closest_tag = self.runtime.block_on(get_closest_ancestor_tag(
+                self.api,
+                self.sha,
+                scope,
+                &self.tags,
+                self.config,
+            ))?;
+            if let Some((tag, _)) = closest_tag {
+                tags.push(tag);
+            }
+        }
+        match tags.is_empty() {
+            true => Ok(None),
+            false => Ok(Some(Reference {
+                commit: None,
+                tags: Some(tags),
+                ignored_commit: None,
+            })),
+        }
+    }
+
+    /// Returns the next commit of the listing of the starting commit, requesting the next page once the current one
+    /// has been consumed. Returns `None` if the whole listing has been iterated.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `next_page`.
+    ///
+    fn next_listed_commit(&mut self) -> Result<Option<GithubCommitDetails>, Error> {
+        if self.current_elem == self.max_elem {
+            self.commits = match self.next_page()? {
+                Some(commits) => commits,
+                None => return Ok(None),
+            };
+            self.max_elem = self.commits.len() as u64;
+            self.current_elem = 0;
+        }
+
+        let commit = self.commits.get(self.current_elem as usize).cloned();
+        self.current_elem += 1;
+        Ok(commit)
+    }
+
+    /// Returns the next commit of the first-parent chain, starting from the first listed commit and following the
+    /// first parent of each returned commit by its SHA, so the chain does not depend on the order of the listing. The
+    /// listed commits are kept until they are reached, and the first parents that are not listed are requested
+    /// directly. Returns `None` once the first parent is not listed and the commits are requested since a date, as it
+    /// was committed before the window.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `next_page`.
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+    /// from the GitHub REST API while requesting a first parent.
+    ///
+    fn next_first_parent_commit(&mut self) -> Result<Option<GithubCommitDetails>, Error> {
+        let sha = match self.first_parent_sha.take() {
+            Some(sha) => sha,
+            None => return self.next_listed_commit(),
+        };
 
-            if self.version_scopes.is_empty() {
+        loop {
+            if let Some(commit) = self.listed_commits.remove(&sha) {
+                return Ok(Some(commit));
+            }
+            match self.next_listed_commit()? {
+                Some(commit) => self.listed_commits.insert(commit.sha.clone(), commit),
+                None => break,
+            };
+        }
+        if self.window.since.is_some() {
+            return Ok(None);
+        }
+        self.runtime.block_on(get_commit(self.api, &sha)).map(Some)
+    }
+
+    /// Cancels the requests of the pages that were not consumed.
+    fn cancel_pages(&mut self) {
+        for request in self.pending_pages.drain(..) {
+            request.abort();
+        }
+    }
+
+    /// Finishes the iteration with the given error, so no more references are returned after it.
+    ///
+    /// # Arguments
+    ///
+    /// * `error` - Error returned by the iterator.
+    ///
+    fn fail(&mut self, error: Error) -> Option<Result<Reference, Error>> {
+        self.is_finished = true;
+        self.cancel_pages();
+        Some(Err(error))
+    }
+}
+
+impl Drop for RefIterator<'_> {
+    /// Cancels the requests of the pages that were not consumed.
+    fn drop(&mut self) {
+        self.cancel_pages();
+    }
+}
+
+impl<'a> Iterator for RefIterator<'a> {
+    type Item = Result<Reference, Error>;
+
+    /// Returns the next commit and its associated tags until the required commits to calculate the version bump have
+    /// been returned. If using scoped versioning, commits with scopes which tag has been already returned will be skipped.
+    ///
+    /// If a tag is associated with multiple commits, the tag with the biggest version will be returned. This is also true
+    /// if scoped versioning is used and there are multiple tags with the same scope in the same commit.
+    ///
+    /// If there is a commit that does not conform the given commit pattern, it will be returned with `None` in the details
+    /// field. If there is a tag that does not conform the given tag pattern, it will be skipped.
+    ///
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.is_finished {
+                return None;
+            }
+            if let Some(error) = self.scope_tracker.cancellation_error() {
+                return self.fail(error);
+            }
+
+            let commit = match self.config.traversal {
+                Traversal::FirstParent => self.next_first_parent_commit(),
+                Traversal::All => self.next_listed_commit(),
+            };
+            let commit = match commit {
+                Ok(Some(commit)) => commit,
+                Ok(None) => {
+                    self.is_finished = true;
+                    self.cancel_pages();
+                    return self.get_window_reference().transpose();
+                }
+                Err(error) => return self.fail(error),
+            };
+            if self.config.traversal == Traversal::FirstParent {
+                match commit.parents.first() {
+                    Some(parent) => self.first_parent_sha = Some(parent.sha.clone()),
+                    None => {
+                        self.is_finished = true;
+                        self.cancel_pages();
+                    }
+                }
+            }
+
+            let mut commit: Commit = match commit.convert_to_git_commit(
+                &self.config.commit_pattern,
+                self.config.scope_rules.case,
+                self.config.max_commit_message_length,
+            ) {
+                Ok(commit) => commit,
+                Err(error) => return self.fail(error),
+            };
+            if let Err(error) = self.config.resolve_scope_commit(&mut commit) {
+                return self.fail(error);
+            }
+            let ignored_commit = match self.config.find_ignored_commit(&commit) {
+                Ok(ignored_commit) => ignored_commit,
+                Err(error) => return self.fail(error),
+            };
+            if self.config.dependency_updates.is_some() {
+                commit.changed_paths = match self
+                    .runtime
+                    .block_on(get_changed_paths(self.api, &commit.sha))
+                {
+                    Ok(changed_paths) => Some(changed_paths),
+                    Err(error) => return self.fail(error),
+                };
+            }
+            let commit_tags = match convert_commit_tags(
+                &commit.sha,
+                &self.tags,
+                &self.config.tag_pattern,
+                self.config.scope_rules.case,
+            ) {
+                Ok(commit_tags) => commit_tags,
+                Err(error) => return self.fail(error),
+            };
+
+            let tags = match self.scope_tracker.find_tags(&commit.sha, &commit_tags) {
+                Some(tags) => match self.is_ancestor(&commit.sha) {
+                    Ok(true) => Some(tags),
+                    Ok(false) => {
+                        logging::warn(&format!(
+                            "the tags of commit '{}' are ignored, the commit is not an ancestor of commit '{}'",
+                            commit.sha, self.sha
+                        ));
+                        None
+                    }
+                    Err(error) => return self.fail(error),
+                },
+                None => None,
+            };
+            let reference = self
+                .scope_tracker
+                .match_reference(commit, tags, ignored_commit);
+            if self.scope_tracker.is_finished() {
                 self.is_finished = true;
+                self.cancel_pages();
+            }
+            if let Some(reference) = reference {
+                return Some(Ok(reference));
+            }
+        }
+    }
+}
+
+/// Returns an HTTP client built with the given options. The client is shared by all requests of the source, so
+/// connections are pooled and reused across pages. Requests are sent with the `User-Agent` header and, if a token is
+/// given, with the `Authorization` header.
+///
+/// If a proxy URL is given, it replaces the proxy environment variables for its scheme, otherwise the `HTTP_PROXY`,
+/// `HTTPS_PROXY` and `ALL_PROXY` environment variables are used if `proxy.use_env` is `true`. Hosts listed in the
+/// `NO_PROXY` environment variable are always reached directly if `proxy.use_env` is `true`. The certificates of
+/// `ca_cert` are trusted in addition to the system ones.
+///
+/// # Arguments
+///
+/// * `options` - Options of the HTTP client.
+///
+/// * `token` - GitHub REST API authentication token to authorize requests.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if a proxy URL is not valid, if the CA certificates
+/// file can not be read, if the token is not a valid header value or if the client can not be built.
+///
+fn build_client(
+    options: &GithubClientOptions,
+    token: Option<&str>,
+) -> Result<reqwest::Client, Error> {
+    let mut builder = reqwest::Client::builder().user_agent(USER_AGENT);
+    if let Some(token) = token {
+        let mut value = match reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token)) {
+            Ok(value) => value,
+            Err(_) => {
+                return Err(Error::new(
+                    ErrorKind::Other,
+                    Some("the GitHub token is not a valid header value"),
+                ))
             }
+        };
+        value.set_sensitive(true);
+
+        let mut headers = reqwest::header::HeaderMap::new();
+        headers.insert(reqwest::header::AUTHORIZATION, value);
+        builder = builder.default_headers(headers);
+    }
+    #[cfg(feature = "rustls")]
+    {
+        builder = builder.use_rustls_tls();
+    }
+    if let Some(path) = &options.ca_cert {
+        for certificate in read_ca_certificates(path)? {
+            builder = builder.add_root_certificate(certificate);
         }
+    }
+    if options.insecure {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    if let Some(timeout) = options.timeout {
+        builder = builder.timeout(timeout);
+    }
+
+    let proxy = &options.proxy;
+    if !proxy.use_env {
+        builder = builder.no_proxy();
+    }
 
-        let commit_details = match &commit.details {
-            Some(details) => details,
+    let no_proxy = match proxy.use_env {
+        true => reqwest::NoProxy::from_env(),
+        false => None,
+    };
+    if let Some(url) = &proxy.http_proxy {
+        let http_proxy = parse_proxy(reqwest::Proxy::http(url.as_str()), url)?;
+        builder = builder.proxy(http_proxy.no_proxy(no_proxy.clone()));
+    }
+    if let Some(url) = &proxy.https_proxy {
+        let https_proxy = parse_proxy(reqwest::Proxy::https(url.as_str()), url)?;
+        builder = builder.proxy(https_proxy.no_proxy(no_proxy));
+    }
+
+    match builder.build() {
+        Ok(client) => Ok(client),
+        Err(error) => Err(Error::new(ErrorKind::Other, Some(&error.to_string()))),
+    }
+}
+
+/// Reads the PEM encoded certificates of the given file. The file can contain a bundle of several certificates.
+///
+/// # Arguments
+///
+/// * `path` - Path of the PEM file.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the file can not be read, if it does not contain
+/// any certificate or if a certificate is not valid.
+///
+fn read_ca_certificates(path: &Path) -> Result<Vec<reqwest::Certificate>, Error> {
+    let contents = fs::read_to_string(path)?;
+    let mut certificates = vec![];
+    for block in contents.split_inclusive(PEM_CERTIFICATE_END) {
+        if !block.contains(PEM_CERTIFICATE_END) {
+            continue;
+        }
+
+        match reqwest::Certificate::from_pem(block.trim().as_bytes()) {
+            Ok(certificate) => certificates.push(certificate),
+            Err(error) => {
+                return Err(Error::new(
+                    ErrorKind::Other,
+                    Some(&format!(
+                        "invalid CA certificate in '{}': {}",
+                        path.display(),
+                        error
+                    )),
+                ))
+            }
+        }
+    }
+
+    if certificates.is_empty() {
+        return Err(Error::new(
+            ErrorKind::Other,
+            Some(&format!("no CA certificates found in '{}'", path.display())),
+        ));
+    }
+    Ok(certificates)
+}
+
+/// Converts the result of parsing a proxy URL into a `Result` with an `error::Error`.
+///
+/// # Arguments
+///
+/// * `result` - Result of parsing the proxy URL.
+///
+/// * `url` - Proxy URL that was parsed.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the proxy URL is not valid.
+///
+fn parse_proxy(
+    result: reqwest::Result<reqwest::Proxy>,
+    url: &str,
+) -> Result<reqwest::Proxy, Error> {
+    match result {
+        Ok(proxy) => Ok(proxy),
+        Err(error) => Err(Error::new(
+            ErrorKind::Other,
+            Some(&format!("invalid proxy URL '{}': {}", url, error)),
+        )),
+    }
+}
+
+/// Sends the given request. If the GitHub REST API responds with a rate-limit error, the request is retried up to
+/// `RATE_LIMIT_MAX_RETRIES` times, waiting the time given by the `Retry-After` or `X-RateLimit-Reset` headers or,
+/// if they are missing, with exponential backoff.
+///
+/// `GET` requests that time out, fail to connect or get a `5xx` response are retried up to the number of retries of
+/// the client with exponential backoff. Other requests are not idempotent, so they are never retried on these
+/// failures. Every attempt is reported to the request observer of the client, if any.
+///
+/// # Arguments
+///
+/// * `api` - GitHub REST API client of the repository.
+///
+/// * `request` - Request to send.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::RateLimited` if the request is still rate-limited after
+/// all retries or if the rate limit resets after `RATE_LIMIT_MAX_WAIT`.
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if the request can not be sent.
+///
+async fn send_request(
+    api: &GithubApi,
+    request: reqwest::RequestBuilder,
+) -> Result<reqwest::Response, Error> {
+    let (method, url) = match request.try_clone().map(|request| request.build()) {
+        Some(Ok(built)) => (built.method().clone(), built.url().to_string()),
+        _ => (reqwest::Method::GET, String::new()),
+    };
+    let retries = match method {
+        reqwest::Method::GET => api.retries,
+        _ => 0,
+    };
+
+    let mut rate_limit_backoff = RATE_LIMIT_INITIAL_BACKOFF;
+    let mut rate_limit_retries = 0;
+    let mut retry_backoff = RETRY_INITIAL_BACKOFF;
+    let mut failures = 0;
+    loop {
+        let attempt = match request.try_clone() {
+            Some(attempt) => attempt,
             None => {
-                return Some(Ok(Reference {
-                    commit: Some(commit),
-                    tags,
-                }))
+                return Err(Error::new(
+                    ErrorKind::GithubRestError,
+                    Some("the request can not be retried"),
+                ))
             }
         };
 
-        if self
-            .version_scopes
-            .contains(commit_details.scope.as_ref().unwrap_or(&String::new()))
-        {
-            return Some(Ok(Reference {
-                commit: Some(commit),
-                tags,
-            }));
+        let started_at = Instant::now();
+        let result = attempt.send().await;
+        if let Some(observer) = &api.observer {
+            observer.on_request(&RequestRecord::new(
+                &method,
+                &url,
+                result.as_ref().ok(),
+                started_at.elapsed(),
+            ));
+        }
+
+        let response = match result {
+            Err(error) if (error.is_timeout() || error.is_connect()) && failures < retries => {
+                tokio::time::sleep(retry_backoff).await;
+                retry_backoff *= 2;
+                failures += 1;
+                continue;
+            }
+            Err(error) => {
+                return Err(Error::new(
+                    ErrorKind::GithubRestError,
+                    Some(&error.to_string()),
+                ))
+            }
+            Ok(res) => res,
+        };
+
+        if response.status().is_server_error() && failures < retries {
+            tokio::time::sleep(retry_backoff).await;
+            retry_backoff *= 2;
+            failures += 1;
+            continue;
+        }
+
+        let wait = match get_rate_limit_wait(&response) {
+            Some(wait) => wait.unwrap_or(rate_limit_backoff),
+            None => return Ok(response),
+        };
+
+        if rate_limit_retries >= RATE_LIMIT_MAX_RETRIES || wait > RATE_LIMIT_MAX_WAIT {
+            let reset = SystemTime::now() + wait;
+            let reset = reset
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            return Err(Error::new(
+                ErrorKind::RateLimited,
+                Some(&format!(
+                    "the rate limit resets at {} (unix time, in {} seconds)",
+                    reset,
+                    wait.as_secs()
+                )),
+            ));
         }
 
-        if tags.is_none() {
-            return self.next();
-        }
+        tokio::time::sleep(wait).await;
+        rate_limit_backoff *= 2;
+        rate_limit_retries += 1;
+    }
+}
+
+/// Returns `None` if the given response is not a rate-limit error. Otherwise, returns the time to wait before
+/// retrying the request obtained from the `Retry-After` or `X-RateLimit-Reset` headers, or `Some(None)` if none of
+/// them are present.
+///
+/// GitHub responds with a `429` status code or with a `403` status code with the `X-RateLimit-Remaining` header set
+/// to `0` or the `Retry-After` header present when the requests are rate-limited.
+///
+/// # Arguments
+///
+/// * `response` - Response to check.
+///
+fn get_rate_limit_wait(response: &reqwest::Response) -> Option<Option<Duration>> {
+    let header = |name: &str| -> Option<u64> {
+        response
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+    };
+
+    let retry_after = header("retry-after");
+    let is_rate_limited = match response.status().as_u16() {
+        429 => true,
+        403 => retry_after.is_some() || header("x-ratelimit-remaining") == Some(0),
+        _ => false,
+    };
+    if !is_rate_limited {
+        return None;
+    }
+
+    if let Some(retry_after) = retry_after {
+        return Some(Some(Duration::from_secs(retry_after)));
+    }
+
+    let reset = match header("x-ratelimit-reset") {
+        Some(reset) => reset,
+        None => return Some(None),
+    };
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    Some(Some(Duration::from_secs(reset.saturating_sub(now))))
+}
+
+/// Type that represents a cached response of the GitHub REST API.
+#[derive(Debug, Deserialize, Serialize)]
+struct CacheEntry {
+    /// URL of the request.
+    url: String,
+    /// Value of the `ETag` header of the response.
+    etag: String,
+    /// Body of the response.
+    body: String,
+    /// Value of the `Link` header of the response, if any.
+    link: Option<String>,
+    /// Version of the format of the entry.
+    version: u32,
+}
+
+/// Type that represents a page of a paginated response of the GitHub REST API.
+struct Page<T> {
+    /// Deserialized body of the response.
+    data: T,
+    /// Pagination links of the response.
+    links: PageLinks,
+}
+
+/// Type that holds the page numbers referenced by the `Link` header of a paginated response.
+#[derive(Debug, Default, PartialEq)]
+struct PageLinks {
+    /// Number of the next page, `None` if the response is the last page.
+    next: Option<u64>,
+    /// Number of the last page, if known.
+    last: Option<u64>,
+}
+
+/// Sends the given `GET` request and deserializes the JSON body of the response. If the HTTP cache is enabled, the
+/// request is sent with the `If-None-Match` header set to the ETag of the cached response of the same URL, and the
+/// cached body is used if the GitHub REST API responds with a `304` status code. Successful responses with an `ETag`
+/// header are stored in the cache.
+///
+/// # Arguments
+///
+/// * `api` - GitHub REST API client of the repository, used to read the HTTP cache directory and the number of
+///   retries of the request.
+///
+/// * `request` - Request to send.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+/// from the GitHub REST API.
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the cache can not be written.
+///
+/// Returns the errors of `send_request`.
+///
+async fn get_json<T: DeserializeOwned>(
+    api: &GithubApi,
+    request: reqwest::RequestBuilder,
+) -> Result<T, Error> {
+    Ok(get_json_page(api, request).await?.data)
+}
+
+/// Sends the given `GET` request like `get_json`, returning the deserialized JSON body of the response together with
+/// the pagination links of its `Link` header.
+///
+/// # Arguments
+///
+/// * `api` - GitHub REST API client of the repository, used to read the HTTP cache directory and the number of
+///   retries of the request.
+///
+/// * `request` - Request to send.
+///
+/// # Errors
+///
+/// Returns the errors of `get_json`.
+///
+async fn get_json_page<T: DeserializeOwned>(
+    api: &GithubApi,
+    request: reqwest::RequestBuilder,
+) -> Result<Page<T>, Error> {
+    let url = match request.try_clone().map(|request| request.build()) {
+        Some(Ok(built)) => built.url().to_string(),
+        _ => {
+            return Err(Error::new(
+                ErrorKind::GithubRestError,
+                Some("the request can not be built"),
+            ))
+        }
+    };
+
+    let cache = api
+        .cache_dir
+        .as_ref()
+        .map(|dir| (dir, get_cache_file_path(dir, &url)));
+    let entry = cache
+        .as_ref()
+        .and_then(|(_, path)| read_cache_entry(path, &url));
+
+    let mut request = request;
+    if let Some(entry) = &entry {
+        request = request.header(reqwest::header::IF_NONE_MATCH, &entry.etag);
+    }
+
+    let response = send_request(api, request).await?;
+    let (body, link) = match (response.status().as_u16(), entry) {
+        (304, Some(entry)) => (entry.body, entry.link),
+        (status, _) if (200..300).contains(&status) => {
+            let header = |name: reqwest::header::HeaderName| -> Option<String> {
+                response
+                    .headers()
+                    .get(name)
+                    .and_then(|value| value.to_str().ok())
+                    .map(|value| value.to_owned())
+            };
+            let etag = header(reqwest::header::ETAG);
+            let link = header(reqwest::header::LINK);
+            let body = match response.text().await {
+                Ok(body) => body,
+                Err(error) => {
+                    return Err(Error::new(
+                        ErrorKind::GithubRestError,
+                        Some(&error.to_string()),
+                    ))
+                }
+            };
+
+            if let (Some((dir, path)), Some(etag)) = (&cache, etag) {
+                let entry = CacheEntry {
+                    url,
+                    etag,
+                    body: body.clone(),
+                    link: link.clone(),
+                    version: CACHE_FORMAT_VERSION,
+                };
+                fs::create_dir_all(dir)?;
+                fs::write(path, serde_json::to_string(&entry)?)?;
+            }
+            (body, link)
+        }
+        _ => {
+            let message = match response.text().await {
+                Ok(body) => body,
+                Err(error) => error.to_string(),
+            };
+            return Err(Error::new(ErrorKind::GithubRestError, Some(&message)));
+        }
+    };
+
+    match serde_json::from_str(&body) {
+        Ok(data) => Ok(Page {
+            data,
+            links: link
+                .map(|link| parse_link_header(&link))
+                .unwrap_or_default(),
+        }),
+        Err(error) => Err(Error::new(
+            ErrorKind::GithubRestError,
+            Some(&error.to_string()),
+        )),
+    }
+}
+
+/// Parses the `next` and `last` page numbers of the given `Link` header value, with the format
+/// `<https://api.github.com/...?page=2>; rel="next", <https://api.github.com/...?page=5>; rel="last"`.
+/// Links without a valid `page` query parameter are ignored.
+///
+/// # Arguments
+///
+/// * `value` - Value of the `Link` header.
+///
+fn parse_link_header(value: &str) -> PageLinks {
+    let mut links = PageLinks::default();
+    for link in value.split(',') {
+        let mut parts = link.split(';').map(|part| part.trim());
+        let page = parts
+            .next()
+            .and_then(|url| url.strip_prefix('<')?.strip_suffix('>'))
+            .and_then(|url| reqwest::Url::parse(url).ok())
+            .and_then(|url| {
+                url.query_pairs()
+                    .find(|(key, _)| key == "page")
+                    .and_then(|(_, page)| page.parse::<u64>().ok())
+            });
+        let rel = parts.find_map(|param| param.strip_prefix("rel="));
+
+        match rel.map(|rel| rel.trim_matches('"')) {
+            Some("next") => links.next = page,
+            Some("last") => links.last = page,
+            _ => {}
+        }
+    }
+    links
+}
+
+/// Obtains all the elements of a paginated resource. The first page is requested to read its `Link` header, and the
+/// following pages are requested concurrently in batches of up to `concurrent_pages` pages while the `Link` header
+/// references a next page. If the last page is not known, pages are requested one by one.
+///
+/// # Arguments
+///
+/// * `get_page` - Function that requests the page with the given number.
+///
+/// * `concurrent_pages` - Number of pages requested concurrently.
+///
+/// # Errors
+///
+/// Returns the errors of `get_page`.
+///
+async fn get_all_pages<T, F, Fut>(get_page: F, concurrent_pages: u64) -> Result<Vec<T>, Error>
+where
+    F: Fn(u64) -> Fut,
+    Fut: Future<Output = Result<Page<Vec<T>>, Error>>,
+{
+    let first = get_page(1).await?;
+    let mut elements = first.data;
+    let mut links = first.links;
+
+    while let Some(next) = links.next {
+        let last = links.last.unwrap_or(next).max(next);
+        let batch_end = last.min(next + concurrent_pages - 1);
+        for page in join_all((next..=batch_end).map(&get_page)).await {
+            let page = page?;
+            elements.extend(page.data);
+            links = page.links;
+        }
+    }
+    Ok(elements)
+}
+
+/// Returns the path of the cache file of the given URL, named after the hex encoded SHA-256 hash of the URL.
+///
+/// # Arguments
+///
+/// * `cache_dir` - Directory of the HTTP cache.
+///
+/// * `url` - URL of the request.
+///
+fn get_cache_file_path(cache_dir: &Path, url: &str) -> PathBuf {
+    cache_dir
+        .join(encode_hex(&Sha256::digest(url)))
+        .with_extension(CACHE_FILE_EXTENSION)
+}
+
+/// Returns the cached response stored in the given path, or `None` if the file does not exist, can not be parsed,
+/// belongs to a different URL or was written with a different cache format version.
+///
+/// # Arguments
+///
+/// * `path` - Path of the cache file.
+///
+/// * `url` - URL of the request.
+///
+fn read_cache_entry(path: &Path, url: &str) -> Option<CacheEntry> {
+    let contents = fs::read_to_string(path).ok()?;
+    let entry: CacheEntry = serde_json::from_str(&contents).ok()?;
+    match entry.url == url && entry.version == CACHE_FORMAT_VERSION {
+        true => Some(entry),
+        false => None,
+    }
+}
+
+/// Obtains tag references whose names start with the given prefix from the given repository.
+///
+/// # Arguments
+///
+/// * `api` - GitHub REST API client of the repository.
+///
+/// * `prefix` - Prefix of the tag names. If it is empty, all tag references are returned.
+///
+/// * `page` - GitHub REST API requests page number. This number must not exceed `u64` limits.
+///
+/// * `per_page` - GitHub REST API elements per request page. Limit is `100`.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+/// from the GitHub REST API.
+///
+async fn get_tag_refs(
+    api: &GithubApi,
+    prefix: &str,
+    page: &u64,
+    per_page: &u64,
+) -> Result<Page<Vec<GithubRef>>, Error> {
+    let uri = match prefix.is_empty() {
+        true => GITHUB_GIT_TAG_REFS_URI.to_owned(),
+        false => format!("{}/{}", GITHUB_GIT_TAG_REFS_URI, prefix),
+    };
+    let request = api.request(
+        reqwest::Method::GET,
+        &format!("{}?page={}&per_page={}", uri, page, per_page),
+    );
+
+    get_json_page(api, request).await
+}
+
+/// Returns the SHA of the commit the given git object points to. Annotated tag objects are peeled, following nested
+/// tag objects, until a commit is found.
+///
+/// # Arguments
+///
+/// * `api` - GitHub REST API client of the repository.
+///
+/// * `object` - Git object referenced by a tag reference.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+/// from the GitHub REST API.
+///
+async fn peel_tag_object(api: &GithubApi, object: GithubGitObject) -> Result<String, Error> {
+    let mut object = object;
+    while object.kind == "tag" {
+        let request = api.request(
+            reqwest::Method::GET,
+            &format!("{}/{}", GITHUB_GIT_TAGS_URI, object.sha),
+        );
+        let tag: GithubGitTag = get_json(api, request).await?;
+        object = tag.object;
+    }
+    Ok(object.sha)
+}
+
+/// Obtains the tags matching the given tag pattern from the given repository with the SHA of the commit they point
+/// to. Only the tag references starting with the literal prefix of the tag pattern are requested, with
+/// `get_all_pages`, and tags that do not match the tag pattern are discarded before their annotated tag objects are
+/// peeled concurrently in batches of `concurrent_pages` requests.
+///
+/// # Arguments
+///
+/// * `api` - GitHub REST API client of the repository.
+///
+/// * `tag_pattern` - Pattern used to parse the tags.
+///
+/// * `concurrent_pages` - Number of pages requested concurrently.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+/// from the GitHub REST API.
+///
+async fn get_all_tags(
+    api: &GithubApi,
+    tag_pattern: &str,
+    concurrent_pages: u64,
+) -> Result<Option<Vec<GithubTag>>, Error> {
+    let prefix = get_literal_prefix(tag_pattern);
+    let prefix = prefix.as_str();
+    let refs = get_all_pages(
+        |page| async move { get_tag_refs(api, prefix, &page, &DEFAULT_PER_PAGE).await },
+        concurrent_pages,
+    )
+    .await?;
+
+    let mut matching_refs = Vec::with_capacity(refs.len());
+    for reference in refs {
+        let name = reference
+            .reference
+            .strip_prefix("refs/tags/")
+            .unwrap_or(&reference.reference);
+        // Only the match is checked, so the case of the scope does not matter.
+        if parse_tag_details(name, tag_pattern, ScopeCase::Preserve)?.is_some() {
+            matching_refs.push(reference);
+        }
+    }
+    let refs = matching_refs;
+    if refs.is_empty() {
+        return Ok(None);
+    }
+
+    let mut tags: Vec<GithubTag> = Vec::with_capacity(refs.len());
+    for batch in refs.chunks(concurrent_pages as usize) {
+        let shas = join_all(
+            batch
+                .iter()
+                .map(|reference| peel_tag_object(api, reference.object.clone())),
+        )
+        .await;
+        for (reference, sha) in batch.iter().zip(shas) {
+            let name = reference
+                .reference
+                .strip_prefix("refs/tags/")
+                .unwrap_or(&reference.reference);
+            tags.push(GithubTag {
+                name: name.to_owned(),
+                commit: GithubTagCommit { sha: sha? },
+            });
+        }
+    }
+
+    Ok(Some(tags))
+}
+
+/// Obtains branches from the given repository.
+///
+/// # Arguments
+///
+/// * `api` - GitHub REST API client of the repository.
+///
+/// * `page` - GitHub REST API requests page number. This number must not exceed `u64` limits.
+///
+/// * `per_page` - GitHub REST API elements per request page. Limit is `100`.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+/// from the GitHub REST API.
+///
+async fn get_branches(
+    api: &GithubApi,
+    page: &u64,
+    per_page: &u64,
+) -> Result<Page<Vec<GithubBranch>>, Error> {
+    let request = api.request(
+        reqwest::Method::GET,
+        &format!(
+            "{}?page={}&per_page={}",
+            GITHUB_BRANCHES_URI, page, per_page
+        ),
+    );
+
+    get_json_page(api, request).await
+}
+
+/// Obtains all branches from the given repository. Pages are requested with `get_all_pages`.
+///
+/// # Arguments
+///
+/// * `api` - GitHub REST API client of the repository.
+///
+/// * `concurrent_pages` - Number of pages requested concurrently.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+/// from the GitHub REST API.
+///
+async fn get_all_branches(api: &GithubApi, concurrent_pages: u64) -> Result<Vec<Branch>, Error> {
+    let branches = get_all_pages(
+        |page| async move { get_branches(api, &page, &DEFAULT_PER_PAGE).await },
+        concurrent_pages,
+    )
+    .await?;
+
+    Ok(branches
+        .into_iter()
+        .map(|branch| Branch {
+            name: branch.name,
+            commit_sha: branch.commit.sha,
+        })
+        .collect())
+}
+
+/// Obtains commits from the given `sha` using the GitHub REST API.
+///
+/// # Arguments
+///
+/// * `api` - GitHub REST API client of the repository.
+///
+/// * `sha` - SHA from where the commits will be requested.
+///
+/// * `window` - Committer date window of the requested commits.
+///
+/// * `page` - GitHub REST API requests page number. This number must not exceed `u64` limits.
+///
+/// * `per_page` - GitHub REST API elements per request page. Limit is `100`.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+/// from the GitHub REST API.
+///
+async fn get_commits_from_commit_sha(
+    api: &GithubApi,
+    sha: &str,
+    window: &CommitWindow,
+    page: &u64,
+    per_page: &u64,
+) -> Result<Page<Vec<GithubCommitDetails>>, Error> {
+    let mut request = api
+        .request(reqwest::Method::GET, GITHUB_COMMITS_URI)
+        .query(&[("sha", sha)]);
+    if let Some(since) = &window.since {
+        request = request.query(&[("since", since)]);
+    }
+    if let Some(until) = &window.until {
+        request = request.query(&[("until", until)]);
+    }
+    let request = request.query(&[("page", page), ("per_page", per_page)]);
+
+    get_json_page(api, request).await
+}
+
+/// Obtains the commits that changed the given path from the given `sha` using the GitHub REST API.
+///
+/// # Arguments
+///
+/// * `api` - GitHub REST API client of the repository.
+///
+/// * `sha` - SHA from where the commits will be requested.
+///
+/// * `path` - Path changed by the commits.
+///
+/// * `since` - ISO 8601 timestamp. If given, only commits committed at or after it are requested.
+///
+/// * `until` - ISO 8601 timestamp. If given, only commits committed at or before it are requested.
+///
+/// * `page` - GitHub REST API requests page number. This number must not exceed `u64` limits.
+///
+/// * `per_page` - GitHub REST API elements per request page. Limit is `100`.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+/// from the GitHub REST API.
+///
+async fn get_commits_by_path(
+    api: &GithubApi,
+    sha: &str,
+    path: &str,
+    since: Option<&str>,
+    until: Option<&str>,
+    page: &u64,
+    per_page: &u64,
+) -> Result<Page<Vec<GithubCommitDetails>>, Error> {
+    let mut request = api
+        .request(reqwest::Method::GET, GITHUB_COMMITS_URI)
+        .query(&[("sha", sha), ("path", path)]);
+    if let Some(since) = since {
+        request = request.query(&[("since", since)]);
+    }
+    if let Some(until) = until {
+        request = request.query(&[("until", until)]);
+    }
+    let request = request.query(&[("page", page), ("per_page", per_page)]);
+
+    get_json_page(api, request).await
+}
 
-        Some(Ok(Reference { commit: None, tags }))
+/// Compares the given commits. Returns `None` if the `base` commit is not an ancestor of, or equal to, the `head`
+/// commit. Otherwise, returns the committer date of the `base` commit, or `Some(None)` if it is not known.
+///
+/// # Arguments
+///
+/// * `api` - GitHub REST API client of the repository.
+///
+/// * `base` - SHA of the base commit.
+///
+/// * `head` - SHA of the head commit.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+/// from the GitHub REST API.
+///
+async fn get_ancestor_date(
+    api: &GithubApi,
+    base: &str,
+    head: &str,
+) -> Result<Option<Option<String>>, Error> {
+    let request = api.request(
+        reqwest::Method::GET,
+        &format!("{}/{}...{}?per_page=1", GITHUB_COMPARE_URI, base, head),
+    );
+
+    let comparison: GithubComparison = get_json(api, request).await?;
+    match comparison.status.as_str() {
+        "ahead" | "identical" => Ok(Some(
+            comparison
+                .base_commit
+                .commit
+                .committer
+                .map(|committer| committer.date),
+        )),
+        _ => Ok(None),
     }
 }
 
-/// Obtains tags from the given repository. If `token` is given, the requests will be authorized.
-/// The requests performed by this function are not yet paginated.
+/// Returns the closest tag of a version scope, which is the tag with the greatest version that references an ancestor
+/// of the given `sha`, with the committer date of its commit if known. Returns `None` if no tag of the scope
+/// references an ancestor of `sha`.
 ///
 /// # Arguments
 ///
-/// * `repo_id` - GitHub repository identifier that will be used to query commits.
+/// * `api` - GitHub REST API client of the repository.
 ///
-/// * `api_url` - GitHub REST API base URL.
+/// * `sha` - SHA of the commit whose closest tag is searched.
 ///
-/// * `token` - GitHub REST API authentication token. If it is `None`, requests will not be authenticated, if it has
-/// a value, requests will be authenticated.
+/// * `scope` - Version scope.
 ///
-/// * `page` - GitHub REST API requests page number. This number must not exceed `u64` limits.
+/// * `tags` - Tags of the repository.
 ///
-/// * `per_page` - GitHub REST API elements per request page. Limit is `100`.
+/// * `config` - Tag Track configuration.
 ///
 /// # Errors
 ///
 /// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
 /// from the GitHub REST API.
 ///
-fn get_tags(
-    repo_id: &String,
-    api_url: &String,
-    token: &Option<String>,
-    page: &u64,
-    per_page: &u64,
-) -> Result<Vec<GithubTag>, Error> {
-    let client = reqwest::blocking::Client::new();
-    let mut client = client
-        .get(format!(
-            "{}/repos/{}{}?page={}&per_page={}",
-            api_url, repo_id, GITHUB_TAGS_URI, page, per_page
-        ))
-        .header(reqwest::header::USER_AGENT, USER_AGENT);
+/// Returns `error::Error` with a kind of `error::ErrorKind::TagPatternError` if the tag pattern is invalid.
+///
+async fn get_closest_ancestor_tag(
+    api: &GithubApi,
+    sha: &str,
+    scope: &str,
+    tags: &[GithubTag],
+    config: &Config,
+) -> Result<Option<(Tag, Option<String>)>, Error> {
+    let mut scope_tags = vec![];
+    for tag in tags {
+        let tag = tag
+            .clone()
+            .convert_to_git_tag(&config.tag_pattern, config.scope_rules.case)?;
+        let is_scope_tag = tag
+            .details
+            .as_ref()
+            .is_some_and(|details| details.scope.as_deref().unwrap_or_default() == scope);
+        if is_scope_tag {
+            scope_tags.push(tag);
+        }
+    }
+    scope_tags.sort_by(|a, b| {
+        let a = &a.details.as_ref().unwrap().version;
+        let b = &b.details.as_ref().unwrap().version;
+        b.cmp(a)
+    });
+
+    for tag in scope_tags {
+        if let Some(date) = get_ancestor_date(api, &tag.commit_sha, sha).await? {
+            return Ok(Some((tag, date)));
+        }
+    }
+    Ok(None)
+}
 
-    if let Some(token) = token {
-        client = client.header(AUTH_HEADER, format!("Bearer {}", token));
+/// Returns whether the given committer date is later than the start of the run, logging a warning if it is. Dates
+/// that can not be parsed are not considered in the future.
+///
+/// # Arguments
+///
+/// * `date` - Committer date, in the `YYYY-MM-DDTHH:MM:SSZ` format.
+///
+/// * `scope` - Version scope of the tag whose commit has the date, used in the warning.
+///
+fn is_future_date(date: &str, scope: &str) -> bool {
+    let is_future = run::parse_timestamp(date).is_some_and(|time| time > run::current().started_at);
+    if is_future {
+        logging::warn(&format!(
+            "the commit of the closest tag of the scope '{}' is dated in the future ({}), its date is ignored",
+            scope, date
+        ));
     }
+    is_future
+}
 
-    let response = match client.send() {
-        Err(error) => {
-            return Err(Error::new(
-                ErrorKind::GithubRestError,
-                Some(&error.to_string()),
-            ))
+/// Returns the references of a version scope whose commits are selected by the path they change. The closest tag of
+/// the scope is the tag with the greatest version that references an ancestor of the given `sha`, and the commits
+/// that changed the path since the committer date of its commit, or since the start of the window if it is later,
+/// are returned with the given scope, regardless of the scope of their message. The committer date of the closest tag
+/// is ignored if it is later than the start of the run, as it comes from a skewed clock or rewritten history and
+/// would filter out every commit. The closest tag is returned after the commits. Commits matching the ignore rules of
+/// the configuration are returned as ignored commits.
+///
+/// # Arguments
+///
+/// * `api` - GitHub REST API client of the repository.
+///
+/// * `sha` - SHA from where the commits will be requested.
+///
+/// * `scope` - Version scope.
+///
+/// * `path` - Directory of the version scope.
+///
+/// * `tags` - Tags of the repository.
+///
+/// * `window` - Committer date window of the requested commits.
+///
+/// * `concurrent_pages` - Number of pages requested concurrently.
+///
+/// * `config` - Tag Track configuration.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+/// from the GitHub REST API.
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::TagPatternError` if the tag pattern is invalid.
+///
+/// Returns the errors of `Config::find_ignored_commit`.
+///
+#[allow(clippy::too_many_arguments)]
+async fn get_path_references(
+    api: &GithubApi,
+    sha: &str,
+    scope: &str,
+    path: &str,
+    tags: &[GithubTag],
+    window: &CommitWindow,
+    concurrent_pages: u64,
+    config: &Config,
+) -> Result<Vec<Reference>, Error> {
+    let closest_tag = get_closest_ancestor_tag(api, sha, scope, tags, config).await?;
+
+    let tag_date = closest_tag
+        .as_ref()
+        .and_then(|(_, date)| date.as_deref())
+        .filter(|date| !is_future_date(date, scope));
+    let since = tag_date.into_iter().chain(window.since.as_deref()).max();
+    let until = window.until.as_deref();
+    let commits = get_all_pages(
+        |page| async move {
+            get_commits_by_path(api, sha, path, since, until, &page, &DEFAULT_PER_PAGE).await
+        },
+        concurrent_pages,
+    )
+    .await?;
+
+    let mut references = vec![];
+    for commit in commits {
+        if closest_tag
+            .as_ref()
+            .is_some_and(|(tag, _)| tag.commit_sha == commit.sha)
+        {
+            continue;
         }
-        Ok(res) => res,
-    };
 
-    let tags: Vec<GithubTag> = match response.status().is_success() {
-        false => {
-            return Err(Error::new(
-                ErrorKind::GithubRestError,
-                Some(&response.text().unwrap()),
-            ))
+        let mut commit = commit.convert_to_git_commit(
+            config.scope_commit_pattern(scope),
+            config.scope_rules.case,
+            config.max_commit_message_length,
+        )?;
+        if let Some(details) = &mut commit.details {
+            details.scope = match scope.is_empty() {
+                true => None,
+                false => Some(scope.to_owned()),
+            };
         }
-        true => match response.json() {
-            Ok(tags) => tags,
-            Err(error) => {
-                return Err(Error::new(
-                    ErrorKind::GithubRestError,
-                    Some(&error.to_string()),
-                ))
-            }
-        },
-    };
+        if let Some(ignored_commit) = config.find_ignored_commit(&commit)? {
+            references.push(Reference {
+                commit: None,
+                tags: None,
+                ignored_commit: Some(ignored_commit),
+            });
+            continue;
+        }
+        if config.dependency_updates.is_some() {
+            commit.changed_paths = Some(get_changed_paths(api, &commit.sha).await?);
+        }
+        references.push(Reference {
+            commit: Some(commit),
+            tags: None,
+            ignored_commit: None,
+        });
+    }
 
-    Ok(tags)
+    if let Some((tag, _)) = closest_tag {
+        references.push(Reference {
+            commit: None,
+            tags: Some(vec![tag]),
+            ignored_commit: None,
+        });
+    }
+    Ok(references)
 }
 
-/// Obtains all tags from the given repository. If `token` is given, the requests will be authorized.
+/// Creates the reference of the given tag and returns the number of retries. On some GitHub Enterprise Server
+/// instances, the reference creation responds with `404` right after the tag object of an annotated tag is created,
+/// until the object is visible. These creations are retried with exponential backoff. As a previous attempt may
+/// have created the reference even if it failed, the reference is read before each retry and after a `422`
+/// response, and the creation succeeds if the reference already points to the given object.
 ///
 /// # Arguments
 ///
-/// * `repo_id` - GitHub repository identifier that will be used to query commits.
+/// * `api` - GitHub REST API client of the repository.
 ///
-/// * `api_url` - GitHub REST API base URL.
+/// * `tag_name` - Name of the tag.
 ///
-/// * `token` - GitHub REST API authentication token. If it is `None`, requests will not be authenticated, if it has
-/// a value, requests will be authenticated.
+/// * `sha` - SHA of the object referenced by the tag.
 ///
-fn get_all_tags(
-    repo_id: &String,
-    api_url: &String,
-    token: &Option<String>,
-) -> Result<Option<Vec<GithubTag>>, Error> {
-    let mut page: u64 = 1;
-    let mut tags: Vec<GithubTag> = vec![];
-
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+/// from the GitHub REST API, if the creation still responds with `404` after `TAG_REF_MAX_RETRIES` retries or if the
+/// reference already exists and points to a different object.
+///
+async fn create_tag_ref(api: &GithubApi, tag_name: &str, sha: &str) -> Result<u32, Error> {
+    let data = serde_json::json!({
+        "ref": format!("refs/tags/{}", tag_name),
+        "sha": sha,
+    });
+
+    let mut backoff = TAG_REF_INITIAL_BACKOFF;
+    let mut retries = 0;
     loop {
-        let t = get_tags(repo_id, api_url, token, &page, &DEFAULT_PER_PAGE)?;
-        if t.is_empty() {
-            break;
+        let request = api
+            .request(reqwest::Method::POST, GITHUB_GIT_REFS_URI)
+            .json(&data);
+        let response = send_request(api, request).await?;
+        let status = response.status().as_u16();
+        if status == 201 {
+            return Ok(retries);
         }
 
-        tags.reserve(t.len());
-        tags.extend(t);
-        page += 1;
-    }
+        if status == 404 || status == 422 {
+            match get_tag_ref_sha(api, tag_name).await? {
+                Some(ref_sha) if ref_sha == sha => return Ok(retries),
+                Some(ref_sha) => {
+                    return Err(Error::new(
+                        ErrorKind::GithubRestError,
+                        Some(&format!(
+                            "the tag '{}' already exists and references '{}'",
+                            tag_name, ref_sha
+                        )),
+                    ))
+                }
+                None => {}
+            }
+        }
+        if status != 404 || retries >= TAG_REF_MAX_RETRIES {
+            return Err(unexpected_write_response(
+                status,
+                response.text().await.unwrap_or_default(),
+            ));
+        }
 
-    if tags.is_empty() {
-        return Ok(None);
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+        retries += 1;
     }
-
-    Ok(Some(tags))
 }
 
-/// Obtains commits from the given `sha` using the GitHub REST API. If `token` is given, the requests will be authorized.
-/// Requests to GitHub REST API are paginated.
+/// Returns the error of an unexpected response to a request that creates a tag or a release. Responses with a `401`
+/// or `403` status code are not authorized, so their error includes the minimal permissions of the token.
 ///
 /// # Arguments
 ///
-/// * `repo_id` - GitHub repository identifier that will be used to query commits.
+/// * `status` - Status code of the response.
 ///
-/// * `api_url` - GitHub REST API base URL.
+/// * `body` - Body of the response.
 ///
-/// * `sha` - SHA from where the commits will be requested.
+fn unexpected_write_response(status: u16, body: String) -> Error {
+    match status {
+        401 | 403 => Error::new(
+            ErrorKind::AuthenticationRequired,
+            Some(&format!("{} - {}", body, TOKEN_PERMISSIONS_HINT)),
+        ),
+        _ => Error::new(ErrorKind::GithubRestError, Some(&body)),
+    }
+}
+
+/// Obtains the SHA of the object referenced by the given tag, or `None` if the tag does not exist. The response is
+/// never cached, as the reference may have been created since the previous request.
 ///
-/// * `token` - GitHub REST API authentication token. If it is `None`, requests will not be authenticated, if it has
-/// a value, requests will be authenticated.
+/// # Arguments
 ///
-/// * `page` - GitHub REST API requests page number. This number must not exceed `u64` limits.
+/// * `api` - GitHub REST API client of the repository.
 ///
-/// * `per_page` - GitHub REST API elements per request page. Limit is `100`.
+/// * `tag_name` - Name of the tag.
 ///
 /// # Errors
 ///
 /// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
 /// from the GitHub REST API.
 ///
-fn get_commits_from_commit_sha(
-    repo_id: &String,
-    api_url: &String,
-    sha: &str,
-    token: &Option<String>,
-    page: &u64,
-    per_page: &u64,
-) -> Result<Vec<GithubCommitDetails>, Error> {
-    let client = reqwest::blocking::Client::new();
-    let mut client = client
-        .get(format!(
-            "{}/repos/{}{}?sha={}&page={}&per_page={}",
-            api_url, repo_id, GITHUB_COMMITS_URI, sha, page, per_page
-        ))
-        .header(reqwest::header::USER_AGENT, USER_AGENT);
-
-    if let Some(token) = token {
-        client = client.header(AUTH_HEADER, format!("Bearer {}", token));
-    }
-
-    let response = match client.send() {
-        Err(error) => {
-            return Err(Error::new(
+async fn get_tag_ref_sha(api: &GithubApi, tag_name: &str) -> Result<Option<String>, Error> {
+    let request = api.request(
+        reqwest::Method::GET,
+        &format!("{}/tags/{}", GITHUB_GIT_REF_URI, tag_name),
+    );
+    let response = send_request(api, request).await?;
+    match response.status().as_u16() {
+        404 => Ok(None),
+        200 => match response.json::<GithubRef>().await {
+            Ok(reference) => Ok(Some(reference.object.sha)),
+            Err(error) => Err(Error::new(
                 ErrorKind::GithubRestError,
                 Some(&error.to_string()),
-            ))
-        }
-        Ok(res) => res,
-    };
-
-    let commits: Vec<GithubCommitDetails> = match response.status().is_success() {
-        false => {
-            return Err(Error::new(
-                ErrorKind::GithubRestError,
-                Some(&response.text().unwrap()),
-            ))
-        }
-        true => match response.json() {
-            Ok(commits) => commits,
-            Err(error) => {
-                return Err(Error::new(
-                    ErrorKind::GithubRestError,
-                    Some(&error.to_string()),
-                ))
-            }
+            )),
         },
-    };
+        _ => Err(Error::new(
+            ErrorKind::GithubRestError,
+            Some(&response.text().await.unwrap_or_default()),
+        )),
+    }
+}
+
+/// Obtains the SHA of the commit referenced by the given reference, which can be a commit SHA, a branch or a tag.
+///
+/// # Arguments
+///
+/// * `api` - GitHub REST API client of the repository.
+///
+/// * `reference` - Reference that will be resolved.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+/// from the GitHub REST API.
+///
+async fn get_commit_sha(api: &GithubApi, reference: &str) -> Result<String, Error> {
+    let request = api.request(
+        reqwest::Method::GET,
+        &format!("{}/{}", GITHUB_COMMITS_URI, reference),
+    );
+
+    let commit: GithubCommitSha = get_json(api, request).await?;
+    Ok(commit.sha)
+}
+
+/// Obtains the given commit with its parents.
+///
+/// # Arguments
+///
+/// * `api` - GitHub REST API client of the repository.
+///
+/// * `sha` - SHA of the commit.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+/// from the GitHub REST API.
+///
+async fn get_commit(api: &GithubApi, sha: &str) -> Result<GithubCommitDetails, Error> {
+    let request = api.request(
+        reqwest::Method::GET,
+        &format!("{}/{}", GITHUB_COMMITS_URI, sha),
+    );
+
+    get_json(api, request).await
+}
 
-    Ok(commits)
+/// Obtains the paths changed by the given commit.
+///
+/// # Arguments
+///
+/// * `api` - GitHub REST API client of the repository.
+///
+/// * `sha` - SHA of the commit.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::GitHubRestError` if there was an unexpected response
+/// from the GitHub REST API.
+///
+async fn get_changed_paths(api: &GithubApi, sha: &str) -> Result<Vec<String>, Error> {
+    let request = api.request(
+        reqwest::Method::GET,
+        &format!("{}/{}", GITHUB_COMMITS_URI, sha),
+    );
+
+    let commit: GithubCommitFiles = get_json(api, request).await?;
+    Ok(commit.files.into_iter().map(|file| file.filename).collect())
 }
 
-/// From a given list of `GitHub` tag, find the list of tags referencing a commit SHA equal to the given `sha` argument.
-/// If a tag with the given SHA cannot be found, `None` will be returned. If there are multiple tags referencing the same
-/// commit SHA, the tag with the highest version will be returned. This is also true if scoped versioning is used and there
-/// are multiple tags with the same scope in the same commit.
+/// Converts the tags referencing the given commit SHA to git tags, extracting their details with the given tag
+/// pattern.
 ///
 /// # Arguments
 ///
@@ -637,61 +2527,674 @@ fn get_commits_from_commit_sha(
 ///
 /// * `tag_pattern` - Pattern used to extract the tag details.
 ///
+/// * `scope_case` - Case applied to the parsed scopes.
+///
 /// # Errors
 ///
-/// Returns `error::Error` with a kind of `error::ErrorKind::TagPatternError` if the tag pattern is invalid.
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidRegexPattern` if the tag pattern is invalid.
 ///
-fn find_tags_from_commit_sha(
+fn convert_commit_tags(
     sha: &str,
     tags: &[GithubTag],
     tag_pattern: &str,
-    valid_scopes: &[String],
-) -> Result<Option<Vec<Tag>>, Error> {
-    let mut found_tags: Vec<Tag> = vec![];
-    for tag in tags {
-        if tag.commit.sha != sha {
-            continue;
+    scope_case: ScopeCase,
+) -> Result<Vec<Tag>, Error> {
+    tags.iter()
+        .filter(|tag| tag.commit.sha == sha)
+        .map(|tag| tag.clone().convert_to_git_tag(tag_pattern, scope_case))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bump::{calculate, BumpResult};
+    use semver::Version;
+    use serde_json::json;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Mutex;
+    use std::thread;
+
+    const REPO_ID: &str = "owner/repo";
+    /// Stack size of the threads that walk long runs of skipped commits, which would overflow it if the commits were
+    /// skipped recursively.
+    const SMALL_STACK_SIZE: usize = 256 * 1024;
+
+    const TAG_PATTERN: &str = r"^((?<scope>[a-z]+)/)?v(?<version>\d+\.\d+\.\d+)$";
+    /// SHA of the tag objects created by `MockApi`.
+    const TAG_OBJECT_SHA: &str = "7a60000000000000000000000000000000000001";
+
+    /// Commit of the history served by `MockApi`.
+    struct MockCommit {
+        sha: String,
+        message: String,
+        /// SHAs of the parents, first parent first.
+        parents: Vec<String>,
+    }
+
+    impl MockCommit {
+        /// Returns the JSON representation of the commit returned by the REST API.
+        fn to_json(&self) -> serde_json::Value {
+            json!({
+                "sha": self.sha,
+                "commit": {"message": self.message, "author": {"name": "A", "email": "a@example.com"}},
+                "parents": self.parents.iter().map(|sha| json!({"sha": sha})).collect::<Vec<_>>(),
+                "files": [],
+            })
         }
+    }
 
-        let tag = tag.clone().convert_to_git_tag(tag_pattern)?;
-        let tag_details = match &tag.details {
-            Some(details) => details,
-            None => continue,
-        };
+    /// Repository served by `MockApi`.
+    #[derive(Default)]
+    struct MockRepo {
+        /// Commits listed from the head commit, newest first.
+        commits: Vec<MockCommit>,
+        /// Tag names with the SHA of their commit.
+        tags: Vec<(String, String)>,
+        /// SHAs of the commits that are not ancestors of the head commit.
+        diverged_shas: Vec<String>,
+        /// SHAs of the commits that are left out of the commit listings, only served by their SHA.
+        unlisted_shas: Vec<String>,
+        /// Number of the first requests answered with a `403` rate-limit error.
+        rate_limited_responses: usize,
+        /// Headers of the rate-limit errors, such as `Retry-After: 0`.
+        rate_limit_headers: Vec<&'static str>,
+    }
 
-        if !valid_scopes.contains(tag_details.scope.as_ref().unwrap_or(&String::new())) {
-            continue;
+    impl MockRepo {
+        /// Adds a commit older than the current commits, tagged with the given tag names.
+        fn push(&mut self, message: &str, tags: &[&str]) -> String {
+            let sha = format!("{:040x}", self.commits.len() + 1);
+            for tag in tags {
+                self.tags.push((tag.to_string(), sha.clone()));
+            }
+            self.commits.push(MockCommit {
+                sha: sha.clone(),
+                message: message.to_owned(),
+                parents: vec![],
+            });
+            sha
         }
 
-        if found_tags.is_empty() {
-            found_tags.push(tag);
-            continue;
+        /// Sets the parents of the commit with the given SHA.
+        fn set_parents(&mut self, sha: &str, parents: &[&str]) {
+            let commit = self
+                .commits
+                .iter_mut()
+                .find(|commit| commit.sha == sha)
+                .unwrap();
+            commit.parents = parents.iter().map(|parent| parent.to_string()).collect();
         }
 
-        let mut found = false;
-        for found_tag in &mut found_tags {
-            let found_tag_details = match &found_tag.details {
-                Some(details) => details,
-                None => continue,
+        /// Returns the status code, `Link` header and body of the response to the given request.
+        fn respond(
+            &self,
+            method: &str,
+            target: &str,
+            base_url: &str,
+        ) -> (u16, Option<String>, String) {
+            let (path, query) = target.split_once('?').unwrap_or((target, ""));
+            let param = |name: &str| -> Option<u64> {
+                query
+                    .split('&')
+                    .find_map(|pair| pair.strip_prefix(&format!("{}=", name)))
+                    .and_then(|value| value.parse().ok())
             };
-            if found_tag_details.scope.as_ref().unwrap_or(&String::new())
-                == tag_details.scope.as_ref().unwrap_or(&String::new())
-                && tag_details.version > found_tag_details.version
-            {
-                *found_tag = tag.clone();
-                found = true;
-                break;
+            let path = path
+                .strip_prefix(&format!("/repos/{}", REPO_ID))
+                .unwrap_or(path);
+
+            if method == "POST" {
+                return match path {
+                    GITHUB_GIT_TAGS_URI => (201, None, json!({"sha": TAG_OBJECT_SHA}).to_string()),
+                    GITHUB_GIT_REFS_URI => (201, None, json!({}).to_string()),
+                    _ => (404, None, json!({"message": "Not Found"}).to_string()),
+                };
+            }
+
+            if path.starts_with(GITHUB_GIT_TAG_REFS_URI) {
+                let refs: Vec<_> = self
+                    .tags
+                    .iter()
+                    .map(|(name, sha)| {
+                        json!({"ref": format!("refs/tags/{}", name), "object": {"sha": sha, "type": "commit"}})
+                    })
+                    .collect();
+                return (200, None, json!(refs).to_string());
+            }
+
+            if path == GITHUB_COMMITS_URI {
+                let page = param("page").unwrap_or(1) as usize;
+                let per_page = param("per_page").unwrap_or(DEFAULT_PER_PAGE) as usize;
+                let listed: Vec<_> = self
+                    .commits
+                    .iter()
+                    .filter(|commit| !self.unlisted_shas.contains(&commit.sha))
+                    .collect();
+                let pages = listed.len().div_ceil(per_page).max(1);
+                let commits: Vec<_> = listed
+                    .iter()
+                    .skip((page - 1) * per_page)
+                    .take(per_page)
+                    .map(|commit| commit.to_json())
+                    .collect();
+                let link = |page: usize, rel: &str| {
+                    format!(
+                        "<{}/repos/{}/commits?page={}&per_page={}>; rel=\"{}\"",
+                        base_url, REPO_ID, page, per_page, rel
+                    )
+                };
+                let link = match page < pages {
+                    true => Some(format!(
+                        "{}, {}",
+                        link(page + 1, "next"),
+                        link(pages, "last")
+                    )),
+                    false => None,
+                };
+                return (200, link, json!(commits).to_string());
             }
+
+            if let Some(sha) = path.strip_prefix(&format!("{}/", GITHUB_COMMITS_URI)) {
+                if let Some(commit) = self.commits.iter().find(|commit| commit.sha == sha) {
+                    return (200, None, commit.to_json().to_string());
+                }
+            }
+
+            if let Some(range) = path.strip_prefix(&format!("{}/", GITHUB_COMPARE_URI)) {
+                let (base, _) = range.split_once("...").unwrap();
+                let status = match self.diverged_shas.iter().any(|sha| sha == base) {
+                    true => "diverged",
+                    false => "ahead",
+                };
+                let body = json!({
+                    "status": status,
+                    "base_commit": {"commit": {"committer": {"date": "2024-01-01T00:00:00Z"}}},
+                });
+                return (200, None, body.to_string());
+            }
+
+            (404, None, json!({"message": "Not Found"}).to_string())
+        }
+    }
+
+    /// Mock of the GitHub REST API serving a `MockRepo` on a local port, recording the targets of the requests and
+    /// the bodies of the `POST` requests.
+    struct MockApi {
+        url: String,
+        requests: Arc<Mutex<Vec<String>>>,
+        posted: Arc<Mutex<Vec<(String, serde_json::Value)>>>,
+    }
+
+    impl MockApi {
+        fn serve(mut repo: MockRepo) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let requests = Arc::new(Mutex::new(vec![]));
+            let posted = Arc::new(Mutex::new(vec![]));
+
+            let base_url = url.clone();
+            let recorded_requests = requests.clone();
+            let recorded_posts = posted.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let mut stream = match stream {
+                        Ok(stream) => stream,
+                        Err(_) => continue,
+                    };
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut request_line = String::new();
+                    reader.read_line(&mut request_line).unwrap();
+                    let mut header = String::new();
+                    let mut content_length = 0;
+                    while reader.read_line(&mut header).is_ok_and(|read| read > 2) {
+                        if let Some((name, value)) = header.split_once(':') {
+                            if name.eq_ignore_ascii_case("content-length") {
+                                content_length = value.trim().parse().unwrap_or_default();
+                            }
+                        }
+                        header.clear();
+                    }
+                    let mut request_body = vec![0; content_length];
+                    reader.read_exact(&mut request_body).unwrap();
+
+                    let mut request_line = request_line.split_whitespace();
+                    let method = request_line.next().unwrap_or_default();
+                    let target = request_line.next().unwrap_or_default();
+                    recorded_requests.lock().unwrap().push(target.to_owned());
+                    if method == "POST" {
+                        recorded_posts.lock().unwrap().push((
+                            target.to_owned(),
+                            serde_json::from_slice(&request_body).unwrap(),
+                        ));
+                    }
+                    let (status, headers, body) = match repo.rate_limited_responses {
+                        0 => {
+                            let (status, link, body) = repo.respond(method, target, &base_url);
+                            let link = link.map(|link| format!("Link: {}\r\n", link));
+                            (status, link.unwrap_or_default(), body)
+                        }
+                        _ => {
+                            repo.rate_limited_responses -= 1;
+                            let headers: String = repo
+                                .rate_limit_headers
+                                .iter()
+                                .map(|header| format!("{}\r\n", header))
+                                .collect();
+                            let body = json!({"message": "API rate limit exceeded"});
+                            (403, headers, body.to_string())
+                        }
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n{}\
+                         Connection: close\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        headers,
+                        body
+                    );
+                    let _ = stream.write_all(response.as_bytes());
+                }
+            });
+            Self {
+                url,
+                requests,
+                posted,
+            }
+        }
+
+        /// Returns the recorded request targets starting with the given path of the repository.
+        fn requests(&self, path: &str) -> Vec<String> {
+            let prefix = format!("/repos/{}{}", REPO_ID, path);
+            self.requests
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|target| target.starts_with(&prefix))
+                .cloned()
+                .collect()
         }
 
-        if !found {
-            found_tags.push(tag);
+        /// Returns the bodies of the recorded `POST` requests to the given path of the repository.
+        fn posted(&self, path: &str) -> Vec<serde_json::Value> {
+            let target = format!("/repos/{}{}", REPO_ID, path);
+            self.posted
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(posted_target, _)| *posted_target == target)
+                .map(|(_, body)| body.clone())
+                .collect()
         }
     }
 
-    if found_tags.is_empty() {
-        return Ok(None);
+    fn config(version_scopes: &[&str]) -> Config {
+        let mut config = Config::new();
+        config.tag_pattern = TAG_PATTERN.to_owned();
+        config.version_scopes = version_scopes
+            .iter()
+            .map(|scope| scope.to_string())
+            .collect();
+        config
+    }
+
+    fn calculate_bump(api: &MockApi, config: &Config, sha: &str) -> BumpResult {
+        let source = GithubSource::new(
+            config,
+            REPO_ID.to_owned(),
+            api.url.clone(),
+            None,
+            false,
+            GithubClientOptions::default(),
+        )
+        .unwrap();
+        calculate(config, &source, sha).unwrap()
+    }
+
+    /// Returns the references returned by the GitHub source from the given commit of the repository served by `api`.
+    fn references(api: &MockApi, config: &Config, sha: &str) -> Vec<Reference> {
+        let source = GithubSource::new(
+            config,
+            REPO_ID.to_owned(),
+            api.url.clone(),
+            None,
+            false,
+            GithubClientOptions::default(),
+        )
+        .unwrap();
+        let references = source
+            .get_ref_iterator(sha, None, &CancellationToken::new())
+            .unwrap();
+        references.collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn deep_history_pages_are_not_requested_after_the_closest_tag() {
+        let mut repo = MockRepo::default();
+        let head = repo.push("fix: handle empty responses", &[]);
+        for i in 0..148 {
+            repo.push(&format!("feat: add feature {}", i), &[]);
+        }
+        repo.push("feat: initial release", &["v1.0.0"]);
+        for i in 0..850 {
+            repo.push(&format!("feat: add old feature {}", i), &[]);
+        }
+        repo.push("feat: first commit", &["v0.1.0"]);
+        let api = MockApi::serve(repo);
+
+        let result = calculate_bump(&api, &config(&[""]), &head);
+        let version_bump = &result.version_bumps[0];
+        assert_eq!(version_bump.closest_tag.as_ref().unwrap().name, "v1.0.0");
+        assert_eq!(version_bump.new_version, Some(Version::new(1, 1, 0)));
+        assert_eq!(version_bump.commits.len(), 149);
+
+        let pages: Vec<String> = api
+            .requests(GITHUB_COMMITS_URI)
+            .iter()
+            .map(|target| {
+                target
+                    .split('&')
+                    .find(|param| param.starts_with("page="))
+                    .unwrap()
+                    .to_owned()
+            })
+            .collect();
+        assert_eq!(pages, vec!["page=1", "page=2"]);
+    }
+
+    #[test]
+    fn long_runs_of_skipped_commits_are_walked() {
+        let mut repo = MockRepo::default();
+        let head = repo.push("fix: handle empty responses", &[]);
+        repo.push("feat(api): add the api", &["api/v0.1.0"]);
+        for i in 0..2000 {
+            repo.push(&format!("feat(api): change {}", i), &[]);
+        }
+        repo.push("feat: first commit", &["v0.1.0"]);
+        let api = MockApi::serve(repo);
+
+        let config = config(&["", "api"]);
+        let references = thread::scope(|scope| {
+            thread::Builder::new()
+                .stack_size(SMALL_STACK_SIZE)
+                .spawn_scoped(scope, || references(&api, &config, &head))
+                .unwrap()
+                .join()
+                .unwrap()
+        });
+        let tag_names: Vec<Vec<&str>> = references
+            .iter()
+            .map(|reference| {
+                reference
+                    .tags
+                    .iter()
+                    .flatten()
+                    .map(|tag| tag.name.as_str())
+                    .collect()
+            })
+            .collect();
+        assert_eq!(tag_names, vec![vec![], vec!["api/v0.1.0"], vec!["v0.1.0"]]);
+        assert!(references[0].commit.is_some());
+        assert!(references[1..]
+            .iter()
+            .all(|reference| reference.commit.is_none()));
+    }
+
+    #[test]
+    fn tags_of_diverged_branches_are_not_used() {
+        let mut repo = MockRepo::default();
+        let head = repo.push("fix: handle empty responses", &[]);
+        let base = repo.push("feat: initial release", &["v2.0.0"]);
+        let release = format!("{:040x}", 0xbad);
+        repo.tags.push(("v2.1.0".to_owned(), release.clone()));
+        repo.diverged_shas.push(release);
+        let api = MockApi::serve(repo);
+
+        let result = calculate_bump(&api, &config(&[""]), &head);
+        let version_bump = &result.version_bumps[0];
+        assert_eq!(version_bump.closest_tag.as_ref().unwrap().name, "v2.0.0");
+        assert_eq!(version_bump.new_version, Some(Version::new(2, 0, 1)));
+        assert_eq!(
+            api.requests(GITHUB_COMPARE_URI),
+            vec![format!(
+                "/repos/{}/compare/{}...{}?per_page=1",
+                REPO_ID, base, head
+            )]
+        );
+    }
+
+    #[test]
+    fn tags_of_commits_that_are_not_ancestors_are_ignored() {
+        let mut repo = MockRepo::default();
+        let head = repo.push("fix: handle empty responses", &[]);
+        let release = repo.push("chore: prepare the release branch", &["v2.1.0"]);
+        repo.push("feat: initial release", &["v2.0.0"]);
+        repo.diverged_shas.push(release);
+        let api = MockApi::serve(repo);
+
+        let result = calculate_bump(&api, &config(&[""]), &head);
+        let version_bump = &result.version_bumps[0];
+        assert_eq!(version_bump.closest_tag.as_ref().unwrap().name, "v2.0.0");
+        assert_eq!(version_bump.new_version, Some(Version::new(2, 0, 1)));
+    }
+
+    #[test]
+    fn iteration_finishes_after_an_error() {
+        let mut repo = MockRepo::default();
+        let head = repo.push("fix: handle empty responses", &[]);
+        repo.push("feat: initial release", &[]);
+        let api = MockApi::serve(repo);
+        let mut config = config(&[""]);
+        config.ignore_commit_patterns = vec!["(".to_owned()];
+        let source = GithubSource::new(
+            &config,
+            REPO_ID.to_owned(),
+            api.url.clone(),
+            None,
+            false,
+            GithubClientOptions::default(),
+        )
+        .unwrap();
+
+        let mut references = source
+            .get_ref_iterator(&head, None, &CancellationToken::new())
+            .unwrap();
+        assert!(references.next().unwrap().is_err());
+        assert!(references.next().is_none());
+    }
+
+    #[test]
+    fn link_header_pages_are_parsed() {
+        let url = "https://api.github.com/repos/owner/repo/commits";
+        let value = format!(
+            "<{}?page=2&per_page=100>; rel=\"next\", <{}?page=5&per_page=100>; rel=\"last\"",
+            url, url
+        );
+        assert_eq!(
+            parse_link_header(&value),
+            PageLinks {
+                next: Some(2),
+                last: Some(5)
+            }
+        );
+
+        let value = format!(
+            "<{}?page=4>; rel=\"prev\", <{}?per_page=100>; rel=\"next\", <invalid>; rel=\"last\"",
+            url, url
+        );
+        assert_eq!(parse_link_header(&value), PageLinks::default());
+    }
+
+    #[test]
+    fn pages_are_not_requested_after_the_last_page() {
+        let mut repo = MockRepo::default();
+        let head = repo.push("fix: handle empty responses", &[]);
+        for i in 0..249 {
+            repo.push(&format!("feat: add feature {}", i), &[]);
+        }
+        let api = MockApi::serve(repo);
+
+        let references = references(&api, &config(&[""]), &head);
+        assert_eq!(references.len(), 250);
+        let mut pages: Vec<String> = api
+            .requests(GITHUB_COMMITS_URI)
+            .iter()
+            .filter_map(|target| target.split('&').find(|param| param.starts_with("page=")))
+            .map(str::to_owned)
+            .collect();
+        pages.sort();
+        assert_eq!(pages, vec!["page=1", "page=2", "page=3"]);
+    }
+
+    #[test]
+    fn rate_limited_requests_are_retried() {
+        let mut repo = MockRepo::default();
+        let head = repo.push("fix: handle empty responses", &[]);
+        repo.push("feat: initial release", &["v1.0.0"]);
+        repo.rate_limited_responses = 2;
+        repo.rate_limit_headers = vec!["Retry-After: 0"];
+        let api = MockApi::serve(repo);
+
+        let result = calculate_bump(&api, &config(&[""]), &head);
+        assert_eq!(
+            result.version_bumps[0].new_version,
+            Some(Version::new(1, 0, 1))
+        );
+        let tag_requests = api.requests(GITHUB_GIT_TAG_REFS_URI);
+        assert_eq!(tag_requests.len(), 3);
+        assert!(tag_requests.iter().all(|target| *target == tag_requests[0]));
+    }
+
+    #[test]
+    fn rate_limits_resetting_too_late_are_reported() {
+        let mut repo = MockRepo::default();
+        let head = repo.push("fix: handle empty responses", &[]);
+        repo.rate_limited_responses = 1;
+        repo.rate_limit_headers =
+            vec!["X-RateLimit-Remaining: 0", "X-RateLimit-Reset: 99999999999"];
+        let api = MockApi::serve(repo);
+        let config = config(&[""]);
+        let source = GithubSource::new(
+            &config,
+            REPO_ID.to_owned(),
+            api.url.clone(),
+            None,
+            false,
+            GithubClientOptions::default(),
+        )
+        .unwrap();
+
+        let error = calculate(&config, &source, &head).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::RateLimited);
+        assert!(error
+            .to_string()
+            .contains("the rate limit resets at 99999999999"));
+        assert_eq!(api.requests("").len(), 1);
     }
 
-    Ok(Some(found_tags))
+    /// Returns a repository whose head merges a `release` branch, newer than the first parent of the merge, into the
+    /// commit that follows the tag `v2.0.0`, returning the SHAs of the merge, of its first parent and of its second
+    /// parent.
+    fn merge_repo() -> (MockRepo, String, String, String) {
+        let mut repo = MockRepo::default();
+        let merge = repo.push("fix: merge the release branch", &[]);
+        let release = repo.push("feat: add the release branch", &[]);
+        let fix = repo.push("fix: handle empty responses", &[]);
+        let initial = repo.push("feat: initial release", &["v2.0.0"]);
+        repo.set_parents(&merge, &[&fix, &release]);
+        repo.set_parents(&release, &[&initial]);
+        repo.set_parents(&fix, &[&initial]);
+        (repo, merge, fix, release)
+    }
+
+    fn first_parent_config() -> Config {
+        let mut config = config(&[""]);
+        config.traversal = Traversal::FirstParent;
+        config
+    }
+
+    /// Returns the SHAs of the commits of the given references.
+    fn commit_shas(references: &[Reference]) -> Vec<&str> {
+        references
+            .iter()
+            .filter_map(|reference| reference.commit.as_ref())
+            .map(|commit| commit.sha.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn first_parent_traversal_skips_newer_second_parents() {
+        let (repo, merge, fix, _) = merge_repo();
+        let api = MockApi::serve(repo);
+        let config = first_parent_config();
+
+        let references = references(&api, &config, &merge);
+        assert_eq!(commit_shas(&references), vec![&merge, &fix]);
+        let result = calculate_bump(&api, &config, &merge);
+        let version_bump = &result.version_bumps[0];
+        assert_eq!(version_bump.closest_tag.as_ref().unwrap().name, "v2.0.0");
+        assert_eq!(version_bump.new_version, Some(Version::new(2, 0, 1)));
+    }
+
+    #[test]
+    fn first_parents_that_are_not_listed_are_requested() {
+        let (mut repo, merge, fix, _) = merge_repo();
+        repo.unlisted_shas.push(fix.clone());
+        let api = MockApi::serve(repo);
+
+        let references = references(&api, &first_parent_config(), &merge);
+        assert_eq!(commit_shas(&references), vec![&merge, &fix]);
+        assert_eq!(
+            api.requests(&format!("{}/{}", GITHUB_COMMITS_URI, fix)),
+            vec![format!("/repos/{}/commits/{}", REPO_ID, fix)]
+        );
+    }
+
+    /// Creates the tag `v1.0.0` of the given commit with the given tag type, returning the mock of the REST API.
+    fn create_tag(tag_type: TagType, sha: &str) -> MockApi {
+        let api = MockApi::serve(MockRepo::default());
+        let mut config = config(&[""]);
+        config.tag_type = tag_type;
+        let source = GithubSource::new(
+            &config,
+            REPO_ID.to_owned(),
+            api.url.clone(),
+            Some("token".to_owned()),
+            false,
+            GithubClientOptions::default(),
+        )
+        .unwrap();
+        source.create_tag("v1.0.0", "Release v1.0.0", sha).unwrap();
+        api
+    }
+
+    #[test]
+    fn annotated_tag_ref_references_the_tag_object() {
+        let sha = format!("{:040x}", 1);
+        let api = create_tag(TagType::Annotated, &sha);
+
+        assert_eq!(
+            api.posted(GITHUB_GIT_TAGS_URI),
+            vec![
+                json!({"tag": "v1.0.0", "message": "Release v1.0.0", "object": sha, "type": "commit"})
+            ]
+        );
+        assert_eq!(
+            api.posted(GITHUB_GIT_REFS_URI),
+            vec![json!({"ref": "refs/tags/v1.0.0", "sha": TAG_OBJECT_SHA})]
+        );
+    }
+
+    #[test]
+    fn lightweight_tag_ref_references_the_commit() {
+        let sha = format!("{:040x}", 1);
+        let api = create_tag(TagType::Lightweight, &sha);
+
+        assert!(api.posted(GITHUB_GIT_TAGS_URI).is_empty());
+        assert_eq!(
+            api.posted(GITHUB_GIT_REFS_URI),
+            vec![json!({"ref": "refs/tags/v1.0.0", "sha": sha})]
+        );
+    }
 }
@@ -0,0 +1,127 @@
+//! This module includes the read-only source. The read-only source wraps another source and rejects every action
+//! that modifies it, such as creating tags or releases, so untrusted runs can read the history without being able to
+//! write to the repository, regardless of the arguments and configuration used to create the wrapped source.
+//!
+
+use crate::cancel::CancellationToken;
+use crate::error::{Error, ErrorKind};
+use crate::git::Branch;
+use crate::source::{Reference, SourceActions, SourceKind, SourceStats};
+
+/// Type that represents a source that can only be read.
+pub struct ReadOnlySource<'a> {
+    /// Source used to read the history.
+    source: Box<SourceKind<'a>>,
+}
+
+impl<'a> ReadOnlySource<'a> {
+    /// Returns a new instance of a `ReadOnlySource` source.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - Source used to read the history.
+    ///
+    pub fn new(source: SourceKind<'a>) -> Self {
+        Self {
+            source: Box::new(source),
+        }
+    }
+}
+
+/// Trait to describe all common actions that all sources need to implement.
+impl<'a> SourceActions<'a> for ReadOnlySource<'a> {
+    /// Returns the references of the wrapped source.
+    ///
+    /// # Arguments
+    ///
+    /// * `sha` - The commit sha to start the iteration from.
+    ///
+    /// * `range_start` - SHA of the commit that starts the range of commits used to calculate the version bump.
+    ///
+    /// * `cancel` - Token used to interrupt the iteration.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of the wrapped source.
+    ///
+    fn get_ref_iterator(
+        &self,
+        sha: &'a str,
+        range_start: Option<&str>,
+        cancel: &CancellationToken,
+    ) -> Result<Box<dyn Iterator<Item = Result<Reference, Error>> + '_>, Error> {
+        self.source.get_ref_iterator(sha, range_start, cancel)
+    }
+
+    /// Returns the latest commit sha of the wrapped source.
+    fn get_latest_commit_sha(&self) -> Result<String, Error> {
+        self.source.get_latest_commit_sha()
+    }
+
+    /// Returns the SHA of the commit referenced by the given reference in the wrapped source.
+    fn resolve_ref(&self, reference: &str) -> Result<String, Error> {
+        self.source.resolve_ref(reference)
+    }
+
+    /// Returns the branches of the wrapped source.
+    fn get_branches(&self) -> Result<Vec<Branch>, Error> {
+        self.source.get_branches()
+    }
+
+    /// Rejects the creation of the tag.
+    ///
+    /// # Errors
+    ///
+    /// Always returns `error::Error` with a kind of `error::ErrorKind::ReadOnly`.
+    ///
+    fn create_tag(
+        &self,
+        tag_name: &str,
+        _tag_message: &str,
+        _commit_sha: &str,
+    ) -> Result<(), Error> {
+        Err(Error::new(
+            ErrorKind::ReadOnly,
+            Some(&format!("can not create tag '{}'", tag_name)),
+        ))
+    }
+
+    /// Rejects the creation of the release.
+    ///
+    /// # Errors
+    ///
+    /// Always returns `error::Error` with a kind of `error::ErrorKind::ReadOnly`.
+    ///
+    fn create_release(&self, tag_name: &str, _title: &str, _body: &str) -> Result<String, Error> {
+        Err(Error::new(
+            ErrorKind::ReadOnly,
+            Some(&format!("can not create release for tag '{}'", tag_name)),
+        ))
+    }
+
+    /// Returns the statistics of the wrapped source.
+    fn get_stats(&self) -> SourceStats {
+        self.source.get_stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::source::git::GitSource;
+
+    #[test]
+    fn actions_that_modify_the_source_are_rejected() {
+        let config = Config::new();
+        let source = ReadOnlySource::new(SourceKind::Git(GitSource::new(&config, false, false)));
+
+        let error = source
+            .create_tag("v1.0.0", "Version 1.0.0", "c1")
+            .unwrap_err();
+        assert_eq!(error.kind, ErrorKind::ReadOnly);
+        assert!(error.to_string().contains("can not create tag 'v1.0.0'"));
+        let error = source.create_release("v1.0.0", "v1.0.0", "").unwrap_err();
+        assert_eq!(error.kind, ErrorKind::ReadOnly);
+    }
+}
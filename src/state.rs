@@ -0,0 +1,368 @@
+//! This module provides the state file used to speed up incremental runs.
+//!
+//! The state file records the last evaluated commit and, for each scope, its closest tag together with the increment
+//! and contributors of the commits evaluated since that tag. The next run only scans the commits added after the last
+//! evaluated commit and combines them with the recorded state, instead of scanning the overlapping history again.
+//!
+//! State files written with a different format version or configuration are ignored.
+//!
+
+use crate::bump::{ScannedReferences, ScopeBump};
+use crate::config::Config;
+use crate::error::{Error, ErrorKind};
+use crate::git::Tag;
+use crate::metadata::hash_config;
+use crate::parsing::TagDetails;
+use crate::version::{max_increment, IncrementKind};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeSet, fs, path::Path};
+
+/// Version of the state file format. State files with a different format version are ignored.
+pub const STATE_FORMAT_VERSION: u64 = 1;
+
+/// Type that represents a state file.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct State {
+    /// Version of the state file format.
+    pub format_version: u64,
+    /// Version of Tag Track that wrote the state.
+    pub tool_version: String,
+    /// Hex encoded SHA-256 hash of the JSON representation of the configuration used to write the state.
+    pub config_sha256: String,
+    /// SHA of the last evaluated commit.
+    pub commit_sha: String,
+    /// State of each scope.
+    pub scopes: Vec<ScopeState>,
+}
+
+/// Type that represents the state of a scope.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ScopeState {
+    /// Scope of the version.
+    pub scope: String,
+    /// Name of the closest tag of the scope.
+    pub tag_name: String,
+    /// SHA of the commit referenced by the closest tag.
+    pub tag_commit_sha: String,
+    /// Version of the closest tag.
+    pub version: String,
+    /// Greatest increment kind of the commits evaluated since the closest tag.
+    pub increment_kind: Option<IncrementKind>,
+    /// Authors of the commits evaluated since the closest tag.
+    pub contributors: BTreeSet<String>,
+}
+
+impl State {
+    /// Returns a new `State` with the current format and tool versions.
+    ///
+    /// # Arguments
+    ///
+    /// * `commit_sha` - SHA of the last evaluated commit.
+    ///
+    /// * `scopes` - State of each scope.
+    ///
+    /// * `config` - Tag Track configuration used to calculate the version bumps.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the configuration cannot be serialized.
+    ///
+    pub fn new(
+        commit_sha: String,
+        scopes: Vec<ScopeState>,
+        config: &Config,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            format_version: STATE_FORMAT_VERSION,
+            tool_version: env!("CARGO_PKG_VERSION").to_owned(),
+            config_sha256: hash_config(config)?,
+            commit_sha,
+            scopes,
+        })
+    }
+}
+
+/// Writes the given state into a JSON file. Missing parent directories are created.
+///
+/// # Arguments
+///
+/// * `state` - State that will be written.
+///
+/// * `path` - Path of the state file.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the file cannot be written.
+///
+pub fn write_state_file(state: &State, path: &Path) -> Result<(), Error> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let contents = serde_json::to_string_pretty(state)?;
+    fs::write(path, contents + "\n")?;
+    Ok(())
+}
+
+/// Reads a state from a JSON file. Returns `None` if the file does not exist or if it was written with a different
+/// format version or configuration.
+///
+/// # Arguments
+///
+/// * `path` - Path of the state file.
+///
+/// * `config` - Tag Track configuration of the current run.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidStateFile` if the file is not a valid state.
+///
+pub fn read_state_file(path: &Path, config: &Config) -> Result<Option<State>, Error> {
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let contents = fs::read_to_string(path)?;
+    let state: State = match serde_json::from_str(&contents) {
+        Ok(state) => state,
+        Err(error) => {
+            return Err(Error::new(
+                ErrorKind::InvalidStateFile,
+                Some(&error.to_string()),
+            ))
+        }
+    };
+
+    if state.format_version != STATE_FORMAT_VERSION || state.config_sha256 != hash_config(config)? {
+        return Ok(None);
+    }
+    Ok(Some(state))
+}
+
+/// Combines the given scanned references with the state of a previous run. The scanned references must contain the
+/// commits added after the last evaluated commit of the state. Scopes whose closest tag was not found in those
+/// commits use the closest tag of the state, and their increment and contributors are combined with the ones of the
+/// state.
+///
+/// # Arguments
+///
+/// * `scanned_references` - References scanned until the last evaluated commit of the state.
+///
+/// * `state` - State of the previous run.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidStateFile` if the version of a tag of the state
+/// is not valid.
+///
+pub fn resume_from_state(
+    scanned_references: &mut ScannedReferences,
+    state: &State,
+) -> Result<(), Error> {
+    for scope_state in &state.scopes {
+        let is_tag_found = scanned_references.closest_tags.iter().any(|tag| {
+            tag.details.as_ref().is_some_and(|details| {
+                details.scope.as_deref().unwrap_or_default() == scope_state.scope
+            })
+        });
+        if is_tag_found {
+            continue;
+        }
+
+        let version = match Version::parse(&scope_state.version) {
+            Ok(version) => version,
+            Err(error) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidStateFile,
+                    Some(&error.to_string()),
+                ))
+            }
+        };
+        scanned_references.closest_tags.push(Tag {
+            commit_sha: scope_state.tag_commit_sha.clone(),
+            name: scope_state.tag_name.clone(),
+            details: Some(TagDetails {
+                version,
+                scope: match scope_state.scope.is_empty() {
+                    true => None,
+                    false => Some(scope_state.scope.clone()),
+                },
+            }),
+        });
+
+        let increment_kind = scanned_references
+            .version_bumps
+            .get(&scope_state.scope)
+            .copied()
+            .flatten();
+        scanned_references.version_bumps.insert(
+            scope_state.scope.clone(),
+            max_increment([increment_kind, scope_state.increment_kind]),
+        );
+        scanned_references
+            .contributors
+            .entry(scope_state.scope.clone())
+            .or_default()
+            .extend(scope_state.contributors.iter().cloned());
+    }
+    Ok(())
+}
+
+/// Returns the state of each scope with a closest tag, used to write the state file.
+///
+/// # Arguments
+///
+/// * `scope_bumps` - Version bump of each scope.
+///
+pub fn get_scope_states(scope_bumps: &[ScopeBump]) -> Vec<ScopeState> {
+    scope_bumps
+        .iter()
+        .filter_map(|scope_bump| {
+            let tag = scope_bump.closest_tag.as_ref()?;
+            let version = scope_bump.old_version.as_ref()?;
+            Some(ScopeState {
+                scope: scope_bump.scope.clone(),
+                tag_name: tag.name.clone(),
+                tag_commit_sha: tag.commit_sha.clone(),
+                version: version.to_string(),
+                increment_kind: scope_bump.increment_kind,
+                contributors: scope_bump.contributors.clone(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::{HashMap, HashSet};
+    use std::path::PathBuf;
+    use std::{env, process};
+
+    /// Returns the path of the given state file in the temporary directory.
+    fn state_path(name: &str) -> PathBuf {
+        env::temp_dir()
+            .join(format!("tag-track-state-{}", process::id()))
+            .join(name)
+    }
+
+    /// Returns the state of the given scope with a closest tag of version `1.0.0`.
+    fn scope_state(scope: &str, tag_name: &str, increment_kind: IncrementKind) -> ScopeState {
+        ScopeState {
+            scope: scope.to_owned(),
+            tag_name: tag_name.to_owned(),
+            tag_commit_sha: "c1".to_owned(),
+            version: "1.0.0".to_owned(),
+            increment_kind: Some(increment_kind),
+            contributors: BTreeSet::from(["alice".to_owned()]),
+        }
+    }
+
+    /// Returns the scanned references of the given closest tags and version bumps.
+    fn scanned_references(
+        closest_tags: Vec<Tag>,
+        version_bumps: &[(&str, IncrementKind)],
+    ) -> ScannedReferences {
+        ScannedReferences {
+            version_bumps: version_bumps
+                .iter()
+                .map(|(scope, increment_kind)| (scope.to_string(), Some(*increment_kind)))
+                .collect(),
+            no_bump_reasons: HashMap::new(),
+            closest_tags,
+            contributors: HashMap::from([(String::new(), BTreeSet::from(["bob".to_owned()]))]),
+            commits: HashMap::new(),
+            provenance: HashMap::new(),
+            dependency_updates: vec![],
+            skipped_commits: vec![],
+            ignored_commits: vec![],
+            non_conforming_commits: HashMap::new(),
+            commit_threshold_scopes: HashSet::new(),
+            commit_suggestions: vec![],
+            is_stopped: true,
+        }
+    }
+
+    #[test]
+    fn state_is_read_back_with_the_same_configuration() {
+        let config = Config::new();
+        let path = state_path("nested/state.json");
+        let scopes = vec![scope_state("", "1.0.0", IncrementKind::Minor)];
+        write_state_file(
+            &State::new("c2".to_owned(), scopes, &config).unwrap(),
+            &path,
+        )
+        .unwrap();
+
+        let state = read_state_file(&path, &config).unwrap().unwrap();
+        assert_eq!(state.commit_sha, "c2");
+        assert_eq!(state.scopes[0].tag_name, "1.0.0");
+
+        let mut changed_config = Config::new();
+        changed_config.initial_version = "1.0.0".to_owned();
+        assert!(read_state_file(&path, &changed_config).unwrap().is_none());
+        assert!(read_state_file(&state_path("missing.json"), &config)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn states_of_other_formats_are_ignored_and_invalid_states_are_rejected() {
+        let config = Config::new();
+        let path = state_path("old.json");
+        let mut state = State::new("c2".to_owned(), vec![], &config).unwrap();
+        state.format_version = STATE_FORMAT_VERSION + 1;
+        write_state_file(&state, &path).unwrap();
+        assert!(read_state_file(&path, &config).unwrap().is_none());
+
+        let path = state_path("invalid.json");
+        fs::write(&path, "{\"commit_sha\": \"c2\"}").unwrap();
+        let error = read_state_file(&path, &config).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidStateFile);
+    }
+
+    #[test]
+    fn resumed_scopes_combine_the_state_with_the_new_commits() {
+        let api_tag = Tag {
+            commit_sha: "c3".to_owned(),
+            name: "api/1.1.0".to_owned(),
+            details: Some(TagDetails {
+                version: Version::new(1, 1, 0),
+                scope: Some("api".to_owned()),
+            }),
+        };
+        let mut scanned_references = scanned_references(
+            vec![api_tag],
+            &[("", IncrementKind::Patch), ("api", IncrementKind::Patch)],
+        );
+        let state = State::new(
+            "c2".to_owned(),
+            vec![
+                scope_state("", "1.0.0", IncrementKind::Minor),
+                scope_state("api", "api/1.0.0", IncrementKind::Major),
+            ],
+            &Config::new(),
+        )
+        .unwrap();
+        resume_from_state(&mut scanned_references, &state).unwrap();
+
+        let tag_names: Vec<&str> = scanned_references
+            .closest_tags
+            .iter()
+            .map(|tag| tag.name.as_str())
+            .collect();
+        assert_eq!(tag_names, vec!["api/1.1.0", "1.0.0"]);
+        assert_eq!(
+            scanned_references.version_bumps[""],
+            Some(IncrementKind::Minor)
+        );
+        assert_eq!(
+            scanned_references.version_bumps["api"],
+            Some(IncrementKind::Patch)
+        );
+        assert_eq!(
+            scanned_references.contributors[""],
+            BTreeSet::from(["alice".to_owned(), "bob".to_owned()])
+        );
+    }
+}
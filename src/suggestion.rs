@@ -0,0 +1,276 @@
+//! This module provides heuristic rewrite suggestions for commit messages that do not match the commit pattern, to
+//! help teams adopting conventional commits.
+//!
+//! The first line of the message is split into a probable type, scope and description. The suggestion fixes common
+//! mistakes, such as a missing colon, a type synonym or a wrongly cased scope, and it is only returned if the
+//! rewritten message matches the commit pattern of the configuration.
+//!
+
+use crate::config::Config;
+use crate::error::Error;
+use crate::parsing::{build_regex, parse_commit_details};
+use serde::Serialize;
+
+/// Pattern used to split the first line of a commit message into a probable type, scope and description.
+const LOOSE_COMMIT_PATTERN: &str = r"^\s*(?<type>[A-Za-z]+)(?:\s*\((?<scope>[^()]*)\))?(?<breaking>!)?\s*(?<separator>[:\-]?)\s*(?<description>.*)$";
+
+/// Conventional commit types and the words that are commonly used instead of them.
+const TYPE_SYNONYMS: [(&str, &[&str]); 11] = [
+    ("feat", &["feat", "feature", "features"]),
+    ("fix", &["fix", "fixes", "fixed", "bugfix", "hotfix"]),
+    ("docs", &["docs", "doc", "documentation"]),
+    ("style", &["style", "format", "formatting"]),
+    ("refactor", &["refactor", "refactoring"]),
+    ("perf", &["perf", "performance"]),
+    ("test", &["test", "tests"]),
+    ("build", &["build"]),
+    ("ci", &["ci"]),
+    ("chore", &["chore", "chores"]),
+    ("revert", &["revert"]),
+];
+
+/// Types guessed from the first word of messages without a type.
+const TYPE_KEYWORDS: [(&str, &[&str]); 6] = [
+    (
+        "feat",
+        &[
+            "add",
+            "adds",
+            "added",
+            "implement",
+            "introduce",
+            "support",
+            "allow",
+            "create",
+        ],
+    ),
+    (
+        "fix",
+        &[
+            "resolve", "resolves", "resolved", "correct", "repair", "prevent",
+        ],
+    ),
+    ("docs", &["document", "documented", "readme"]),
+    (
+        "refactor",
+        &[
+            "rename",
+            "move",
+            "simplify",
+            "restructure",
+            "clean",
+            "cleanup",
+            "extract",
+        ],
+    ),
+    ("test", &["cover", "covers"]),
+    ("chore", &["bump", "upgrade", "update", "remove", "delete"]),
+];
+
+/// Type that represents a rewrite suggestion for a commit message that does not match the commit pattern.
+#[derive(Serialize, Debug, Clone)]
+pub struct CommitSuggestion {
+    /// Commit SHA.
+    pub sha: String,
+    /// First line of the original commit message.
+    pub message: String,
+    /// Suggested first line of the commit message.
+    pub suggestion: String,
+    /// Explanations of the changes applied by the suggestion.
+    pub hints: Vec<String>,
+}
+
+/// Returns a rewrite suggestion for the given commit message, or `None` if no suggestion matching the commit pattern
+/// can be found.
+///
+/// # Arguments
+///
+/// * `sha` - SHA of the commit.
+///
+/// * `message` - Commit message that does not match the commit pattern.
+///
+/// * `config` - Tag Track configuration.
+///
+/// # Errors
+///
+/// Returns the errors of `parsing::parse_commit_details`.
+///
+pub fn suggest_commit_rewrite(
+    sha: &str,
+    message: &str,
+    config: &Config,
+) -> Result<Option<CommitSuggestion>, Error> {
+    let first_line = message.lines().next().unwrap_or_default().trim();
+    let captures = match build_regex(LOOSE_COMMIT_PATTERN)?.captures(first_line) {
+        Some(captures) => captures,
+        None => return Ok(None),
+    };
+
+    let mut hints = vec![];
+    let word = &captures["type"];
+    let has_separator = !captures["separator"].is_empty();
+    let (commit_type, description) = match find_type(word) {
+        Some(commit_type) => {
+            if word != commit_type {
+                hints.push(format!(
+                    "use the type `{}` instead of `{}`",
+                    commit_type, word
+                ));
+            }
+            if &captures["separator"] != ":" {
+                hints.push(
+                    "separate the type and scope from the description with a colon".to_owned(),
+                );
+            }
+            (commit_type, captures["description"].to_owned())
+        }
+        None if captures.name("scope").is_none() && !has_separator => {
+            let commit_type = match guess_type(word) {
+                Some(commit_type) => commit_type,
+                None => return Ok(None),
+            };
+            hints.push(format!(
+                "add a type, the probable type of the commit is `{}`",
+                commit_type
+            ));
+            (commit_type, first_line.to_owned())
+        }
+        None => return Ok(None),
+    };
+
+    let scope = captures.name("scope").map(|scope| {
+        let scope = scope.as_str().trim();
+        let version_scope = config
+            .version_scopes
+            .iter()
+            .find(|version_scope| version_scope.eq_ignore_ascii_case(scope));
+        match version_scope {
+            Some(version_scope) if version_scope != scope => {
+                hints.push(format!(
+                    "use the scope `{}` instead of `{}`",
+                    version_scope, scope
+                ));
+                version_scope.clone()
+            }
+            _ => scope.to_owned(),
+        }
+    });
+
+    let description = description.trim();
+    if description.is_empty() {
+        return Ok(None);
+    }
+
+    let suggestion = format!(
+        "{}{}{}: {}",
+        commit_type,
+        scope
+            .map(|scope| format!("({})", scope))
+            .unwrap_or_default(),
+        captures
+            .name("breaking")
+            .map_or("", |breaking| breaking.as_str()),
+        description
+    );
+    if suggestion == first_line
+        || parse_commit_details(&suggestion, &config.commit_pattern, config.scope_rules.case)?
+            .is_none()
+    {
+        return Ok(None);
+    }
+    if hints.is_empty() {
+        hints.push("remove the whitespace between the type, scope and colon".to_owned());
+    }
+
+    Ok(Some(CommitSuggestion {
+        sha: sha.to_owned(),
+        message: first_line.to_owned(),
+        suggestion,
+        hints,
+    }))
+}
+
+/// Returns the conventional commit type of the given word if it is a type or a common synonym of a type, ignoring
+/// its casing.
+///
+/// # Arguments
+///
+/// * `word` - Probable type of the commit.
+///
+fn find_type(word: &str) -> Option<&'static str> {
+    let word = word.to_ascii_lowercase();
+    TYPE_SYNONYMS
+        .iter()
+        .find(|(_, synonyms)| synonyms.contains(&word.as_str()))
+        .map(|(commit_type, _)| *commit_type)
+}
+
+/// Returns the probable conventional commit type of a message without a type from its first word, ignoring its
+/// casing.
+///
+/// # Arguments
+///
+/// * `word` - First word of the commit message.
+///
+fn guess_type(word: &str) -> Option<&'static str> {
+    let word = word.to_ascii_lowercase();
+    TYPE_KEYWORDS
+        .iter()
+        .find(|(_, keywords)| keywords.contains(&word.as_str()))
+        .map(|(commit_type, _)| *commit_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the suggestion and hints for the given message, with the version scopes `api` and `cli`.
+    fn suggest(message: &str) -> Option<(String, Vec<String>)> {
+        let mut config = Config::new();
+        config.version_scopes = vec!["api".to_owned(), "cli".to_owned()];
+        suggest_commit_rewrite("abc1234", message, &config)
+            .unwrap()
+            .map(|suggestion| (suggestion.suggestion, suggestion.hints))
+    }
+
+    #[test]
+    fn type_synonyms_separators_and_scope_casing_are_fixed() {
+        let (suggestion, hints) = suggest("Feature(API) add the endpoint\n\nbody").unwrap();
+        assert_eq!(suggestion, "feat(api): add the endpoint");
+        assert_eq!(
+            hints,
+            vec![
+                "use the type `feat` instead of `Feature`",
+                "separate the type and scope from the description with a colon",
+                "use the scope `api` instead of `API`",
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_types_are_guessed() {
+        let (suggestion, hints) = suggest("Resolve the crash on empty responses").unwrap();
+        assert_eq!(suggestion, "fix: Resolve the crash on empty responses");
+        assert_eq!(
+            hints,
+            vec!["add a type, the probable type of the commit is `fix`"]
+        );
+    }
+
+    #[test]
+    fn whitespace_before_the_colon_is_removed() {
+        let (suggestion, hints) = suggest("fix (cli) : handle empty responses").unwrap();
+        assert_eq!(suggestion, "fix(cli): handle empty responses");
+        assert_eq!(
+            hints,
+            vec!["remove the whitespace between the type, scope and colon"]
+        );
+    }
+
+    #[test]
+    fn unknown_messages_have_no_suggestion() {
+        assert!(suggest("wip").is_none());
+        assert!(suggest("Merge branch 'main'").is_none());
+        assert!(suggest("fix:").is_none());
+    }
+}
@@ -0,0 +1,379 @@
+//! This module provides the template engine used to render release notes with user provided templates. The templates
+//! use a subset of the Handlebars syntax:
+//!
+//! - `{{name}}` renders the value of a field. Nested fields are separated by dots, such as `{{commit.sha}}`, and
+//!   `{{this}}` renders the current item of an `each` block. Fields that are not found are rendered as an empty
+//!   string.
+//! - `{{#each name}}...{{/each}}` renders the block once for each item of a list. The fields of the item are looked
+//!   up first, then the fields of the enclosing blocks.
+//! - `{{#if name}}...{{else}}...{{/if}}` renders the block if the value is not empty, `false` or missing, otherwise
+//!   the optional `else` block.
+//! - `{{! comment }}` is not rendered.
+//!
+//! Values are rendered as is, without HTML escaping. Block tags that are alone in their line do not render the line,
+//! so templates can place them on their own lines without adding blank lines to the output.
+//!
+
+use crate::error::{Error, ErrorKind};
+use serde_json::Value;
+
+/// Type that represents a node of a parsed template.
+#[derive(Debug, Clone)]
+enum Node {
+    /// Text rendered as is.
+    Text(String),
+    /// Field whose value is rendered.
+    Field(String),
+    /// Block rendered once for each item of the list of the field.
+    Each(String, Vec<Node>),
+    /// Block rendered if the value of the field is truthy, otherwise the `else` block is rendered.
+    If(String, Vec<Node>, Vec<Node>),
+}
+
+/// Type that represents a block of the template that is being parsed.
+struct OpenBlock {
+    /// Name of the block helper, `each` or `if`.
+    helper: String,
+    /// Field of the block.
+    field: String,
+    /// Nodes of the block.
+    nodes: Vec<Node>,
+    /// Nodes of the `else` block, `None` if the `else` tag was not found.
+    else_nodes: Option<Vec<Node>>,
+}
+
+/// Type that represents a parsed template.
+#[derive(Debug, Clone)]
+pub struct Template {
+    /// Nodes of the template.
+    nodes: Vec<Node>,
+}
+
+impl Template {
+    /// Parses the given template.
+    ///
+    /// # Arguments
+    ///
+    /// * `template` - Contents of the template.
+    ///
+    /// # Errors
+    ///
+    /// Returns `error::Error` with a kind of `error::ErrorKind::InvalidTemplate` if a tag is not closed, if a block
+    /// helper is unknown or if the blocks are not balanced.
+    ///
+    pub fn parse(template: &str) -> Result<Self, Error> {
+        let mut root = vec![];
+        let mut blocks: Vec<OpenBlock> = vec![];
+        let mut rest = template;
+        let mut at_line_start = true;
+        loop {
+            let start = match rest.find("{{") {
+                Some(start) => start,
+                None => {
+                    push_text(&mut root, &mut blocks, rest);
+                    break;
+                }
+            };
+            let after = &rest[start + 2..];
+            let end = match after.find("}}") {
+                Some(end) => end,
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidTemplate,
+                        Some("a `{{` tag is not closed"),
+                    ))
+                }
+            };
+            let tag = after[..end].trim();
+            let mut preceding = &rest[..start];
+            rest = &after[end + 2..];
+
+            let is_block_tag = tag.starts_with(['#', '/', '!']) || tag == "else";
+            let line_prefix_start = preceding.rfind('\n').map(|index| index + 1);
+            let line_prefix = &preceding[line_prefix_start.unwrap_or(0)..];
+            let line_suffix_end = rest.find('\n').map(|index| index + 1);
+            let line_suffix = &rest[..line_suffix_end.unwrap_or(rest.len())];
+            if is_block_tag
+                && (at_line_start || line_prefix_start.is_some())
+                && line_prefix.trim().is_empty()
+                && line_suffix.trim().is_empty()
+            {
+                preceding = &preceding[..preceding.len() - line_prefix.len()];
+                rest = &rest[line_suffix.len()..];
+                at_line_start = true;
+            } else {
+                at_line_start = false;
+            }
+            push_text(&mut root, &mut blocks, preceding);
+
+            if let Some(tag) = tag.strip_prefix('#') {
+                let tag = tag.trim();
+                let (helper, field) = tag.split_once(char::is_whitespace).unwrap_or((tag, ""));
+                if helper != "each" && helper != "if" {
+                    return Err(Error::new(
+                        ErrorKind::InvalidTemplate,
+                        Some(&format!("unknown block helper `{}`", helper)),
+                    ));
+                }
+                blocks.push(OpenBlock {
+                    helper: helper.to_owned(),
+                    field: field.trim().to_owned(),
+                    nodes: vec![],
+                    else_nodes: None,
+                });
+            } else if let Some(helper) = tag.strip_prefix('/') {
+                let block = match blocks.pop() {
+                    Some(block) if block.helper == helper.trim() => block,
+                    _ => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidTemplate,
+                            Some(&format!(
+                                "unexpected closing tag `{{{{/{}}}}}`",
+                                helper.trim()
+                            )),
+                        ))
+                    }
+                };
+                let node = match block.helper.as_str() {
+                    "each" => Node::Each(block.field, block.nodes),
+                    _ => Node::If(
+                        block.field,
+                        block.nodes,
+                        block.else_nodes.unwrap_or_default(),
+                    ),
+                };
+                current_nodes(&mut root, &mut blocks).push(node);
+            } else if tag == "else" {
+                match blocks.last_mut() {
+                    Some(block) if block.helper == "if" && block.else_nodes.is_none() => {
+                        block.else_nodes = Some(vec![]);
+                    }
+                    _ => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidTemplate,
+                            Some("`{{else}}` is only allowed once inside an `if` block"),
+                        ))
+                    }
+                }
+            } else if !tag.starts_with('!') {
+                current_nodes(&mut root, &mut blocks).push(Node::Field(tag.to_owned()));
+            }
+        }
+
+        match blocks.last() {
+            Some(block) => Err(Error::new(
+                ErrorKind::InvalidTemplate,
+                Some(&format!("the `{}` block is not closed", block.helper)),
+            )),
+            None => Ok(Self { nodes: root }),
+        }
+    }
+
+    /// Renders the template with the given data.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Data whose fields are available to the template.
+    ///
+    pub fn render(&self, data: &Value) -> String {
+        let mut rendered = String::new();
+        render_nodes(&self.nodes, &mut vec![data], &mut rendered);
+        rendered
+    }
+}
+
+/// Returns the nodes where the parsed nodes are added, which are the nodes of the innermost open block or the root
+/// nodes if there are no open blocks.
+///
+/// # Arguments
+///
+/// * `root` - Root nodes of the template.
+///
+/// * `blocks` - Open blocks, from the outermost to the innermost one.
+///
+fn current_nodes<'a>(root: &'a mut Vec<Node>, blocks: &'a mut [OpenBlock]) -> &'a mut Vec<Node> {
+    match blocks.last_mut() {
+        Some(OpenBlock {
+            else_nodes: Some(else_nodes),
+            ..
+        }) => else_nodes,
+        Some(block) => &mut block.nodes,
+        None => root,
+    }
+}
+
+/// Adds the given text to the current nodes, if it is not empty.
+///
+/// # Arguments
+///
+/// * `root` - Root nodes of the template.
+///
+/// * `blocks` - Open blocks, from the outermost to the innermost one.
+///
+/// * `text` - Text to add.
+///
+fn push_text(root: &mut Vec<Node>, blocks: &mut [OpenBlock], text: &str) {
+    if !text.is_empty() {
+        current_nodes(root, blocks).push(Node::Text(text.to_owned()));
+    }
+}
+
+/// Renders the given nodes into `rendered`.
+///
+/// # Arguments
+///
+/// * `nodes` - Nodes to render.
+///
+/// * `scopes` - Data of the enclosing blocks, from the outermost to the innermost one.
+///
+/// * `rendered` - Rendered output.
+///
+fn render_nodes(nodes: &[Node], scopes: &mut Vec<&Value>, rendered: &mut String) {
+    for node in nodes {
+        match node {
+            Node::Text(text) => rendered.push_str(text),
+            Node::Field(field) => {
+                if let Some(value) = lookup(scopes, field) {
+                    rendered.push_str(&format_value(value));
+                }
+            }
+            Node::Each(field, block) => {
+                if let Some(Value::Array(items)) = lookup(scopes, field) {
+                    for item in items {
+                        scopes.push(item);
+                        render_nodes(block, scopes, rendered);
+                        scopes.pop();
+                    }
+                }
+            }
+            Node::If(field, block, else_block) => {
+                match lookup(scopes, field).is_some_and(is_truthy) {
+                    true => render_nodes(block, scopes, rendered),
+                    false => render_nodes(else_block, scopes, rendered),
+                }
+            }
+        }
+    }
+}
+
+/// Returns the value of the given field, looked up from the innermost scope to the outermost one, or `None` if it is
+/// not found.
+///
+/// # Arguments
+///
+/// * `scopes` - Data of the enclosing blocks, from the outermost to the innermost one.
+///
+/// * `field` - Field to look up. Nested fields are separated by dots and `this` references the innermost scope.
+///
+fn lookup<'a>(scopes: &[&'a Value], field: &str) -> Option<&'a Value> {
+    let mut segments = field.split('.');
+    let first = segments.next()?;
+    let mut value = match first {
+        "this" => *scopes.last()?,
+        _ => scopes.iter().rev().find_map(|scope| scope.get(first))?,
+    };
+    for segment in segments {
+        value = value.get(segment)?;
+    }
+    Some(value)
+}
+
+/// Returns `false` if the given value is `null`, `false`, an empty string or an empty list.
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(value) => *value,
+        Value::String(value) => !value.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        _ => true,
+    }
+}
+
+/// Formats the given value. Lists are rendered as their items separated by commas and `null` as an empty string.
+fn format_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(value) => value.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(format_value)
+            .collect::<Vec<String>>()
+            .join(", "),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Parses and renders the given template with the given data.
+    fn render(template: &str, data: Value) -> String {
+        Template::parse(template).unwrap().render(&data)
+    }
+
+    #[test]
+    fn fields_and_nested_fields_are_rendered() {
+        let data = json!({"version": "1.2.0", "tag": {"name": "v1.2.0"}, "authors": ["a", "b"], "count": 3});
+        assert_eq!(
+            render(
+                "{{tag.name}} ({{ version }}) by {{authors}}, {{count}} commits{{missing}}",
+                data
+            ),
+            "v1.2.0 (1.2.0) by a, b, 3 commits"
+        );
+    }
+
+    #[test]
+    fn blocks_alone_in_their_line_do_not_render_the_line() {
+        let template = "## {{version}}\n\
+                        {{#each commits}}\n\
+                        - {{description}} ({{sha}}){{#if breaking}} **breaking**{{/if}} in {{version}}\n\
+                        {{/each}}\n\
+                        {{! the list of contributors }}\n\
+                        {{#if contributors}}\n\
+                        Thanks {{contributors}}\n\
+                        {{else}}\n\
+                        No contributors\n\
+                        {{/if}}\n";
+        let data = json!({
+            "version": "1.2.0",
+            "commits": [
+                {"description": "add a page", "sha": "c1", "breaking": true},
+                {"description": "fix a link", "sha": "c2", "breaking": false},
+            ],
+            "contributors": [],
+        });
+        assert_eq!(
+            render(template, data),
+            "## 1.2.0\n\
+             - add a page (c1) **breaking** in 1.2.0\n\
+             - fix a link (c2) in 1.2.0\n\
+             No contributors\n"
+        );
+        assert_eq!(
+            render(
+                "{{#each items}}[{{this}}]{{/each}}",
+                json!({"items": [1, 2]})
+            ),
+            "[1][2]"
+        );
+    }
+
+    #[test]
+    fn invalid_templates_are_rejected() {
+        let templates = [
+            "{{version",
+            "{{#unless version}}{{/unless}}",
+            "{{#each commits}}",
+            "{{#if breaking}}{{/each}}",
+            "{{#if breaking}}{{else}}{{else}}{{/if}}",
+            "{{else}}",
+        ];
+        for template in templates {
+            let error = Template::parse(template).unwrap_err();
+            assert_eq!(error.kind, ErrorKind::InvalidTemplate, "{}", template);
+        }
+    }
+}
@@ -0,0 +1,448 @@
+//! This module provides the validation of the configuration file run by the `config validate` subcommand.
+//!
+//! The regular loading of the configuration ignores unknown fields and only warns about them, so a typo such as
+//! `bump_ruls` falls back to the default value of the field. The validation reports every unknown field, as if the
+//! configuration types denied unknown fields, checks that the regex patterns compile and expose their required
+//! capturing groups, and checks the initial version and the version scopes. Issues are located in the file by their
+//! line when possible.
+//!
+
+use crate::config::{Config, ParsedConfig};
+use crate::error::Error;
+use crate::parsing::{
+    build_regex, normalize_scope, validate_scope, DESCRIPTION_CAPTURING_GROUP_NAME,
+    LINE_CAPTURING_GROUP_NAME, TYPE_CAPTURING_GROUP_NAME, VERSION_CAPTURING_GROUP_NAME,
+};
+use crate::version::parse_user_version;
+use serde::Serialize;
+use serde_json::Value;
+use std::{fs, path::Path};
+
+/// Maximum edit distance between an unknown field and a known field for the known field to be suggested.
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// Placeholder of the version that the `new_tag_name` template must contain.
+const NEW_TAG_NAME_VERSION_PLACEHOLDER: &str = "{version}";
+
+/// Type that represents an issue found in the configuration file.
+#[derive(Debug, Serialize, Clone)]
+pub struct ConfigDiagnostic {
+    /// Path of the field with the issue, with the items of lists referenced by their position starting at 0, such as
+    /// `bump_rules.0.bump`. `None` if the issue is not related to a field, such as a syntax error.
+    pub field: Option<String>,
+    /// Line of the configuration file where the issue is found, starting at 1. `None` if the line is not known, such
+    /// as for fields that are not in the file and use their default value.
+    pub line: Option<usize>,
+    /// Description of the issue.
+    pub message: String,
+}
+
+impl ConfigDiagnostic {
+    /// Returns a new diagnostic for the given field, located at the line of the field in the configuration file.
+    ///
+    /// # Arguments
+    ///
+    /// * `field` - Path of the field with the issue.
+    ///
+    /// * `message` - Description of the issue.
+    ///
+    /// * `contents` - Contents of the configuration file.
+    ///
+    fn for_field(field: &str, message: String, contents: &str) -> Self {
+        Self {
+            field: Some(field.to_owned()),
+            line: find_field_line(contents, field),
+            message,
+        }
+    }
+}
+
+/// Validates the given configuration file and returns the issues found, empty if the file is valid. The format of
+/// the file is detected from its extension, as with `config::parse_config_file`. If the file can not be parsed, the
+/// parse error and the unknown fields found before it are returned.
+///
+/// # Arguments
+///
+/// * `path` - Path of the configuration file.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::Other` if the file can not be read.
+///
+pub fn validate_config_file(path: &Path) -> Result<Vec<ConfigDiagnostic>, Error> {
+    let contents = fs::read_to_string(path)?;
+
+    let mut unknown_fields = vec![];
+    let on_unknown_field = |field: serde_ignored::Path| unknown_fields.push(field.to_string());
+    let result: Result<ParsedConfig, (String, Option<usize>)> =
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("toml") => {
+                serde_ignored::deserialize(toml::Deserializer::new(&contents), on_unknown_field)
+                    .map_err(|error| {
+                        let line = error
+                            .span()
+                            .map(|span| contents[..span.start].matches('\n').count() + 1);
+                        (error.message().to_owned(), line)
+                    })
+            }
+            Some("json") => serde_ignored::deserialize(
+                &mut serde_json::Deserializer::from_str(&contents),
+                on_unknown_field,
+            )
+            .map_err(|error| {
+                (
+                    error.to_string(),
+                    Some(error.line()).filter(|line| *line > 0),
+                )
+            }),
+            _ => serde_ignored::deserialize(
+                serde_yaml::Deserializer::from_str(&contents),
+                on_unknown_field,
+            )
+            .map_err(|error| {
+                let line = error.location().map(|location| location.line());
+                (error.to_string(), line)
+            }),
+        };
+
+    let mut diagnostics: Vec<ConfigDiagnostic> = unknown_fields
+        .iter()
+        .map(|field| ConfigDiagnostic::for_field(field, unknown_field_message(field), &contents))
+        .collect();
+
+    let parsed_config = match result {
+        Ok(parsed_config) => parsed_config,
+        Err((message, line)) => {
+            diagnostics.push(ConfigDiagnostic {
+                field: None,
+                line,
+                message,
+            });
+            return Ok(diagnostics);
+        }
+    };
+    let config = Config::from(parsed_config);
+
+    let patterns = [
+        (
+            "tag_pattern",
+            &config.tag_pattern,
+            &[VERSION_CAPTURING_GROUP_NAME][..],
+        ),
+        (
+            "commit_pattern",
+            &config.commit_pattern,
+            &[TYPE_CAPTURING_GROUP_NAME, DESCRIPTION_CAPTURING_GROUP_NAME][..],
+        ),
+        (
+            "release_branch_pattern",
+            &config.release_branch_pattern,
+            &[LINE_CAPTURING_GROUP_NAME][..],
+        ),
+        ("scope_rules.pattern", &config.scope_rules.pattern, &[][..]),
+    ];
+    let mut is_scope_pattern_valid = true;
+    for (field, pattern, required_groups) in patterns {
+        if let Some(message) = check_pattern(pattern, required_groups) {
+            if field == "scope_rules.pattern" {
+                is_scope_pattern_valid = false;
+            }
+            diagnostics.push(ConfigDiagnostic::for_field(field, message, &contents));
+        }
+    }
+    for (scope, scope_config) in &config.scopes {
+        let patterns = [
+            (
+                "tag_pattern",
+                &scope_config.tag_pattern,
+                &config.tag_pattern,
+                &[VERSION_CAPTURING_GROUP_NAME][..],
+            ),
+            (
+                "commit_pattern",
+                &scope_config.commit_pattern,
+                &config.commit_pattern,
+                &[TYPE_CAPTURING_GROUP_NAME, DESCRIPTION_CAPTURING_GROUP_NAME][..],
+            ),
+        ];
+        for (field, pattern, top_level_pattern, required_groups) in patterns {
+            // Scopes that do not override a pattern use the top-level one, which is already checked.
+            if pattern == top_level_pattern {
+                continue;
+            }
+            if let Some(message) = check_pattern(pattern, required_groups) {
+                let field = format!("scopes.{}.{}", scope, field);
+                diagnostics.push(ConfigDiagnostic::for_field(&field, message, &contents));
+            }
+        }
+    }
+
+    for (index, pattern) in config.ignore_commit_patterns.iter().enumerate() {
+        if let Some(message) = check_pattern(pattern, &[]) {
+            let field = format!("ignore_commit_patterns.{}", index);
+            diagnostics.push(ConfigDiagnostic::for_field(&field, message, &contents));
+        }
+    }
+
+    if let Some(new_tag_name) = &config.new_tag_name {
+        if !new_tag_name.contains(NEW_TAG_NAME_VERSION_PLACEHOLDER) {
+            diagnostics.push(ConfigDiagnostic::for_field(
+                "new_tag_name",
+                format!(
+                    "the template `{}` does not contain the `{}` placeholder, every new tag would have the same name",
+                    new_tag_name, NEW_TAG_NAME_VERSION_PLACEHOLDER
+                ),
+                &contents,
+            ));
+        }
+    }
+
+    if let Err(error) = parse_user_version(&config.initial_version, "initial_version") {
+        diagnostics.push(ConfigDiagnostic::for_field(
+            "initial_version",
+            error.to_string(),
+            &contents,
+        ));
+    }
+
+    if is_scope_pattern_valid {
+        let case = config.scope_rules.case;
+        let scopes = config
+            .version_scopes
+            .iter()
+            .enumerate()
+            .map(|(index, scope)| (format!("version_scopes.{}", index), scope))
+            .chain(
+                config
+                    .scope_paths
+                    .keys()
+                    .map(|scope| (format!("scope_paths.{}", scope), scope)),
+            )
+            .chain(
+                config
+                    .scopes
+                    .keys()
+                    .map(|scope| (format!("scopes.{}", scope), scope)),
+            );
+        for (field, scope) in scopes {
+            if let Err(error) = validate_scope(&normalize_scope(scope, case), &config.scope_rules) {
+                diagnostics.push(ConfigDiagnostic::for_field(
+                    &field,
+                    error.to_string(),
+                    &contents,
+                ));
+            }
+        }
+    }
+
+    Ok(diagnostics)
+}
+
+/// Returns the description of the given unknown field of the configuration file, suggesting the closest known field
+/// if there is one.
+///
+/// # Arguments
+///
+/// * `field` - Path of the unknown field.
+///
+pub fn unknown_field_message(field: &str) -> String {
+    match suggest_field(field) {
+        Some(suggestion) => format!(
+            "unknown field `{}`, did you mean `{}`? Unknown fields are ignored and the default value of the \
+             intended field is used",
+            field, suggestion
+        ),
+        None => format!(
+            "unknown field `{}`, check its spelling or remove it. Unknown fields are ignored",
+            field
+        ),
+    }
+}
+
+/// Checks that the given regex pattern compiles and exposes the given capturing groups. Returns the description of
+/// the issue, or `None` if the pattern is valid.
+///
+/// # Arguments
+///
+/// * `pattern` - Regex pattern to check.
+///
+/// * `required_groups` - Names of the capturing groups that the pattern must expose.
+///
+fn check_pattern(pattern: &str, required_groups: &[&str]) -> Option<String> {
+    let re = match build_regex(pattern) {
+        Ok(re) => re,
+        Err(error) => return Some(error.to_string()),
+    };
+    let missing_groups: Vec<String> = required_groups
+        .iter()
+        .filter(|group| !re.capture_names().flatten().any(|name| name == **group))
+        .map(|group| format!("`{}`", group))
+        .collect();
+    match missing_groups.is_empty() {
+        true => None,
+        false => Some(format!(
+            "the pattern `{}` does not expose the required capturing groups {}, add them with the `(?<name>...)` \
+             syntax",
+            pattern,
+            missing_groups.join(", ")
+        )),
+    }
+}
+
+/// Returns the line, starting at 1, where the key of the given field is defined in the configuration file, or `None`
+/// if it is not found. The key is the last segment of the field that is not a list position. Keys are matched
+/// textually, so the first line that defines a key with the same name is returned.
+///
+/// # Arguments
+///
+/// * `contents` - Contents of the configuration file.
+///
+/// * `field` - Path of the field.
+///
+fn find_field_line(contents: &str, field: &str) -> Option<usize> {
+    let key = field
+        .rsplit('.')
+        .find(|segment| segment.parse::<usize>().is_err())?;
+    contents
+        .lines()
+        .position(|line| {
+            let line = line
+                .trim_start()
+                .trim_start_matches("- ")
+                .trim_start_matches(['"', '\'', '[']);
+            line.strip_prefix(key).is_some_and(|rest| {
+                rest.trim_start_matches(['"', '\''])
+                    .trim_start()
+                    .starts_with([':', '=', ']'])
+            })
+        })
+        .map(|index| index + 1)
+}
+
+/// Returns the known field closest to the given unknown field, or `None` if no known field is close enough. The
+/// known fields are the fields of the same object in the default configuration.
+///
+/// # Arguments
+///
+/// * `field` - Path of the unknown field.
+///
+fn suggest_field(field: &str) -> Option<String> {
+    let (parent, unknown) = match field.rsplit_once('.') {
+        Some((parent, unknown)) => (Some(parent), unknown),
+        None => (None, field),
+    };
+
+    let mut value = serde_json::to_value(Config::new()).ok()?;
+    for segment in parent.into_iter().flat_map(|parent| parent.split('.')) {
+        value = match value {
+            Value::Array(items) => items.into_iter().next()?,
+            Value::Object(mut fields) => fields.remove(segment)?,
+            _ => return None,
+        };
+    }
+    let fields = match value {
+        Value::Object(fields) => fields,
+        _ => return None,
+    };
+
+    fields
+        .keys()
+        .map(|known| (edit_distance(unknown, known), known))
+        .filter(|(distance, _)| *distance <= MAX_SUGGESTION_DISTANCE)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| known.clone())
+}
+
+/// Returns the Levenshtein distance between the given strings, the minimum number of single character insertions,
+/// deletions and substitutions needed to change one into the other.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use std::{env, process};
+
+    /// Writes a configuration file with the given name and contents to the temporary directory and returns its path.
+    fn config_file(name: &str, contents: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("tag-track-validate-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    /// Returns the field and line of each diagnostic of the given configuration file.
+    fn locations(path: &Path) -> Vec<(Option<String>, Option<usize>)> {
+        validate_config_file(path)
+            .unwrap()
+            .into_iter()
+            .map(|diagnostic| (diagnostic.field, diagnostic.line))
+            .collect()
+    }
+
+    #[test]
+    fn valid_configuration_has_no_diagnostics() {
+        let path = config_file(
+            "valid.yml",
+            "tag_pattern: '^v(?<version>.*)$'\nversion_scopes: [api]\nbump_rules:\n  - bump: minor\n    \
+             types: [feat]\n",
+        );
+        assert!(validate_config_file(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn unknown_fields_are_reported_with_a_suggestion() {
+        let path = config_file(
+            "typo.yml",
+            "tag_pattern: '^v(?<version>.*)$'\nbump_ruls:\n  - bump: minor\n",
+        );
+        let diagnostics = validate_config_file(&path).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].field.as_deref(), Some("bump_ruls"));
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert!(diagnostics[0]
+            .message
+            .contains("did you mean `bump_rules`?"));
+    }
+
+    #[test]
+    fn invalid_patterns_are_reported_at_their_line() {
+        let path = config_file(
+            "patterns.yml",
+            "initial_version: 0.1.0\ntag_pattern: '^v(?<number>.*)$'\ncommit_pattern: '('\n",
+        );
+        assert_eq!(
+            locations(&path),
+            vec![
+                (Some("tag_pattern".to_owned()), Some(2)),
+                (Some("commit_pattern".to_owned()), Some(3)),
+            ]
+        );
+        let diagnostics = validate_config_file(&path).unwrap();
+        assert!(diagnostics[0]
+            .message
+            .contains("does not expose the required capturing groups `version`"));
+    }
+
+    #[test]
+    fn syntax_errors_are_reported_at_their_line() {
+        let path = config_file(
+            "syntax.toml",
+            "initial_version = \"0.1.0\"\ntag_pattern = \n",
+        );
+        assert_eq!(locations(&path), vec![(None, Some(2))]);
+    }
+}
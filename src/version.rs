@@ -3,21 +3,248 @@
 //! Version increments follow the Semantic Versioning 2.0
 //!
 
-use crate::{config::BumpRule, git::Commit};
+use crate::{
+    config::{BumpRule, PrereleaseStrategy},
+    error::{Error, ErrorKind},
+    git::Commit,
+};
 use semver::{BuildMetadata, Prerelease, Version};
 use serde::{Deserialize, Serialize};
 
+/// Pre-release identifier of snapshot versions.
+const SNAPSHOT_IDENTIFIER: &str = "nightly";
+
+/// Length of the abbreviated commit SHA included in the build section of snapshot versions.
+const SNAPSHOT_SHA_LENGTH: usize = 7;
+
 /// Types for different version.
 /// The increment types follow the Semantic Version specification.
-#[derive(Eq, PartialEq, Hash, Debug, Deserialize, Serialize)]
+///
+/// Variants are declared by precedence, so increment kinds can be compared and the biggest version change is
+/// the greatest value (`Major` > `Minor` > `Patch` > `Prerelease`).
+#[derive(Eq, PartialEq, Ord, PartialOrd, Hash, Debug, Clone, Copy, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum IncrementKind {
-    /// Increment the major section of a version.
-    Major,
-    /// Increment the minor section of a version.
-    Minor,
+    /// Increment the pre-release counter of a version.
+    Prerelease,
     /// Increment the patch section of a version.
     Patch,
+    /// Increment the minor section of a version.
+    Minor,
+    /// Increment the major section of a version.
+    Major,
+}
+
+impl IncrementKind {
+    /// Returns the name of the increment kind used in the configuration file.
+    pub fn as_str(&self) -> &str {
+        use IncrementKind::*;
+
+        match *self {
+            Prerelease => "prerelease",
+            Patch => "patch",
+            Minor => "minor",
+            Major => "major",
+        }
+    }
+}
+
+/// Returns the increment kind with the highest precedence from the given increments, ignoring missing increments.
+/// Returns `None` if there are no increments.
+///
+/// # Arguments
+///
+/// * `increments` - Increments to compare.
+///
+pub fn max_increment<I>(increments: I) -> Option<IncrementKind>
+where
+    I: IntoIterator<Item = Option<IncrementKind>>,
+{
+    increments.into_iter().flatten().max()
+}
+
+/// Increments the pre-release counter of a `semver::Version`. If the version already has a pre-release section
+/// ending with a numeric identifier, that identifier is incremented, for example `1.0.0-rc.1` becomes `1.0.0-rc.2`.
+/// If the pre-release section does not end with a numeric identifier, `.1` is appended to it. If the version does
+/// not have a pre-release section, the patch section is incremented and the pre-release section is set to
+/// `<identifier>.1`, for example `1.0.0` becomes `1.0.1-rc.1`. The build section is always emptied.
+///
+/// # Arguments
+///
+/// * `version` - Version that will be modified.
+///
+/// * `identifier` - Pre-release identifier used when the version does not have a pre-release section.
+///
+/// # Errors
+///
+/// Returns `error::Error` if the resulting pre-release section is not valid.
+///
+pub fn increment_prerelease(version: &mut Version, identifier: &str) -> Result<(), Error> {
+    let pre = if version.pre.is_empty() {
+        version.patch += 1;
+        format!("{}.1", identifier)
+    } else {
+        match version.pre.as_str().rsplit_once('.') {
+            Some((prefix, counter)) if is_numeric_identifier(counter) => {
+                format!("{}.{}", prefix, counter.parse::<u64>()? + 1)
+            }
+            _ if is_numeric_identifier(version.pre.as_str()) => {
+                (version.pre.as_str().parse::<u64>()? + 1).to_string()
+            }
+            _ => format!("{}.1", version.pre.as_str()),
+        }
+    };
+
+    version.pre = Prerelease::new(&pre)?;
+    version.build = BuildMetadata::EMPTY;
+    Ok(())
+}
+
+/// Parses a version given by the user, such as the `initial_version` of the configuration. Contrary to
+/// `semver::Version::parse`, the errors report the position of the first invalid character and how to fix it.
+///
+/// # Arguments
+///
+/// * `version` - Version that will be parsed.
+///
+/// * `name` - Name of the configuration field or CLI argument of the version, included in the errors.
+///
+/// # Errors
+///
+/// Returns `error::Error` with a kind of `error::ErrorKind::InvalidVersion` if the version is not a valid semantic
+/// version.
+///
+pub fn parse_user_version(version: &str, name: &str) -> Result<Version, Error> {
+    if let Some((position, description)) = find_version_error(version) {
+        return Err(Error::new(
+            ErrorKind::InvalidVersion,
+            Some(&format!(
+                "{} '{}' at position {}: {}",
+                name, version, position, description
+            )),
+        ));
+    }
+    Version::parse(version).map_err(|error| {
+        Error::new(
+            ErrorKind::InvalidVersion,
+            Some(&format!("{} '{}': {}", name, version, error)),
+        )
+    })
+}
+
+/// Returns the position, starting at `1`, and the description of the first invalid character of the given version,
+/// or `None` if it is a valid semantic version.
+///
+/// # Arguments
+///
+/// * `version` - Version that will be validated.
+///
+fn find_version_error(version: &str) -> Option<(usize, String)> {
+    let chars: Vec<char> = version.chars().collect();
+    if let Some(prefix @ ('v' | 'V')) = chars.first() {
+        return Some((
+            1,
+            format!(
+                "unexpected `{}`, drop it from the version as the prefixes of the tag names are matched by \
+                 `tag_pattern`",
+                prefix
+            ),
+        ));
+    }
+
+    let mut index = 0;
+    for (section, separator) in [("major", Some('.')), ("minor", Some('.')), ("patch", None)] {
+        let start = index;
+        while chars.get(index).is_some_and(char::is_ascii_digit) {
+            index += 1;
+        }
+        if index == start {
+            return Some((
+                index + 1,
+                format!("expected the {} version number", section),
+            ));
+        }
+        if chars[start] == '0' && index - start > 1 {
+            return Some((
+                start + 1,
+                format!("the {} version number has a leading zero", section),
+            ));
+        }
+        if chars[start..index]
+            .iter()
+            .collect::<String>()
+            .parse::<u64>()
+            .is_err()
+        {
+            return Some((
+                start + 1,
+                format!("the {} version number is too large", section),
+            ));
+        }
+        if let Some(separator) = separator {
+            if chars.get(index) != Some(&separator) {
+                return Some((
+                    index + 1,
+                    format!(
+                        "expected `{}` after the {} version number",
+                        separator, section
+                    ),
+                ));
+            }
+            index += 1;
+        }
+    }
+
+    let mut expected = "`-`, `+` or the end of the version";
+    for (prefix, section) in [('-', "pre-release"), ('+', "build metadata")] {
+        if chars.get(index) != Some(&prefix) {
+            continue;
+        }
+        index += 1;
+        loop {
+            let start = index;
+            while chars
+                .get(index)
+                .is_some_and(|c| c.is_ascii_alphanumeric() || *c == '-')
+            {
+                index += 1;
+            }
+            if index == start {
+                return Some((index + 1, format!("expected a {} identifier", section)));
+            }
+            let identifier = &chars[start..index];
+            if prefix == '-'
+                && identifier.len() > 1
+                && identifier[0] == '0'
+                && identifier.iter().all(char::is_ascii_digit)
+            {
+                return Some((
+                    start + 1,
+                    "the numeric pre-release identifier has a leading zero".to_owned(),
+                ));
+            }
+            if chars.get(index) != Some(&'.') {
+                break;
+            }
+            index += 1;
+        }
+        expected = match prefix {
+            '-' => "`.`, `+` or the end of the version",
+            _ => "`.` or the end of the version",
+        };
+    }
+
+    chars.get(index).map(|c| {
+        (
+            index + 1,
+            format!("unexpected `{}`, expected {}", c, expected),
+        )
+    })
+}
+
+/// Returns `true` if the given pre-release identifier is numeric.
+fn is_numeric_identifier(identifier: &str) -> bool {
+    !identifier.is_empty() && identifier.chars().all(|c| c.is_ascii_digit())
 }
 
 /// Increments the patch section of a `semver::Version`. This also empties the pre-release
@@ -62,6 +289,121 @@ pub fn increment_major(version: &mut Version) {
     version.build = BuildMetadata::EMPTY;
 }
 
+/// Applies the given major, minor or patch increment to a pre-release version following the given strategy. The
+/// increment is included in the release of the pre-release if the sections after the incremented one are `0`, for
+/// example a minor increment is included in `1.4.0-rc.2` but not in `1.4.1-rc.2`. If it is included, the
+/// `Continue` strategy increments the pre-release counter and the `Finalize` strategy removes the pre-release
+/// section. If it is not included, the `Continue` strategy applies the increment and starts a new pre-release
+/// series with `<identifier>.1`, while the `Finalize` strategy applies the increment. The `Ignore` strategy always
+/// applies the increment. Release versions and `Prerelease` increments must not be given to this function.
+///
+/// # Arguments
+///
+/// * `version` - Pre-release version that will be modified.
+///
+/// * `increment_kind` - Major, minor or patch increment.
+///
+/// * `strategy` - Strategy used to apply the increment.
+///
+/// * `identifier` - Pre-release identifier used when a new pre-release series is started.
+///
+/// # Errors
+///
+/// Returns `error::Error` if the resulting pre-release section is not valid.
+///
+pub fn increment_prerelease_version(
+    version: &mut Version,
+    increment_kind: IncrementKind,
+    strategy: PrereleaseStrategy,
+    identifier: &str,
+) -> Result<(), Error> {
+    let is_included = match increment_kind {
+        IncrementKind::Major => version.minor == 0 && version.patch == 0,
+        IncrementKind::Minor => version.patch == 0,
+        IncrementKind::Patch | IncrementKind::Prerelease => true,
+    };
+    let increment = |version: &mut Version| match increment_kind {
+        IncrementKind::Major => increment_major(version),
+        IncrementKind::Minor => increment_minor(version),
+        IncrementKind::Patch | IncrementKind::Prerelease => increment_patch(version),
+    };
+
+    match (strategy, is_included) {
+        (PrereleaseStrategy::Continue, true) => increment_prerelease(version, identifier)?,
+        (PrereleaseStrategy::Continue, false) => {
+            increment(version);
+            version.pre = Prerelease::new(&format!("{}.1", identifier))?;
+        }
+        (PrereleaseStrategy::Finalize, true) => {
+            version.pre = Prerelease::EMPTY;
+            version.build = BuildMetadata::EMPTY;
+        }
+        (PrereleaseStrategy::Finalize, false) | (PrereleaseStrategy::Ignore, _) => {
+            increment(version)
+        }
+    }
+    Ok(())
+}
+
+/// Returns the snapshot version of the given next version, with the pre-release section `nightly.<date>` and the
+/// build section `sha.<short sha>`, for example `1.4.0-nightly.20240601+sha.abc1234`, so snapshots sort before the
+/// next version. If the current version is a pre-release of the same major, minor and patch sections as the next
+/// version, the snapshot identifiers are appended to its pre-release section, for example
+/// `1.4.0-rc.1.nightly.20240601`, so snapshots also sort after the current version.
+///
+/// # Arguments
+///
+/// * `current` - Version of the closest tag.
+///
+/// * `next` - Next version calculated from the current version.
+///
+/// * `date` - Date of the snapshot in the `YYYYMMDD` format.
+///
+/// * `sha` - SHA of the commit of the snapshot.
+///
+/// # Errors
+///
+/// Returns `error::Error` if the resulting pre-release or build sections are not valid.
+///
+pub fn snapshot_version(
+    current: &Version,
+    next: &Version,
+    date: &str,
+    sha: &str,
+) -> Result<Version, Error> {
+    let is_same_release =
+        (current.major, current.minor, current.patch) == (next.major, next.minor, next.patch);
+    let pre = match is_same_release && !current.pre.is_empty() {
+        true => format!("{}.{}.{}", current.pre, SNAPSHOT_IDENTIFIER, date),
+        false => format!("{}.{}", SNAPSHOT_IDENTIFIER, date),
+    };
+
+    let mut version = Version::new(next.major, next.minor, next.patch);
+    version.pre = Prerelease::new(&pre)?;
+    version.build = BuildMetadata::new(&format!(
+        "sha.{}",
+        sha.get(..SNAPSHOT_SHA_LENGTH).unwrap_or(sha)
+    ))?;
+    Ok(version)
+}
+
+/// Resets a `semver::Version` to the start of the given epoch, setting the major section to the epoch and emptying
+/// the other sections, for example `2023.4.1` becomes `2024.0.0` with the epoch `2024`.
+///
+/// # Arguments
+///
+/// * `version` - Version that will be modified.
+///
+/// * `epoch` - New major section of the version.
+///
+pub fn reset_epoch(version: &mut Version, epoch: u64) {
+    version.major = epoch;
+    version.minor = 0;
+    version.patch = 0;
+    version.pre = Prerelease::EMPTY;
+    version.build = BuildMetadata::EMPTY;
+}
+
 /// Calculate which kind of increment is produced by a commit based on the given rules.
 ///
 /// # Arguments
@@ -71,13 +413,29 @@ pub fn increment_major(version: &mut Version) {
 /// * `rules` - Rules to calculate the increment.
 ///
 pub fn calculate_increment(commit: &Commit, rules: &[BumpRule]) -> Option<IncrementKind> {
+    calculate_triggered_increment(commit, rules).map(|(increment_kind, _)| increment_kind)
+}
+
+/// Calculate which kind of increment is produced by a commit based on the given rules, together with the position in
+/// `rules`, starting at 1, of the first rule that triggered that increment.
+///
+/// # Arguments
+///
+/// * `commit` - Commit to calculate the increment.
+///
+/// * `rules` - Rules to calculate the increment.
+///
+pub fn calculate_triggered_increment(
+    commit: &Commit,
+    rules: &[BumpRule],
+) -> Option<(IncrementKind, usize)> {
     let commit_details = match &commit.details {
         Some(details) => details,
         None => return None,
     };
 
-    let mut increment_kind: Option<IncrementKind> = None;
-    for rule in rules {
+    let mut increment: Option<(IncrementKind, usize)> = None;
+    for (index, rule) in rules.iter().enumerate() {
         let mut bump = false;
 
         // Check commit type
@@ -122,22 +480,61 @@ pub fn calculate_increment(commit: &Commit, rules: &[BumpRule]) -> Option<Increm
         }
 
         if bump {
-            increment_kind = match &rule.bump {
-                IncrementKind::Major => Some(IncrementKind::Major),
-                IncrementKind::Minor => Some(IncrementKind::Minor),
-                IncrementKind::Patch => {
-                    if increment_kind.is_some() {
-                        continue;
-                    }
-                    Some(IncrementKind::Patch)
-                }
-            };
-
-            if let Some(IncrementKind::Major) = increment_kind {
-                return increment_kind;
+            if increment.is_none_or(|(increment_kind, _)| rule.bump > increment_kind) {
+                increment = Some((rule.bump, index + 1));
+            }
+
+            if let Some((IncrementKind::Major, _)) = increment {
+                return increment;
             }
         }
     }
 
-    increment_kind
+    increment
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Applies the given increment to the given pre-release version with the given strategy.
+    fn increment(
+        version: &str,
+        increment_kind: IncrementKind,
+        strategy: PrereleaseStrategy,
+    ) -> String {
+        let mut version = Version::parse(version).unwrap();
+        increment_prerelease_version(&mut version, increment_kind, strategy, "rc").unwrap();
+        version.to_string()
+    }
+
+    #[test]
+    fn continue_strategy_continues_the_prerelease_series() {
+        use IncrementKind::*;
+
+        let strategy = PrereleaseStrategy::Continue;
+        assert_eq!(increment("1.4.0-rc.2", Patch, strategy), "1.4.0-rc.3");
+        assert_eq!(increment("1.4.0-rc.2", Minor, strategy), "1.4.0-rc.3");
+        assert_eq!(increment("1.4.0-rc.2", Major, strategy), "2.0.0-rc.1");
+        assert_eq!(increment("1.4.1-rc.2", Minor, strategy), "1.5.0-rc.1");
+    }
+
+    #[test]
+    fn finalize_strategy_releases_the_prerelease() {
+        use IncrementKind::*;
+
+        let strategy = PrereleaseStrategy::Finalize;
+        assert_eq!(increment("1.4.0-rc.2", Patch, strategy), "1.4.0");
+        assert_eq!(increment("1.4.0-rc.2+build.1", Minor, strategy), "1.4.0");
+        assert_eq!(increment("1.4.0-rc.2", Major, strategy), "2.0.0");
+    }
+
+    #[test]
+    fn ignore_strategy_applies_the_increment() {
+        use IncrementKind::*;
+
+        let strategy = PrereleaseStrategy::Ignore;
+        assert_eq!(increment("1.4.0-rc.2", Patch, strategy), "1.4.1");
+        assert_eq!(increment("1.4.0-rc.2", Minor, strategy), "1.5.0");
+    }
 }